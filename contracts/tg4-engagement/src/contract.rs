@@ -1,11 +1,14 @@
+use std::convert::TryFrom;
+
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coin, to_binary, Addr, BankMsg, Binary, Coin, CustomQuery, Decimal, Deps, DepsMut, Env, Event,
-    MessageInfo, Order, StdResult, Timestamp, Uint128,
+    coin, to_binary, Addr, Api, Attribute, BankMsg, Binary, Coin, CustomQuery, Decimal, Deps,
+    DepsMut, Env, Event, MessageInfo, Order, QuerierWrapper, Reply, StdError, StdResult, Storage,
+    SubMsgResult, Timestamp, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
-use cw_storage_plus::Bound;
+use cw_storage_plus::{Bound, Map, PrimaryKey};
 use cw_utils::{ensure_from_older_version, maybe_addr};
 use tg4::{
     HooksResponse, Member, MemberChangedHookMsg, MemberDiff, MemberInfo, MemberListResponse,
@@ -14,12 +17,20 @@ use tg4::{
 
 use crate::error::ContractError;
 use crate::msg::{
-    DelegatedResponse, ExecuteMsg, HalflifeInfo, HalflifeResponse, InstantiateMsg, MigrateMsg,
-    PreauthResponse, QueryMsg, RewardsResponse, SudoMsg,
+    default_max_denoms, DecayMode, DecayResponse, DelegatedResponse, DelegationsResponse,
+    DistributionParamsResponse, ExecuteMsg, HalflifeInfo, HalflifeResponse, InstantiateMsg,
+    InvariantsResponse, MigrateMsg, PreauthResponse, ProjectedMemberResponse,
+    ProjectedPointsResponse, QueryMsg, RewardMultiplierResponse, RewardsResponse,
+    SimulateDistributionResponse, SimulateSlashResponse, SudoMsg,
 };
 use crate::state::{
-    Distribution, Halflife, WithdrawAdjustment, DISTRIBUTION, HALFLIFE, PREAUTH_SLASHING,
-    SHARES_SHIFT, SLASHERS, WITHDRAW_ADJUSTMENT,
+    halflife_floor, init_delegated_by, init_member_count, init_reward_total, load_distributions,
+    member_tags, migrate_single_denom_distribution, reward_multiplier, reward_points, Distribution,
+    Halflife, PendingForward, WithdrawAdjustment, AUTO_DISTRIBUTE, DELEGATED, DELEGATED_BY, DENOM,
+    DISTRIBUTION, DISTRIBUTION_FEE, FEE_RECIPIENT, HALFLIFE, HALFLIFE_FLOOR, MAX_DENOMS,
+    MAX_REWARD_MULTIPLIER, MEMBER_COUNT, MEMBER_TAGS, PENDING_FORWARD, PREAUTH_SLASHING,
+    REWARD_MULTIPLIERS, REWARD_TOTAL, SHARES_SHIFT, SLASHERS, TAG_DISTRIBUTION, TAG_TOTAL,
+    TAG_WITHDRAW_ADJUSTMENT, WITHDRAW_ADJUSTMENT,
 };
 use tg_bindings::{request_privileges, Privilege, PrivilegeChangeMsg, TgradeMsg, TgradeQuery};
 use tg_utils::{members, validate_portion, Duration, ADMIN, HOOKS, PREAUTH_HOOKS, TOTAL};
@@ -31,18 +42,23 @@ pub type SubMsg = cosmwasm_std::SubMsg<TgradeMsg>;
 const CONTRACT_NAME: &str = "crates.io:tg4-engagement";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Reply id of rewards forwarded to another contract by `WithdrawAndDistribute`
+const FORWARD_REPLY_ID: u64 = 1;
+const DECAY_HOOK_REPLY_ID: u64 = 2;
+
 // Note, you can use StdResult in some functions where you do not
 // make use of the custom errors
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut<TgradeQuery>,
+    mut deps: DepsMut<TgradeQuery>,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let denom = msg.denom.clone();
     create(
-        deps,
+        deps.branch(),
         msg.admin,
         msg.members,
         msg.preauths_hooks,
@@ -50,12 +66,52 @@ pub fn instantiate(
         env.block.height,
         env.block.time,
         msg.halflife,
+        msg.decay,
         msg.denom,
+        msg.max_denoms,
+        msg.distribution_fee,
+        msg.fee_recipient,
+        msg.auto_distribute,
+        msg.halflife_floor,
+        msg.initial_adjustments,
     )?;
 
+    // Rewards of initial adjustments have to be covered by funds sent
+    let required = DISTRIBUTION.load(deps.storage, &denom)?.withdrawable_total;
+    if !required.is_zero() {
+        let sent: Uint128 = info
+            .funds
+            .iter()
+            .filter(|fund| fund.denom == denom)
+            .map(|fund| fund.amount)
+            .sum();
+        if sent < required {
+            return Err(ContractError::AdjustmentsNotFunded { required });
+        }
+    }
+
     Ok(Response::default())
 }
 
+/// Sets the distribution fee. Non-zero fee requires a recipient.
+fn set_distribution_fee(
+    storage: &mut dyn Storage,
+    fee: Decimal,
+    recipient: Option<Addr>,
+) -> Result<(), ContractError> {
+    if fee >= Decimal::one() {
+        return Err(ContractError::InvalidDistributionFee {});
+    }
+    if fee.is_zero() {
+        FEE_RECIPIENT.remove(storage);
+    } else {
+        let recipient = recipient.ok_or(ContractError::MissingFeeRecipient {})?;
+        FEE_RECIPIENT.save(storage, &recipient)?;
+    }
+    DISTRIBUTION_FEE.save(storage, &fee)?;
+    Ok(())
+}
+
 // create is the instantiation logic with set_contract_version removed so it can more
 // easily be imported in other contracts
 #[allow(clippy::too_many_arguments)]
@@ -68,36 +124,58 @@ pub fn create<Q: CustomQuery>(
     height: u64,
     time: Timestamp,
     halflife: Option<Duration>,
+    decay: Option<DecayMode>,
     denom: String,
+    max_denoms: u32,
+    distribution_fee: Decimal,
+    fee_recipient: Option<String>,
+    auto_distribute: bool,
+    halflife_floor: u64,
+    initial_adjustments: Vec<(String, WithdrawAdjustment)>,
 ) -> Result<(), ContractError> {
+    if max_denoms == 0 {
+        return Err(ContractError::InvalidMaxDenoms {});
+    }
+    MAX_DENOMS.save(deps.storage, &max_denoms)?;
+
+    AUTO_DISTRIBUTE.save(deps.storage, &auto_distribute)?;
+
     let admin_addr = admin
         .map(|admin| deps.api.addr_validate(&admin))
         .transpose()?;
-    ADMIN.set(deps.branch(), admin_addr)?;
+    ADMIN.set(deps.branch(), admin_addr.clone())?;
+
+    let fee_recipient = fee_recipient
+        .map(|recipient| deps.api.addr_validate(&recipient))
+        .transpose()?;
+    set_distribution_fee(deps.storage, distribution_fee, fee_recipient.or(admin_addr))?;
 
     PREAUTH_HOOKS.set_auth(deps.storage, preauths_hooks)?;
     PREAUTH_SLASHING.set_auth(deps.storage, preauths_slashing)?;
 
-    let data = Halflife {
-        halflife,
-        last_applied: time,
-    };
-    HALFLIFE.save(deps.storage, &data)?;
-
-    let distribution = Distribution {
-        denom,
-        shares_per_point: Uint128::zero(),
-        shares_leftover: 0,
-        distributed_total: Uint128::zero(),
-        withdrawable_total: Uint128::zero(),
+    let decay = match (halflife, decay) {
+        (Some(_), Some(_)) => return Err(ContractError::ConflictingDecay {}),
+        (Some(halflife), None) => Some(DecayMode::Halflife(halflife)),
+        (None, decay) => decay,
     };
-    DISTRIBUTION.save(deps.storage, &distribution)?;
+    if let Some(decay) = &decay {
+        decay.validate()?;
+    }
+    HALFLIFE.save(deps.storage, &Halflife::new(decay, time))?;
+    HALFLIFE_FLOOR.save(deps.storage, &halflife_floor)?;
+
+    DENOM.save(deps.storage, &denom)?;
+    DISTRIBUTION.save(deps.storage, &denom, &Distribution::new(&denom))?;
 
     let mut total = 0u64;
+    let mut count = 0u64;
 
     for member in members_list.into_iter() {
         total += member.points;
         let member_addr = deps.api.addr_validate(&member.addr)?;
+        if members().may_load(deps.storage, &member_addr)?.is_none() {
+            count += 1;
+        }
         members().save(
             deps.storage,
             &member_addr,
@@ -105,20 +183,70 @@ pub fn create<Q: CustomQuery>(
             height,
         )?;
 
-        let adjustment = WithdrawAdjustment {
-            shares_correction: 0i128.into(),
-            withdrawn_rewards: Uint128::zero(),
-            delegated: member_addr.clone(),
-        };
-        WITHDRAW_ADJUSTMENT.save(deps.storage, &member_addr, &adjustment)?;
+        WITHDRAW_ADJUSTMENT.save(
+            deps.storage,
+            (&member_addr, &denom),
+            &WithdrawAdjustment::default(),
+        )?;
     }
     TOTAL.save(deps.storage, &total)?;
+    REWARD_TOTAL.save(deps.storage, &total.into())?;
+    MEMBER_COUNT.save(deps.storage, &count)?;
+
+    seed_adjustments(deps.storage, deps.api, &denom, initial_adjustments)?;
 
     SLASHERS.instantiate(deps.storage)?;
 
     Ok(())
 }
 
+/// Seeds withdraw adjustments of members in `denom`. Rewards withdrawable and already withdrawn
+/// with them are accounted in the distribution, as if they were distributed by this contract.
+fn seed_adjustments(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    denom: &str,
+    adjustments: Vec<(String, WithdrawAdjustment)>,
+) -> Result<(), ContractError> {
+    if adjustments.is_empty() {
+        return Ok(());
+    }
+
+    let mut shares = 0u128;
+    let mut withdrawn = Uint128::zero();
+    let mut seeded = vec![];
+    for (addr, adjustment) in adjustments {
+        let addr = api.addr_validate(&addr)?;
+        if members().may_load(storage, &addr)?.is_none() {
+            return Err(ContractError::AdjustmentForNonMember(addr.into()));
+        }
+
+        // Nothing is distributed yet, so the correction alone has to cover withdrawn rewards
+        let correction = i128::from(adjustment.shares_correction);
+        if seeded.contains(&addr)
+            || correction < 0
+            || (correction as u128 >> SHARES_SHIFT) < adjustment.withdrawn_rewards.u128()
+        {
+            return Err(ContractError::InvalidAdjustment(addr.into()));
+        }
+
+        shares += correction as u128;
+        withdrawn += adjustment.withdrawn_rewards;
+        WITHDRAW_ADJUSTMENT.save(storage, (&addr, denom), &adjustment)?;
+        seeded.push(addr);
+    }
+
+    // Shares are rounded up to full tokens, the excess is left for the next distribution
+    let distributed = (shares + (1 << SHARES_SHIFT) - 1) >> SHARES_SHIFT;
+    let mut distribution = DISTRIBUTION.load(storage, denom)?;
+    distribution.shares_leftover = ((distributed << SHARES_SHIFT) - shares) as u64;
+    distribution.distributed_total = Uint128::new(distributed);
+    distribution.withdrawable_total = Uint128::new(distributed) - withdrawn;
+    DISTRIBUTION.save(storage, denom, &distribution)?;
+
+    Ok(())
+}
+
 // And declare a custom Error variant for the ones where you will want to make use of it
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
@@ -138,16 +266,39 @@ pub fn execute(
         )?),
         UpdateMembers { add, remove } => execute_update_members(deps, env, info, add, remove),
         AddPoints { addr, points } => execute_add_points(deps, env, info, addr, points),
+        AddPointsBatch { additions } => execute_add_points_batch(deps, env, info, additions),
+        DeductPoints { addr, points } => execute_deduct_points(deps, env, info, addr, points),
         AddHook { addr } => execute_add_hook(deps, info, addr),
         RemoveHook { addr } => execute_remove_hook(deps, info, addr),
-        DistributeRewards { sender } => execute_distribute_rewards(deps, env, info, sender),
-        WithdrawRewards { owner, receiver } => {
-            execute_withdraw_rewards(deps, info, owner, receiver)
+        DistributeRewards { sender, denom } => {
+            execute_distribute_rewards(deps, env, info, sender, denom)
+        }
+        WithdrawRewards {
+            owner,
+            receiver,
+            as_vesting,
+        } => execute_withdraw_rewards(deps, info, owner, receiver, as_vesting),
+        WithdrawRewardsBatch { owners, receiver } => {
+            execute_withdraw_rewards_batch(deps, info, owners, receiver)
+        }
+        WithdrawAndDistribute { owner, target } => {
+            execute_withdraw_and_distribute(deps, info, owner, target)
         }
         DelegateWithdrawal { delegated } => execute_delegate_withdrawal(deps, info, delegated),
         AddSlasher { addr } => execute_add_slasher(deps, info, addr),
         RemoveSlasher { addr } => execute_remove_slasher(deps, info, addr),
         Slash { addr, portion } => execute_slash(deps, env, info, addr, portion),
+        SlashAndRedistribute { addr, portion } => {
+            execute_slash_and_redistribute(deps, env, info, addr, portion)
+        }
+        SetRewardMultiplier { addr, multiplier } => {
+            execute_set_reward_multiplier(deps, info, addr, multiplier)
+        }
+        SetMemberTags { addr, tags } => execute_set_member_tags(deps, info, addr, tags),
+        DistributeRewardsTo { tag } => execute_distribute_rewards_to(deps, env, info, tag),
+        AdminWithdrawUndistributed { receiver } => {
+            execute_admin_withdraw_undistributed(deps, env, info, receiver)
+        }
     }
 }
 
@@ -168,7 +319,7 @@ pub fn execute_add_points<Q: CustomQuery>(
     let old_points = query_member(deps.as_ref(), addr.clone(), None)?;
 
     // make the local update
-    let diff = update_members(
+    let (diff, _) = update_members(
         deps.branch(),
         env.block.height,
         vec![Member {
@@ -185,6 +336,86 @@ pub fn execute_add_points<Q: CustomQuery>(
     Ok(res)
 }
 
+pub fn execute_add_points_batch<Q: CustomQuery>(
+    mut deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    additions: Vec<(String, u64)>,
+) -> Result<Response, ContractError> {
+    let mut res = Response::new()
+        .add_attribute("action", "add_points_batch")
+        .add_attribute("additions", additions.len().to_string());
+
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    // `update_members` sets points, so multiple additions to single member are accumulated
+    let mut to_add: Vec<Member> = vec![];
+    for (addr, points) in additions {
+        match to_add.iter_mut().find(|member| member.addr == addr) {
+            Some(member) => member.points += points,
+            None => {
+                let old_points = query_member(deps.as_ref(), addr.clone(), None)?;
+                to_add.push(Member {
+                    addr,
+                    points: old_points.points.unwrap_or_default() + points,
+                    start_height: old_points.start_height,
+                });
+            }
+        }
+    }
+
+    // make the local update
+    let (diff, _) = update_members(deps.branch(), env.block.height, to_add, vec![])?;
+    // call all registered hooks
+    res.messages = HOOKS.prepare_hooks(deps.storage, |h| {
+        diff.clone().into_cosmos_msg(h).map(SubMsg::new)
+    })?;
+    Ok(res)
+}
+
+pub fn execute_deduct_points<Q: CustomQuery>(
+    mut deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    addr: String,
+    points: u64,
+) -> Result<Response, ContractError> {
+    let mut res = Response::new()
+        .add_attribute("action", "deduct_points")
+        .add_attribute("from_member", addr.to_string())
+        .add_attribute("amount", points.to_string());
+
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let old_member = query_member(deps.as_ref(), addr.clone(), None)?;
+    let old_points = match old_member.points {
+        Some(points) => points,
+        // Nothing to deduct from non-member
+        None => return Ok(res),
+    };
+
+    // Member left without points is removed, so its rewards are paid out
+    let new_points = old_points.saturating_sub(points);
+    let (to_add, to_remove) = if new_points == 0 {
+        (vec![], vec![addr])
+    } else {
+        let member = Member {
+            addr,
+            points: new_points,
+            start_height: old_member.start_height,
+        };
+        (vec![member], vec![])
+    };
+
+    // make the local update
+    let (diff, payouts) = update_members(deps.branch(), env.block.height, to_add, to_remove)?;
+    // call all registered hooks
+    res.messages = HOOKS.prepare_hooks(deps.storage, |h| {
+        diff.clone().into_cosmos_msg(h).map(SubMsg::new)
+    })?;
+    Ok(res.add_submessages(payouts))
+}
+
 pub fn execute_add_hook<Q: CustomQuery>(
     deps: DepsMut<Q>,
     info: MessageInfo,
@@ -246,12 +477,12 @@ pub fn execute_update_members<Q: CustomQuery>(
     ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
 
     // make the local update
-    let diff = update_members(deps.branch(), env.block.height, add, remove)?;
+    let (diff, payouts) = update_members(deps.branch(), env.block.height, add, remove)?;
     // call all registered hooks
     res.messages = HOOKS.prepare_hooks(deps.storage, |h| {
         diff.clone().into_cosmos_msg(h).map(SubMsg::new)
     })?;
-    Ok(res)
+    Ok(res.add_submessages(payouts))
 }
 
 pub fn execute_distribute_rewards<Q: CustomQuery>(
@@ -259,8 +490,9 @@ pub fn execute_distribute_rewards<Q: CustomQuery>(
     env: Env,
     info: MessageInfo,
     sender: Option<String>,
+    denom: Option<String>,
 ) -> Result<Response, ContractError> {
-    let total = TOTAL.load(deps.storage)? as u128;
+    let total = REWARD_TOTAL.load(deps.storage)?;
 
     // There are no shares in play - noone to distribute to
     if total == 0 {
@@ -270,44 +502,281 @@ pub fn execute_distribute_rewards<Q: CustomQuery>(
     let sender = sender
         .map(|sender| deps.api.addr_validate(&sender))
         .transpose()?
-        .unwrap_or(info.sender);
+        .unwrap_or_else(|| info.sender.clone());
 
-    let mut distribution = DISTRIBUTION.load(deps.storage)?;
+    let denoms = match denom {
+        Some(denom) => vec![denom],
+        None => distributed_denoms(deps.storage, &info.funds)?,
+    };
 
-    let withdrawable: u128 = distribution.withdrawable_total.into();
-    let balance: u128 = deps
-        .querier
-        .query_balance(env.contract.address, distribution.denom.clone())?
+    let distributed = match distribute(deps, &env, denoms, None, total, None)? {
+        Some(distributed) => distributed,
+        None => return Ok(Response::new()),
+    };
+
+    let resp = Response::new()
+        .add_attribute("action", "distribute_rewards")
+        .add_attribute("sender", sender.as_str())
+        .add_attributes(distributed.attributes)
+        .add_submessages(distributed.messages);
+
+    Ok(resp)
+}
+
+pub fn execute_distribute_rewards_to<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    tag: String,
+) -> Result<Response, ContractError> {
+    let total = TAG_TOTAL.may_load(deps.storage, &tag)?.unwrap_or_default();
+
+    // There are no tagged shares in play - noone to distribute to
+    if total == 0 {
+        return Err(ContractError::NoMembersToDistributeTo {});
+    }
+
+    // Only tokens sent with the message are distributed, so tokens pending for all members can't
+    // be redirected to the tag
+    let denoms = info.funds.iter().map(|c| c.denom.clone()).collect();
+    let distributed = match distribute(deps, &env, denoms, Some(&info.funds), total, Some(&tag))? {
+        Some(distributed) => distributed,
+        None => return Ok(Response::new()),
+    };
+
+    let resp = Response::new()
+        .add_attribute("action", "distribute_rewards_to")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("tag", tag)
+        .add_attributes(distributed.attributes)
+        .add_submessages(distributed.messages);
+
+    Ok(resp)
+}
+
+/// All already distributed denoms, and any new ones sent in `funds`
+fn distributed_denoms(storage: &dyn Storage, funds: &[Coin]) -> StdResult<Vec<String>> {
+    let mut denoms = DISTRIBUTION
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for fund in funds {
+        if !denoms.contains(&fund.denom) {
+            denoms.push(fund.denom.clone());
+        }
+    }
+    Ok(denoms)
+}
+
+/// Distributes all undistributed tokens of given denoms among `total` reward points - of members
+/// having `tag` if given, or all members otherwise. If `sent` is given, at most the `sent` amount
+/// of every denom is distributed. Returns response with attributes describing distributed amounts
+/// and messages sending out the distribution fee, or `None` if nothing was distributed.
+fn distribute<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: &Env,
+    denoms: Vec<String>,
+    sent: Option<&[Coin]>,
+    total: u128,
+    tag: Option<&str>,
+) -> Result<Option<Response>, ContractError> {
+    let fee = DISTRIBUTION_FEE.may_load(deps.storage)?.unwrap_or_default();
+
+    let mut attributes = vec![];
+    let mut fees = vec![];
+
+    for denom in denoms {
+        let limit = sent.map(|sent| {
+            sent.iter()
+                .filter(|c| c.denom == denom)
+                .map(|c| c.amount.u128())
+                .sum()
+        });
+        let (amount, fee_amount) = distribute_denom(
+            deps.storage,
+            &deps.querier,
+            env,
+            &denom,
+            limit,
+            total,
+            tag,
+            fee,
+        )?;
+        if amount != 0 {
+            attributes.push(Attribute::new("denom", &denom));
+            attributes.push(Attribute::new("amount", amount.to_string()));
+        }
+        if fee_amount != 0 {
+            attributes.push(Attribute::new("fee", fee_amount.to_string()));
+            fees.push(coin(fee_amount, denom));
+        }
+    }
+
+    if attributes.is_empty() {
+        return Ok(None);
+    }
+
+    let mut resp = Response::new().add_attributes(attributes);
+    if !fees.is_empty() {
+        let fee_recipient = FEE_RECIPIENT.load(deps.storage)?;
+        resp = resp.add_submessage(SubMsg::new(BankMsg::Send {
+            to_address: fee_recipient.to_string(),
+            amount: fees,
+        }));
+    }
+
+    Ok(Some(resp))
+}
+
+/// Distributes all undistributed tokens of single denom among `total` points. Creates
+/// distribution for the denom if it was never distributed before, as long as the
+/// `MAX_DENOMS` limit is not reached. If `limit` is given, at most that many undistributed tokens
+/// are distributed. If `tag` is given, shares are added to the distribution of the tag instead.
+/// The `fee` portion of tokens is not distributed, and is expected to be sent out by the caller.
+/// Returns amounts of tokens distributed and taken as a fee.
+#[allow(clippy::too_many_arguments)]
+fn distribute_denom<Q: CustomQuery>(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper<Q>,
+    env: &Env,
+    denom: &str,
+    limit: Option<u128>,
+    total: u128,
+    tag: Option<&str>,
+    fee: Decimal,
+) -> Result<(u128, u128), ContractError> {
+    let mut balance: u128 = querier
+        .query_balance(&env.contract.address, denom)?
         .amount
         .into();
 
-    let amount = balance - withdrawable;
-    if amount == 0 {
-        return Ok(Response::new());
+    let mut distribution = match DISTRIBUTION.may_load(storage, denom)? {
+        Some(distribution) => distribution,
+        // Nothing to distribute, no reason to track the denom
+        None if balance == 0 => return Ok((0, 0)),
+        None => {
+            let max = MAX_DENOMS.load(storage)?;
+            let count = DISTRIBUTION
+                .keys(storage, None, None, Order::Ascending)
+                .count();
+            if count >= max as usize {
+                return Err(ContractError::TooManyDenoms { max });
+            }
+            Distribution::new(denom)
+        }
+    };
+
+    // Tokens above the limit are left for later distribution
+    if let Some(limit) = limit {
+        let withdrawable: u128 = distribution.withdrawable_total.into();
+        balance = balance.min(withdrawable.saturating_add(limit));
     }
 
-    let leftover: u128 = distribution.shares_leftover.into();
-    let points = (amount << SHARES_SHIFT) + leftover;
-    let points_per_share = points / total;
-    distribution.shares_leftover = (points % total) as u64;
+    let tag_distribution = tag
+        .map(|tag| TAG_DISTRIBUTION.may_load(storage, (tag, denom)))
+        .transpose()?
+        .map(Option::unwrap_or_default);
+    let shares_leftover = tag_distribution
+        .as_ref()
+        .map_or(distribution.shares_leftover, |tagged| {
+            tagged.shares_leftover
+        });
+
+    let DistributionSplit {
+        amount,
+        fee_amount,
+        points_per_share,
+        leftover,
+    } = split_distribution(&distribution, shares_leftover, balance, total, fee)?;
+    if amount == 0 {
+        return Ok((0, 0));
+    }
 
     // Everything goes back to 128-bits/16-bytes
+    match (tag, tag_distribution) {
+        (Some(tag), Some(mut tagged)) => {
+            tagged.shares_leftover = leftover;
+            tagged.shares_per_point = checked_add(tagged.shares_per_point, points_per_share)?;
+            tagged.distributed_total = checked_add(tagged.distributed_total, amount)?;
+            TAG_DISTRIBUTION.save(storage, (tag, denom), &tagged)?;
+        }
+        _ => {
+            distribution.shares_leftover = leftover;
+            distribution.shares_per_point =
+                checked_add(distribution.shares_per_point, points_per_share)?;
+        }
+    }
+
     // Full amount is added here to total withdrawable, as it should not be considered on its own
     // on future distributions - even if because of calculation offsets it is not fully
     // distributed, the error is handled by leftover.
-    distribution.shares_per_point += Uint128::from(points_per_share);
-    distribution.distributed_total += Uint128::from(amount);
-    distribution.withdrawable_total += Uint128::from(amount);
+    distribution.distributed_total = checked_add(distribution.distributed_total, amount)?;
+    distribution.withdrawable_total = checked_add(distribution.withdrawable_total, amount)?;
 
-    DISTRIBUTION.save(deps.storage, &distribution)?;
+    DISTRIBUTION.save(storage, denom, &distribution)?;
 
-    let resp = Response::new()
-        .add_attribute("action", "distribute_rewards")
-        .add_attribute("sender", sender.as_str())
-        .add_attribute("denom", &distribution.denom)
-        .add_attribute("amount", &amount.to_string());
+    Ok((amount, fee_amount))
+}
 
-    Ok(resp)
+/// Adds `amount` to distribution accumulator `total`, failing instead of wrapping around
+fn checked_add(total: Uint128, amount: u128) -> Result<Uint128, ContractError> {
+    total
+        .checked_add(Uint128::new(amount))
+        .map_err(|_| ContractError::DistributionOverflow {})
+}
+
+/// Outcome of distributing undistributed tokens on top of existing distribution
+struct DistributionSplit {
+    /// Tokens distributed to members
+    amount: u128,
+    /// Tokens taken as a distribution fee
+    fee_amount: u128,
+    /// Shares added to every point
+    points_per_share: u128,
+    /// Shares left undistributed after this distribution
+    leftover: u64,
+}
+
+/// Calculates how `balance` of contract tokens would be distributed among `total` points, without
+/// applying it. `shares_leftover` is the leftover of distribution the shares are added to - either
+/// `distribution` itself, or a tagged one. `total` has to be non-zero. Fails with
+/// `DistributionOverflow` if the amount is too big to be represented in shares.
+fn split_distribution(
+    distribution: &Distribution,
+    shares_leftover: u64,
+    balance: u128,
+    total: u128,
+    fee: Decimal,
+) -> Result<DistributionSplit, ContractError> {
+    let withdrawable: u128 = distribution.withdrawable_total.into();
+    let amount = balance - withdrawable;
+    if amount == 0 {
+        return Ok(DistributionSplit {
+            amount: 0,
+            fee_amount: 0,
+            points_per_share: 0,
+            leftover: shares_leftover,
+        });
+    }
+
+    // Fee is always lower than 1, so there is always something left to distribute
+    let fee_amount = (Uint128::new(amount) * fee).u128();
+    let amount = amount - fee_amount;
+
+    let leftover: u128 = shares_leftover.into();
+    // Shifting would silently drop the most significant bits of too big amounts
+    if amount > u128::MAX >> SHARES_SHIFT {
+        return Err(ContractError::DistributionOverflow {});
+    }
+    let points = (amount << SHARES_SHIFT)
+        .checked_add(leftover)
+        .ok_or(ContractError::DistributionOverflow {})?;
+
+    Ok(DistributionSplit {
+        amount,
+        fee_amount,
+        points_per_share: points / total,
+        leftover: (points % total) as u64,
+    })
 }
 
 pub fn execute_withdraw_rewards<Q: CustomQuery>(
@@ -315,102 +784,320 @@ pub fn execute_withdraw_rewards<Q: CustomQuery>(
     info: MessageInfo,
     owner: Option<String>,
     receiver: Option<String>,
+    as_vesting: bool,
 ) -> Result<Response, ContractError> {
     let owner = owner.map_or_else(
         || Ok(info.sender.clone()),
         |owner| deps.api.addr_validate(&owner),
     )?;
 
-    let mut distribution = DISTRIBUTION.load(deps.storage)?;
-    let mut adjustment = WITHDRAW_ADJUSTMENT.load(deps.storage, &owner)?;
-
-    if ![&owner, &adjustment.delegated].contains(&&info.sender) {
-        return Err(ContractError::Unauthorized(
-            "Sender is neither owner or delegated".to_owned(),
-        ));
-    }
+    // Undelegation always goes to the owner vesting account
+    let receiver = if as_vesting {
+        owner.clone()
+    } else {
+        receiver
+            .map(|receiver| deps.api.addr_validate(&receiver))
+            .transpose()?
+            .unwrap_or_else(|| info.sender.clone())
+    };
 
-    let reward = withdrawable_rewards(deps.as_ref(), &owner, &distribution, &adjustment)?;
-    let receiver = receiver
-        .map(|receiver| deps.api.addr_validate(&receiver))
-        .transpose()?
-        .unwrap_or_else(|| info.sender.clone());
+    let rewards = withdraw_owner_rewards(deps, &info.sender, &owner)?;
 
-    if reward.amount.is_zero() {
+    if rewards.is_empty() {
         // Just do nothing
         return Ok(Response::new());
     }
 
-    adjustment.withdrawn_rewards += reward.amount;
-    WITHDRAW_ADJUSTMENT.save(deps.storage, &owner, &adjustment)?;
-    distribution.withdrawable_total -= reward.amount;
-    DISTRIBUTION.save(deps.storage, &distribution)?;
-
-    let resp = Response::new()
+    let mut resp = Response::new()
         .add_attribute("action", "withdraw_rewards")
         .add_attribute("sender", info.sender.as_str())
         .add_attribute("owner", owner.as_str())
-        .add_attribute("receiver", receiver.as_str())
-        .add_attribute("reward", &reward.denom)
-        .add_attribute("amount", &reward.amount.to_string())
-        .add_submessage(SubMsg::new(BankMsg::Send {
+        .add_attribute("receiver", receiver.as_str());
+    for reward in &rewards {
+        resp = resp
+            .add_attribute("reward", &reward.denom)
+            .add_attribute("amount", reward.amount.to_string());
+    }
+
+    let resp = if as_vesting {
+        resp.add_attribute("as_vesting", "true")
+            .add_submessages(rewards.into_iter().map(|funds| {
+                SubMsg::new(TgradeMsg::Undelegate {
+                    funds,
+                    recipient: receiver.to_string(),
+                })
+            }))
+    } else {
+        resp.add_submessage(SubMsg::new(BankMsg::Send {
             to_address: receiver.to_string(),
-            amount: vec![reward],
-        }));
+            amount: rewards,
+        }))
+    };
 
     Ok(resp)
 }
 
-pub fn execute_delegate_withdrawal<Q: CustomQuery>(
-    deps: DepsMut<Q>,
+pub fn execute_withdraw_rewards_batch<Q: CustomQuery>(
+    mut deps: DepsMut<Q>,
     info: MessageInfo,
-    delegated: String,
+    owners: Vec<String>,
+    receiver: Option<String>,
 ) -> Result<Response, ContractError> {
-    let delegated = deps.api.addr_validate(&delegated)?;
-
-    WITHDRAW_ADJUSTMENT.update(deps.storage, &info.sender, |data| -> StdResult<_> {
-        Ok(data.map_or_else(
-            || WithdrawAdjustment {
-                shares_correction: 0.into(),
-                withdrawn_rewards: Uint128::zero(),
-                delegated: delegated.clone(),
-            },
-            |mut data| {
-                data.delegated = delegated.clone();
-                data
-            },
-        ))
-    })?;
+    let receiver = receiver
+        .map(|receiver| deps.api.addr_validate(&receiver))
+        .transpose()?
+        .unwrap_or_else(|| info.sender.clone());
 
-    let resp = Response::new()
-        .add_attribute("action", "delegate_withdrawal")
+    let mut resp = Response::new()
+        .add_attribute("action", "withdraw_rewards_batch")
         .add_attribute("sender", info.sender.as_str())
-        .add_attribute("delegated", &delegated);
+        .add_attribute("receiver", receiver.as_str());
+
+    for owner in owners {
+        let owner = deps.api.addr_validate(&owner)?;
+        let rewards = withdraw_owner_rewards(deps.branch(), &info.sender, &owner)?;
+        if rewards.is_empty() {
+            continue;
+        }
+
+        resp = resp.add_attribute("owner", owner.as_str());
+        for reward in &rewards {
+            resp = resp
+                .add_attribute("reward", &reward.denom)
+                .add_attribute("amount", reward.amount.to_string());
+        }
+        resp = resp.add_submessage(SubMsg::new(BankMsg::Send {
+            to_address: receiver.to_string(),
+            amount: rewards,
+        }));
+    }
 
     Ok(resp)
 }
 
-/// Adds new slasher to contract
-pub fn execute_add_slasher<Q: CustomQuery>(
-    deps: DepsMut<Q>,
+pub fn execute_withdraw_and_distribute<Q: CustomQuery>(
+    mut deps: DepsMut<Q>,
     info: MessageInfo,
-    slasher: String,
+    owner: Option<String>,
+    target: String,
 ) -> Result<Response, ContractError> {
-    if !ADMIN.is_admin(deps.as_ref(), &info.sender)? {
-        PREAUTH_SLASHING.use_auth(deps.storage)?;
+    let owner = owner.map_or_else(
+        || Ok(info.sender.clone()),
+        |owner| deps.api.addr_validate(&owner),
+    )?;
+    let target = deps.api.addr_validate(&target)?;
+
+    let rewards = withdraw_owner_rewards(deps.branch(), &info.sender, &owner)?;
+
+    if rewards.is_empty() {
+        // Just do nothing
+        return Ok(Response::new());
     }
 
-    SLASHERS.add_slasher(deps.storage, deps.api.addr_validate(&slasher)?)?;
+    PENDING_FORWARD.save(
+        deps.storage,
+        &PendingForward {
+            owner: owner.clone(),
+            rewards: rewards.clone(),
+        },
+    )?;
 
-    let res = Response::new()
-        .add_attribute("action", "add_slasher")
-        .add_attribute("slasher", slasher)
-        .add_attribute("sender", info.sender);
+    let mut resp = Response::new()
+        .add_attribute("action", "withdraw_and_distribute")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("owner", owner.as_str())
+        .add_attribute("target", target.as_str());
+    for reward in &rewards {
+        resp = resp
+            .add_attribute("reward", &reward.denom)
+            .add_attribute("amount", reward.amount.to_string());
+    }
 
-    Ok(res)
+    // Failing target must not revert the withdrawal, rewards are accrued back to owner instead
+    let resp = resp.add_submessage(SubMsg::reply_always(
+        WasmMsg::Execute {
+            contract_addr: target.to_string(),
+            msg: to_binary(&ExecuteMsg::DistributeRewards {
+                sender: Some(owner.to_string()),
+                denom: None,
+            })?,
+            funds: rewards,
+        },
+        FORWARD_REPLY_ID,
+    ));
+
+    Ok(resp)
 }
 
-/// Removes slasher from contract
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut<TgradeQuery>, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        FORWARD_REPLY_ID => forward_reply(deps, msg.result),
+        DECAY_HOOK_REPLY_ID => Ok(decay_hook_reply(msg.result)),
+        _ => Err(ContractError::UnrecognisedReply(msg.id)),
+    }
+}
+
+/// Logs failure of member changed hook sent on points decay, without reverting the decay
+fn decay_hook_reply(result: SubMsgResult) -> Response {
+    match result {
+        SubMsgResult::Err(err) => Response::new()
+            .add_attribute("action", "decay_hook_failed")
+            .add_attribute("error", err),
+        SubMsgResult::Ok(_) => Response::new(),
+    }
+}
+
+/// Clears rewards pending after `WithdrawAndDistribute`, accruing them back to their owner if
+/// the forward failed
+fn forward_reply<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    result: SubMsgResult,
+) -> Result<Response, ContractError> {
+    let PendingForward { owner, rewards } = PENDING_FORWARD.load(deps.storage)?;
+    PENDING_FORWARD.remove(deps.storage);
+
+    let err = match result {
+        SubMsgResult::Err(err) => err,
+        SubMsgResult::Ok(_) => return Ok(Response::new()),
+    };
+
+    let mut resp = Response::new()
+        .add_attribute("action", "withdraw_and_distribute_failed")
+        .add_attribute("owner", owner.as_str())
+        .add_attribute("error", err);
+
+    for reward in rewards {
+        let key = (&owner, reward.denom.as_str());
+        WITHDRAW_ADJUSTMENT.update(deps.storage, key, |adjustment| -> StdResult<_> {
+            let mut adjustment = adjustment.unwrap_or_default();
+            adjustment.withdrawn_rewards -= reward.amount;
+            Ok(adjustment)
+        })?;
+        DISTRIBUTION.update(
+            deps.storage,
+            &reward.denom,
+            |distribution| -> StdResult<_> {
+                let mut distribution =
+                    distribution.unwrap_or_else(|| Distribution::new(reward.denom.clone()));
+                distribution.withdrawable_total += reward.amount;
+                Ok(distribution)
+            },
+        )?;
+
+        resp = resp
+            .add_attribute("reward", &reward.denom)
+            .add_attribute("amount", reward.amount.to_string());
+    }
+
+    Ok(resp)
+}
+
+/// Marks all rewards of `owner` as withdrawn, and returns them. Fails if `sender` is neither
+/// `owner` nor the address delegated by `owner`.
+fn withdraw_owner_rewards<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    sender: &Addr,
+    owner: &Addr,
+) -> Result<Vec<Coin>, ContractError> {
+    let delegated = DELEGATED
+        .may_load(deps.storage, owner)?
+        .unwrap_or_else(|| owner.clone());
+
+    if ![owner, &delegated].contains(&sender) {
+        return Err(ContractError::Unauthorized(
+            "Sender is neither owner or delegated".to_owned(),
+        ));
+    }
+
+    let mut rewards = vec![];
+    for mut distribution in load_distributions(deps.storage)? {
+        let key = (owner, distribution.denom.as_str());
+        let mut adjustment = WITHDRAW_ADJUSTMENT
+            .may_load(deps.storage, key)?
+            .unwrap_or_default();
+        fold_tagged_rewards(deps.storage, owner, &distribution.denom, &mut adjustment)?;
+
+        let reward = withdrawable_rewards(deps.as_ref(), owner, &distribution, &adjustment)?;
+        if reward.amount.is_zero() {
+            continue;
+        }
+
+        adjustment.withdrawn_rewards += reward.amount;
+        WITHDRAW_ADJUSTMENT.save(deps.storage, key, &adjustment)?;
+        distribution.withdrawable_total -= reward.amount;
+        DISTRIBUTION.save(deps.storage, &distribution.denom, &distribution)?;
+
+        rewards.push(reward);
+    }
+
+    Ok(rewards)
+}
+
+/// Moves rewards of `owner` from distributions of `denom` to its tags into its regular
+/// `adjustment`, so they are withdrawn together with regular rewards.
+fn fold_tagged_rewards(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    denom: &str,
+    adjustment: &mut WithdrawAdjustment,
+) -> Result<(), ContractError> {
+    let points = member_reward_points(storage, owner)?;
+    for (tag, mut tag_adjustment, amount) in tagged_rewards(storage, owner, denom, points)? {
+        tag_adjustment.withdrawn_rewards += amount;
+        TAG_WITHDRAW_ADJUSTMENT.save(storage, (owner, &tag, denom), &tag_adjustment)?;
+
+        let shares_correction: i128 = adjustment.shares_correction.into();
+        adjustment.shares_correction =
+            (shares_correction + ((amount.u128() as i128) << SHARES_SHIFT)).into();
+    }
+    Ok(())
+}
+
+pub fn execute_delegate_withdrawal<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    delegated: String,
+) -> Result<Response, ContractError> {
+    let delegated = deps.api.addr_validate(&delegated)?;
+
+    if let Some(old) = DELEGATED.may_load(deps.storage, &info.sender)? {
+        DELEGATED_BY.remove(deps.storage, (&old, &info.sender));
+    }
+    DELEGATED.save(deps.storage, &info.sender, &delegated)?;
+    if delegated != info.sender {
+        DELEGATED_BY.save(deps.storage, (&delegated, &info.sender), &())?;
+    }
+
+    let resp = Response::new()
+        .add_attribute("action", "delegate_withdrawal")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("delegated", &delegated);
+
+    Ok(resp)
+}
+
+/// Adds new slasher to contract
+pub fn execute_add_slasher<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    slasher: String,
+) -> Result<Response, ContractError> {
+    if !ADMIN.is_admin(deps.as_ref(), &info.sender)? {
+        PREAUTH_SLASHING.use_auth(deps.storage)?;
+    }
+
+    SLASHERS.add_slasher(deps.storage, deps.api.addr_validate(&slasher)?)?;
+
+    let res = Response::new()
+        .add_attribute("action", "add_slasher")
+        .add_attribute("slasher", slasher)
+        .add_attribute("sender", info.sender);
+
+    Ok(res)
+}
+
+/// Removes slasher from contract
 pub fn execute_remove_slasher<Q: CustomQuery>(
     deps: DepsMut<Q>,
     info: MessageInfo,
@@ -457,9 +1144,10 @@ pub fn execute_slash<Q: CustomQuery>(
 
     validate_portion(portion)?;
 
-    let ppw: u128 = DISTRIBUTION.load(deps.storage)?.shares_per_point.into();
+    let distributions = load_distributions(deps.storage)?;
 
     let mut diff = 0i128;
+    let mut old_points = 0u64;
 
     members().update(
         deps.storage,
@@ -475,11 +1163,18 @@ pub fn execute_slash<Q: CustomQuery>(
             let new = old - slash;
 
             diff = -(slash.u128() as i128);
+            old_points = old.u128() as _;
 
             Ok(MemberInfo::new(new.u128() as _))
         },
     )?;
-    apply_points_correction(deps.branch(), &addr, ppw, diff)?;
+    apply_points_correction(
+        deps.branch(),
+        &addr,
+        &distributions,
+        old_points,
+        (old_points as i128 + diff) as _,
+    )?;
 
     TOTAL.update(deps.storage, |total| -> StdResult<_> {
         Ok((total as i128 + diff) as _)
@@ -493,27 +1188,309 @@ pub fn execute_slash<Q: CustomQuery>(
     Ok(res)
 }
 
-/// Calculates withdrawable_rewards from distribution and adjustment info.
+pub fn execute_slash_and_redistribute<Q: CustomQuery>(
+    mut deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    addr: String,
+    portion: Decimal,
+) -> Result<Response, ContractError> {
+    if !SLASHERS.is_slasher(deps.storage, &info.sender)? {
+        return Err(ContractError::Unauthorized(
+            "Sender is not on slashers list".to_owned(),
+        ));
+    }
+    let addr = Addr::unchecked(&addr);
+    // check if address belongs to member, otherwise leave early
+    let old = match members().may_load(deps.storage, &addr)? {
+        Some(old) => old,
+        None => return Ok(Response::new()),
+    };
+
+    validate_portion(portion)?;
+
+    let distributions = load_distributions(deps.storage)?;
+
+    let slashed = (Uint128::new(old.points as _) * portion).u128() as u64;
+    members().replace(
+        deps.storage,
+        &addr,
+        Some(&MemberInfo::new(old.points - slashed)),
+        Some(&old),
+        env.block.height,
+    )?;
+    apply_points_correction(
+        deps.branch(),
+        &addr,
+        &distributions,
+        old.points,
+        old.points - slashed,
+    )?;
+
+    let total = TOTAL.load(deps.storage)?;
+    // Points of all members but the slashed one, used as redistribution base
+    let remaining = (total - old.points) as u128;
+
+    let mut redistributed = 0u64;
+    if slashed > 0 && remaining > 0 {
+        // Every credited member needs its points correction updated, so this is linear in
+        // number of members
+        let others: Vec<_> = members()
+            .range(deps.storage, None, None, Order::Ascending)
+            .filter(|item| !matches!(item, Ok((member, _)) if *member == addr))
+            .collect::<StdResult<_>>()?;
+
+        for (member, info) in others {
+            let credit = (slashed as u128 * info.points as u128 / remaining) as u64;
+            if credit == 0 {
+                continue;
+            }
+            members().replace(
+                deps.storage,
+                &member,
+                Some(&MemberInfo::new(info.points + credit)),
+                Some(&info),
+                env.block.height,
+            )?;
+            apply_points_correction(
+                deps.branch(),
+                &member,
+                &distributions,
+                info.points,
+                info.points + credit,
+            )?;
+            redistributed += credit;
+        }
+    }
+
+    TOTAL.save(deps.storage, &(total - slashed + redistributed))?;
+
+    let res = Response::new()
+        .add_attribute("action", "slash_and_redistribute")
+        .add_attribute("addr", &addr)
+        .add_attribute("sender", info.sender)
+        .add_attribute("slashed", slashed.to_string())
+        .add_attribute("redistributed", redistributed.to_string());
+
+    Ok(res)
+}
+
+pub fn execute_admin_withdraw_undistributed<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    receiver: String,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+    let receiver = deps.api.addr_validate(&receiver)?;
+
+    // Distributions are not touched - `withdrawable_total` stays in the contract
+    let mut amount = vec![];
+    for distribution in load_distributions(deps.storage)? {
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, &distribution.denom)?
+            .amount;
+        let undistributed = balance - distribution.withdrawable_total;
+        if !undistributed.is_zero() {
+            amount.push(coin(undistributed.u128(), distribution.denom));
+        }
+    }
+
+    let mut res = Response::new()
+        .add_attribute("action", "admin_withdraw_undistributed")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("receiver", receiver.as_str());
+    for c in &amount {
+        res = res
+            .add_attribute("denom", &c.denom)
+            .add_attribute("amount", c.amount.to_string());
+    }
+    if !amount.is_empty() {
+        res = res.add_message(BankMsg::Send {
+            to_address: receiver.into(),
+            amount,
+        });
+    }
+
+    Ok(res)
+}
+
+pub fn execute_set_reward_multiplier<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    addr: String,
+    multiplier: Decimal,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    if multiplier.is_zero() || multiplier > Decimal::from_ratio(MAX_REWARD_MULTIPLIER, 1u64) {
+        return Err(ContractError::InvalidRewardMultiplier {
+            max: MAX_REWARD_MULTIPLIER,
+        });
+    }
+
+    let addr = deps.api.addr_validate(&addr)?;
+    let points = members()
+        .may_load(deps.storage, &addr)?
+        .unwrap_or_default()
+        .points;
+
+    // Rewards already distributed are kept, as the shares correction compensates the change
+    let old_multiplier = reward_multiplier(deps.storage, &addr)?;
+    let diff =
+        reward_points(points, multiplier) as i128 - reward_points(points, old_multiplier) as i128;
+    if diff != 0 {
+        let distributions = load_distributions(deps.storage)?;
+        apply_shares_correction(deps.storage, &addr, &distributions, diff)?;
+    }
+
+    if multiplier == Decimal::one() {
+        REWARD_MULTIPLIERS.remove(deps.storage, &addr);
+    } else {
+        REWARD_MULTIPLIERS.save(deps.storage, &addr, &multiplier)?;
+    }
+
+    let res = Response::new()
+        .add_attribute("action", "set_reward_multiplier")
+        .add_attribute("addr", addr)
+        .add_attribute("multiplier", multiplier.to_string())
+        .add_attribute("sender", info.sender);
+
+    Ok(res)
+}
+
+pub fn execute_set_member_tags<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    addr: String,
+    mut tags: Vec<String>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    if tags.iter().any(String::is_empty) {
+        return Err(ContractError::EmptyTag {});
+    }
+    tags.sort();
+    tags.dedup();
+
+    let addr = deps.api.addr_validate(&addr)?;
+    let points = member_reward_points(deps.storage, &addr)? as i128;
+
+    // Leaving or joining a tag is accounted as losing or gaining all points within the tag, so
+    // rewards already earned are preserved and earlier rewards are not shared with newcomers
+    let old_tags = member_tags(deps.storage, &addr)?;
+    for tag in old_tags.iter().filter(|tag| !tags.contains(tag)) {
+        apply_tag_correction(deps.storage, &addr, tag, -points)?;
+    }
+    for tag in tags.iter().filter(|tag| !old_tags.contains(tag)) {
+        apply_tag_correction(deps.storage, &addr, tag, points)?;
+    }
+
+    let mut res = Response::new()
+        .add_attribute("action", "set_member_tags")
+        .add_attribute("addr", addr.as_str())
+        .add_attribute("sender", info.sender);
+
+    if tags.is_empty() {
+        MEMBER_TAGS.remove(deps.storage, &addr);
+    } else {
+        res = res.add_attribute("tags", tags.join(","));
+        MEMBER_TAGS.save(deps.storage, &addr, &tags)?;
+    }
+
+    Ok(res)
+}
+
+/// Points of member weighted by its reward multiplier, zero for non-members
+fn member_reward_points(storage: &dyn Storage, addr: &Addr) -> StdResult<u128> {
+    let points = members()
+        .may_load(storage, addr)?
+        .unwrap_or_default()
+        .points;
+    Ok(reward_points(points, reward_multiplier(storage, addr)?))
+}
+
+/// Rewards from shares of `points` and the withdraw `adjustment`. Fails with
+/// `DistributionOverflow` if shares of `points` don't fit in 128 bits.
+fn shares_rewards(
+    shares_per_point: Uint128,
+    points: u128,
+    adjustment: &WithdrawAdjustment,
+) -> Result<Uint128, ContractError> {
+    let ppw: u128 = shares_per_point.into();
+    let correction: i128 = adjustment.shares_correction.into();
+    let withdrawn: u128 = adjustment.withdrawn_rewards.into();
+    let points = ppw
+        .checked_mul(points)
+        .and_then(|points| i128::try_from(points).ok())
+        .and_then(|points| points.checked_add(correction))
+        .ok_or(ContractError::DistributionOverflow {})?;
+    let amount = points as u128 >> SHARES_SHIFT;
+    Ok(Uint128::new(amount - withdrawn))
+}
+
+/// Calculates withdrawable_rewards from distribution and adjustment info, including rewards
+/// distributed to tags of `owner`.
+///
+/// It is based on current points of `owner` weighted by its reward multiplier, but it is
+/// equivalent to computing rewards against points at the time of every distribution: whenever
+/// points or the multiplier change (including slashing and halflife), `shares_correction` is
+/// updated by `apply_points_correction`, so rewards already earned are preserved.
 pub fn withdrawable_rewards<Q: CustomQuery>(
     deps: Deps<Q>,
     owner: &Addr,
     distribution: &Distribution,
     adjustment: &WithdrawAdjustment,
-) -> StdResult<Coin> {
-    let ppw: u128 = distribution.shares_per_point.into();
-    let points: u128 = members()
-        .may_load(deps.storage, owner)?
-        .unwrap_or_default()
-        .points
-        .into();
-    let correction: i128 = adjustment.shares_correction.into();
-    let withdrawn: u128 = adjustment.withdrawn_rewards.into();
-    let points = (ppw * points) as i128;
-    let points = points + correction;
-    let amount = points as u128 >> SHARES_SHIFT;
-    let amount = amount - withdrawn;
+) -> Result<Coin, ContractError> {
+    let points = member_reward_points(deps.storage, owner)?;
+    let amount = shares_rewards(distribution.shares_per_point, points, adjustment)?;
+    let tagged: Uint128 = tagged_rewards(deps.storage, owner, &distribution.denom, points)?
+        .into_iter()
+        .map(|(_, _, amount)| amount)
+        .sum();
+
+    Ok(coin((amount + tagged).u128(), &distribution.denom))
+}
+
+/// Rewards of `owner` in `denom` distributed to its tags, `points` being its current reward
+/// points. Tags removed from `owner` are included, as rewards earned while tagged are kept.
+/// Returns the tag, the withdraw adjustment and the reward for every tag with non-zero reward.
+fn tagged_rewards(
+    storage: &dyn Storage,
+    owner: &Addr,
+    denom: &str,
+    points: u128,
+) -> Result<Vec<(String, WithdrawAdjustment, Uint128)>, ContractError> {
+    let current = member_tags(storage, owner)?;
+
+    let mut tags = TAG_WITHDRAW_ADJUSTMENT
+        .sub_prefix(owner)
+        .keys(storage, None, None, Order::Ascending)
+        .filter(|key| !matches!(key, Ok((_, key_denom)) if key_denom != denom))
+        .map(|key| key.map(|(tag, _)| tag))
+        .collect::<StdResult<Vec<_>>>()?;
+    tags.extend(current.iter().cloned());
+    tags.sort();
+    tags.dedup();
+
+    let mut rewards = vec![];
+    for tag in tags {
+        let distribution = match TAG_DISTRIBUTION.may_load(storage, (&tag, denom))? {
+            Some(distribution) => distribution,
+            None => continue,
+        };
+        let adjustment = TAG_WITHDRAW_ADJUSTMENT
+            .may_load(storage, (owner, &tag, denom))?
+            .unwrap_or_default();
+        let points = if current.contains(&tag) { points } else { 0 };
+        let amount = shares_rewards(distribution.shares_per_point, points, &adjustment)?;
+        if !amount.is_zero() {
+            rewards.push((tag, adjustment, amount));
+        }
+    }
 
-    Ok(coin(amount, &distribution.denom))
+    Ok(rewards)
 }
 
 pub fn sudo_add_member<Q: CustomQuery>(
@@ -527,7 +1504,7 @@ pub fn sudo_add_member<Q: CustomQuery>(
         .add_attribute("points", add.points.to_string());
 
     // make the local update
-    let diff = update_members(deps.branch(), env.block.height, vec![add], vec![])?;
+    let (diff, _) = update_members(deps.branch(), env.block.height, vec![add], vec![])?;
     // call all registered hooks
     res.messages = HOOKS.prepare_hooks(deps.storage, |h| {
         diff.clone().into_cosmos_msg(h).map(SubMsg::new)
@@ -536,22 +1513,26 @@ pub fn sudo_add_member<Q: CustomQuery>(
 }
 
 // the logic from execute_update_members extracted for easier import
+// Returns the hook message, and messages paying out rewards of removed members
 pub fn update_members<Q: CustomQuery>(
     mut deps: DepsMut<Q>,
     height: u64,
     to_add: Vec<Member>,
     to_remove: Vec<String>,
-) -> Result<MemberChangedHookMsg, ContractError> {
+) -> Result<(MemberChangedHookMsg, Vec<SubMsg>), ContractError> {
     let mut total = TOTAL.load(deps.storage)?;
+    let mut count = MEMBER_COUNT.load(deps.storage)?;
     let mut diffs: Vec<MemberDiff> = vec![];
+    let mut payouts = vec![];
 
-    let ppw: u128 = DISTRIBUTION.load(deps.storage)?.shares_per_point.into();
+    let mut distributions = load_distributions(deps.storage)?;
 
     // add all new members and update total
     for add in to_add.into_iter() {
         let add_addr = deps.api.addr_validate(&add.addr)?;
 
-        let mut diff = 0;
+        let mut old_points = 0;
+        let new_points = add.points;
         let mut insert_funds = false;
         members().update(deps.storage, &add_addr, height, |old| -> StdResult<_> {
             diffs.push(MemberDiff::new(
@@ -560,13 +1541,22 @@ pub fn update_members<Q: CustomQuery>(
                 Some(add.points),
             ));
             insert_funds = old.is_none();
+            if old.is_none() {
+                count += 1;
+            }
             let old = old.unwrap_or_default();
             total -= old.points;
             total += add.points;
-            diff = add.points as i128 - old.points as i128;
+            old_points = old.points;
             Ok(MemberInfo::new(add.points))
         })?;
-        apply_points_correction(deps.branch(), &add_addr, ppw, diff)?;
+        apply_points_correction(
+            deps.branch(),
+            &add_addr,
+            &distributions,
+            old_points,
+            new_points,
+        )?;
     }
 
     for remove in to_remove.into_iter() {
@@ -576,38 +1566,180 @@ pub fn update_members<Q: CustomQuery>(
         if let Some(MemberInfo { points, .. }) = old {
             diffs.push(MemberDiff::new(remove, Some(points), None));
             total -= points;
+            count -= 1;
             members().remove(deps.storage, &remove_addr, height)?;
-            apply_points_correction(deps.branch(), &remove_addr, ppw, -(points as i128))?;
+            apply_points_correction(deps.branch(), &remove_addr, &distributions, points, 0)?;
+            let rewards = settle_removed_rewards(deps.storage, &remove_addr, &mut distributions)?;
+            if !rewards.is_empty() {
+                payouts.push(SubMsg::new(BankMsg::Send {
+                    to_address: remove_addr.to_string(),
+                    amount: rewards,
+                }));
+            }
         }
     }
 
     TOTAL.save(deps.storage, &total)?;
-    Ok(MemberChangedHookMsg { diffs })
+    MEMBER_COUNT.save(deps.storage, &count)?;
+    Ok((MemberChangedHookMsg { diffs }, payouts))
+}
+
+/// Pays out rewards not yet withdrawn by removed member `addr`, including rewards distributed
+/// to its tags, so nothing is left assigned to an address which is not a member anymore.
+/// Adjustments are reset to account only withdrawn rewards, and remainders of shares not worth
+/// a full token are returned to distributions as leftover. Has to be called after points of
+/// `addr` are corrected to zero. `distributions` are updated in place, as they are reused on
+/// bulk updates. Returns rewards to be sent to `addr`.
+fn settle_removed_rewards(
+    storage: &mut dyn Storage,
+    addr: &Addr,
+    distributions: &mut [Distribution],
+) -> Result<Vec<Coin>, ContractError> {
+    let mut rewards = vec![];
+    for distribution in distributions.iter_mut() {
+        let key = (addr, distribution.denom.as_str());
+        let mut adjustment = WITHDRAW_ADJUSTMENT
+            .may_load(storage, key)?
+            .unwrap_or_default();
+        fold_tagged_rewards(storage, addr, &distribution.denom, &mut adjustment)?;
+        let (reward, remainder) = settle_adjustment(&mut adjustment);
+        save_adjustment(&WITHDRAW_ADJUSTMENT, storage, key, &adjustment)?;
+
+        distribution.shares_leftover += remainder;
+        distribution.withdrawable_total -= reward;
+        DISTRIBUTION.save(storage, &distribution.denom, distribution)?;
+
+        if !reward.is_zero() {
+            rewards.push(coin(reward.u128(), &distribution.denom));
+        }
+    }
+
+    // Tagged rewards are already folded into regular ones, only remainders are left
+    let tagged = TAG_WITHDRAW_ADJUSTMENT
+        .sub_prefix(addr)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for ((tag, denom), mut adjustment) in tagged {
+        let (_, remainder) = settle_adjustment(&mut adjustment);
+        save_adjustment(
+            &TAG_WITHDRAW_ADJUSTMENT,
+            storage,
+            (addr, &tag, &denom),
+            &adjustment,
+        )?;
+        TAG_DISTRIBUTION.update(storage, (&tag, &denom), |tagged| -> StdResult<_> {
+            let mut tagged = tagged.unwrap_or_default();
+            tagged.shares_leftover += remainder;
+            Ok(tagged)
+        })?;
+    }
+
+    Ok(rewards)
 }
 
-/// Applies points correction for given address.
-/// `shares_per_point` is current value from `SHARES_PER_POINT` - not loaded in function, to
+/// Marks all rewards of `adjustment` of an address with no points as withdrawn, and drops the
+/// shares not worth a full token from the correction. Returns rewards which were withdrawable,
+/// and the dropped shares.
+fn settle_adjustment(adjustment: &mut WithdrawAdjustment) -> (Uint128, u64) {
+    let shares = i128::from(adjustment.shares_correction) as u128;
+    let total = shares >> SHARES_SHIFT;
+    let reward = total - adjustment.withdrawn_rewards.u128();
+    adjustment.withdrawn_rewards = Uint128::new(total);
+    adjustment.shares_correction = ((total << SHARES_SHIFT) as i128).into();
+    (
+        Uint128::new(reward),
+        (shares - (total << SHARES_SHIFT)) as u64,
+    )
+}
+
+/// Saves `adjustment`, or removes it if it doesn't adjust anything
+fn save_adjustment<'a, K: PrimaryKey<'a>>(
+    map: &Map<'a, K, WithdrawAdjustment>,
+    storage: &mut dyn Storage,
+    key: K,
+    adjustment: &WithdrawAdjustment,
+) -> StdResult<()> {
+    if *adjustment == WithdrawAdjustment::default() {
+        map.remove(storage, key);
+        Ok(())
+    } else {
+        map.save(storage, key, adjustment)
+    }
+}
+
+/// Applies points correction for given address, for every distributed denom.
+/// `distributions` are current values from `DISTRIBUTION` - not loaded in function, to
 /// avoid multiple queries on bulk updates.
-/// `diff` is the points change
+/// `old_points` and `new_points` are member points before and after the change, they are
+/// weighted by the member reward multiplier.
 pub fn apply_points_correction<Q: CustomQuery>(
     deps: DepsMut<Q>,
     addr: &Addr,
-    shares_per_point: u128,
+    distributions: &[Distribution],
+    old_points: u64,
+    new_points: u64,
+) -> StdResult<()> {
+    let multiplier = reward_multiplier(deps.storage, addr)?;
+    let diff = reward_points(new_points, multiplier) as i128
+        - reward_points(old_points, multiplier) as i128;
+    apply_shares_correction(deps.storage, addr, distributions, diff)
+}
+
+/// Applies correction of `diff` reward points for given address, for every distributed denom,
+/// and updates `REWARD_TOTAL` accordingly.
+fn apply_shares_correction(
+    storage: &mut dyn Storage,
+    addr: &Addr,
+    distributions: &[Distribution],
     diff: i128,
 ) -> StdResult<()> {
-    WITHDRAW_ADJUSTMENT.update(deps.storage, addr, |old| -> StdResult<_> {
-        let mut old = old.unwrap_or_else(|| {
-            // This should never happen, but better this than panic
-            WithdrawAdjustment {
-                shares_correction: 0.into(),
-                withdrawn_rewards: Uint128::zero(),
-                delegated: addr.clone(),
-            }
-        });
-        let shares_correction: i128 = old.shares_correction.into();
-        old.shares_correction = (shares_correction - shares_per_point as i128 * diff).into();
-        Ok(old)
+    REWARD_TOTAL.update(storage, |total| -> StdResult<_> {
+        Ok((total as i128 + diff) as _)
     })?;
+    for distribution in distributions {
+        let shares_per_point: u128 = distribution.shares_per_point.into();
+        WITHDRAW_ADJUSTMENT.update(
+            storage,
+            (addr, &distribution.denom),
+            |old| -> StdResult<_> {
+                let mut old = old.unwrap_or_default();
+                let shares_correction: i128 = old.shares_correction.into();
+                old.shares_correction =
+                    (shares_correction - shares_per_point as i128 * diff).into();
+                Ok(old)
+            },
+        )?;
+    }
+    for tag in member_tags(storage, addr)? {
+        apply_tag_correction(storage, addr, &tag, diff)?;
+    }
+    Ok(())
+}
+
+/// Applies correction of `diff` reward points for given address, for every denom distributed to
+/// `tag`, and updates `TAG_TOTAL` accordingly.
+fn apply_tag_correction(
+    storage: &mut dyn Storage,
+    addr: &Addr,
+    tag: &str,
+    diff: i128,
+) -> StdResult<()> {
+    TAG_TOTAL.update(storage, tag, |total| -> StdResult<_> {
+        Ok((total.unwrap_or_default() as i128 + diff) as _)
+    })?;
+    let distributions = TAG_DISTRIBUTION
+        .prefix(tag)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (denom, distribution) in distributions {
+        let shares_per_point: u128 = distribution.shares_per_point.into();
+        TAG_WITHDRAW_ADJUSTMENT.update(storage, (addr, tag, &denom), |old| -> StdResult<_> {
+            let mut old = old.unwrap_or_default();
+            let shares_correction: i128 = old.shares_correction.into();
+            old.shares_correction = (shares_correction - shares_per_point as i128 * diff).into();
+            Ok(old)
+        })?;
+    }
     Ok(())
 }
 
@@ -622,7 +1754,9 @@ pub fn sudo(deps: DepsMut<TgradeQuery>, env: Env, msg: SudoMsg) -> Result<Respon
 }
 
 fn privilege_promote<Q: CustomQuery>(deps: DepsMut<Q>) -> Result<Response, ContractError> {
-    if HALFLIFE.load(deps.storage)?.halflife.is_some() {
+    if HALFLIFE.load(deps.storage)?.decay().is_some()
+        || AUTO_DISTRIBUTE.may_load(deps.storage)?.unwrap_or_default()
+    {
         let msgs = request_privileges(&[Privilege::EndBlocker]);
         Ok(Response::new().add_submessages(msgs))
     } else {
@@ -630,22 +1764,66 @@ fn privilege_promote<Q: CustomQuery>(deps: DepsMut<Q>) -> Result<Response, Contr
     }
 }
 
-fn points_reduction(points: u64) -> u64 {
-    points - (points / 2)
+/// Points reduction of a member for single halflife. Never reduces points below `floor`, which
+/// has to be lower than `points`.
+fn points_reduction(points: u64, floor: u64) -> u64 {
+    points - std::cmp::max(points / 2, floor)
+}
+
+/// Points reduction of a member for linear decay applied `periods` times. Never reduces points
+/// below `floor`, which has to be lower than `points`.
+fn linear_points_reduction(points: u64, amount: u64, periods: u64, floor: u64) -> u64 {
+    std::cmp::min(points - floor, amount.saturating_mul(periods))
 }
 
 fn end_block<Q: CustomQuery>(mut deps: DepsMut<Q>, env: Env) -> Result<Response, ContractError> {
-    let resp = Response::new();
+    let mut resp = Response::new();
+
+    // Rewards are distributed before halflife, so they are split according to points members
+    // had while rewards were arriving
+    if AUTO_DISTRIBUTE.may_load(deps.storage)?.unwrap_or_default() {
+        let total = REWARD_TOTAL.load(deps.storage)?;
+        // There is noone to distribute to, keep rewards for later
+        if total > 0 {
+            let denoms = DISTRIBUTION
+                .keys(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?;
+            if let Some(distributed) = distribute(deps.branch(), &env, denoms, None, total, None)? {
+                let evt = Event::new("distribute_rewards").add_attributes(distributed.attributes);
+                resp = resp.add_event(evt).add_submessages(distributed.messages);
+            }
+        }
+    }
 
     // If duration of half life added to timestamp of last applied
     // if lesser then current timestamp, do nothing
-    if !HALFLIFE.load(deps.storage)?.should_apply(env.block.time) {
-        return Ok(resp);
-    }
+    let halflife = HALFLIFE.load(deps.storage)?;
+    let decay = match halflife.decay() {
+        Some(decay) if halflife.should_apply(env.block.time) => decay,
+        _ => return Ok(resp),
+    };
 
-    let ppw: u128 = DISTRIBUTION.load(deps.storage)?.shares_per_point.into();
+    // Linear decay is applied once per every full interval elapsed, and only those intervals
+    // are accounted as applied
+    let (periods, last_applied) = match decay {
+        DecayMode::Halflife(_) => (1, env.block.time),
+        DecayMode::Linear { interval, .. } => {
+            let periods =
+                (env.block.time.seconds() - halflife.last_applied.seconds()) / interval.seconds();
+            (
+                periods,
+                halflife
+                    .last_applied
+                    .plus_seconds(periods * interval.seconds()),
+            )
+        }
+    };
+
+    let distributions = load_distributions(deps.storage)?;
+    let floor = halflife_floor(deps.storage)?;
 
     let mut reduction = 0;
+    let mut diffs = vec![];
 
     let members_to_update: Vec<_> = members()
         .range(deps.storage, None, None, Order::Ascending)
@@ -658,7 +1836,7 @@ fn end_block<Q: CustomQuery>(mut deps: DepsMut<Q>, env: Env) -> Result<Response,
                         start_height,
                     },
                 ) = item?;
-                if points <= 1 {
+                if points <= floor {
                     return Ok(None);
                 }
                 Ok(Some(Member {
@@ -672,8 +1850,18 @@ fn end_block<Q: CustomQuery>(mut deps: DepsMut<Q>, env: Env) -> Result<Response,
         .collect::<StdResult<_>>()?;
 
     for member in members_to_update {
-        let diff = points_reduction(member.points);
+        let diff = match decay {
+            DecayMode::Halflife(_) => points_reduction(member.points, floor),
+            DecayMode::Linear { amount, .. } => {
+                linear_points_reduction(member.points, amount, periods, floor)
+            }
+        };
         reduction += diff;
+        diffs.push(MemberDiff::new(
+            member.addr.clone(),
+            Some(member.points),
+            Some(member.points - diff),
+        ));
         let addr = Addr::unchecked(member.addr);
         members().replace(
             deps.storage,
@@ -682,69 +1870,263 @@ fn end_block<Q: CustomQuery>(mut deps: DepsMut<Q>, env: Env) -> Result<Response,
             Some(&MemberInfo::new(member.points)),
             env.block.height,
         )?;
-        apply_points_correction(deps.branch(), &addr, ppw, -(diff as i128))?;
+        apply_points_correction(
+            deps.branch(),
+            &addr,
+            &distributions,
+            member.points,
+            member.points - diff,
+        )?;
     }
 
     // We need to update half life's last applied timestamp to current one
-    HALFLIFE.update(deps.storage, |hf| -> StdResult<_> {
-        Ok(Halflife {
-            halflife: hf.halflife,
-            last_applied: env.block.time,
-        })
-    })?;
+    HALFLIFE.save(
+        deps.storage,
+        &Halflife {
+            last_applied,
+            ..halflife
+        },
+    )?;
 
     let mut total = TOTAL.load(deps.storage)?;
     total -= reduction;
     TOTAL.save(deps.storage, &total)?;
 
-    let evt = Event::new("halflife")
+    let evt = match decay {
+        DecayMode::Halflife(_) => Event::new("halflife"),
+        DecayMode::Linear { .. } => {
+            Event::new("linear_decay").add_attribute("periods", periods.to_string())
+        }
+    };
+    let evt = evt
         .add_attribute("height", env.block.height.to_string())
         .add_attribute("reduction", reduction.to_string());
-    let resp = resp.add_event(evt);
+    let mut resp = resp.add_event(evt);
+
+    // All reductions are reported to every hook at once. Failing hooks must not block the decay,
+    // so their errors are caught and only logged
+    if !diffs.is_empty() {
+        let diff = MemberChangedHookMsg { diffs };
+        let hooks = HOOKS.prepare_hooks(deps.storage, |h| {
+            diff.clone()
+                .into_cosmos_msg(h)
+                .map(|msg| SubMsg::reply_on_error(msg, DECAY_HOOK_REPLY_ID))
+        })?;
+        resp = resp.add_submessages(hooks);
+    }
 
     Ok(resp)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps<TgradeQuery>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps<TgradeQuery>, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     use QueryMsg::*;
     match msg {
         Member {
             addr,
             at_height: height,
-        } => to_binary(&query_member(deps, addr, height)?),
-        ListMembers { start_after, limit } => to_binary(&list_members(deps, start_after, limit)?),
-        ListMembersByPoints { start_after, limit } => {
-            to_binary(&list_members_by_points(deps, start_after, limit)?)
+        } => Ok(to_binary(&query_member(deps, addr, height)?)?),
+        ListMembers { start_after, limit } => {
+            Ok(to_binary(&list_members(deps, start_after, limit)?)?)
         }
-        TotalPoints {} => to_binary(&query_total_points(deps)?),
-        Admin {} => to_binary(&ADMIN.query_admin(deps)?),
+        ListMembersByPoints { start_after, limit } => Ok(to_binary(&list_members_by_points(
+            deps,
+            start_after,
+            limit,
+        )?)?),
+        ListMembersInRange {
+            min_points,
+            max_points,
+            start_after,
+            limit,
+        } => Ok(to_binary(&list_members_in_range(
+            deps,
+            min_points,
+            max_points,
+            start_after,
+            limit,
+        )?)?),
+        TotalPoints {} => Ok(to_binary(&query_total_points(deps)?)?),
+        Admin {} => Ok(to_binary(&ADMIN.query_admin(deps)?)?),
         Hooks {} => {
             let hooks = HOOKS.list_hooks(deps.storage)?;
-            to_binary(&HooksResponse { hooks })
+            Ok(to_binary(&HooksResponse { hooks })?)
         }
         Preauths {} => {
             let preauths = PREAUTH_HOOKS.get_auth(deps.storage)?;
-            to_binary(&PreauthResponse { preauths })
+            Ok(to_binary(&PreauthResponse { preauths })?)
+        }
+        WithdrawableRewards { owner } => Ok(to_binary(&query_withdrawable_rewards(deps, owner)?)?),
+        WithdrawableRewardsAll { owner } => {
+            Ok(to_binary(&query_withdrawable_rewards_all(deps, owner)?)?)
+        }
+        WithdrawableRewardsForTag { owner, tag } => Ok(to_binary(
+            &query_withdrawable_rewards_for_tag(deps, owner, tag)?,
+        )?),
+        ListWithdrawableRewards { start_after, limit } => Ok(to_binary(
+            &list_withdrawable_rewards(deps, start_after, limit)?,
+        )?),
+        DistributedRewards {} => Ok(to_binary(&query_distributed_rewards(deps)?)?),
+        UndistributedRewards {} => Ok(to_binary(&query_undistributed_rewards(deps, env)?)?),
+        Delegated { owner } => Ok(to_binary(&query_delegated(deps, owner)?)?),
+        DelegationsTo {
+            delegate,
+            start_after,
+            limit,
+        } => Ok(to_binary(&query_delegations_to(
+            deps,
+            delegate,
+            start_after,
+            limit,
+        )?)?),
+        Halflife {} => Ok(to_binary(&query_halflife(deps)?)?),
+        Decay {} => Ok(to_binary(&query_decay(deps)?)?),
+        ProjectedMember {
+            addr,
+            after_halflives,
+        } => Ok(to_binary(&query_projected_member(
+            deps,
+            addr,
+            after_halflives,
+        )?)?),
+        ProjectedPoints { addr, at_time } => {
+            Ok(to_binary(&query_projected_points(deps, addr, at_time)?)?)
+        }
+        SurvivingTotalPoints { halflives } => {
+            Ok(to_binary(&query_surviving_total_points(deps, halflives)?)?)
+        }
+        MemberCount {} => Ok(to_binary(&MEMBER_COUNT.load(deps.storage)?)?),
+        IsMember { addr } => {
+            let addr = deps.api.addr_validate(&addr)?;
+            Ok(to_binary(
+                &members().may_load(deps.storage, &addr)?.is_some(),
+            )?)
         }
-        WithdrawableRewards { owner } => to_binary(&query_withdrawable_rewards(deps, owner)?),
-        DistributedRewards {} => to_binary(&query_distributed_rewards(deps)?),
-        UndistributedRewards {} => to_binary(&query_undistributed_rewards(deps, env)?),
-        Delegated { owner } => to_binary(&query_delegated(deps, owner)?),
-        Halflife {} => to_binary(&query_halflife(deps)?),
         IsSlasher { addr } => {
             let addr = deps.api.addr_validate(&addr)?;
-            to_binary(&SLASHERS.is_slasher(deps.storage, &addr)?)
+            Ok(to_binary(&SLASHERS.is_slasher(deps.storage, &addr)?)?)
+        }
+        ListSlashers {} => Ok(to_binary(&SLASHERS.list_slashers(deps.storage)?)?),
+        DistributionData { denom } => {
+            let denom = denom.map_or_else(|| DENOM.load(deps.storage), Ok)?;
+            Ok(to_binary(&DISTRIBUTION.may_load(deps.storage, &denom)?)?)
+        }
+        SimulateDistribution { denom } => {
+            Ok(to_binary(&query_simulate_distribution(deps, env, denom)?)?)
+        }
+        DistributionParams {} => Ok(to_binary(&query_distribution_params(deps)?)?),
+        SimulateSlash { addr, portion } => {
+            Ok(to_binary(&query_simulate_slash(deps, addr, portion)?)?)
+        }
+        WithdrawAdjustmentData { addr, denom } => {
+            let addr = deps.api.addr_validate(&addr)?;
+            let denom = denom.map_or_else(|| DENOM.load(deps.storage), Ok)?;
+            Ok(to_binary(
+                &WITHDRAW_ADJUSTMENT.may_load(deps.storage, (&addr, &denom))?,
+            )?)
         }
-        ListSlashers {} => to_binary(&SLASHERS.list_slashers(deps.storage)?),
-        DistributionData {} => to_binary(&DISTRIBUTION.may_load(deps.storage)?),
-        WithdrawAdjustmentData { addr } => {
+        RewardMultiplier { addr } => {
             let addr = deps.api.addr_validate(&addr)?;
-            to_binary(&WITHDRAW_ADJUSTMENT.may_load(deps.storage, &addr)?)
+            let multiplier = reward_multiplier(deps.storage, &addr)?;
+            Ok(to_binary(&RewardMultiplierResponse { multiplier })?)
         }
+        Invariants {} => Ok(to_binary(&query_invariants(deps)?)?),
     }
 }
 
+fn query_invariants<Q: CustomQuery>(deps: Deps<Q>) -> Result<InvariantsResponse, ContractError> {
+    let members = members()
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (addr, mi) = item?;
+            let multiplier = reward_multiplier(deps.storage, &addr)?;
+            Ok((addr, mi.points, reward_points(mi.points, multiplier)))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let points_sum: u64 = members.iter().map(|(_, points, _)| points).sum();
+    let reward_points_sum: u128 = members.iter().map(|(_, _, points)| points).sum();
+
+    let tags = members
+        .iter()
+        .map(|(addr, _, _)| member_tags(deps.storage, addr))
+        .collect::<StdResult<Vec<_>>>()?;
+    let tag_points_sum = |tag: &str| -> u128 {
+        members
+            .iter()
+            .zip(&tags)
+            .filter(|(_, tags)| tags.iter().any(|t| t == tag))
+            .map(|((_, _, points), _)| points)
+            .sum()
+    };
+
+    let mut distributed_total_matches_withdrawals = true;
+    let mut shares_corrections_consistent =
+        REWARD_TOTAL.may_load(deps.storage)?.unwrap_or_default() == reward_points_sum;
+    for item in TAG_TOTAL.range(deps.storage, None, None, Order::Ascending) {
+        let (tag, total) = item?;
+        shares_corrections_consistent &= total == tag_points_sum(&tag);
+    }
+
+    for distribution in load_distributions(deps.storage)? {
+        let shares_per_point: u128 = distribution.shares_per_point.into();
+
+        let mut withdrawn = Uint128::zero();
+        let mut corrections = 0i128;
+        for item in WITHDRAW_ADJUSTMENT.range(deps.storage, None, None, Order::Ascending) {
+            let ((_, denom), adjustment) = item?;
+            if denom == distribution.denom {
+                withdrawn += adjustment.withdrawn_rewards;
+                corrections += i128::from(adjustment.shares_correction);
+            }
+        }
+
+        let mut withdrawable = Uint128::zero();
+        let mut shares = corrections;
+        for (addr, _, points) in &members {
+            let adjustment = WITHDRAW_ADJUSTMENT
+                .may_load(deps.storage, (addr, &distribution.denom))?
+                .unwrap_or_default();
+            withdrawable += withdrawable_rewards(deps, addr, &distribution, &adjustment)?.amount;
+            shares += (shares_per_point * points) as i128;
+        }
+
+        // Tagged rewards are moved to regular adjustments on withdrawal, and marked as withdrawn
+        // in tagged adjustments
+        let mut tags_leftover = 0i128;
+        for item in TAG_DISTRIBUTION.range(deps.storage, None, None, Order::Ascending) {
+            let ((tag, denom), tagged) = item?;
+            if denom == distribution.denom {
+                let shares_per_point: u128 = tagged.shares_per_point.into();
+                shares += (shares_per_point * tag_points_sum(&tag)) as i128;
+                tags_leftover += tagged.shares_leftover as i128;
+            }
+        }
+        for item in TAG_WITHDRAW_ADJUSTMENT.range(deps.storage, None, None, Order::Ascending) {
+            let ((_, _, denom), adjustment) = item?;
+            if denom == distribution.denom {
+                shares += i128::from(adjustment.shares_correction);
+                shares -= (adjustment.withdrawn_rewards.u128() << SHARES_SHIFT) as i128;
+            }
+        }
+
+        distributed_total_matches_withdrawals &= distribution.distributed_total
+            == distribution.withdrawable_total + withdrawn
+            && withdrawable <= distribution.withdrawable_total;
+
+        let distributed_shares = (distribution.distributed_total.u128() << SHARES_SHIFT) as i128
+            - distribution.shares_leftover as i128
+            - tags_leftover;
+        shares_corrections_consistent &= shares == distributed_shares;
+    }
+
+    Ok(InvariantsResponse {
+        total_matches_sum_of_points: TOTAL.load(deps.storage)? == points_sum,
+        distributed_total_matches_withdrawals,
+        shares_corrections_consistent,
+    })
+}
+
 fn query_total_points<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<TotalPointsResponse> {
     let points = TOTAL.load(deps.storage)?;
     Ok(TotalPointsResponse { points })
@@ -766,28 +2148,68 @@ fn query_member<Q: CustomQuery>(
 pub fn query_withdrawable_rewards<Q: CustomQuery>(
     deps: Deps<Q>,
     owner: String,
-) -> StdResult<RewardsResponse> {
+) -> Result<RewardsResponse, ContractError> {
     // Not checking address, as if it is invalid it is guaranteed not to appear in maps, so
     // `withdrawable_rewards` would return error itself.
     let owner = Addr::unchecked(&owner);
-    let distribution = DISTRIBUTION.load(deps.storage)?;
-    let adjustment = if let Some(adj) = WITHDRAW_ADJUSTMENT.may_load(deps.storage, &owner)? {
-        adj
-    } else {
-        return Ok(RewardsResponse {
-            rewards: coin(0, distribution.denom),
-        });
-    };
+    let denom = DENOM.load(deps.storage)?;
+    let distribution = DISTRIBUTION.load(deps.storage, &denom)?;
+    let adjustment = WITHDRAW_ADJUSTMENT
+        .may_load(deps.storage, (&owner, &denom))?
+        .unwrap_or_default();
 
     let rewards = withdrawable_rewards(deps, &owner, &distribution, &adjustment)?;
     Ok(RewardsResponse { rewards })
 }
 
+pub fn query_withdrawable_rewards_all<Q: CustomQuery>(
+    deps: Deps<Q>,
+    owner: String,
+) -> Result<Vec<Coin>, ContractError> {
+    let owner = Addr::unchecked(&owner);
+    load_distributions(deps.storage)?
+        .iter()
+        .map(|distribution| {
+            let adjustment = WITHDRAW_ADJUSTMENT
+                .may_load(deps.storage, (&owner, &distribution.denom))?
+                .unwrap_or_default();
+            withdrawable_rewards(deps, &owner, distribution, &adjustment)
+        })
+        .collect()
+}
+
+pub fn query_withdrawable_rewards_for_tag<Q: CustomQuery>(
+    deps: Deps<Q>,
+    owner: String,
+    tag: String,
+) -> Result<Vec<Coin>, ContractError> {
+    let owner = Addr::unchecked(&owner);
+    // Removed tags are not accounted with points anymore, only with the correction
+    let points = if member_tags(deps.storage, &owner)?.contains(&tag) {
+        member_reward_points(deps.storage, &owner)?
+    } else {
+        0
+    };
+
+    TAG_DISTRIBUTION
+        .prefix(&tag)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, distribution) = item?;
+            let adjustment = TAG_WITHDRAW_ADJUSTMENT
+                .may_load(deps.storage, (&owner, &tag, &denom))?
+                .unwrap_or_default();
+            let amount = shares_rewards(distribution.shares_per_point, points, &adjustment)?;
+            Ok(coin(amount.u128(), denom))
+        })
+        .collect()
+}
+
 pub fn query_undistributed_rewards<Q: CustomQuery>(
     deps: Deps<Q>,
     env: Env,
 ) -> StdResult<RewardsResponse> {
-    let distribution = DISTRIBUTION.load(deps.storage)?;
+    let distribution = DISTRIBUTION.load(deps.storage, &DENOM.load(deps.storage)?)?;
     let balance = deps
         .querier
         .query_balance(env.contract.address, distribution.denom.clone())?
@@ -801,8 +2223,93 @@ pub fn query_undistributed_rewards<Q: CustomQuery>(
     })
 }
 
+/// Replicates `execute_slash` arithmetic without touching the state
+pub fn query_simulate_slash<Q: CustomQuery>(
+    deps: Deps<Q>,
+    addr: String,
+    portion: Decimal,
+) -> StdResult<SimulateSlashResponse> {
+    validate_portion(portion).map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    let total = TOTAL.load(deps.storage)?;
+    let addr = Addr::unchecked(&addr);
+    let old_points = match members().may_load(deps.storage, &addr)? {
+        Some(member) => member.points,
+        None => {
+            return Ok(SimulateSlashResponse {
+                old_points: 0,
+                new_points: 0,
+                total_after: total,
+            })
+        }
+    };
+
+    let old = Uint128::new(old_points as _);
+    let slash = (old * portion).u128() as u64;
+
+    Ok(SimulateSlashResponse {
+        old_points,
+        new_points: old_points - slash,
+        total_after: total - slash,
+    })
+}
+
+pub fn query_simulate_distribution<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: Env,
+    denom: Option<String>,
+) -> Result<SimulateDistributionResponse, ContractError> {
+    let total = REWARD_TOTAL.load(deps.storage)?;
+    let denom = denom.map_or_else(|| DENOM.load(deps.storage), Ok)?;
+    let distribution = DISTRIBUTION
+        .may_load(deps.storage, &denom)?
+        .unwrap_or_else(|| Distribution::new(&denom));
+
+    // Nothing would be distributed if there are no members
+    if total == 0 {
+        return Ok(SimulateDistributionResponse {
+            points_per_share: Uint128::zero(),
+            amount: Uint128::zero(),
+            leftover: 0,
+        });
+    }
+
+    let balance = deps
+        .querier
+        .query_balance(env.contract.address, &denom)?
+        .amount;
+    let fee = DISTRIBUTION_FEE.may_load(deps.storage)?.unwrap_or_default();
+    let split = split_distribution(
+        &distribution,
+        distribution.shares_leftover,
+        balance.u128(),
+        total,
+        fee,
+    )?;
+
+    Ok(SimulateDistributionResponse {
+        points_per_share: split.points_per_share.into(),
+        amount: split.amount.into(),
+        leftover: split.leftover,
+    })
+}
+
+pub fn query_distribution_params<Q: CustomQuery>(
+    deps: Deps<Q>,
+) -> StdResult<DistributionParamsResponse> {
+    let shares_per_point = DISTRIBUTION
+        .may_load(deps.storage, &DENOM.load(deps.storage)?)?
+        .map(|distribution| distribution.shares_per_point)
+        .unwrap_or_default();
+
+    Ok(DistributionParamsResponse {
+        shares_shift: SHARES_SHIFT,
+        shares_per_point,
+    })
+}
+
 pub fn query_distributed_rewards<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<RewardsResponse> {
-    let distribution = DISTRIBUTION.load(deps.storage)?;
+    let distribution = DISTRIBUTION.load(deps.storage, &DENOM.load(deps.storage)?)?;
     Ok(RewardsResponse {
         rewards: coin(distribution.distributed_total.into(), &distribution.denom),
     })
@@ -814,17 +2321,150 @@ pub fn query_delegated<Q: CustomQuery>(
 ) -> StdResult<DelegatedResponse> {
     let owner = deps.api.addr_validate(&owner)?;
 
-    let delegated = WITHDRAW_ADJUSTMENT
-        .may_load(deps.storage, &owner)?
-        .map_or(owner, |data| data.delegated);
+    let delegated = DELEGATED.may_load(deps.storage, &owner)?.unwrap_or(owner);
 
     Ok(DelegatedResponse { delegated })
 }
 
+pub fn query_delegations_to<Q: CustomQuery>(
+    deps: Deps<Q>,
+    delegate: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<DelegationsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let delegate = deps.api.addr_validate(&delegate)?;
+    let addr = maybe_addr(deps.api, start_after)?;
+    let start = addr.as_ref().map(Bound::exclusive);
+
+    let owners = DELEGATED_BY
+        .prefix(&delegate)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+
+    Ok(DelegationsResponse { owners })
+}
+
+fn query_decay<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<DecayResponse> {
+    let halflife = HALFLIFE.load(deps.storage)?;
+    Ok(DecayResponse {
+        decay: halflife.decay(),
+        last_applied: halflife.last_applied,
+    })
+}
+
+fn query_projected_member<Q: CustomQuery>(
+    deps: Deps<Q>,
+    addr: String,
+    after_halflives: Option<u32>,
+) -> StdResult<ProjectedMemberResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let points = members().may_load(deps.storage, &addr)?.map(|mi| mi.points);
+    let halflife = HALFLIFE.load(deps.storage)?;
+    let decay = match halflife.decay() {
+        Some(decay) => decay,
+        None => {
+            return Ok(ProjectedMemberResponse {
+                points,
+                next_halflife: None,
+            })
+        }
+    };
+
+    let times = after_halflives.unwrap_or(1);
+    let floor = halflife_floor(deps.storage)?;
+    let points = points.map(|points| decayed_points(points, &decay, times.into(), floor));
+
+    Ok(ProjectedMemberResponse {
+        points,
+        next_halflife: Some(
+            halflife
+                .last_applied
+                .plus_seconds(decay.interval().seconds()),
+        ),
+    })
+}
+
+fn query_projected_points<Q: CustomQuery>(
+    deps: Deps<Q>,
+    addr: String,
+    at_time: Timestamp,
+) -> StdResult<ProjectedPointsResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let points = members().may_load(deps.storage, &addr)?.map(|mi| mi.points);
+    let halflife = HALFLIFE.load(deps.storage)?;
+
+    let decays = match halflife.decay() {
+        Some(decay) if at_time > halflife.last_applied => {
+            (at_time.seconds() - halflife.last_applied.seconds()) / decay.interval().seconds()
+        }
+        _ => 0,
+    };
+    let floor = halflife_floor(deps.storage)?;
+    let points = match halflife.decay() {
+        Some(decay) => points.map(|points| decayed_points(points, &decay, decays, floor)),
+        None => points,
+    };
+
+    Ok(ProjectedPointsResponse { points, decays })
+}
+
+fn query_surviving_total_points<Q: CustomQuery>(
+    deps: Deps<Q>,
+    halflives: u32,
+) -> StdResult<TotalPointsResponse> {
+    let decay = match HALFLIFE.load(deps.storage)?.decay() {
+        Some(decay) => decay,
+        None => return query_total_points(deps),
+    };
+
+    let floor = halflife_floor(deps.storage)?;
+    let points = members()
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (_, member) = item?;
+            Ok(decayed_points(
+                member.points,
+                &decay,
+                halflives.into(),
+                floor,
+            ))
+        })
+        .sum::<StdResult<u64>>()?;
+
+    Ok(TotalPointsResponse { points })
+}
+
+/// Points left after applying `decay` `times` in a row, never reducing below `floor`
+fn decayed_points(points: u64, decay: &DecayMode, times: u64, floor: u64) -> u64 {
+    // Members at the floor (or below) are not reduced anymore
+    if points <= floor {
+        return points;
+    }
+
+    match decay {
+        DecayMode::Halflife(_) => {
+            let mut points = points;
+            for _ in 0..times {
+                if points <= floor {
+                    break;
+                }
+                points -= points_reduction(points, floor);
+            }
+            points
+        }
+        DecayMode::Linear { amount, .. } => {
+            points - linear_points_reduction(points, *amount, times, floor)
+        }
+    }
+}
+
 fn query_halflife<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<HalflifeResponse> {
     let Halflife {
         halflife,
         last_applied: last_halflife,
+        ..
     } = HALFLIFE.load(deps.storage)?;
 
     Ok(HalflifeResponse {
@@ -875,6 +2515,33 @@ fn list_members<Q: CustomQuery>(
     Ok(MemberListResponse { members: members? })
 }
 
+fn list_withdrawable_rewards<Q: CustomQuery>(
+    deps: Deps<Q>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<(Addr, Coin)>, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let addr = maybe_addr(deps.api, start_after)?;
+    let start = addr.as_ref().map(Bound::exclusive);
+
+    let denom = DENOM.load(deps.storage)?;
+    let distribution = DISTRIBUTION.load(deps.storage, &denom)?;
+
+    members()
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|addr| {
+            let addr = addr?;
+            // Missing adjustment means no correction was ever needed
+            let adjustment = WITHDRAW_ADJUSTMENT
+                .may_load(deps.storage, (&addr, &denom))?
+                .unwrap_or_default();
+            let rewards = withdrawable_rewards(deps, &addr, &distribution, &adjustment)?;
+            Ok((addr, rewards))
+        })
+        .collect()
+}
+
 fn list_members_by_points<Q: CustomQuery>(
     deps: Deps<Q>,
     start_after: Option<Member>,
@@ -891,7 +2558,55 @@ fn list_members_by_points<Q: CustomQuery>(
     let members: StdResult<Vec<_>> = members()
         .idx
         .points
-        .range(deps.storage, None, start, Order::Descending)
+        .range(deps.storage, None, start, Order::Descending)
+        .take(limit)
+        .map(|item| {
+            let (
+                addr,
+                MemberInfo {
+                    points,
+                    start_height,
+                },
+            ) = item?;
+            Ok(Member {
+                addr: addr.into(),
+                points,
+                start_height,
+            })
+        })
+        .collect();
+
+    Ok(MemberListResponse { members: members? })
+}
+
+fn list_members_in_range<Q: CustomQuery>(
+    deps: Deps<Q>,
+    min_points: u64,
+    max_points: u64,
+    start_after: Option<Member>,
+    limit: Option<u32>,
+) -> StdResult<MemberListResponse> {
+    if min_points > max_points {
+        return Ok(MemberListResponse { members: vec![] });
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    // Empty address sorts before any other address with the same points
+    let min = Bound::inclusive((min_points, Addr::unchecked("")));
+    let max = match start_after {
+        Some(m) if m.points <= max_points => Some(Bound::exclusive((
+            m.points,
+            deps.api.addr_validate(&m.addr)?,
+        ))),
+        _ => max_points
+            .checked_add(1)
+            .map(|points| Bound::exclusive((points, Addr::unchecked("")))),
+    };
+
+    let members: StdResult<Vec<_>> = members()
+        .idx
+        .points
+        .range(deps.storage, Some(min), max, Order::Descending)
         .take(limit)
         .map(|item| {
             let (
@@ -919,6 +2634,9 @@ pub fn migrate(
     msg: MigrateMsg,
 ) -> Result<Response, ContractError> {
     ensure_from_older_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    if msg.halflife.is_some() && msg.decay.is_some() {
+        return Err(ContractError::ConflictingDecay {});
+    }
     if let Some(duration) = msg.halflife {
         // Update half life's duration
         // Zero duration means no / remove half life
@@ -929,10 +2647,50 @@ pub fn migrate(
                 } else {
                     None
                 },
+                linear: None,
                 last_applied: hf.last_applied,
             })
         })?;
     };
+    if let Some(decay) = msg.decay {
+        decay.validate()?;
+        HALFLIFE.update(deps.storage, |hf| -> StdResult<_> {
+            Ok(Halflife::new(Some(decay), hf.last_applied))
+        })?;
+    }
+    if let Some(max_denoms) = msg.max_denoms {
+        if max_denoms == 0 {
+            return Err(ContractError::InvalidMaxDenoms {});
+        }
+        MAX_DENOMS.save(deps.storage, &max_denoms)?;
+    }
+    if let Some(auto_distribute) = msg.auto_distribute {
+        AUTO_DISTRIBUTE.save(deps.storage, &auto_distribute)?;
+    }
+    if let Some(halflife_floor) = msg.halflife_floor {
+        HALFLIFE_FLOOR.save(deps.storage, &halflife_floor)?;
+    }
+    if msg.distribution_fee.is_some() || msg.fee_recipient.is_some() {
+        let fee = match msg.distribution_fee {
+            Some(fee) => fee,
+            None => DISTRIBUTION_FEE.may_load(deps.storage)?.unwrap_or_default(),
+        };
+        let recipient = match msg.fee_recipient {
+            Some(recipient) => Some(deps.api.addr_validate(&recipient)?),
+            None => match FEE_RECIPIENT.may_load(deps.storage)? {
+                Some(recipient) => Some(recipient),
+                None => ADMIN.get(deps.as_ref())?,
+            },
+        };
+        set_distribution_fee(deps.storage, fee, recipient)?;
+    }
+    migrate_single_denom_distribution(deps.storage)?;
+    if MAX_DENOMS.may_load(deps.storage)?.is_none() {
+        MAX_DENOMS.save(deps.storage, &default_max_denoms())?;
+    }
+    init_reward_total(deps.storage)?;
+    init_member_count(deps.storage)?;
+    init_delegated_by(deps.storage)?;
     Ok(Response::new())
 }
 
@@ -941,9 +2699,10 @@ mod tests {
     use super::*;
 
     use crate::i128::Int128;
+    use crate::msg::{default_halflife_floor, default_max_denoms};
 
     use cosmwasm_std::testing::{mock_env, mock_info};
-    use cosmwasm_std::{from_slice, Api, OwnedDeps, Querier, StdError, Storage};
+    use cosmwasm_std::{coin, coins, from_slice, Api, OwnedDeps, Querier, StdError, Storage};
     use cw_controllers::AdminError;
     use cw_storage_plus::Map;
     use tg4::{member_key, TOTAL_KEY};
@@ -982,7 +2741,14 @@ mod tests {
             preauths_hooks: 1,
             preauths_slashing: 0,
             halflife: Some(Duration::new(HALFLIFE)),
+            decay: None,
             denom: "usdc".to_owned(),
+            max_denoms: default_max_denoms(),
+            distribution_fee: Decimal::zero(),
+            fee_recipient: None,
+            auto_distribute: false,
+            halflife_floor: default_halflife_floor(),
+            initial_adjustments: vec![],
         };
         let info = mock_info("creator", &[]);
         instantiate(deps, mock_env(), info, msg).unwrap();
@@ -1003,7 +2769,15 @@ mod tests {
         let preauths = PREAUTH_HOOKS.get_auth(&deps.storage).unwrap();
         assert_eq!(1, preauths);
 
-        let raw = query(deps.as_ref(), mock_env(), QueryMsg::DistributionData {}).unwrap();
+        let max_denoms = MAX_DENOMS.load(&deps.storage).unwrap();
+        assert_eq!(default_max_denoms(), max_denoms);
+
+        let raw = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::DistributionData { denom: None },
+        )
+        .unwrap();
         let res: Distribution = from_slice(&raw).unwrap();
         assert_eq!(
             res,
@@ -1021,6 +2795,7 @@ mod tests {
             mock_env(),
             QueryMsg::WithdrawAdjustmentData {
                 addr: USER1.to_owned(),
+                denom: None,
             },
         )
         .unwrap();
@@ -1030,11 +2805,203 @@ mod tests {
             WithdrawAdjustment {
                 shares_correction: Int128::zero(),
                 withdrawn_rewards: Uint128::zero(),
-                delegated: Addr::unchecked("user1"),
             }
         );
     }
 
+    #[test]
+    fn migration_backfills_member_count() {
+        let mut deps = mock_deps_tgrade();
+        do_instantiate(deps.as_mut());
+        assert_eq!(MEMBER_COUNT.load(&deps.storage).unwrap(), 2);
+
+        // Contracts created before member count was introduced don't have it
+        MEMBER_COUNT.remove(&mut deps.storage);
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.16.0").unwrap();
+        migrate(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg {
+                halflife: None,
+                decay: None,
+                max_denoms: None,
+                auto_distribute: None,
+                halflife_floor: None,
+                distribution_fee: None,
+                fee_recipient: None,
+            },
+        )
+        .unwrap();
+
+        let raw = query(deps.as_ref(), mock_env(), QueryMsg::MemberCount {}).unwrap();
+        let count: u64 = from_slice(&raw).unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn instantiation_requires_positive_max_denoms() {
+        let mut deps = mock_deps_tgrade();
+        let msg = InstantiateMsg {
+            admin: Some(INIT_ADMIN.into()),
+            members: vec![],
+            preauths_hooks: 0,
+            preauths_slashing: 0,
+            halflife: None,
+            decay: None,
+            denom: "usdc".to_owned(),
+            max_denoms: 0,
+            distribution_fee: Decimal::zero(),
+            fee_recipient: None,
+            auto_distribute: false,
+            halflife_floor: default_halflife_floor(),
+            initial_adjustments: vec![],
+        };
+        let info = mock_info("creator", &[]);
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidMaxDenoms {});
+    }
+
+    #[test]
+    fn instantiation_validates_distribution_fee() {
+        let mut deps = mock_deps_tgrade();
+        let msg = InstantiateMsg {
+            admin: Some(INIT_ADMIN.into()),
+            members: vec![],
+            preauths_hooks: 0,
+            preauths_slashing: 0,
+            halflife: None,
+            decay: None,
+            denom: "usdc".to_owned(),
+            max_denoms: default_max_denoms(),
+            distribution_fee: Decimal::one(),
+            fee_recipient: Some(INIT_ADMIN.into()),
+            auto_distribute: false,
+            halflife_floor: default_halflife_floor(),
+            initial_adjustments: vec![],
+        };
+        let info = mock_info("creator", &[]);
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::InvalidDistributionFee {});
+
+        let msg = InstantiateMsg {
+            admin: None,
+            distribution_fee: Decimal::percent(10),
+            fee_recipient: None,
+            auto_distribute: false,
+            ..msg
+        };
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::MissingFeeRecipient {});
+
+        // Admin receives the fee if no recipient is given
+        let msg = InstantiateMsg {
+            admin: Some(INIT_ADMIN.into()),
+            ..msg
+        };
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            FEE_RECIPIENT.load(&deps.storage).unwrap(),
+            Addr::unchecked(INIT_ADMIN)
+        );
+    }
+
+    #[test]
+    fn instantiation_validates_decay() {
+        let mut deps = mock_deps_tgrade();
+        let msg = InstantiateMsg {
+            admin: Some(INIT_ADMIN.into()),
+            members: vec![],
+            preauths_hooks: 0,
+            preauths_slashing: 0,
+            halflife: Some(Duration::new(HALFLIFE)),
+            decay: Some(DecayMode::Linear {
+                amount: 1,
+                interval: Duration::new(HALFLIFE),
+            }),
+            denom: "usdc".to_owned(),
+            max_denoms: default_max_denoms(),
+            distribution_fee: Decimal::zero(),
+            fee_recipient: None,
+            auto_distribute: false,
+            halflife_floor: default_halflife_floor(),
+            initial_adjustments: vec![],
+        };
+        let info = mock_info("creator", &[]);
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::ConflictingDecay {});
+
+        let msg = InstantiateMsg {
+            halflife: None,
+            decay: Some(DecayMode::Linear {
+                amount: 0,
+                interval: Duration::new(HALFLIFE),
+            }),
+            ..msg
+        };
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidDecay {});
+    }
+
+    #[test]
+    fn instantiation_validates_initial_adjustments() {
+        let mut deps = mock_deps_tgrade();
+        let adjustment = WithdrawAdjustment {
+            shares_correction: Int128::from((100u128 << SHARES_SHIFT) as i128),
+            withdrawn_rewards: Uint128::new(40),
+        };
+        let msg = InstantiateMsg {
+            admin: Some(INIT_ADMIN.into()),
+            members: vec![Member {
+                addr: USER1.into(),
+                points: USER1_POINTS,
+                start_height: None,
+            }],
+            preauths_hooks: 0,
+            preauths_slashing: 0,
+            halflife: None,
+            decay: None,
+            denom: "usdc".to_owned(),
+            max_denoms: default_max_denoms(),
+            distribution_fee: Decimal::zero(),
+            fee_recipient: None,
+            auto_distribute: false,
+            halflife_floor: default_halflife_floor(),
+            initial_adjustments: vec![(USER2.to_owned(), adjustment.clone())],
+        };
+        let info = mock_info("creator", &coins(60, "usdc"));
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::AdjustmentForNonMember(USER2.to_owned()));
+
+        let overdrawn = WithdrawAdjustment {
+            withdrawn_rewards: Uint128::new(101),
+            ..adjustment.clone()
+        };
+        let msg = InstantiateMsg {
+            initial_adjustments: vec![(USER1.to_owned(), overdrawn)],
+            ..msg
+        };
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::InvalidAdjustment(USER1.to_owned()));
+
+        let msg = InstantiateMsg {
+            initial_adjustments: vec![(USER1.to_owned(), adjustment)],
+            ..msg
+        };
+        let info = mock_info("creator", &coins(59, "usdc"));
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AdjustmentsNotFunded {
+                required: Uint128::new(60)
+            }
+        );
+
+        let info = mock_info("creator", &coins(60, "usdc"));
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let rewards = query_withdrawable_rewards(deps.as_ref(), USER1.to_owned()).unwrap();
+        assert_eq!(rewards.rewards, coin(60, "usdc"));
+    }
+
     #[test]
     fn try_member_queries() {
         let mut deps = mock_deps_tgrade();
@@ -1173,6 +3140,91 @@ mod tests {
         assert_eq!(members.len(), 0);
     }
 
+    #[test]
+    fn try_list_members_in_range() {
+        let mut deps = mock_deps_tgrade();
+        do_instantiate(deps.as_mut());
+
+        let add = [(USER3, 20), ("user4", USER2_POINTS), ("user5", 1)]
+            .iter()
+            .map(|(addr, points)| Member {
+                addr: (*addr).to_owned(),
+                points: *points,
+                start_height: None,
+            })
+            .collect();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(INIT_ADMIN, &[]),
+            ExecuteMsg::UpdateMembers {
+                add,
+                remove: vec![],
+            },
+        )
+        .unwrap();
+
+        let member = |addr: &str, points| Member {
+            addr: addr.to_owned(),
+            points,
+            start_height: None,
+        };
+
+        // Bounds are inclusive, members are sorted by (descending) points
+        let members = list_members_in_range(deps.as_ref(), 6, 11, None, None)
+            .unwrap()
+            .members;
+        assert_eq!(
+            members,
+            vec![
+                member(USER1, 11),
+                member("user4", USER2_POINTS),
+                member(USER2, USER2_POINTS),
+            ]
+        );
+
+        // Pagination goes through members with the same points
+        let members = list_members_in_range(deps.as_ref(), 6, 11, None, Some(2))
+            .unwrap()
+            .members;
+        assert_eq!(
+            members,
+            vec![member(USER1, 11), member("user4", USER2_POINTS)]
+        );
+        let start_after = Some(members[1].clone());
+        let members = list_members_in_range(deps.as_ref(), 6, 11, start_after, Some(2))
+            .unwrap()
+            .members;
+        assert_eq!(members, vec![member(USER2, USER2_POINTS)]);
+        let start_after = Some(members[0].clone());
+        let members = list_members_in_range(deps.as_ref(), 6, 11, start_after, None)
+            .unwrap()
+            .members;
+        assert_eq!(members, vec![]);
+
+        // Whole range
+        let members = list_members_in_range(deps.as_ref(), 0, u64::MAX, None, None)
+            .unwrap()
+            .members;
+        assert_eq!(members.len(), 5);
+
+        // Single points value
+        let members = list_members_in_range(deps.as_ref(), 1, 1, None, None)
+            .unwrap()
+            .members;
+        assert_eq!(members, vec![member("user5", 1)]);
+
+        // Empty ranges
+        let members = list_members_in_range(deps.as_ref(), 12, 19, None, None)
+            .unwrap()
+            .members;
+        assert_eq!(members, vec![]);
+        let members = list_members_in_range(deps.as_ref(), 11, 6, None, None)
+            .unwrap()
+            .members;
+        assert_eq!(members, vec![]);
+    }
+
     #[test]
     fn try_halflife_queries() {
         let mut deps = mock_deps_tgrade();
@@ -1219,13 +3271,76 @@ mod tests {
             preauths_hooks: 1,
             preauths_slashing: 0,
             halflife: None,
+            decay: None,
             denom: "usdc".to_owned(),
+            max_denoms: default_max_denoms(),
+            distribution_fee: Decimal::zero(),
+            fee_recipient: None,
+            auto_distribute: false,
+            halflife_floor: default_halflife_floor(),
+            initial_adjustments: vec![],
         };
         let info = mock_info("creator", &[]);
 
         instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
         assert_eq!(query_halflife(deps.as_ref()).unwrap().halflife_info, None);
+
+        let projected = query_projected_member(deps.as_ref(), USER1.into(), Some(3)).unwrap();
+        assert_eq!(
+            projected,
+            ProjectedMemberResponse {
+                points: Some(USER1_POINTS),
+                next_halflife: None,
+            }
+        );
+    }
+
+    #[test]
+    fn try_projected_member_query() {
+        let mut deps = mock_deps_tgrade();
+        do_instantiate(deps.as_mut());
+
+        let next_halflife = Some(mock_env().block.time.plus_seconds(HALFLIFE));
+
+        // Single halflife by default
+        let projected = query_projected_member(deps.as_ref(), USER1.into(), None).unwrap();
+        assert_eq!(
+            projected,
+            ProjectedMemberResponse {
+                points: Some(5),
+                next_halflife,
+            }
+        );
+
+        // 11 -> 5 -> 2 -> 1 -> 1
+        let projected = query_projected_member(deps.as_ref(), USER1.into(), Some(4)).unwrap();
+        assert_eq!(projected.points, Some(1));
+        let projected = query_projected_member(deps.as_ref(), USER2.into(), Some(2)).unwrap();
+        assert_eq!(projected.points, Some(1));
+        let projected = query_projected_member(deps.as_ref(), USER2.into(), Some(0)).unwrap();
+        assert_eq!(projected.points, Some(USER2_POINTS));
+
+        // Not a member
+        let projected = query_projected_member(deps.as_ref(), USER3.into(), None).unwrap();
+        assert_eq!(
+            projected,
+            ProjectedMemberResponse {
+                points: None,
+                next_halflife,
+            }
+        );
+
+        // Projection matches actual halflife
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(HALFLIFE);
+        end_block(deps.as_mut(), env).unwrap();
+        assert_eq!(
+            query_member(deps.as_ref(), USER1.into(), None)
+                .unwrap()
+                .points,
+            Some(5)
+        );
     }
 
     #[test]
@@ -1623,7 +3738,8 @@ mod tests {
 
         // end block at half life
         env.block.time = env.block.time.plus_seconds(HALFLIFE);
-        let expected_reduction = points_reduction(USER1_POINTS) + points_reduction(USER2_POINTS);
+        let expected_reduction =
+            points_reduction(USER1_POINTS, 1) + points_reduction(USER2_POINTS, 1);
         let evt = Event::new("halflife")
             .add_attribute("height", env.block.height.to_string())
             .add_attribute("reduction", expected_reduction.to_string());
@@ -1655,6 +3771,49 @@ mod tests {
         assert_users(&deps, Some(1), Some(1), None, None);
     }
 
+    #[test]
+    fn halflife_fires_hooks() {
+        let mut deps = mock_deps_tgrade();
+        do_instantiate(deps.as_mut());
+
+        let hook = String::from("hook1");
+        let admin_info = mock_info(INIT_ADMIN, &[]);
+        let add_msg = ExecuteMsg::AddHook { addr: hook.clone() };
+        execute(deps.as_mut(), mock_env(), admin_info, add_msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(HALFLIFE);
+        let res = end_block(deps.as_mut(), env).unwrap();
+
+        // Single message with all reductions
+        let diffs = vec![
+            MemberDiff::new(USER1, Some(USER1_POINTS), Some(USER1_POINTS / 2)),
+            MemberDiff::new(USER2, Some(USER2_POINTS), Some(USER2_POINTS / 2)),
+        ];
+        let hook_msg = MemberChangedHookMsg { diffs }
+            .into_cosmos_msg(hook)
+            .map(|msg| SubMsg::reply_on_error(msg, DECAY_HOOK_REPLY_ID))
+            .unwrap();
+        assert_eq!(res.messages, vec![hook_msg]);
+
+        // Reverting hook is logged, and the decay stays applied
+        let res = reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: DECAY_HOOK_REPLY_ID,
+                result: SubMsgResult::Err("hook failed".to_owned()),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            Response::new()
+                .add_attribute("action", "decay_hook_failed")
+                .add_attribute("error", "hook failed")
+        );
+    }
+
     mod points {
         use super::*;
 
@@ -1684,6 +3843,88 @@ mod tests {
             let new_member = query_member(deps.as_ref(), new_user, None).unwrap();
             assert_eq!(new_member.points, Some(10));
         }
+
+        #[test]
+        fn deduct_more_than_member_has() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            let env = mock_env();
+            let info = mock_info(INIT_ADMIN, &[]);
+
+            // Originally USER2 has 6 points, so it is removed
+            execute_deduct_points(deps.as_mut(), env, info, USER2.to_owned(), 10).unwrap();
+            assert_users(&deps, Some(USER1_POINTS), None, None, None);
+            assert_eq!(TOTAL.load(&deps.storage).unwrap(), USER1_POINTS);
+        }
+
+        #[test]
+        fn deduct_from_nonexisting_member() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            let env = mock_env();
+            let info = mock_info(INIT_ADMIN, &[]);
+
+            let res =
+                execute_deduct_points(deps.as_mut(), env, info, USER3.to_owned(), 10).unwrap();
+            assert!(res.messages.is_empty());
+            assert_users(&deps, Some(USER1_POINTS), Some(USER2_POINTS), None, None);
+        }
+
+        #[test]
+        fn add_batch_to_existing_and_new_members() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            let hook = String::from("hook1");
+            let admin_info = mock_info(INIT_ADMIN, &[]);
+            let add_msg = ExecuteMsg::AddHook { addr: hook.clone() };
+            execute(deps.as_mut(), mock_env(), admin_info.clone(), add_msg).unwrap();
+
+            let additions = vec![
+                (USER1.to_owned(), 10),
+                (USER3.to_owned(), 5),
+                (USER1.to_owned(), 4),
+            ];
+
+            // Only admin can add points
+            let err = execute_add_points_batch(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(USER1, &[]),
+                additions.clone(),
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::Admin(AdminError::NotAdmin {}));
+
+            let res =
+                execute_add_points_batch(deps.as_mut(), mock_env(), admin_info, additions).unwrap();
+
+            // USER3 was not a member before
+            assert_users(
+                &deps,
+                Some(USER1_POINTS + 14),
+                Some(USER2_POINTS),
+                Some(5),
+                None,
+            );
+            assert_eq!(
+                TOTAL.load(&deps.storage).unwrap(),
+                USER1_POINTS + USER2_POINTS + 19
+            );
+
+            // Single message with all changes
+            let diffs = vec![
+                MemberDiff::new(USER1, Some(USER1_POINTS), Some(USER1_POINTS + 14)),
+                MemberDiff::new(USER3, None, Some(5)),
+            ];
+            let hook_msg = MemberChangedHookMsg { diffs }
+                .into_cosmos_msg(hook)
+                .map(SubMsg::new)
+                .unwrap();
+            assert_eq!(res.messages, vec![hook_msg]);
+        }
     }
 
     #[test]
@@ -1710,4 +3951,64 @@ mod tests {
         .unwrap();
         assert_eq!(res, Response::new());
     }
+
+    #[test]
+    fn withdraw_rewards_as_vesting() {
+        let mut deps = mock_deps_tgrade();
+        do_instantiate(deps.as_mut());
+
+        let env = mock_env();
+        deps.querier
+            .update_balance(env.contract.address.clone(), coins(170, "usdc"));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(INIT_ADMIN, &[]),
+            ExecuteMsg::DistributeRewards {
+                sender: None,
+                denom: None,
+            },
+        )
+        .unwrap();
+
+        // Regular withdrawal is a bank send to the receiver
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(USER2, &[]),
+            ExecuteMsg::WithdrawRewards {
+                owner: None,
+                receiver: Some(USER3.to_owned()),
+                as_vesting: false,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            [SubMsg::new(BankMsg::Send {
+                to_address: USER3.to_owned(),
+                amount: coins(60, "usdc"),
+            })]
+        );
+
+        // Vesting withdrawal is an undelegation to the owner, regardless of receiver
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(USER1, &[]),
+            ExecuteMsg::WithdrawRewards {
+                owner: None,
+                receiver: Some(USER3.to_owned()),
+                as_vesting: true,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            [SubMsg::new(TgradeMsg::Undelegate {
+                funds: coin(110, "usdc"),
+                recipient: USER1.to_owned(),
+            })]
+        );
+    }
 }
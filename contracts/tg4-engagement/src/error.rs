@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
 
 use cw_controllers::AdminError;
@@ -29,4 +29,43 @@ pub enum ContractError {
 
     #[error("No members to distribute tokens to")]
     NoMembersToDistributeTo {},
+
+    #[error("Max denoms must be greater than zero")]
+    InvalidMaxDenoms {},
+
+    #[error("Distribution fee must be lower than 1")]
+    InvalidDistributionFee {},
+
+    #[error("Fee recipient is required for non-zero distribution fee")]
+    MissingFeeRecipient {},
+
+    #[error("Cannot distribute more than {max} denoms")]
+    TooManyDenoms { max: u32 },
+
+    #[error("Decay amount and interval must be greater than zero")]
+    InvalidDecay {},
+
+    #[error("Only one of halflife and decay may be set")]
+    ConflictingDecay {},
+
+    #[error("Reward multiplier must be greater than zero and at most {max}")]
+    InvalidRewardMultiplier { max: u64 },
+
+    #[error("Initial adjustment provided for non-member: {0}")]
+    AdjustmentForNonMember(String),
+
+    #[error("Invalid initial adjustment for: {0}")]
+    InvalidAdjustment(String),
+
+    #[error("Initial adjustments require {required} tokens to be sent")]
+    AdjustmentsNotFunded { required: Uint128 },
+
+    #[error("Member tags must not be empty")]
+    EmptyTag {},
+
+    #[error("Unrecognised reply id: {0}")]
+    UnrecognisedReply(u64),
+
+    #[error("Rewards distribution arithmetic overflow")]
+    DistributionOverflow {},
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::{Addr, Coin, Decimal, Timestamp};
+use cosmwasm_std::{Addr, Coin, Decimal, Timestamp, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -6,6 +6,9 @@ use tg4::Member;
 use tg_bindings::{Evidence, PrivilegeChangeMsg};
 use tg_utils::Duration;
 
+use crate::error::ContractError;
+use crate::state::WithdrawAdjustment;
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct InstantiateMsg {
@@ -18,8 +21,76 @@ pub struct InstantiateMsg {
     #[serde(default)]
     pub preauths_slashing: u64,
     pub halflife: Option<Duration>,
+    /// Points decay applied on end block. Alternative to `halflife`, only one of them may be set.
+    #[serde(default)]
+    pub decay: Option<DecayMode>,
     /// Denom of tokens which may be distributed by this contract.
     pub denom: String,
+    /// Maximum number of distinct denoms which may be distributed by this contract. Protects
+    /// against bloating the distribution data with dust denoms. 10 by default.
+    #[serde(default = "default_max_denoms")]
+    pub max_denoms: u32,
+    /// Portion of every distribution which is sent to `fee_recipient` instead of being
+    /// distributed to members. Has to be lower than 1. No fee by default.
+    #[serde(default)]
+    pub distribution_fee: Decimal,
+    /// Address receiving the distribution fee. Defaults to `admin`; one of them is required if
+    /// `distribution_fee` is not zero.
+    pub fee_recipient: Option<String>,
+    /// If set, rewards sent to this contract are distributed at the end of every block, without
+    /// the need to call `ExecuteMsg::DistributeRewards`. Requires the contract to be privileged.
+    #[serde(default)]
+    pub auto_distribute: bool,
+    /// Points decay never reduces members below this many points. 1 by default.
+    #[serde(default = "default_halflife_floor")]
+    pub halflife_floor: u64,
+    /// Withdraw adjustments of members in the instantiation denom, for seeding rewards state
+    /// migrated from another contract. Corrections are applied against zero shares per point, so
+    /// they have to include shares of member points in the source contract. Rewards withdrawable
+    /// this way have to be sent with the instantiation message.
+    #[serde(default)]
+    pub initial_adjustments: Vec<(String, WithdrawAdjustment)>,
+}
+
+pub fn default_max_denoms() -> u32 {
+    10
+}
+
+pub fn default_halflife_floor() -> u64 {
+    1
+}
+
+/// Way in which members points decay over time. Points are never reduced below the configured
+/// floor (1 by default).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DecayMode {
+    /// Points of every member are halved once per given duration
+    Halflife(Duration),
+    /// `amount` points are subtracted from every member per each elapsed `interval`
+    Linear { amount: u64, interval: Duration },
+}
+
+impl DecayMode {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        match self {
+            DecayMode::Halflife(halflife) if halflife.seconds() == 0 => {
+                Err(ContractError::InvalidDecay {})
+            }
+            DecayMode::Linear { amount, interval } if *amount == 0 || interval.seconds() == 0 => {
+                Err(ContractError::InvalidDecay {})
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Duration between consecutive decay applications
+    pub fn interval(&self) -> Duration {
+        match self {
+            DecayMode::Halflife(halflife) => *halflife,
+            DecayMode::Linear { interval, .. } => *interval,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -35,6 +106,12 @@ pub enum ExecuteMsg {
     },
     /// Add points to member's address
     AddPoints { addr: String, points: u64 },
+    /// Add points to multiple members at once, creating missing ones. Hooks are informed about
+    /// all changes with a single message. Must be called by Admin
+    AddPointsBatch { additions: Vec<(String, u64)> },
+    /// Deduct points from member's address, saturating at zero. Member left with no points is
+    /// removed. Must be called by Admin
+    DeductPoints { addr: String, points: u64 },
     /// Add a new hook to be informed of all membership changes. Must be called by Admin
     AddHook { addr: String },
     /// Remove a hook. Must be called by Admin
@@ -46,8 +123,12 @@ pub enum ExecuteMsg {
         /// Original source of rewards, informational. If present overwrites "sender" field on
         /// propagated event.
         sender: Option<String>,
+        /// Denom to distribute. If not present, all previously distributed denoms and all denoms
+        /// sent with this message are distributed.
+        denom: Option<String>,
     },
-    /// Withdraws rewards which were previously distributed and assigned to sender.
+    /// Withdraws rewards which were previously distributed and assigned to sender. Rewards in all
+    /// denoms are sent at once.
     WithdrawRewards {
         /// Account from which assigned rewards would be withdrawn; `sender` by default. `sender` has
         /// to be eligible for withdrawal from `owner` address to perform this call (`owner` has to
@@ -55,6 +136,25 @@ pub enum ExecuteMsg {
         owner: Option<String>,
         /// Address where to transfer funds. If not present, funds would be sent to `sender`.
         receiver: Option<String>,
+        /// If set, funds are undelegated to vesting account of `owner` instead of being sent,
+        /// and `receiver` is ignored.
+        #[serde(default)]
+        as_vesting: bool,
+    },
+    /// Withdraws rewards of multiple owners at once, sending them to `receiver` (`sender` by
+    /// default). `sender` has to be eligible for withdrawal from every owner, otherwise the whole
+    /// batch fails. Owners with no rewards are skipped.
+    WithdrawRewardsBatch {
+        owners: Vec<String>,
+        receiver: Option<String>,
+    },
+    /// Withdraws rewards assigned to `owner` (`sender` by default), and distributes them among
+    /// members of the `target` engagement contract by sending them with
+    /// `DistributeRewards {}`. Authorization is the same as for `WithdrawRewards`.
+    /// If the `target` fails, the rewards are accrued back to `owner` instead of reverting.
+    WithdrawAndDistribute {
+        owner: Option<String>,
+        target: String,
     },
     /// Sets given address as allowed for senders funds withdrawal. Funds still can be withdrawn by
     /// sender himself, but this additional account is allowed to perform it as well. There can be only
@@ -70,6 +170,24 @@ pub enum ExecuteMsg {
     RemoveSlasher { addr: String },
     /// Slash engagement points from address
     Slash { addr: String, portion: Decimal },
+    /// Slash engagement points from address, crediting the slashed points to all other members
+    /// proportionally to their current points. Rounding remainder is dropped.
+    SlashAndRedistribute { addr: String, portion: Decimal },
+    /// Sets multiplier applied to points of `addr` in rewards distribution (1 by default). Only
+    /// future distributions are affected. Must be called by Admin.
+    SetRewardMultiplier { addr: String, multiplier: Decimal },
+    /// Replaces tags assigned to `addr`. Rewards distributed to a tag before it was assigned are
+    /// not shared with the member, and rewards earned while tagged are kept when the tag is
+    /// removed. Must be called by Admin.
+    SetMemberTags { addr: String, tags: Vec<String> },
+    /// Like `DistributeRewards {}`, but only tokens sent with the message are distributed, and only
+    /// among members having `tag`, proportionally to their points. Rewards are withdrawn together
+    /// with regular ones.
+    DistributeRewardsTo { tag: String },
+    /// Sends all tokens not yet distributed (contract balance minus rewards already distributed
+    /// and not yet withdrawn) of every distributed denom to `receiver`. Rewards already earned by
+    /// members are not affected. Must be called by Admin.
+    AdminWithdrawUndistributed { receiver: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -89,6 +207,14 @@ pub enum QueryMsg {
         start_after: Option<Member>,
         limit: Option<u32>,
     },
+    /// Returns MemberListResponse with members having points within `[min_points, max_points]`,
+    /// sorted by points descending
+    ListMembersInRange {
+        min_points: u64,
+        max_points: u64,
+        start_after: Option<Member>,
+        limit: Option<u32>,
+    },
     /// Returns MemberResponse
     Member {
         addr: String,
@@ -98,28 +224,84 @@ pub enum QueryMsg {
     Hooks {},
     /// Return the current number of preauths. Returns PreauthResponse.
     Preauths {},
-    /// Return how many rewards are assigned for withdrawal from the given address. Returns
-    /// `RewardsResponse`.
+    /// Return how many rewards in the instantiation denom are assigned for withdrawal from the
+    /// given address. Returns `RewardsResponse`.
     WithdrawableRewards { owner: String },
-    /// Return how many rewards were distributed in total by this contract. Returns
-    /// `RewardsResponse`.
+    /// Return how many rewards in every distributed denom are assigned for withdrawal from the
+    /// given address. Returns `Vec<Coin>`.
+    WithdrawableRewardsAll { owner: String },
+    /// Return how many rewards in every denom distributed to `tag` are assigned for withdrawal
+    /// from the given address. Returns `Vec<Coin>`.
+    WithdrawableRewardsForTag { owner: String, tag: String },
+    /// Return how many rewards in the instantiation denom are assigned for withdrawal for every
+    /// member, paginated by member address. Returns `Vec<(Addr, Coin)>`.
+    ListWithdrawableRewards {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Return how many rewards in the instantiation denom were distributed in total by this
+    /// contract. Returns `RewardsResponse`.
     DistributedRewards {},
     /// Return how many funds were sent to this contract since last `ExecuteMsg::DistributeFunds`,
     /// and await for distribution. Returns `RewardsResponse`.
     UndistributedRewards {},
     /// Return address allowed for withdrawal of the funds assigned to owner. Returns `DelegateResponse`
     Delegated { owner: String },
+    /// Return owners who delegated withdrawal of their funds to `delegate`, paginated by owner
+    /// address. Returns `DelegationsResponse`
+    DelegationsTo {
+        delegate: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     /// Returns information about the half-life, including the duration in seconds, the last
     /// and the next occurrence.
     Halflife {},
+    /// Returns configured points decay (either halflife or linear) with the time it was last
+    /// applied. Returns `DecayResponse`.
+    Decay {},
+    /// Returns points of the member after `after_halflives` (1 by default) subsequent points
+    /// decays (halflife or linear), together with the time of the next decay. Points are
+    /// returned unchanged if no decay is configured. Returns `ProjectedMemberResponse`.
+    ProjectedMember {
+        addr: String,
+        after_halflives: Option<u32>,
+    },
+    /// Returns points of the member at `at_time`, assuming points decay (halflife or linear) is
+    /// applied at every decay boundary up to then. Returns `ProjectedPointsResponse`.
+    ProjectedPoints { addr: String, at_time: Timestamp },
+    /// Returns total points after `halflives` subsequent points decays (halflife or linear),
+    /// taking into account that members at `halflife_floor` (or below) are never reduced.
+    /// Returns `TotalPointsResponse`.
+    SurvivingTotalPoints { halflives: u32 },
+    /// Returns number of members (u64)
+    MemberCount {},
+    /// Returns information (bool) about whether the given address is a member
+    IsMember { addr: String },
     /// Returns information (bool) about whether the given address is an active slasher
     IsSlasher { addr: String },
     /// Returns all active slashers as a vector of addresses
     ListSlashers {},
-    /// Returns rewards distribution data
-    DistributionData {},
-    /// Returns withdraw adjustment data
-    WithdrawAdjustmentData { addr: String },
+    /// Returns rewards distribution data of given denom, instantiation denom by default
+    DistributionData { denom: Option<String> },
+    /// Returns outcome of `DistributeRewards` for given denom (instantiation denom by default)
+    /// if it was called now, without performing it. Returns `SimulateDistributionResponse`.
+    SimulateDistribution { denom: Option<String> },
+    /// Returns fixed point parameters of rewards distribution of instantiation denom.
+    /// Returns `DistributionParamsResponse`.
+    DistributionParams {},
+    /// Returns outcome of `Slash` of `addr` by `portion` if it was called now, without
+    /// performing it. Returns `SimulateSlashResponse`.
+    SimulateSlash { addr: String, portion: Decimal },
+    /// Returns withdraw adjustment data of given denom, instantiation denom by default
+    WithdrawAdjustmentData { addr: String, denom: Option<String> },
+    /// Returns multiplier applied to points of `addr` in rewards distribution.
+    /// Returns `RewardMultiplierResponse`.
+    RewardMultiplier { addr: String },
+    /// Checks consistency of points and rewards accounting. All members and withdraw adjustments
+    /// are scanned, so the gas cost grows with the number of members - it is meant for periodic
+    /// monitoring, not to be called from other contracts. Returns `InvariantsResponse`.
+    Invariants {},
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -145,6 +327,24 @@ pub enum SudoMsg {
     UpdateMember(Member),
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct RewardMultiplierResponse {
+    pub multiplier: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct InvariantsResponse {
+    /// Total points equal the sum of all members points
+    pub total_matches_sum_of_points: bool,
+    /// For every denom, distributed rewards equal withdrawable rewards plus rewards already
+    /// withdrawn, and members can't withdraw more than the withdrawable total (the difference
+    /// is a rounding leftover)
+    pub distributed_total_matches_withdrawals: bool,
+    /// Reward total equals the sum of members points weighted by their multipliers, and for
+    /// every denom, members shares with their corrections sum up to all distributed shares
+    pub shares_corrections_consistent: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct PreauthResponse {
     pub preauths: u64,
@@ -160,6 +360,11 @@ pub struct DelegatedResponse {
     pub delegated: Addr,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct DelegationsResponse {
+    pub owners: Vec<Addr>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct HalflifeResponse {
     // `None` means the halflife functionality is disabled for this instance.
@@ -173,6 +378,57 @@ pub struct HalflifeInfo {
     pub next_halflife: Timestamp,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct ProjectedMemberResponse {
+    // `None` if the address is not a member.
+    pub points: Option<u64>,
+    // `None` means decay is disabled for this instance.
+    pub next_halflife: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct ProjectedPointsResponse {
+    // `None` if the address is not a member.
+    pub points: Option<u64>,
+    // Number of points decays applied until the requested time.
+    pub decays: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct SimulateDistributionResponse {
+    /// Shares which would be added to every point (scaled by `SHARES_SHIFT`)
+    pub points_per_share: Uint128,
+    /// Tokens which would be distributed to members, excluding distribution fee
+    pub amount: Uint128,
+    /// Shares which would be left undistributed for the next distribution
+    pub leftover: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct DistributionParamsResponse {
+    /// Number of bits shares are shifted by to keep precision of distributed tokens
+    pub shares_shift: u8,
+    /// Shares distributed so far to every point (scaled by `shares_shift`)
+    pub shares_per_point: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct SimulateSlashResponse {
+    /// Points of the address before slashing, zero for non-members
+    pub old_points: u64,
+    /// Points of the address after slashing, the same as `old_points` for non-members
+    pub new_points: u64,
+    /// Total points after slashing
+    pub total_after: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct DecayResponse {
+    // `None` means decay is disabled for this instance.
+    pub decay: Option<DecayMode>,
+    pub last_applied: Timestamp,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct SlasherResponse {
     pub is_slasher: bool,
@@ -187,6 +443,19 @@ pub struct ListSlashersResponse {
 #[serde(rename_all = "snake_case")]
 pub struct MigrateMsg {
     pub halflife: Option<Duration>,
+    /// Changes points decay, overriding `halflife`. Only one of them may be set.
+    pub decay: Option<DecayMode>,
+    pub max_denoms: Option<u32>,
+    pub auto_distribute: Option<bool>,
+    /// Changes the number of points below which points decay never reduces members.
+    pub halflife_floor: Option<u64>,
+    /// Changes the distribution fee. Has to be lower than 1.
+    #[serde(default)]
+    pub distribution_fee: Option<Decimal>,
+    /// Changes the address receiving the distribution fee. If no recipient was set before, the
+    /// fee is sent to the current admin by default.
+    #[serde(default)]
+    pub fee_recipient: Option<String>,
 }
 
 #[cfg(test)]
@@ -1,7 +1,7 @@
 mod suite;
 
 use crate::error::ContractError;
-use cosmwasm_std::{coin, coins, Decimal, Event};
+use cosmwasm_std::{coin, coins, Addr, Decimal, Event};
 use suite::{expected_members, SuiteBuilder};
 use tg4::Member;
 use tg_utils::{Duration, PreauthError};
@@ -17,6 +17,8 @@ fn member(addr: &str, points: u64) -> Member {
 
 mod funds_distribution {
     use super::*;
+    use crate::msg::SimulateDistributionResponse;
+    use cosmwasm_std::Uint128;
 
     fn distribution_event(sender: &str, denom: &str, amount: u128) -> Event {
         Event::new("wasm")
@@ -237,6 +239,65 @@ mod funds_distribution {
         assert_eq!(suite.token_balance(&members[3]).unwrap(), 0);
     }
 
+    #[test]
+    fn removed_member_rewards_paid_out() {
+        let members = ["member1", "member2", "member3", "member4"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_member(members[1], 2)
+            .with_member(members[2], 4)
+            .with_funds(members[3], 1000)
+            .build();
+
+        let denom = suite.denom.clone();
+        let admin = suite.admin().to_owned();
+
+        suite.set_member_tags(&admin, members[1], &["tag"]).unwrap();
+        suite
+            .distribute_funds(members[3], None, &coins(700, &denom))
+            .unwrap();
+        suite
+            .distribute_funds_to(members[3], "tag", &coins(100, &denom))
+            .unwrap();
+        // Partial withdrawal before removal is accounted
+        suite.withdraw_funds(members[1], None, None).unwrap();
+        suite
+            .distribute_funds(members[3], None, &coins(100, &denom))
+            .unwrap();
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 300);
+
+        // Regular and tagged rewards are paid out on removal
+        suite
+            .modify_members(&admin, &[], &[members[1], members[2]])
+            .unwrap();
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 328);
+        assert_eq!(suite.token_balance(members[2]).unwrap(), 457);
+        assert_eq!(
+            suite.withdrawable_rewards(members[1]).unwrap(),
+            coin(0, &denom)
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[2]).unwrap(),
+            coin(0, &denom)
+        );
+
+        // Withdrawing after removal does nothing
+        suite.withdraw_funds(members[1], None, None).unwrap();
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 328);
+
+        // Nothing is orphaned - everything left on the contract belongs to the remaining member,
+        // up to the rounding leftover
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 114);
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 1);
+        assert_eq!(suite.undistributed_funds().unwrap(), coin(0, &denom));
+
+        let invariants = suite.invariants().unwrap();
+        assert!(invariants.distributed_total_matches_withdrawals);
+        assert!(invariants.shares_corrections_consistent);
+    }
+
     #[test]
     fn points_changed_after_distribution_accumulated() {
         let members = vec![
@@ -560,371 +621,2201 @@ mod funds_distribution {
     }
 
     #[test]
-    fn querying_unknown_address() {
-        let suite = SuiteBuilder::new().with_denom("usdc").build();
+    fn delegations_tracked_both_ways() {
+        let members = ["member1", "member2", "member3", "keeper1", "keeper2"];
 
-        let resp = suite.withdrawable_rewards("unknown").unwrap();
-        assert_eq!(resp, coin(0, "usdc"))
-    }
-}
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_member(members[1], 1)
+            .with_member(members[2], 1)
+            .build();
 
-mod slashing {
-    use super::*;
+        for member in &members[..3] {
+            suite.delegate_withdrawal(member, members[3]).unwrap();
+        }
+        assert_eq!(
+            suite.delegations_to(members[3], None, None).unwrap(),
+            [members[0], members[1], members[2]].map(Addr::unchecked)
+        );
+        assert_eq!(
+            suite
+                .delegations_to(members[3], members[0].to_owned(), 1)
+                .unwrap(),
+            [Addr::unchecked(members[1])]
+        );
+
+        // Changing delegation moves the owner to the new delegate
+        suite.delegate_withdrawal(members[1], members[4]).unwrap();
+        assert_eq!(suite.delegated(members[1]).unwrap().as_str(), members[4]);
+        assert_eq!(
+            suite.delegations_to(members[3], None, None).unwrap(),
+            [members[0], members[2]].map(Addr::unchecked)
+        );
+        assert_eq!(
+            suite.delegations_to(members[4], None, None).unwrap(),
+            [Addr::unchecked(members[1])]
+        );
+
+        // Delegating to self removes the delegation
+        suite.delegate_withdrawal(members[0], members[0]).unwrap();
+        assert_eq!(suite.delegated(members[0]).unwrap().as_str(), members[0]);
+        assert_eq!(
+            suite.delegations_to(members[3], None, None).unwrap(),
+            [Addr::unchecked(members[2])]
+        );
+        assert!(suite
+            .delegations_to(members[0], None, None)
+            .unwrap()
+            .is_empty());
+    }
 
     #[test]
-    fn slasher_slashes() {
-        // Initialize two members with equal points of 10. Slash one of members. Ensure proper
-        // points. Perform distribution and withdraw, ensure proper payouts.
-        let members = vec!["member1", "member2", "member3"];
+    fn distribution_fee_skimmed() {
+        let members = ["member1", "member2", "distributor"];
+        let treasury = "treasury";
 
         let mut suite = SuiteBuilder::new()
-            .with_member(members[0], 10)
-            .with_member(members[1], 10)
-            .with_funds(members[2], 600)
+            .with_member(members[0], 1)
+            .with_member(members[1], 3)
+            .with_funds(members[2], 400)
+            .with_distribution_fee(Decimal::percent(10), treasury)
             .build();
 
-        let admin = suite.owner.clone();
         let denom = suite.denom.clone();
 
-        suite.add_slasher(admin.as_str(), members[2]).unwrap();
-
-        assert!(!suite.is_slasher(members[1]).unwrap());
-        assert!(suite.is_slasher(members[2]).unwrap());
-
-        suite
-            .slash(members[2], members[0], Decimal::percent(50))
+        let resp = suite
+            .distribute_funds(members[2], None, &coins(400, &denom))
             .unwrap();
+        resp.assert_event(&distribution_event(members[2], &denom, 360).add_attribute("fee", "40"));
 
-        let mut slashed_members = suite.members().unwrap();
-        slashed_members.sort_by_key(|member| member.addr.clone());
-
+        assert_eq!(suite.token_balance(treasury).unwrap(), 40);
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 360);
+        assert_eq!(suite.distributed_funds().unwrap(), coin(360, &denom));
+        assert_eq!(suite.undistributed_funds().unwrap(), coin(0, &denom));
         assert_eq!(
-            slashed_members,
-            vec![member(members[0], 5), member(members[1], 10)]
+            suite.withdrawable_rewards(members[0]).unwrap(),
+            coin(90, &denom)
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[1]).unwrap(),
+            coin(270, &denom)
         );
-
-        suite
-            .distribute_funds(members[2], None, &coins(600, &denom))
-            .unwrap();
 
         suite.withdraw_funds(members[0], None, None).unwrap();
         suite.withdraw_funds(members[1], None, None).unwrap();
-
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 90);
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 270);
         assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
-        assert_eq!(suite.token_balance(members[0]).unwrap(), 200);
-        assert_eq!(suite.token_balance(members[1]).unwrap(), 400);
-        assert_eq!(suite.token_balance(members[2]).unwrap(), 0);
     }
 
     #[test]
-    fn admin_cant_slash() {
-        // Initialize two members with equal points of 10. Slash one of members. Ensure proper
-        // points. Perform distribution and withdraw, ensure proper payouts.
-        let members = vec!["member1", "member2", "member3"];
+    fn deducting_points_preserves_rewards() {
+        let members = ["member1", "member2", "distributor"];
 
         let mut suite = SuiteBuilder::new()
-            .with_member(members[0], 10)
-            .with_member(members[1], 10)
-            .with_funds(members[2], 600)
+            .with_member(members[0], 2)
+            .with_member(members[1], 2)
+            .with_funds(members[2], 700)
             .build();
 
-        let admin = suite.owner.clone();
         let denom = suite.denom.clone();
+        let admin = suite.admin().to_owned();
 
-        let err = suite
-            .slash(admin.as_str(), members[0], Decimal::percent(50))
-            .unwrap_err();
+        suite
+            .distribute_funds(members[2], None, &coins(400, &denom))
+            .unwrap();
 
+        // Only admin can deduct points
+        let err = suite.deduct_points(members[1], members[0], 1).unwrap_err();
         assert_eq!(
-            ContractError::Unauthorized("Sender is not on slashers list".to_owned()),
+            ContractError::Admin(cw_controllers::AdminError::NotAdmin {}),
             err.downcast().unwrap()
         );
 
-        let mut slashed_members = suite.members().unwrap();
-        slashed_members.sort_by_key(|member| member.addr.clone());
-
-        assert_eq!(
-            slashed_members,
-            vec![member(members[0], 10), member(members[1], 10)]
-        );
+        suite.deduct_points(&admin, members[0], 1).unwrap();
+        assert_eq!(suite.total_points().unwrap(), 3);
 
+        // Rewards earned before deduction are kept, further ones follow new points
         suite
-            .distribute_funds(members[2], None, &coins(600, &denom))
+            .distribute_funds(members[2], None, &coins(300, &denom))
             .unwrap();
+        assert_eq!(
+            suite.withdrawable_rewards(members[0]).unwrap(),
+            coin(300, &denom)
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[1]).unwrap(),
+            coin(400, &denom)
+        );
 
-        suite.withdraw_funds(members[0], None, None).unwrap();
-        suite.withdraw_funds(members[1], None, None).unwrap();
-
-        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
+        // Removed member gets its rewards paid out
+        suite.deduct_points(&admin, members[0], 5).unwrap();
+        assert_eq!(suite.total_points().unwrap(), 2);
         assert_eq!(suite.token_balance(members[0]).unwrap(), 300);
-        assert_eq!(suite.token_balance(members[1]).unwrap(), 300);
-        assert_eq!(suite.token_balance(members[2]).unwrap(), 0);
     }
 
     #[test]
-    fn non_slasher_cant_slash() {
-        // Initialize two members with equal points of 10. Slash one of members. Ensure proper
-        // points. Perform distribution and withdraw, ensure proper payouts.
-        let members = vec!["member1", "member2", "member3"];
+    fn admin_withdraws_undistributed_funds() {
+        let members = ["member1", "member2", "distributor"];
+        let treasury = "treasury";
 
         let mut suite = SuiteBuilder::new()
-            .with_member(members[0], 10)
-            .with_member(members[1], 10)
-            .with_funds(members[2], 600)
+            .with_member(members[0], 1)
+            .with_member(members[1], 3)
+            .with_funds(members[2], 500)
             .build();
 
         let denom = suite.denom.clone();
+        let admin = suite.admin().to_owned();
+        let contract = suite.contract.to_string();
+
+        suite
+            .distribute_funds(members[2], None, &coins(400, &denom))
+            .unwrap();
+        suite
+            .send_funds(members[2], &contract, &coins(100, &denom))
+            .unwrap();
+        let distribution = suite.distribution_data(None).unwrap().unwrap();
 
+        // Only admin can sweep funds
         let err = suite
-            .slash(members[2], members[0], Decimal::percent(50))
+            .admin_withdraw_undistributed(members[0], treasury)
             .unwrap_err();
-
         assert_eq!(
-            ContractError::Unauthorized("Sender is not on slashers list".to_owned()),
+            ContractError::Admin(cw_controllers::AdminError::NotAdmin {}),
             err.downcast().unwrap()
         );
 
-        let mut slashed_members = suite.members().unwrap();
-        slashed_members.sort_by_key(|member| member.addr.clone());
-
+        suite
+            .admin_withdraw_undistributed(&admin, treasury)
+            .unwrap();
+        assert_eq!(suite.token_balance(treasury).unwrap(), 100);
+        assert_eq!(suite.undistributed_funds().unwrap(), coin(0, &denom));
         assert_eq!(
-            slashed_members,
-            vec![member(members[0], 10), member(members[1], 10)]
+            suite.distribution_data(None).unwrap().unwrap(),
+            distribution
         );
 
+        // Nothing more to sweep
         suite
-            .distribute_funds(members[2], None, &coins(600, &denom))
+            .admin_withdraw_undistributed(&admin, treasury)
             .unwrap();
+        assert_eq!(suite.token_balance(treasury).unwrap(), 100);
 
+        // Members still withdraw rewards distributed before
         suite.withdraw_funds(members[0], None, None).unwrap();
         suite.withdraw_funds(members[1], None, None).unwrap();
-
-        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
-        assert_eq!(suite.token_balance(members[0]).unwrap(), 300);
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 100);
         assert_eq!(suite.token_balance(members[1]).unwrap(), 300);
-        assert_eq!(suite.token_balance(members[2]).unwrap(), 0);
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
     }
 
     #[test]
-    fn remove_slasher() {
-        // Add then remove slasher by admin. Then ensure that the removed slasher can't slash
-        let members = vec!["member1", "member2", "member3"];
+    fn distribution_fee_sent_to_admin_by_default() {
+        let members = ["member1", "member2", "distributor"];
 
-        let mut suite = SuiteBuilder::new().with_member(members[0], 10).build();
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_member(members[1], 3)
+            .with_funds(members[2], 400)
+            .with_distribution_fee(Decimal::percent(10), None)
+            .build();
 
-        let admin = suite.owner.clone();
+        let denom = suite.denom.clone();
+        let admin = suite.admin().to_owned();
 
-        suite.add_slasher(admin.as_ref(), members[1]).unwrap();
-        suite.add_slasher(admin.as_ref(), members[2]).unwrap();
+        suite
+            .distribute_funds(members[2], None, &coins(400, &denom))
+            .unwrap();
+
+        assert_eq!(suite.token_balance(&admin).unwrap(), 40);
+        // Only the remaining 360 tokens are split between 4 points
+        let distribution = suite.distribution_data(None).unwrap().unwrap();
         assert_eq!(
-            suite.list_slashers().unwrap(),
-            vec![members[1].to_owned(), members[2].to_owned()]
+            distribution.shares_per_point,
+            Uint128::new(90 << crate::state::SHARES_SHIFT)
         );
+        assert_eq!(distribution.distributed_total, Uint128::new(360));
+    }
 
-        suite.remove_slasher(admin.as_ref(), members[1]).unwrap();
-        assert_eq!(suite.list_slashers().unwrap(), vec![members[2].to_owned()]);
+    #[test]
+    fn distribution_params_reflect_distributed_shares() {
+        let members = ["member1", "member2", "distributor"];
 
-        suite.remove_slasher(admin.as_ref(), members[2]).unwrap();
-        assert!(suite.list_slashers().unwrap().is_empty());
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_member(members[1], 3)
+            .with_funds(members[2], 400)
+            .build();
 
-        let err = suite
-            .slash(members[1], members[0], Decimal::percent(50))
-            .unwrap_err();
+        let denom = suite.denom.clone();
+
+        let params = suite.distribution_params().unwrap();
+        assert_eq!(params.shares_shift, crate::state::SHARES_SHIFT);
+        assert_eq!(params.shares_per_point, Uint128::zero());
+
+        suite
+            .distribute_funds(members[2], None, &coins(400, &denom))
+            .unwrap();
 
+        let params = suite.distribution_params().unwrap();
         assert_eq!(
-            ContractError::Unauthorized("Sender is not on slashers list".to_owned()),
-            err.downcast().unwrap()
+            params.shares_per_point,
+            Uint128::new(100 << crate::state::SHARES_SHIFT)
         );
     }
 
     #[test]
-    fn slasher_removes_himself() {
-        // Add then remove slasher by himself. Then ensure that the removed slasher can't slash
-        let members = vec!["member1", "member2"];
-
-        let mut suite = SuiteBuilder::new().with_member(members[0], 10).build();
+    fn overflowing_distribution_fails_gracefully() {
+        let members = ["member1", "distributor"];
 
-        let admin = suite.owner.clone();
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_funds(members[1], 1 << 97)
+            .build();
 
-        suite.add_slasher(admin.as_ref(), members[1]).unwrap();
-        suite.remove_slasher(members[1], members[1]).unwrap();
+        let denom = suite.denom.clone();
 
+        // Amount doesn't fit in 128 bits once shifted to shares
         let err = suite
-            .slash(members[1], members[0], Decimal::percent(50))
+            .distribute_funds(members[1], None, &coins(1 << 97, &denom))
             .unwrap_err();
-
         assert_eq!(
-            ContractError::Unauthorized("Sender is not on slashers list".to_owned()),
+            ContractError::DistributionOverflow {},
             err.downcast().unwrap()
         );
+
+        assert_eq!(
+            suite.distribution_params().unwrap().shares_per_point,
+            Uint128::zero()
+        );
     }
 
     #[test]
-    fn non_admin_cant_add_slasher_without_preauth() {
-        // Add then remove slasher by himself. Then ensure that the removed slasher can't slash
-        let members = vec!["member1", "member2"];
+    fn overflowing_withdrawal_fails_gracefully() {
+        let members = ["member1", "distributor"];
+        let amount = u128::MAX >> crate::state::SHARES_SHIFT;
 
-        let mut suite = SuiteBuilder::new().with_member(members[0], 10).build();
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_funds(members[1], amount)
+            .build();
 
-        let err = suite.add_slasher(members[0], members[1]).unwrap_err();
+        let denom = suite.denom.clone();
 
+        // Largest amount which can be distributed, but its shares exceed signed 128 bits
+        suite
+            .distribute_funds(members[1], None, &coins(amount, &denom))
+            .unwrap();
+
+        suite.withdrawable_rewards(members[0]).unwrap_err();
+        let err = suite.withdraw_funds(members[0], None, None).unwrap_err();
         assert_eq!(
-            ContractError::Preauth(PreauthError::NoPreauth {}),
+            ContractError::DistributionOverflow {},
             err.downcast().unwrap()
         );
     }
 
     #[test]
-    fn add_slasher_with_preauth() {
-        // Initialize two members with equal points of 10. Slash one of members. Ensure proper
-        // points. Perform distribution and withdraw, ensure proper payouts.
-        let members = vec!["member1", "member2", "member3"];
+    fn auto_distribution_on_end_block() {
+        let members = ["member1", "member2", "distributor"];
 
         let mut suite = SuiteBuilder::new()
-            .with_member(members[0], 10)
-            .with_member(members[1], 10)
-            .with_funds(members[2], 600)
-            .with_preaths_slashing(1)
+            .with_member(members[0], 1)
+            .with_member(members[1], 3)
+            .with_funds(members[2], 400)
+            .with_auto_distribute()
             .build();
 
         let denom = suite.denom.clone();
-
-        suite.add_slasher(members[2], members[2]).unwrap();
+        let contract = suite.contract.clone();
 
         suite
-            .slash(members[2], members[0], Decimal::percent(50))
+            .send_funds(members[2], contract.as_str(), &coins(400, &denom))
             .unwrap();
+        assert_eq!(suite.undistributed_funds().unwrap(), coin(400, &denom));
 
-        let mut slashed_members = suite.members().unwrap();
-        slashed_members.sort_by_key(|member| member.addr.clone());
+        suite.app.next_block().unwrap();
 
+        assert_eq!(suite.undistributed_funds().unwrap(), coin(0, &denom));
+        assert_eq!(suite.distributed_funds().unwrap(), coin(400, &denom));
         assert_eq!(
-            slashed_members,
-            vec![member(members[0], 5), member(members[1], 10)]
+            suite.withdrawable_rewards(members[0]).unwrap(),
+            coin(100, &denom)
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[1]).unwrap(),
+            coin(300, &denom)
         );
+    }
+
+    #[test]
+    fn auto_distribution_without_members() {
+        let members = ["member1", "distributor"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_funds(members[1], 400)
+            .with_auto_distribute()
+            .build();
+
+        let denom = suite.denom.clone();
+        let contract = suite.contract.clone();
+        let admin = suite.admin().to_owned();
 
         suite
-            .distribute_funds(members[2], None, &coins(600, &denom))
+            .send_funds(members[1], contract.as_str(), &coins(400, &denom))
             .unwrap();
 
-        suite.withdraw_funds(members[0], None, None).unwrap();
-        suite.withdraw_funds(members[1], None, None).unwrap();
+        // Nothing to distribute to, rewards wait for members
+        suite.app.next_block().unwrap();
+        assert_eq!(suite.undistributed_funds().unwrap(), coin(400, &denom));
 
-        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
-        assert_eq!(suite.token_balance(members[0]).unwrap(), 200);
-        assert_eq!(suite.token_balance(members[1]).unwrap(), 400);
-        assert_eq!(suite.token_balance(members[2]).unwrap(), 0);
+        suite
+            .modify_members(&admin, &[(members[0], 1)], &[])
+            .unwrap();
+        suite.app.next_block().unwrap();
+        assert_eq!(
+            suite.withdrawable_rewards(members[0]).unwrap(),
+            coin(400, &denom)
+        );
     }
 
     #[test]
-    fn cant_remove_other_slasher() {
-        // Add then remove slasher by other slasher. Then ensure that the removed slasher can
-        // slash still.
-        let members = vec!["member1", "member2"];
-
-        let mut suite = SuiteBuilder::new().with_member(members[0], 10).build();
+    fn batch_withdrawal() {
+        let members = ["member1", "member2", "member3", "member4", "distributor"];
+        let keeper = "keeper";
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 2)
+            .with_member(members[1], 3)
+            .with_member(members[2], 5)
+            .with_funds(members[4], 200)
+            .build();
+
+        let denom = suite.denom.clone();
+
+        suite
+            .distribute_funds(members[4], None, &coins(100, &denom))
+            .unwrap();
+
+        for member in &members[..3] {
+            suite.delegate_withdrawal(member, keeper).unwrap();
+        }
+        // Owner with nothing to withdraw is skipped
+        suite.withdraw_funds(members[2], None, None).unwrap();
+
+        let resp = suite
+            .withdraw_funds_batch(keeper, &members[..3], members[3])
+            .unwrap();
+        resp.assert_event(
+            &Event::new("wasm")
+                .add_attribute("action", "withdraw_rewards_batch")
+                .add_attribute("sender", keeper)
+                .add_attribute("receiver", members[3])
+                .add_attribute("owner", members[0])
+                .add_attribute("reward", &denom)
+                .add_attribute("amount", "20")
+                .add_attribute("owner", members[1])
+                .add_attribute("reward", &denom)
+                .add_attribute("amount", "30"),
+        );
+
+        assert_eq!(suite.token_balance(members[3]).unwrap(), 50);
+        assert_eq!(suite.token_balance(members[2]).unwrap(), 50);
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
+
+        suite
+            .distribute_funds(members[4], None, &coins(100, &denom))
+            .unwrap();
+
+        // Keeper is not delegated by `member4`, so whole batch fails
+        let err = suite
+            .withdraw_funds_batch(keeper, &[members[0], members[3]], None)
+            .unwrap_err();
+        assert_eq!(
+            ContractError::Unauthorized("Sender is neither owner or delegated".to_owned()),
+            err.downcast().unwrap()
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[0]).unwrap(),
+            coin(20, &denom)
+        );
+        assert_eq!(suite.token_balance(keeper).unwrap(), 0);
+    }
+
+    #[test]
+    fn withdraw_and_distribute_to_sub_engagement() {
+        let members = ["member1", "subdao", "distributor"];
+        let sub_members = ["submember1", "submember2"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_member(members[1], 3)
+            .with_funds(members[2], 400)
+            .build();
+        let sub = suite
+            .instantiate_engagement(vec![member(sub_members[0], 1), member(sub_members[1], 2)])
+            .unwrap();
+
+        let denom = suite.denom.clone();
+
+        suite
+            .distribute_funds(members[2], None, &coins(400, &denom))
+            .unwrap();
+
+        // Only owner or delegated can forward the rewards
+        let err = suite
+            .withdraw_and_distribute(members[0], members[1], &sub)
+            .unwrap_err();
+        assert_eq!(
+            ContractError::Unauthorized("Sender is neither owner or delegated".to_owned()),
+            err.downcast().unwrap()
+        );
+
+        let resp = suite
+            .withdraw_and_distribute(members[1], None, &sub)
+            .unwrap();
+        resp.assert_event(
+            &Event::new("wasm")
+                .add_attribute("action", "withdraw_and_distribute")
+                .add_attribute("sender", members[1])
+                .add_attribute("owner", members[1])
+                .add_attribute("target", sub.as_str())
+                .add_attribute("reward", &denom)
+                .add_attribute("amount", "300"),
+        );
+
+        assert_eq!(
+            suite.withdrawable_rewards(members[1]).unwrap(),
+            coin(0, &denom)
+        );
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 100);
+        assert_eq!(suite.token_balance(sub.as_str()).unwrap(), 300);
+        // Successful forward leaves nothing pending
+        assert_eq!(suite.pending_forward().unwrap(), None);
+
+        suite.withdraw_funds_from(&sub, sub_members[0]).unwrap();
+        suite.withdraw_funds_from(&sub, sub_members[1]).unwrap();
+
+        assert_eq!(suite.token_balance(sub_members[0]).unwrap(), 100);
+        assert_eq!(suite.token_balance(sub_members[1]).unwrap(), 200);
+        assert_eq!(suite.token_balance(sub.as_str()).unwrap(), 0);
+    }
+
+    #[test]
+    fn withdraw_and_distribute_to_failing_target_accrues_locally() {
+        let members = ["member1", "subdao", "distributor"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_member(members[1], 3)
+            .with_funds(members[2], 400)
+            .build();
+        // Sub-contract without members reverts on distribution
+        let sub = suite.instantiate_engagement(vec![]).unwrap();
+
+        let denom = suite.denom.clone();
+
+        suite
+            .distribute_funds(members[2], None, &coins(400, &denom))
+            .unwrap();
+
+        let resp = suite
+            .withdraw_and_distribute(members[1], None, &sub)
+            .unwrap();
+        resp.assert_event(
+            &Event::new("wasm")
+                .add_attribute("action", "withdraw_and_distribute_failed")
+                .add_attribute("owner", members[1]),
+        );
+
+        // Failed portion stays withdrawable by the owner
+        assert_eq!(
+            suite.withdrawable_rewards(members[1]).unwrap(),
+            coin(300, &denom)
+        );
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 400);
+        assert_eq!(suite.token_balance(sub.as_str()).unwrap(), 0);
+        assert_eq!(suite.pending_forward().unwrap(), None);
+
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        suite.withdraw_funds(members[1], None, None).unwrap();
+
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 100);
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 300);
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
+    }
+
+    #[test]
+    fn listing_withdrawable_rewards() {
+        let members = ["member1", "member2", "member3", "distributor"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_member(members[1], 2)
+            .with_member(members[2], 5)
+            .with_funds(members[3], 400)
+            .build();
+
+        let denom = suite.denom.clone();
+
+        suite
+            .distribute_funds(members[3], None, &coins(400, &denom))
+            .unwrap();
+        suite.withdraw_funds(members[1], None, None).unwrap();
+
+        let rewards = suite.list_withdrawable_rewards(None, None).unwrap();
+        assert_eq!(
+            rewards,
+            vec![
+                (Addr::unchecked(members[0]), coin(50, &denom)),
+                (Addr::unchecked(members[1]), coin(0, &denom)),
+                (Addr::unchecked(members[2]), coin(250, &denom)),
+            ]
+        );
+
+        // Paginated
+        let page = suite.list_withdrawable_rewards(None, 2).unwrap();
+        assert_eq!(page, rewards[..2]);
+        let page = suite
+            .list_withdrawable_rewards(page[1].0.to_string(), 2)
+            .unwrap();
+        assert_eq!(page, rewards[2..]);
+    }
+
+    #[test]
+    fn querying_unknown_address() {
+        let suite = SuiteBuilder::new().with_denom("usdc").build();
+
+        let resp = suite.withdrawable_rewards("unknown").unwrap();
+        assert_eq!(resp, coin(0, "usdc"))
+    }
+
+    #[test]
+    fn simulated_distribution_matches_actual() {
+        let members = ["member1", "member2", "member3"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_member(members[1], 2)
+            .with_funds(members[2], 300)
+            .with_distribution_fee(Decimal::percent(10), members[2])
+            .build();
+
+        let denom = suite.denom.clone();
+        let contract = suite.contract.clone();
+
+        suite
+            .send_funds(members[2], contract.as_str(), &coins(100, &denom))
+            .unwrap();
+
+        // 10 tokens taken as a fee
+        let simulated = suite.simulate_distribution(None).unwrap();
+        assert_eq!(simulated.amount, Uint128::new(90));
+        assert_eq!(simulated.points_per_share, Uint128::new((90 << 32) / 3));
+        assert_eq!(simulated.leftover, 0);
+
+        // Simulation doesn't change anything
+        assert_eq!(suite.simulate_distribution(None).unwrap(), simulated);
+
+        suite.distribute_funds(members[2], None, &[]).unwrap();
+        let distribution = suite.distribution_data(None).unwrap().unwrap();
+        assert_eq!(distribution.shares_per_point, simulated.points_per_share);
+        assert_eq!(distribution.shares_leftover, simulated.leftover);
+        assert_eq!(distribution.distributed_total, simulated.amount);
+
+        // Leftover of non-divisible amount is accounted for
+        suite
+            .send_funds(members[2], contract.as_str(), &coins(101, &denom))
+            .unwrap();
+        let simulated = suite.simulate_distribution(None).unwrap();
+        assert_eq!(simulated.amount, Uint128::new(91));
+        assert_eq!(simulated.leftover, 1);
+        suite.distribute_funds(members[2], None, &[]).unwrap();
+        let new_distribution = suite.distribution_data(None).unwrap().unwrap();
+        assert_eq!(
+            new_distribution.shares_per_point,
+            distribution.shares_per_point + simulated.points_per_share
+        );
+        assert_eq!(new_distribution.shares_leftover, simulated.leftover);
+
+        // Nothing left to distribute
+        let simulated = suite.simulate_distribution(None).unwrap();
+        assert_eq!(simulated.amount, Uint128::zero());
+        assert_eq!(simulated.points_per_share, Uint128::zero());
+    }
+
+    #[test]
+    fn simulated_distribution_without_members() {
+        let suite = SuiteBuilder::new().build();
+
+        assert_eq!(
+            suite.simulate_distribution(None).unwrap(),
+            SimulateDistributionResponse {
+                points_per_share: Uint128::zero(),
+                amount: Uint128::zero(),
+                leftover: 0,
+            }
+        );
+    }
+}
+
+mod multi_denom_distribution {
+    use super::*;
+
+    #[test]
+    fn multiple_denoms_distributed_and_withdrawn() {
+        let members = ["member1", "member2", "distributor"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_member(members[1], 3)
+            .with_funds(members[2], 400)
+            .with_denom_funds(members[2], coin(800, "uatom"))
+            .build();
+
+        let denom = suite.denom.clone();
+
+        let resp = suite
+            .distribute_funds(members[2], None, &[coin(800, "uatom"), coin(400, &denom)])
+            .unwrap();
+
+        resp.assert_event(
+            &Event::new("wasm")
+                .add_attribute("denom", "uatom")
+                .add_attribute("amount", "800"),
+        );
+        resp.assert_event(
+            &Event::new("wasm")
+                .add_attribute("denom", &denom)
+                .add_attribute("amount", "400"),
+        );
+
+        assert_eq!(
+            suite.withdrawable_rewards_all(members[0]).unwrap(),
+            vec![coin(200, "uatom"), coin(100, &denom)]
+        );
+        assert_eq!(
+            suite.withdrawable_rewards_all(members[1]).unwrap(),
+            vec![coin(600, "uatom"), coin(300, &denom)]
+        );
+        // Single denom query still refers to the instantiation denom
+        assert_eq!(
+            suite.withdrawable_rewards(members[0]).unwrap(),
+            coin(100, &denom)
+        );
+
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        suite.withdraw_funds(members[1], None, None).unwrap();
+
+        assert_eq!(suite.denom_balance(members[0], "uatom").unwrap(), 200);
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 100);
+        assert_eq!(suite.denom_balance(members[1], "uatom").unwrap(), 600);
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 300);
+        assert_eq!(
+            suite
+                .denom_balance(suite.contract.as_str(), "uatom")
+                .unwrap(),
+            0
+        );
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
+
+        assert_eq!(
+            suite.withdrawable_rewards_all(members[0]).unwrap(),
+            vec![coin(0, "uatom"), coin(0, &denom)]
+        );
+    }
+
+    #[test]
+    fn points_changed_between_denoms_distribution() {
+        let members = ["member1", "member2", "distributor"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_member(members[1], 1)
+            .with_funds(members[2], 100)
+            .with_denom_funds(members[2], coin(400, "uatom"))
+            .build();
+
+        let denom = suite.denom.clone();
+        let owner = suite.owner.clone();
+
+        suite
+            .distribute_funds(members[2], None, &coins(100, &denom))
+            .unwrap();
+        suite
+            .modify_members(owner.as_str(), &[(members[0], 3)], &[])
+            .unwrap();
+        suite
+            .distribute_funds(members[2], None, &coins(400, "uatom"))
+            .unwrap();
+
+        assert_eq!(
+            suite.withdrawable_rewards_all(members[0]).unwrap(),
+            vec![coin(300, "uatom"), coin(50, &denom)]
+        );
+        assert_eq!(
+            suite.withdrawable_rewards_all(members[1]).unwrap(),
+            vec![coin(100, "uatom"), coin(50, &denom)]
+        );
+    }
+
+    #[test]
+    fn distributing_single_denom() {
+        let members = ["member1", "distributor"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_funds(members[1], 100)
+            .with_denom_funds(members[1], coin(200, "uatom"))
+            .build();
+
+        let denom = suite.denom.clone();
+
+        suite
+            .distribute_denom_funds(
+                members[1],
+                "uatom",
+                &[coin(200, "uatom"), coin(100, &denom)],
+            )
+            .unwrap();
+
+        assert_eq!(
+            suite.withdrawable_rewards_all(members[0]).unwrap(),
+            vec![coin(200, "uatom"), coin(0, &denom)]
+        );
+        assert_eq!(suite.undistributed_funds().unwrap(), coin(100, &denom));
+
+        // Undistributed funds are picked by the next distribution
+        suite.distribute_funds(members[1], None, &[]).unwrap();
+        assert_eq!(
+            suite.withdrawable_rewards_all(members[0]).unwrap(),
+            vec![coin(200, "uatom"), coin(100, &denom)]
+        );
+    }
+
+    #[test]
+    fn distributing_too_many_denoms() {
+        let members = ["member1", "distributor"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_max_denoms(2)
+            .with_denom_funds(members[1], coin(100, "uatom"))
+            .with_denom_funds(members[1], coin(100, "ueth"))
+            .build();
+
+        let denom = suite.denom.clone();
+
+        // Instantiation denom is already counted
+        suite
+            .distribute_funds(members[1], None, &coins(100, "uatom"))
+            .unwrap();
+
+        let err = suite
+            .distribute_funds(members[1], None, &coins(100, "ueth"))
+            .unwrap_err();
+        assert_eq!(
+            ContractError::TooManyDenoms { max: 2 },
+            err.downcast().unwrap()
+        );
+
+        assert_eq!(
+            suite.withdrawable_rewards_all(members[0]).unwrap(),
+            vec![coin(100, "uatom"), coin(0, &denom)]
+        );
+    }
+}
+
+mod slashing {
+    use super::*;
+    use crate::msg::SimulateSlashResponse;
+
+    #[test]
+    fn slasher_slashes() {
+        // Initialize two members with equal points of 10. Slash one of members. Ensure proper
+        // points. Perform distribution and withdraw, ensure proper payouts.
+        let members = vec!["member1", "member2", "member3"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 10)
+            .with_member(members[1], 10)
+            .with_funds(members[2], 600)
+            .build();
+
+        let admin = suite.owner.clone();
+        let denom = suite.denom.clone();
+
+        suite.add_slasher(admin.as_str(), members[2]).unwrap();
+
+        assert!(!suite.is_slasher(members[1]).unwrap());
+        assert!(suite.is_slasher(members[2]).unwrap());
+
+        suite
+            .slash(members[2], members[0], Decimal::percent(50))
+            .unwrap();
+
+        let mut slashed_members = suite.members().unwrap();
+        slashed_members.sort_by_key(|member| member.addr.clone());
+
+        assert_eq!(
+            slashed_members,
+            vec![member(members[0], 5), member(members[1], 10)]
+        );
+
+        suite
+            .distribute_funds(members[2], None, &coins(600, &denom))
+            .unwrap();
+
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        suite.withdraw_funds(members[1], None, None).unwrap();
+
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 200);
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 400);
+        assert_eq!(suite.token_balance(members[2]).unwrap(), 0);
+    }
+
+    #[test]
+    fn simulate_slash_matches_slash() {
+        let members = ["member1", "member2", "member3"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 15)
+            .with_member(members[1], 10)
+            .build();
+
+        let admin = suite.owner.clone();
+        suite.add_slasher(admin.as_str(), members[2]).unwrap();
+
+        // Invalid portions are rejected
+        suite
+            .simulate_slash(members[0], Decimal::zero())
+            .unwrap_err();
+        suite
+            .simulate_slash(members[0], Decimal::percent(101))
+            .unwrap_err();
+
+        // Non-member slash is a no-op
+        assert_eq!(
+            suite
+                .simulate_slash(members[2], Decimal::percent(50))
+                .unwrap(),
+            SimulateSlashResponse {
+                old_points: 0,
+                new_points: 0,
+                total_after: 25,
+            }
+        );
+
+        let simulated = suite
+            .simulate_slash(members[0], Decimal::percent(30))
+            .unwrap();
+        assert_eq!(
+            simulated,
+            SimulateSlashResponse {
+                old_points: 15,
+                new_points: 11,
+                total_after: 21,
+            }
+        );
+        // Simulation doesn't change anything
+        assert_eq!(suite.total_points().unwrap(), 25);
+
+        suite
+            .slash(members[2], members[0], Decimal::percent(30))
+            .unwrap();
+
+        let mut slashed_members = suite.members().unwrap();
+        slashed_members.sort_by_key(|member| member.addr.clone());
+        assert_eq!(
+            slashed_members,
+            vec![
+                member(members[0], simulated.new_points),
+                member(members[1], 10)
+            ]
+        );
+        assert_eq!(suite.total_points().unwrap(), simulated.total_after);
+    }
+
+    #[test]
+    fn admin_cant_slash() {
+        // Initialize two members with equal points of 10. Slash one of members. Ensure proper
+        // points. Perform distribution and withdraw, ensure proper payouts.
+        let members = vec!["member1", "member2", "member3"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 10)
+            .with_member(members[1], 10)
+            .with_funds(members[2], 600)
+            .build();
+
+        let admin = suite.owner.clone();
+        let denom = suite.denom.clone();
+
+        let err = suite
+            .slash(admin.as_str(), members[0], Decimal::percent(50))
+            .unwrap_err();
+
+        assert_eq!(
+            ContractError::Unauthorized("Sender is not on slashers list".to_owned()),
+            err.downcast().unwrap()
+        );
+
+        let mut slashed_members = suite.members().unwrap();
+        slashed_members.sort_by_key(|member| member.addr.clone());
+
+        assert_eq!(
+            slashed_members,
+            vec![member(members[0], 10), member(members[1], 10)]
+        );
+
+        suite
+            .distribute_funds(members[2], None, &coins(600, &denom))
+            .unwrap();
+
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        suite.withdraw_funds(members[1], None, None).unwrap();
+
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 300);
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 300);
+        assert_eq!(suite.token_balance(members[2]).unwrap(), 0);
+    }
+
+    #[test]
+    fn non_slasher_cant_slash() {
+        // Initialize two members with equal points of 10. Slash one of members. Ensure proper
+        // points. Perform distribution and withdraw, ensure proper payouts.
+        let members = vec!["member1", "member2", "member3"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 10)
+            .with_member(members[1], 10)
+            .with_funds(members[2], 600)
+            .build();
+
+        let denom = suite.denom.clone();
+
+        let err = suite
+            .slash(members[2], members[0], Decimal::percent(50))
+            .unwrap_err();
+
+        assert_eq!(
+            ContractError::Unauthorized("Sender is not on slashers list".to_owned()),
+            err.downcast().unwrap()
+        );
+
+        let mut slashed_members = suite.members().unwrap();
+        slashed_members.sort_by_key(|member| member.addr.clone());
+
+        assert_eq!(
+            slashed_members,
+            vec![member(members[0], 10), member(members[1], 10)]
+        );
+
+        suite
+            .distribute_funds(members[2], None, &coins(600, &denom))
+            .unwrap();
+
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        suite.withdraw_funds(members[1], None, None).unwrap();
+
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 300);
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 300);
+        assert_eq!(suite.token_balance(members[2]).unwrap(), 0);
+    }
+
+    #[test]
+    fn remove_slasher() {
+        // Add then remove slasher by admin. Then ensure that the removed slasher can't slash
+        let members = vec!["member1", "member2", "member3"];
+
+        let mut suite = SuiteBuilder::new().with_member(members[0], 10).build();
+
+        let admin = suite.owner.clone();
+
+        suite.add_slasher(admin.as_ref(), members[1]).unwrap();
+        suite.add_slasher(admin.as_ref(), members[2]).unwrap();
+        assert_eq!(
+            suite.list_slashers().unwrap(),
+            vec![members[1].to_owned(), members[2].to_owned()]
+        );
+
+        suite.remove_slasher(admin.as_ref(), members[1]).unwrap();
+        assert_eq!(suite.list_slashers().unwrap(), vec![members[2].to_owned()]);
+
+        suite.remove_slasher(admin.as_ref(), members[2]).unwrap();
+        assert!(suite.list_slashers().unwrap().is_empty());
+
+        let err = suite
+            .slash(members[1], members[0], Decimal::percent(50))
+            .unwrap_err();
+
+        assert_eq!(
+            ContractError::Unauthorized("Sender is not on slashers list".to_owned()),
+            err.downcast().unwrap()
+        );
+    }
+
+    #[test]
+    fn slasher_removes_himself() {
+        // Add then remove slasher by himself. Then ensure that the removed slasher can't slash
+        let members = vec!["member1", "member2"];
+
+        let mut suite = SuiteBuilder::new().with_member(members[0], 10).build();
+
+        let admin = suite.owner.clone();
+
+        suite.add_slasher(admin.as_ref(), members[1]).unwrap();
+        suite.remove_slasher(members[1], members[1]).unwrap();
+
+        let err = suite
+            .slash(members[1], members[0], Decimal::percent(50))
+            .unwrap_err();
+
+        assert_eq!(
+            ContractError::Unauthorized("Sender is not on slashers list".to_owned()),
+            err.downcast().unwrap()
+        );
+    }
+
+    #[test]
+    fn non_admin_cant_add_slasher_without_preauth() {
+        // Add then remove slasher by himself. Then ensure that the removed slasher can't slash
+        let members = vec!["member1", "member2"];
+
+        let mut suite = SuiteBuilder::new().with_member(members[0], 10).build();
+
+        let err = suite.add_slasher(members[0], members[1]).unwrap_err();
+
+        assert_eq!(
+            ContractError::Preauth(PreauthError::NoPreauth {}),
+            err.downcast().unwrap()
+        );
+    }
+
+    #[test]
+    fn add_slasher_with_preauth() {
+        // Initialize two members with equal points of 10. Slash one of members. Ensure proper
+        // points. Perform distribution and withdraw, ensure proper payouts.
+        let members = vec!["member1", "member2", "member3"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 10)
+            .with_member(members[1], 10)
+            .with_funds(members[2], 600)
+            .with_preaths_slashing(1)
+            .build();
+
+        let denom = suite.denom.clone();
+
+        suite.add_slasher(members[2], members[2]).unwrap();
+
+        suite
+            .slash(members[2], members[0], Decimal::percent(50))
+            .unwrap();
+
+        let mut slashed_members = suite.members().unwrap();
+        slashed_members.sort_by_key(|member| member.addr.clone());
+
+        assert_eq!(
+            slashed_members,
+            vec![member(members[0], 5), member(members[1], 10)]
+        );
+
+        suite
+            .distribute_funds(members[2], None, &coins(600, &denom))
+            .unwrap();
+
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        suite.withdraw_funds(members[1], None, None).unwrap();
+
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 200);
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 400);
+        assert_eq!(suite.token_balance(members[2]).unwrap(), 0);
+    }
+
+    #[test]
+    fn cant_remove_other_slasher() {
+        // Add then remove slasher by other slasher. Then ensure that the removed slasher can
+        // slash still.
+        let members = vec!["member1", "member2"];
+
+        let mut suite = SuiteBuilder::new().with_member(members[0], 10).build();
+
+        let admin = suite.owner.clone();
+
+        suite.add_slasher(admin.as_ref(), members[1]).unwrap();
+        let err = suite.remove_slasher(members[0], members[1]).unwrap_err();
+
+        assert_eq!(
+            ContractError::Unauthorized(
+                "Only slasher might remove himself or sender is not an admin".to_owned()
+            ),
+            err.downcast().unwrap()
+        );
+
+        suite
+            .slash(members[1], members[0], Decimal::percent(50))
+            .unwrap();
+    }
+
+    #[test]
+    fn slashing_after_distribution() {
+        // Perform full tokens distribution and withdrawal. Then slash one member. Perform another
+        // full distribution and withdrawal. Ensure all funds are as expected (the second
+        // distribution rewards are splitted with aligned points.
+        let members = vec!["member1", "member2", "member3"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 10)
+            .with_member(members[1], 10)
+            .with_funds(members[2], 1200)
+            .build();
+
+        let admin = suite.owner.clone();
+        let denom = suite.denom.clone();
+
+        suite.add_slasher(admin.as_str(), members[2]).unwrap();
+
+        suite
+            .distribute_funds(members[2], None, &coins(600, &denom))
+            .unwrap();
+
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        suite.withdraw_funds(members[1], None, None).unwrap();
+
+        suite
+            .slash(members[2], members[0], Decimal::percent(50))
+            .unwrap();
+
+        suite
+            .distribute_funds(members[2], None, &coins(600, &denom))
+            .unwrap();
+
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        suite.withdraw_funds(members[1], None, None).unwrap();
+
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 500);
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 700);
+        assert_eq!(suite.token_balance(members[2]).unwrap(), 0);
+    }
+
+    #[test]
+    fn slashing_while_withdrawal_pending() {
+        // Perform rewards distribution, but don't withdraw funds. Then slash one member. Perform
+        // another distribution, and withdraw all funds. Ensure funds are as expected.
+        let members = vec!["member1", "member2", "member3"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 10)
+            .with_member(members[1], 10)
+            .with_funds(members[2], 1200)
+            .build();
+
+        let admin = suite.owner.clone();
+        let denom = suite.denom.clone();
+
+        suite.add_slasher(admin.as_str(), members[2]).unwrap();
+
+        suite
+            .distribute_funds(members[2], None, &coins(600, &denom))
+            .unwrap();
+
+        suite
+            .slash(members[2], members[0], Decimal::percent(50))
+            .unwrap();
+
+        suite
+            .distribute_funds(members[2], None, &coins(600, &denom))
+            .unwrap();
+
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        suite.withdraw_funds(members[1], None, None).unwrap();
+
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 500);
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 700);
+        assert_eq!(suite.token_balance(members[2]).unwrap(), 0);
+    }
+
+    #[test]
+    fn slashing_does_not_reduce_earned_rewards() {
+        // Rewards are assigned according to points at the time of distribution. Slashing a member
+        // before withdrawal doesn't affect rewards already earned, even if all points are slashed.
+        let members = ["member1", "member2", "distributor"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 10)
+            .with_member(members[1], 30)
+            .with_funds(members[2], 400)
+            .with_denom_funds(members[2], coin(800, "eth"))
+            .build();
+
+        let admin = suite.owner.clone();
+        let denom = suite.denom.clone();
+
+        suite.add_slasher(admin.as_str(), members[2]).unwrap();
+
+        suite
+            .distribute_funds(members[2], None, &coins(400, &denom))
+            .unwrap();
+        suite
+            .distribute_denom_funds(members[2], "eth", &coins(800, "eth"))
+            .unwrap();
+
+        suite
+            .slash(members[2], members[0], Decimal::percent(100))
+            .unwrap();
+        suite
+            .slash(members[2], members[1], Decimal::percent(50))
+            .unwrap();
+
+        assert_eq!(
+            suite.withdrawable_rewards_all(members[0]).unwrap(),
+            [coin(200, "eth"), coin(100, &denom)]
+        );
+        assert_eq!(
+            suite.withdrawable_rewards_all(members[1]).unwrap(),
+            [coin(600, "eth"), coin(300, &denom)]
+        );
+
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        suite.withdraw_funds(members[1], None, None).unwrap();
+
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 100);
+        assert_eq!(suite.denom_balance(members[0], "eth").unwrap(), 200);
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 300);
+        assert_eq!(suite.denom_balance(members[1], "eth").unwrap(), 600);
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
+        assert_eq!(
+            suite.denom_balance(suite.contract.as_str(), "eth").unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn slashing_between_distributions_only_affects_later_rewards() {
+        let members = ["member1", "member2", "distributor"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 20)
+            .with_member(members[1], 20)
+            .with_funds(members[2], 1000)
+            .build();
+
+        let admin = suite.owner.clone();
+        let denom = suite.denom.clone();
+
+        suite.add_slasher(admin.as_str(), members[2]).unwrap();
+
+        // Split 20 / 20
+        suite
+            .distribute_funds(members[2], None, &coins(400, &denom))
+            .unwrap();
+
+        // Slashed to 5 points, earned 200 is kept
+        suite
+            .slash(members[2], members[0], Decimal::percent(75))
+            .unwrap();
+        assert_eq!(
+            suite.withdrawable_rewards(members[0]).unwrap(),
+            coin(200, &denom)
+        );
+
+        // Split 5 / 20
+        suite
+            .distribute_funds(members[2], None, &coins(500, &denom))
+            .unwrap();
+        assert_eq!(
+            suite.withdrawable_rewards(members[0]).unwrap(),
+            coin(300, &denom)
+        );
+
+        // Slashed again after withdrawal
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        suite
+            .slash(members[2], members[0], Decimal::percent(100))
+            .unwrap();
+
+        suite
+            .distribute_funds(members[2], None, &coins(100, &denom))
+            .unwrap();
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        suite.withdraw_funds(members[1], None, None).unwrap();
+
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 300);
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 700);
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
+    }
+
+    #[test]
+    fn slash_and_redistribute_conserves_points() {
+        let members = ["member1", "member2", "member3", "member4"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 10)
+            .with_member(members[1], 20)
+            .with_member(members[2], 30)
+            .with_funds(members[3], 1200)
+            .build();
+
+        let admin = suite.owner.clone();
+        let denom = suite.denom.clone();
+
+        suite.add_slasher(admin.as_str(), members[3]).unwrap();
+
+        suite
+            .distribute_funds(members[3], None, &coins(600, &denom))
+            .unwrap();
+
+        suite
+            .slash_and_redistribute(members[3], members[0], Decimal::percent(50))
+            .unwrap();
+
+        // Slashed 5 points split 2:3 between remaining members
+        let mut slashed_members = suite.members().unwrap();
+        slashed_members.sort_by_key(|member| member.addr.clone());
+        assert_eq!(
+            slashed_members,
+            [
+                member(members[0], 5),
+                member(members[1], 22),
+                member(members[2], 33)
+            ]
+        );
+        assert_eq!(suite.total_points().unwrap(), 60);
+
+        // Rewards earned before redistribution are unaffected
+        assert_eq!(
+            suite.withdrawable_rewards(members[0]).unwrap(),
+            coin(100, &denom)
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[1]).unwrap(),
+            coin(200, &denom)
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[2]).unwrap(),
+            coin(300, &denom)
+        );
+
+        suite
+            .distribute_funds(members[3], None, &coins(600, &denom))
+            .unwrap();
+
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        suite.withdraw_funds(members[1], None, None).unwrap();
+        suite.withdraw_funds(members[2], None, None).unwrap();
+
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 150);
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 420);
+        assert_eq!(suite.token_balance(members[2]).unwrap(), 630);
+        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
+    }
+
+    #[test]
+    fn slash_and_redistribute_drops_remainder() {
+        let members = ["member1", "member2", "member3", "member4"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 10)
+            .with_member(members[1], 10)
+            .with_member(members[2], 10)
+            .build();
 
         let admin = suite.owner.clone();
+        suite.add_slasher(admin.as_str(), members[3]).unwrap();
+
+        let err = suite
+            .slash_and_redistribute(members[0], members[1], Decimal::percent(50))
+            .unwrap_err();
+        assert_eq!(
+            ContractError::Unauthorized("Sender is not on slashers list".to_owned()),
+            err.downcast().unwrap()
+        );
+
+        suite
+            .slash_and_redistribute(members[3], members[0], Decimal::percent(50))
+            .unwrap();
+
+        // 5 points slashed, 2 credited to every remaining member, 1 lost to rounding
+        let mut slashed_members = suite.members().unwrap();
+        slashed_members.sort_by_key(|member| member.addr.clone());
+        assert_eq!(
+            slashed_members,
+            [
+                member(members[0], 5),
+                member(members[1], 12),
+                member(members[2], 12)
+            ]
+        );
+        assert_eq!(suite.total_points().unwrap(), 29);
+    }
+}
+
+mod halflife {
+    use super::*;
+    use crate::msg::ProjectedPointsResponse;
+
+    #[test]
+    fn projected_points_match_applied_halflives() {
+        let members = ["member1", "member2", "member3"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_member(members[1], 3)
+            .with_member(members[2], 100)
+            .with_halflife(Duration::new(100))
+            .build();
+        let start = suite
+            .halflife()
+            .unwrap()
+            .halflife_info
+            .unwrap()
+            .last_halflife;
+        let at_time = start.plus_seconds(250);
+
+        let projected: Vec<_> = members
+            .iter()
+            .map(|member| suite.projected_points(member, at_time).unwrap())
+            .collect();
+        assert_eq!(
+            projected,
+            [
+                ProjectedPointsResponse {
+                    points: Some(1),
+                    decays: 2
+                },
+                ProjectedPointsResponse {
+                    points: Some(1),
+                    decays: 2
+                },
+                ProjectedPointsResponse {
+                    points: Some(25),
+                    decays: 2
+                },
+            ]
+        );
+        assert_eq!(
+            suite.projected_points("nonmember", at_time).unwrap().points,
+            None
+        );
+        // Nothing decays before the first boundary
+        assert_eq!(
+            suite
+                .projected_points(members[2], start.plus_seconds(99))
+                .unwrap(),
+            ProjectedPointsResponse {
+                points: Some(100),
+                decays: 0
+            }
+        );
+
+        // Apply both halflives on their boundaries
+        suite.app.advance_seconds(100);
+        suite.app.end_block().unwrap();
+        suite.app.advance_seconds(100);
+        suite.app.end_block().unwrap();
+        suite.app.advance_seconds(50);
+        suite.app.end_block().unwrap();
+
+        let points: Vec<_> = suite
+            .members()
+            .unwrap()
+            .into_iter()
+            .map(|member| member.points)
+            .collect();
+        let projected: Vec<_> = projected
+            .into_iter()
+            .map(|projected| projected.points.unwrap())
+            .collect();
+        assert_eq!(points, projected);
+    }
+
+    #[test]
+    fn surviving_total_points_match_applied_halflives() {
+        let mut suite = SuiteBuilder::new()
+            .with_member("member1", 1)
+            .with_member("member2", 3)
+            .with_member("member3", 100)
+            .with_member("member4", 0)
+            .with_halflife(Duration::new(100))
+            .build();
+
+        assert_eq!(suite.surviving_total_points(0).unwrap(), 104);
+
+        let projected: Vec<_> = (1..=4)
+            .map(|halflives| suite.surviving_total_points(halflives).unwrap())
+            .collect();
+        // Members never drop below the default floor of one point, so the total never decays to
+        // zero
+        assert_eq!(projected, [52, 27, 14, 8]);
+        assert_eq!(suite.surviving_total_points(100).unwrap(), 3);
+
+        for expected in projected {
+            suite.app.advance_seconds(100);
+            suite.app.end_block().unwrap();
+            assert_eq!(suite.total_points().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn points_plateau_at_floor() {
+        let members = ["member1", "member2", "member3"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 3)
+            .with_member(members[1], 12)
+            .with_member(members[2], 100)
+            .with_halflife(Duration::new(100))
+            .with_halflife_floor(5)
+            .build();
+
+        // 100 -> 50 -> 25 -> 12 -> 6 -> 5 -> 5
+        assert_eq!(suite.surviving_total_points(6).unwrap(), 3 + 5 + 5);
+
+        for _ in 0..6 {
+            suite.app.advance_seconds(100);
+            suite.app.end_block().unwrap();
+        }
+
+        // Members below the floor are not reduced at all
+        assert_eq!(
+            suite.members().unwrap(),
+            expected_members(vec![(members[0], 3), (members[1], 5), (members[2], 5)])
+        );
+        assert_eq!(suite.total_points().unwrap(), 13);
+    }
+
+    #[test]
+    fn migration_changes_floor() {
+        let mut suite = SuiteBuilder::new()
+            .with_member("member1", 100)
+            .with_halflife(Duration::new(100))
+            .build();
+        let admin = suite.admin().to_owned();
+
+        suite
+            .migrate(
+                &admin,
+                &crate::msg::MigrateMsg {
+                    halflife: None,
+                    decay: None,
+                    max_denoms: None,
+                    auto_distribute: None,
+                    halflife_floor: Some(30),
+                    distribution_fee: None,
+                    fee_recipient: None,
+                },
+            )
+            .unwrap();
+
+        for _ in 0..4 {
+            suite.app.advance_seconds(100);
+            suite.app.end_block().unwrap();
+        }
+
+        // 100 -> 50 -> 30 -> 30 -> 30
+        assert_eq!(suite.total_points().unwrap(), 30);
+        assert_eq!(
+            suite.projected_member("member1", 10).unwrap().points,
+            Some(30)
+        );
+    }
+}
+
+mod linear_decay {
+    use super::*;
+    use crate::msg::DecayMode;
+
+    #[test]
+    fn points_reduced_per_elapsed_interval() {
+        let members = ["member1", "member2", "member3"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_member(members[1], 3)
+            .with_member(members[2], 10)
+            .with_decay(DecayMode::Linear {
+                amount: 2,
+                interval: Duration::new(100),
+            })
+            .build();
+        let start = suite.decay().unwrap().last_applied;
+
+        let projected = suite.projected_member(members[2], 3).unwrap();
+        assert_eq!(projected.points, Some(4));
+        assert_eq!(projected.next_halflife, Some(start.plus_seconds(100)));
+        assert_eq!(
+            suite.projected_member(members[1], None).unwrap().points,
+            Some(1)
+        );
+
+        // Less than a single interval elapsed, nothing changes
+        suite.app.advance_seconds(50);
+        suite.app.next_block().unwrap();
+        let mut resp = suite.members().unwrap();
+        resp.sort_by_key(|member| member.addr.clone());
+        assert_eq!(
+            resp,
+            expected_members(vec![(members[0], 1), (members[1], 3), (members[2], 10)])
+        );
+
+        // Two intervals elapsed, points never go below 1
+        suite.app.advance_seconds(150);
+        suite.app.next_block().unwrap();
+        let mut resp = suite.members().unwrap();
+        resp.sort_by_key(|member| member.addr.clone());
+        assert_eq!(
+            resp,
+            expected_members(vec![(members[0], 1), (members[1], 1), (members[2], 6)])
+        );
+
+        // Only full intervals are accounted as applied, the remainder counts towards the next one
+        let decay = suite.decay().unwrap();
+        assert_eq!(decay.last_applied, start.plus_seconds(200));
+
+        suite.app.advance_seconds(90);
+        suite.app.next_block().unwrap();
+        let mut resp = suite.members().unwrap();
+        resp.sort_by_key(|member| member.addr.clone());
+        assert_eq!(
+            resp,
+            expected_members(vec![(members[0], 1), (members[1], 1), (members[2], 4)])
+        );
+    }
+
+    #[test]
+    fn event_emitted_with_total_reduction() {
+        let mut suite = SuiteBuilder::new()
+            .with_member("member1", 5)
+            .with_member("member2", 10)
+            .with_decay(DecayMode::Linear {
+                amount: 3,
+                interval: Duration::new(100),
+            })
+            .build();
+
+        suite.app.advance_seconds(100);
+        let (resp, _) = suite.app.end_block().unwrap();
+        let height = suite.app.block_info().height;
+
+        assert_eq!(resp.len(), 1);
+        resp[0].assert_event(
+            &Event::new("wasm-linear_decay")
+                .add_attribute("periods", "1")
+                .add_attribute("height", height.to_string())
+                .add_attribute("reduction", "6"),
+        );
+    }
+
+    #[test]
+    fn rewards_split_after_decay() {
+        let members = ["member1", "member2", "member3"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 2)
+            .with_member(members[1], 6)
+            .with_funds(members[2], 1000)
+            .with_decay(DecayMode::Linear {
+                amount: 1,
+                interval: Duration::new(100),
+            })
+            .build();
+        let denom = suite.denom.clone();
+
+        // Pre-decay split, total points 2 + 6 = 8
+        suite
+            .distribute_funds(members[2], None, &coins(400, &denom))
+            .unwrap();
+
+        suite.app.advance_seconds(100);
+        suite.app.next_block().unwrap();
+
+        // Post-decay split, total points 1 + 5 = 6
+        suite
+            .distribute_funds(members[2], None, &coins(600, &denom))
+            .unwrap();
+
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        suite.withdraw_funds(members[1], None, None).unwrap();
+
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 200);
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 800);
+    }
+}
+
+mod reward_multipliers {
+    use super::*;
+    use cw_controllers::AdminError;
+
+    #[test]
+    fn doubled_member_earns_double_share() {
+        let members = ["member1", "member2", "member3"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 10)
+            .with_member(members[1], 10)
+            .with_funds(members[2], 600)
+            .build();
+        let admin = suite.admin().to_owned();
+        let denom = suite.denom.clone();
+
+        suite
+            .set_reward_multiplier(&admin, members[0], Decimal::percent(200))
+            .unwrap();
+        assert_eq!(
+            suite.reward_multiplier(members[0]).unwrap(),
+            Decimal::percent(200)
+        );
+        assert_eq!(suite.reward_multiplier(members[1]).unwrap(), Decimal::one());
+        // Points themselves are not affected
+        assert_eq!(suite.total_points().unwrap(), 20);
+
+        suite
+            .distribute_funds(members[2], None, &coins(300, &denom))
+            .unwrap();
+
+        assert_eq!(
+            suite.withdrawable_rewards(members[0]).unwrap(),
+            coin(200, &denom)
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[1]).unwrap(),
+            coin(100, &denom)
+        );
+
+        // Resetting the multiplier affects only future distributions
+        suite
+            .set_reward_multiplier(&admin, members[0], Decimal::one())
+            .unwrap();
+        suite
+            .distribute_funds(members[2], None, &coins(300, &denom))
+            .unwrap();
+
+        assert_eq!(
+            suite.withdrawable_rewards(members[0]).unwrap(),
+            coin(350, &denom)
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[1]).unwrap(),
+            coin(250, &denom)
+        );
+
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        suite.withdraw_funds(members[1], None, None).unwrap();
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 350);
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 250);
+    }
+
+    #[test]
+    fn multiplier_applies_to_points_changes() {
+        let members = ["member1", "member2", "member3"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 10)
+            .with_member(members[1], 20)
+            .with_funds(members[2], 400)
+            .build();
+        let admin = suite.admin().to_owned();
+        let denom = suite.denom.clone();
+
+        // Multiplier set before joining is applied to new member
+        suite
+            .set_reward_multiplier(&admin, members[2], Decimal::percent(300))
+            .unwrap();
+        suite
+            .modify_members(&admin, &[(members[0], 20), (members[2], 10)], &[])
+            .unwrap();
+
+        suite
+            .distribute_funds(members[2], None, &coins(350, &denom))
+            .unwrap();
+
+        assert_eq!(
+            suite.withdrawable_rewards(members[0]).unwrap(),
+            coin(100, &denom)
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[1]).unwrap(),
+            coin(100, &denom)
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[2]).unwrap(),
+            coin(150, &denom)
+        );
+    }
+
+    #[test]
+    fn multiplier_bounded() {
+        let mut suite = SuiteBuilder::new().with_member("member1", 10).build();
+        let admin = suite.admin().to_owned();
+
+        let err = suite
+            .set_reward_multiplier(&admin, "member1", Decimal::zero())
+            .unwrap_err();
+        assert_eq!(
+            ContractError::InvalidRewardMultiplier { max: 10 },
+            err.downcast().unwrap()
+        );
+
+        let err = suite
+            .set_reward_multiplier(&admin, "member1", Decimal::percent(1001))
+            .unwrap_err();
+        assert_eq!(
+            ContractError::InvalidRewardMultiplier { max: 10 },
+            err.downcast().unwrap()
+        );
+
+        suite
+            .set_reward_multiplier(&admin, "member1", Decimal::percent(1000))
+            .unwrap();
+
+        let err = suite
+            .set_reward_multiplier("member1", "member1", Decimal::percent(200))
+            .unwrap_err();
+        assert_eq!(
+            ContractError::Admin(AdminError::NotAdmin {}),
+            err.downcast().unwrap()
+        );
+    }
+}
+
+mod tagged_distribution {
+    use super::*;
+
+    #[test]
+    fn untagged_members_dont_accrue() {
+        let members = ["member1", "member2", "member3", "member4"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 10)
+            .with_member(members[1], 30)
+            .with_member(members[2], 60)
+            .with_funds(members[3], 1000)
+            .build();
+        let admin = suite.admin().to_owned();
+        let denom = suite.denom.clone();
+
+        suite
+            .set_member_tags(&admin, members[0], &["validators"])
+            .unwrap();
+        suite
+            .set_member_tags(&admin, members[1], &["validators", "oversight"])
+            .unwrap();
+
+        suite
+            .distribute_funds_to(members[3], "validators", &coins(400, &denom))
+            .unwrap();
+
+        assert_eq!(
+            suite
+                .withdrawable_rewards_for_tag(members[0], "validators")
+                .unwrap(),
+            coins(100, &denom)
+        );
+        assert_eq!(
+            suite
+                .withdrawable_rewards_for_tag(members[1], "validators")
+                .unwrap(),
+            coins(300, &denom)
+        );
+        assert_eq!(
+            suite
+                .withdrawable_rewards_for_tag(members[2], "validators")
+                .unwrap(),
+            coins(0, &denom)
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[2]).unwrap(),
+            coin(0, &denom)
+        );
+        // Nothing is left for regular distribution
+        assert_eq!(suite.undistributed_funds().unwrap(), coin(0, &denom));
 
-        suite.add_slasher(admin.as_ref(), members[1]).unwrap();
-        let err = suite.remove_slasher(members[0], members[1]).unwrap_err();
+        suite
+            .distribute_funds(members[3], None, &coins(500, &denom))
+            .unwrap();
 
+        // Regular rewards are withdrawn together with tagged ones
         assert_eq!(
-            ContractError::Unauthorized(
-                "Only slasher might remove himself or sender is not an admin".to_owned()
-            ),
-            err.downcast().unwrap()
+            suite.withdrawable_rewards(members[0]).unwrap(),
+            coin(150, &denom)
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[1]).unwrap(),
+            coin(450, &denom)
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[2]).unwrap(),
+            coin(300, &denom)
+        );
+
+        for member in &members[..3] {
+            suite.withdraw_funds(member, None, None).unwrap();
+        }
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 150);
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 450);
+        assert_eq!(suite.token_balance(members[2]).unwrap(), 300);
+        assert_eq!(
+            suite
+                .withdrawable_rewards_for_tag(members[1], "validators")
+                .unwrap(),
+            coins(0, &denom)
         );
 
+        let invariants = suite.invariants().unwrap();
+        assert!(invariants.distributed_total_matches_withdrawals);
+        assert!(invariants.shares_corrections_consistent);
+    }
+
+    #[test]
+    fn tagged_distribution_leaves_pending_rewards() {
+        let members = ["member1", "member2", "member3"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 10)
+            .with_member(members[1], 30)
+            .with_funds(members[2], 1000)
+            .build();
+        let admin = suite.admin().to_owned();
+        let denom = suite.denom.clone();
+        let contract = suite.contract.to_string();
+
+        suite.set_member_tags(&admin, members[0], &["tag"]).unwrap();
+
+        // Tokens pending for all members are not redirected to the tag
         suite
-            .slash(members[1], members[0], Decimal::percent(50))
+            .send_funds(members[2], &contract, &coins(400, &denom))
+            .unwrap();
+        suite.distribute_funds_to(members[1], "tag", &[]).unwrap();
+        assert_eq!(suite.undistributed_funds().unwrap(), coin(400, &denom));
+
+        suite
+            .distribute_funds_to(members[2], "tag", &coins(100, &denom))
             .unwrap();
+        assert_eq!(
+            suite
+                .withdrawable_rewards_for_tag(members[0], "tag")
+                .unwrap(),
+            coins(100, &denom)
+        );
+        assert_eq!(suite.undistributed_funds().unwrap(), coin(400, &denom));
+
+        suite.distribute_funds(members[1], None, &[]).unwrap();
+        assert_eq!(
+            suite.withdrawable_rewards(members[0]).unwrap(),
+            coin(200, &denom)
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[1]).unwrap(),
+            coin(300, &denom)
+        );
     }
 
     #[test]
-    fn slashing_after_distribution() {
-        // Perform full tokens distribution and withdrawal. Then slash one member. Perform another
-        // full distribution and withdrawal. Ensure all funds are as expected (the second
-        // distribution rewards are splitted with aligned points.
-        let members = vec!["member1", "member2", "member3"];
+    fn tag_changes_preserve_earned_rewards() {
+        let members = ["member1", "member2", "member3"];
 
         let mut suite = SuiteBuilder::new()
             .with_member(members[0], 10)
             .with_member(members[1], 10)
-            .with_funds(members[2], 1200)
+            .with_funds(members[2], 1000)
             .build();
-
-        let admin = suite.owner.clone();
+        let admin = suite.admin().to_owned();
         let denom = suite.denom.clone();
 
-        suite.add_slasher(admin.as_str(), members[2]).unwrap();
+        suite.set_member_tags(&admin, members[0], &["tag"]).unwrap();
+        suite
+            .distribute_funds_to(members[2], "tag", &coins(100, &denom))
+            .unwrap();
 
+        // Newly tagged member doesn't share earlier rewards, and untagged one keeps them
+        suite.set_member_tags(&admin, members[0], &[]).unwrap();
+        suite.set_member_tags(&admin, members[1], &["tag"]).unwrap();
         suite
-            .distribute_funds(members[2], None, &coins(600, &denom))
+            .distribute_funds_to(members[2], "tag", &coins(200, &denom))
             .unwrap();
 
-        suite.withdraw_funds(members[0], None, None).unwrap();
-        suite.withdraw_funds(members[1], None, None).unwrap();
+        assert_eq!(
+            suite
+                .withdrawable_rewards_for_tag(members[0], "tag")
+                .unwrap(),
+            coins(100, &denom)
+        );
+        assert_eq!(
+            suite
+                .withdrawable_rewards_for_tag(members[1], "tag")
+                .unwrap(),
+            coins(200, &denom)
+        );
 
+        // Points changes of tagged members are accounted in the tag
         suite
-            .slash(members[2], members[0], Decimal::percent(50))
+            .modify_members(&admin, &[(members[0], 30)], &[])
+            .unwrap();
+        suite.set_member_tags(&admin, members[0], &["tag"]).unwrap();
+        suite
+            .distribute_funds_to(members[2], "tag", &coins(400, &denom))
             .unwrap();
 
+        assert_eq!(
+            suite
+                .withdrawable_rewards_for_tag(members[0], "tag")
+                .unwrap(),
+            coins(400, &denom)
+        );
+        assert_eq!(
+            suite
+                .withdrawable_rewards_for_tag(members[1], "tag")
+                .unwrap(),
+            coins(300, &denom)
+        );
+
+        let invariants = suite.invariants().unwrap();
+        assert!(invariants.distributed_total_matches_withdrawals);
+        assert!(invariants.shares_corrections_consistent);
+    }
+
+    #[test]
+    fn distribution_to_tag_without_members_fails() {
+        let mut suite = SuiteBuilder::new()
+            .with_member("member1", 10)
+            .with_funds("member2", 100)
+            .build();
+        let denom = suite.denom.clone();
+
+        let err = suite
+            .distribute_funds_to("member2", "tag", &coins(100, &denom))
+            .unwrap_err();
+        assert_eq!(
+            ContractError::NoMembersToDistributeTo {},
+            err.downcast().unwrap()
+        );
+    }
+}
+
+mod initial_adjustments {
+    use super::*;
+    use crate::i128::Int128;
+    use crate::state::{WithdrawAdjustment, SHARES_SHIFT};
+    use cosmwasm_std::Uint128;
+
+    #[test]
+    fn seeded_rewards_accumulate_with_distribution() {
+        let members = ["member1", "member2", "member3"];
+
+        // member1 had 150 tokens assigned before, and withdrawn 50 of them
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_member(members[1], 3)
+            .with_funds(members[2], 400)
+            .with_initial_adjustment(
+                members[0],
+                WithdrawAdjustment {
+                    shares_correction: Int128::from((150u128 << SHARES_SHIFT) as i128),
+                    withdrawn_rewards: Uint128::new(50),
+                },
+                100,
+            )
+            .build();
+
+        let denom = suite.denom.clone();
+
+        assert_eq!(
+            suite.withdrawable_rewards(members[0]).unwrap(),
+            coin(100, &denom)
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[1]).unwrap(),
+            coin(0, &denom)
+        );
+        assert!(
+            suite
+                .invariants()
+                .unwrap()
+                .distributed_total_matches_withdrawals
+        );
+
         suite
-            .distribute_funds(members[2], None, &coins(600, &denom))
+            .distribute_funds(members[2], None, &coins(400, &denom))
             .unwrap();
 
+        assert_eq!(
+            suite.withdrawable_rewards(members[0]).unwrap(),
+            coin(200, &denom)
+        );
+        assert_eq!(
+            suite.withdrawable_rewards(members[1]).unwrap(),
+            coin(300, &denom)
+        );
+
         suite.withdraw_funds(members[0], None, None).unwrap();
         suite.withdraw_funds(members[1], None, None).unwrap();
 
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 200);
+        assert_eq!(suite.token_balance(members[1]).unwrap(), 300);
         assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
-        assert_eq!(suite.token_balance(members[0]).unwrap(), 500);
-        assert_eq!(suite.token_balance(members[1]).unwrap(), 700);
-        assert_eq!(suite.token_balance(members[2]).unwrap(), 0);
+
+        let invariants = suite.invariants().unwrap();
+        assert!(invariants.distributed_total_matches_withdrawals);
+        assert!(invariants.shares_corrections_consistent);
     }
+}
+
+mod member_count {
+    use super::*;
 
     #[test]
-    fn slashing_while_withdrawal_pending() {
-        // Perform rewards distribution, but don't withdraw funds. Then slash one member. Perform
-        // another distribution, and withdraw all funds. Ensure funds are as expected.
-        let members = vec!["member1", "member2", "member3"];
+    fn member_count_tracks_updates() {
+        let members = ["member1", "member2", "member3", "member4"];
 
         let mut suite = SuiteBuilder::new()
             .with_member(members[0], 10)
-            .with_member(members[1], 10)
-            .with_funds(members[2], 1200)
+            .with_member(members[1], 20)
             .build();
+        let admin = suite.admin().to_string();
 
-        let admin = suite.owner.clone();
-        let denom = suite.denom.clone();
-
-        suite.add_slasher(admin.as_str(), members[2]).unwrap();
+        assert_eq!(suite.member_count().unwrap(), 2);
+        assert!(suite.is_member(members[0]).unwrap());
+        assert!(!suite.is_member(members[2]).unwrap());
 
+        // Updating existing member doesn't change the count, removing non-member is ignored
         suite
-            .distribute_funds(members[2], None, &coins(600, &denom))
+            .modify_members(
+                &admin,
+                &[(members[1], 5), (members[2], 3), (members[3], 0)],
+                &[],
+            )
             .unwrap();
+        assert_eq!(suite.member_count().unwrap(), 4);
+        // Member with zero points is still a member
+        assert!(suite.is_member(members[3]).unwrap());
 
         suite
-            .slash(members[2], members[0], Decimal::percent(50))
+            .modify_members(&admin, &[], &[members[0], members[3], "non-member"])
             .unwrap();
+        assert_eq!(suite.member_count().unwrap(), 2);
+        assert!(!suite.is_member(members[0]).unwrap());
+        assert!(suite.is_member(members[1]).unwrap());
 
+        // Re-adding removed member counts it again
         suite
-            .distribute_funds(members[2], None, &coins(600, &denom))
+            .modify_members(&admin, &[(members[0], 1)], &[])
             .unwrap();
-
-        suite.withdraw_funds(members[0], None, None).unwrap();
-        suite.withdraw_funds(members[1], None, None).unwrap();
-
-        assert_eq!(suite.token_balance(suite.contract.as_str()).unwrap(), 0);
-        assert_eq!(suite.token_balance(members[0]).unwrap(), 500);
-        assert_eq!(suite.token_balance(members[1]).unwrap(), 700);
-        assert_eq!(suite.token_balance(members[2]).unwrap(), 0);
+        assert_eq!(suite.member_count().unwrap(), 3);
+        assert_eq!(suite.members().unwrap().len(), 3);
     }
 }
 
 mod migration {
     use super::*;
-    use crate::msg::MigrateMsg;
+    use crate::msg::{DecayMode, MigrateMsg};
 
     #[test]
     fn migration_can_alter_cfg() {
@@ -941,6 +2832,12 @@ mod migration {
                 &admin,
                 &MigrateMsg {
                     halflife: Some(Duration::new(200)),
+                    decay: None,
+                    max_denoms: None,
+                    auto_distribute: None,
+                    halflife_floor: None,
+                    distribution_fee: None,
+                    fee_recipient: None,
                 },
             )
             .unwrap();
@@ -949,6 +2846,134 @@ mod migration {
         assert_eq!(cfg.halflife_info.unwrap().halflife.seconds(), 200);
     }
 
+    #[test]
+    fn migration_from_single_denom_layout_keeps_rewards() {
+        let members = ["member1", "member2", "distributor", "delegated"];
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_member(members[1], 3)
+            .with_funds(members[2], 800)
+            .build();
+        let admin = suite.admin().to_string();
+        let denom = suite.denom.clone();
+
+        suite
+            .distribute_funds(members[2], None, &coins(400, &denom))
+            .unwrap();
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        suite.delegate_withdrawal(members[1], members[3]).unwrap();
+
+        suite.downgrade_to_single_denom().unwrap();
+        suite
+            .migrate(
+                &admin,
+                &MigrateMsg {
+                    halflife: None,
+                    decay: None,
+                    max_denoms: None,
+                    auto_distribute: None,
+                    halflife_floor: None,
+                    distribution_fee: None,
+                    fee_recipient: None,
+                },
+            )
+            .unwrap();
+
+        // Pending rewards, withdrawals and delegations are preserved
+        assert_eq!(suite.distributed_funds().unwrap(), coin(400, &denom));
+        assert_eq!(
+            suite
+                .withdrawable_rewards(members[0])
+                .unwrap()
+                .amount
+                .u128(),
+            0
+        );
+        assert_eq!(
+            suite
+                .withdrawable_rewards(members[1])
+                .unwrap()
+                .amount
+                .u128(),
+            300
+        );
+        assert_eq!(suite.delegated(members[1]).unwrap(), members[3]);
+        assert_eq!(
+            suite.delegations_to(members[3], None, None).unwrap(),
+            vec![Addr::unchecked(members[1])]
+        );
+
+        suite.withdraw_funds(members[3], members[1], None).unwrap();
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 100);
+        assert_eq!(suite.token_balance(members[3]).unwrap(), 300);
+
+        // Further distributions are accounted the same way as before migration
+        suite
+            .distribute_funds(members[2], None, &coins(400, &denom))
+            .unwrap();
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        assert_eq!(suite.token_balance(members[0]).unwrap(), 200);
+        assert_eq!(
+            suite
+                .withdrawable_rewards(members[1])
+                .unwrap()
+                .amount
+                .u128(),
+            300
+        );
+    }
+
+    #[test]
+    fn migration_can_set_distribution_fee() {
+        let members = ["member1", "member2", "distributor"];
+        let treasury = "treasury";
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 1)
+            .with_member(members[1], 3)
+            .with_funds(members[2], 800)
+            .build();
+        let admin = suite.admin().to_string();
+        let denom = suite.denom.clone();
+
+        let migrate_msg = |fee, recipient: Option<&str>| MigrateMsg {
+            halflife: None,
+            decay: None,
+            max_denoms: None,
+            auto_distribute: None,
+            halflife_floor: None,
+            distribution_fee: fee,
+            fee_recipient: recipient.map(str::to_owned),
+        };
+
+        let err = suite
+            .migrate(&admin, &migrate_msg(Some(Decimal::one()), None))
+            .unwrap_err();
+        assert_eq!(
+            ContractError::InvalidDistributionFee {},
+            err.downcast().unwrap()
+        );
+
+        // Fee goes to admin if no recipient was ever set
+        suite
+            .migrate(&admin, &migrate_msg(Some(Decimal::percent(10)), None))
+            .unwrap();
+        suite
+            .distribute_funds(members[2], None, &coins(400, &denom))
+            .unwrap();
+        assert_eq!(suite.token_balance(&admin).unwrap(), 40);
+
+        // Changing only the recipient keeps the fee
+        suite
+            .migrate(&admin, &migrate_msg(None, Some(treasury)))
+            .unwrap();
+        suite
+            .distribute_funds(members[2], None, &coins(400, &denom))
+            .unwrap();
+        assert_eq!(suite.token_balance(treasury).unwrap(), 40);
+        assert_eq!(suite.distributed_funds().unwrap(), coin(720, &denom));
+    }
+
     #[test]
     fn migration_can_remove_halflife() {
         let mut suite = SuiteBuilder::new()
@@ -964,6 +2989,12 @@ mod migration {
                 &admin,
                 &MigrateMsg {
                     halflife: Some(Duration::new(0)),
+                    decay: None,
+                    max_denoms: None,
+                    auto_distribute: None,
+                    halflife_floor: None,
+                    distribution_fee: None,
+                    fee_recipient: None,
                 },
             )
             .unwrap();
@@ -971,4 +3002,106 @@ mod migration {
         let cfg = suite.halflife().unwrap();
         assert!(cfg.halflife_info.is_none());
     }
+
+    #[test]
+    fn migration_can_switch_to_linear_decay() {
+        let mut suite = SuiteBuilder::new()
+            .with_halflife(Duration::new(100))
+            .build();
+        let admin = suite.admin().to_string();
+
+        let decay = DecayMode::Linear {
+            amount: 5,
+            interval: Duration::new(200),
+        };
+        suite
+            .migrate(
+                &admin,
+                &MigrateMsg {
+                    halflife: None,
+                    decay: Some(decay.clone()),
+                    max_denoms: None,
+                    auto_distribute: None,
+                    halflife_floor: None,
+                    distribution_fee: None,
+                    fee_recipient: None,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(suite.decay().unwrap().decay, Some(decay));
+        assert!(suite.halflife().unwrap().halflife_info.is_none());
+    }
+}
+
+mod invariants {
+    use super::*;
+
+    fn assert_invariants(suite: &suite::Suite) {
+        let invariants = suite.invariants().unwrap();
+        assert!(invariants.total_matches_sum_of_points);
+        assert!(invariants.distributed_total_matches_withdrawals);
+        assert!(invariants.shares_corrections_consistent);
+    }
+
+    #[test]
+    fn hold_after_operations() {
+        let members = ["member1", "member2", "member3", "member4"];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(members[0], 10)
+            .with_member(members[1], 7)
+            .with_member(members[2], 3)
+            .with_funds(members[3], 2000)
+            .with_halflife(Duration::new(100))
+            .build();
+        let admin = suite.admin().to_owned();
+        let denom = suite.denom.clone();
+        suite.add_slasher(&admin, &admin).unwrap();
+        assert_invariants(&suite);
+
+        suite
+            .distribute_funds(members[3], None, &coins(333, &denom))
+            .unwrap();
+        assert_invariants(&suite);
+
+        suite.withdraw_funds(members[0], None, None).unwrap();
+        assert_invariants(&suite);
+
+        suite
+            .slash(&admin, members[1], Decimal::percent(50))
+            .unwrap();
+        suite
+            .distribute_funds(members[3], None, &coins(101, &denom))
+            .unwrap();
+        assert_invariants(&suite);
+
+        suite
+            .slash_and_redistribute(&admin, members[0], Decimal::percent(30))
+            .unwrap();
+        suite
+            .set_reward_multiplier(&admin, members[2], Decimal::percent(150))
+            .unwrap();
+        suite
+            .distribute_funds(members[3], None, &coins(457, &denom))
+            .unwrap();
+        assert_invariants(&suite);
+
+        // Halflife is applied
+        suite.app.advance_seconds(125);
+        suite.app.end_block().unwrap();
+        suite
+            .distribute_funds(members[3], None, &coins(89, &denom))
+            .unwrap();
+        suite.withdraw_funds(members[1], None, None).unwrap();
+        suite.withdraw_funds(members[2], None, None).unwrap();
+        assert_invariants(&suite);
+
+        // Member removed while having rewards to withdraw
+        suite.modify_members(&admin, &[], &[members[2]]).unwrap();
+        suite
+            .distribute_funds(members[3], None, &coins(71, &denom))
+            .unwrap();
+        assert_invariants(&suite);
+    }
 }
@@ -1,10 +1,19 @@
 use crate::error::ContractError;
 use crate::msg::*;
+use crate::state::{
+    Distribution, LegacyWithdrawAdjustment, WithdrawAdjustment, DELEGATED, DELEGATED_BY, DENOM,
+    DISTRIBUTION, LEGACY_DISTRIBUTION, LEGACY_WITHDRAW_ADJUSTMENT, MAX_DENOMS, PENDING_FORWARD,
+    WITHDRAW_ADJUSTMENT,
+};
 use anyhow::Result as AnyResult;
-use cosmwasm_std::{Addr, Coin, CosmosMsg, Decimal, StdResult};
+use cosmwasm_std::{
+    coin, coins, Addr, Binary, Coin, CosmosMsg, Decimal, DepsMut, Empty, Env, Order, StdResult,
+    Timestamp,
+};
+use cw2::set_contract_version;
 use cw_multi_test::{AppResponse, Contract, ContractWrapper, CosmosRouter, Executor};
 use derivative::Derivative;
-use tg4::{Member, MemberListResponse};
+use tg4::{Member, MemberListResponse, TotalPointsResponse};
 use tg_bindings::{TgradeMsg, TgradeQuery};
 use tg_bindings_test::TgradeApp;
 use tg_utils::Duration;
@@ -16,7 +25,59 @@ fn contract_engagement() -> Box<dyn Contract<TgradeMsg, TgradeQuery>> {
         crate::contract::query,
     )
     .with_migrate(crate::contract::migrate)
-    .with_sudo(crate::contract::sudo);
+    .with_sudo(crate::contract::sudo)
+    .with_reply(crate::contract::reply);
+
+    Box::new(contract)
+}
+
+/// Rewrites storage of the contract to the single denom layout of 0.16, so migration from it can
+/// be tested
+fn downgrade_to_single_denom(
+    deps: DepsMut<TgradeQuery>,
+    _env: Env,
+    _msg: Empty,
+) -> Result<crate::contract::Response, ContractError> {
+    let denom = DENOM.load(deps.storage)?;
+    let distribution = DISTRIBUTION.load(deps.storage, &denom)?;
+    LEGACY_DISTRIBUTION.save(deps.storage, &distribution)?;
+    DISTRIBUTION.remove(deps.storage, &denom);
+    DENOM.remove(deps.storage);
+    MAX_DENOMS.remove(deps.storage);
+
+    let adjustments = WITHDRAW_ADJUSTMENT
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for ((addr, adjustment_denom), adjustment) in adjustments {
+        assert_eq!(adjustment_denom, denom);
+        let delegated = DELEGATED.may_load(deps.storage, &addr)?;
+        let legacy = LegacyWithdrawAdjustment {
+            shares_correction: adjustment.shares_correction,
+            withdrawn_rewards: adjustment.withdrawn_rewards,
+            delegated: delegated.unwrap_or_else(|| addr.clone()),
+        };
+        LEGACY_WITHDRAW_ADJUSTMENT.save(deps.storage, &addr, &legacy)?;
+        WITHDRAW_ADJUSTMENT.remove(deps.storage, (&addr, &denom));
+        DELEGATED.remove(deps.storage, &addr);
+    }
+    let delegations = DELEGATED_BY
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (delegated, owner) in delegations {
+        DELEGATED_BY.remove(deps.storage, (&delegated, &owner));
+    }
+
+    set_contract_version(deps.storage, "crates.io:tg4-engagement", "0.16.0")?;
+    Ok(crate::contract::Response::new())
+}
+
+fn contract_engagement_0_16() -> Box<dyn Contract<TgradeMsg, TgradeQuery>> {
+    let contract = ContractWrapper::new(
+        crate::contract::execute,
+        crate::contract::instantiate,
+        crate::contract::query,
+    )
+    .with_migrate(downgrade_to_single_denom);
 
     Box::new(contract)
 }
@@ -37,10 +98,22 @@ pub fn expected_members(members: Vec<(&str, u64)>) -> Vec<Member> {
 pub struct SuiteBuilder {
     members: Vec<Member>,
     funds: Vec<(Addr, u128)>,
+    denom_funds: Vec<(Addr, Coin)>,
     halflife: Option<Duration>,
+    decay: Option<DecayMode>,
     #[derivative(Default(value = "\"usdc\".to_owned()"))]
     denom: String,
     preauths_slashing: u64,
+    #[derivative(Default(value = "default_max_denoms()"))]
+    max_denoms: u32,
+    distribution_fee: Decimal,
+    fee_recipient: Option<String>,
+    auto_distribute: bool,
+    #[derivative(Default(value = "default_halflife_floor()"))]
+    halflife_floor: u64,
+    initial_adjustments: Vec<(String, WithdrawAdjustment)>,
+    /// Tokens sent with instantiation by the owner
+    instantiate_funds: u128,
 }
 
 impl SuiteBuilder {
@@ -59,11 +132,59 @@ impl SuiteBuilder {
         self
     }
 
+    /// Sets initial amount of tokens in any denom on address
+    pub fn with_denom_funds(mut self, addr: &str, funds: Coin) -> Self {
+        self.denom_funds.push((Addr::unchecked(addr), funds));
+        self
+    }
+
+    pub fn with_max_denoms(mut self, max_denoms: u32) -> Self {
+        self.max_denoms = max_denoms;
+        self
+    }
+
+    pub fn with_distribution_fee<'a>(
+        mut self,
+        fee: Decimal,
+        recipient: impl Into<Option<&'a str>>,
+    ) -> Self {
+        self.distribution_fee = fee;
+        self.fee_recipient = recipient.into().map(str::to_owned);
+        self
+    }
+
+    pub fn with_auto_distribute(mut self) -> Self {
+        self.auto_distribute = true;
+        self
+    }
+
+    /// Seeds withdraw adjustment of `addr`, sending `funds` with instantiation to cover it
+    pub fn with_initial_adjustment(
+        mut self,
+        addr: &str,
+        adjustment: WithdrawAdjustment,
+        funds: u128,
+    ) -> Self {
+        self.initial_adjustments.push((addr.to_owned(), adjustment));
+        self.instantiate_funds += funds;
+        self
+    }
+
+    pub fn with_halflife_floor(mut self, floor: u64) -> Self {
+        self.halflife_floor = floor;
+        self
+    }
+
     pub fn with_halflife(mut self, halflife: Duration) -> Self {
         self.halflife = Some(halflife);
         self
     }
 
+    pub fn with_decay(mut self, decay: DecayMode) -> Self {
+        self.decay = Some(decay);
+        self
+    }
+
     pub fn with_denom(mut self, denom: &str) -> Self {
         self.denom = denom.to_owned();
         self
@@ -76,9 +197,23 @@ impl SuiteBuilder {
 
     #[track_caller]
     pub fn build(self) -> Suite {
-        let funds = self.funds;
+        let denom = self.denom;
+        let funds = self
+            .funds
+            .into_iter()
+            .map(|(addr, amount)| (addr, coin(amount, &denom)))
+            .chain(self.denom_funds);
 
         let owner = Addr::unchecked("owner");
+        let instantiate_funds = match self.instantiate_funds {
+            0 => vec![],
+            amount => coins(amount, &denom),
+        };
+        let funds = funds.chain(
+            instantiate_funds
+                .iter()
+                .map(|funds| (owner.clone(), funds.clone())),
+        );
 
         let mut app = TgradeApp::new(owner.as_str());
 
@@ -86,18 +221,17 @@ impl SuiteBuilder {
         app.back_to_genesis();
 
         let block_info = app.block_info();
-        let denom = self.denom;
 
         app.init_modules(|router, api, storage| -> AnyResult<()> {
-            for (addr, amount) in funds {
+            for (addr, funds) in funds {
                 router.execute(
                     api,
                     storage,
                     &block_info,
                     owner.clone(),
                     CosmosMsg::Custom(TgradeMsg::MintTokens {
-                        denom: denom.clone(),
-                        amount: amount.into(),
+                        denom: funds.denom,
+                        amount: funds.amount,
                         recipient: addr.to_string(),
                     }),
                 )?;
@@ -118,9 +252,16 @@ impl SuiteBuilder {
                     preauths_hooks: 0,
                     preauths_slashing: self.preauths_slashing,
                     halflife: self.halflife,
+                    decay: self.decay,
                     denom: denom.clone(),
+                    max_denoms: self.max_denoms,
+                    distribution_fee: self.distribution_fee,
+                    fee_recipient: self.fee_recipient,
+                    auto_distribute: self.auto_distribute,
+                    halflife_floor: self.halflife_floor,
+                    initial_adjustments: self.initial_adjustments,
                 },
-                &[],
+                &instantiate_funds,
                 "engagement",
                 Some(owner.to_string()),
             )
@@ -169,11 +310,72 @@ impl Suite {
             self.contract.clone(),
             &ExecuteMsg::DistributeRewards {
                 sender: sender.into().map(str::to_owned),
+                denom: None,
+            },
+            funds,
+        )
+    }
+
+    pub fn distribute_denom_funds(
+        &mut self,
+        executor: &str,
+        denom: &str,
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.contract.clone(),
+            &ExecuteMsg::DistributeRewards {
+                sender: None,
+                denom: Some(denom.to_owned()),
             },
             funds,
         )
     }
 
+    /// Sends tokens with a regular bank message, without distributing them
+    pub fn send_funds(
+        &mut self,
+        executor: &str,
+        recipient: &str,
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        self.app
+            .send_tokens(Addr::unchecked(executor), Addr::unchecked(recipient), funds)
+    }
+
+    pub fn deduct_points(
+        &mut self,
+        executor: &str,
+        addr: &str,
+        points: u64,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.contract.clone(),
+            &ExecuteMsg::DeductPoints {
+                addr: addr.to_owned(),
+                points,
+            },
+            &[],
+        )
+    }
+
+    pub fn admin_withdraw_undistributed(
+        &mut self,
+        executor: &str,
+        receiver: &str,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.contract.clone(),
+            &ExecuteMsg::AdminWithdrawUndistributed {
+                receiver: receiver.to_owned(),
+            },
+            &[],
+        )
+    }
+
     pub fn admin(&self) -> &str {
         self.owner.as_str()
     }
@@ -190,6 +392,85 @@ impl Suite {
             &ExecuteMsg::WithdrawRewards {
                 owner: owner.into().map(str::to_owned),
                 receiver: receiver.into().map(str::to_owned),
+                as_vesting: false,
+            },
+            &[],
+        )
+    }
+
+    pub fn withdraw_and_distribute<'s>(
+        &mut self,
+        executor: &str,
+        owner: impl Into<Option<&'s str>>,
+        target: &Addr,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.contract.clone(),
+            &ExecuteMsg::WithdrawAndDistribute {
+                owner: owner.into().map(str::to_owned),
+                target: target.to_string(),
+            },
+            &[],
+        )
+    }
+
+    /// Instantiates another engagement contract with given members, distributing the same denom
+    pub fn instantiate_engagement(&mut self, members: Vec<Member>) -> AnyResult<Addr> {
+        self.app.instantiate_contract(
+            self.code_id,
+            self.owner.clone(),
+            &InstantiateMsg {
+                admin: Some(self.owner.to_string()),
+                members,
+                preauths_hooks: 0,
+                preauths_slashing: 0,
+                halflife: None,
+                decay: None,
+                denom: self.denom.clone(),
+                max_denoms: default_max_denoms(),
+                distribution_fee: Decimal::zero(),
+                fee_recipient: None,
+                auto_distribute: false,
+                halflife_floor: default_halflife_floor(),
+                initial_adjustments: vec![],
+            },
+            &[],
+            "sub-engagement",
+            None,
+        )
+    }
+
+    /// Withdraws rewards of `executor` from any engagement contract
+    pub fn withdraw_funds_from(
+        &mut self,
+        contract: &Addr,
+        executor: &str,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            contract.clone(),
+            &ExecuteMsg::WithdrawRewards {
+                owner: None,
+                receiver: None,
+                as_vesting: false,
+            },
+            &[],
+        )
+    }
+
+    pub fn withdraw_funds_batch<'s>(
+        &mut self,
+        executor: &str,
+        owners: &[&str],
+        receiver: impl Into<Option<&'s str>>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.contract.clone(),
+            &ExecuteMsg::WithdrawRewardsBatch {
+                owners: owners.iter().map(|owner| (*owner).to_owned()).collect(),
+                receiver: receiver.into().map(str::to_owned),
             },
             &[],
         )
@@ -274,6 +555,89 @@ impl Suite {
         )
     }
 
+    pub fn slash_and_redistribute(
+        &mut self,
+        executor: &str,
+        addr: &str,
+        portion: Decimal,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.contract.clone(),
+            &ExecuteMsg::SlashAndRedistribute {
+                addr: addr.to_owned(),
+                portion,
+            },
+            &[],
+        )
+    }
+
+    pub fn set_reward_multiplier(
+        &mut self,
+        executor: &str,
+        addr: &str,
+        multiplier: Decimal,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.contract.clone(),
+            &ExecuteMsg::SetRewardMultiplier {
+                addr: addr.to_owned(),
+                multiplier,
+            },
+            &[],
+        )
+    }
+
+    pub fn set_member_tags(
+        &mut self,
+        executor: &str,
+        addr: &str,
+        tags: &[&str],
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.contract.clone(),
+            &ExecuteMsg::SetMemberTags {
+                addr: addr.to_owned(),
+                tags: tags.iter().map(|tag| (*tag).to_owned()).collect(),
+            },
+            &[],
+        )
+    }
+
+    pub fn distribute_funds_to(
+        &mut self,
+        executor: &str,
+        tag: &str,
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.contract.clone(),
+            &ExecuteMsg::DistributeRewardsTo {
+                tag: tag.to_owned(),
+            },
+            funds,
+        )
+    }
+
+    pub fn invariants(&self) -> StdResult<InvariantsResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.contract.clone(), &QueryMsg::Invariants {})
+    }
+
+    pub fn reward_multiplier(&self, addr: &str) -> StdResult<Decimal> {
+        let resp: RewardMultiplierResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::RewardMultiplier {
+                addr: addr.to_owned(),
+            },
+        )?;
+        Ok(resp.multiplier)
+    }
+
     pub fn is_slasher(&self, addr: &str) -> Result<bool, ContractError> {
         let is_slasher: bool = self.app.wrap().query_wasm_smart(
             self.contract.clone(),
@@ -302,6 +666,48 @@ impl Suite {
         Ok(resp.rewards)
     }
 
+    /// Raw `pending_forward` item, `None` if no forward is in flight
+    pub fn pending_forward(&self) -> StdResult<Option<Binary>> {
+        self.app
+            .wrap()
+            .query_wasm_raw(self.contract.clone(), PENDING_FORWARD.as_slice())
+            .map(|raw| raw.map(Binary))
+    }
+
+    pub fn withdrawable_rewards_all(&self, owner: &str) -> Result<Vec<Coin>, ContractError> {
+        let rewards = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::WithdrawableRewardsAll {
+                owner: owner.to_owned(),
+            },
+        )?;
+        Ok(rewards)
+    }
+
+    pub fn withdrawable_rewards_for_tag(&self, owner: &str, tag: &str) -> StdResult<Vec<Coin>> {
+        self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::WithdrawableRewardsForTag {
+                owner: owner.to_owned(),
+                tag: tag.to_owned(),
+            },
+        )
+    }
+
+    pub fn list_withdrawable_rewards(
+        &self,
+        start_after: impl Into<Option<String>>,
+        limit: impl Into<Option<u32>>,
+    ) -> StdResult<Vec<(Addr, Coin)>> {
+        self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::ListWithdrawableRewards {
+                start_after: start_after.into(),
+                limit: limit.into(),
+            },
+        )
+    }
+
     pub fn distributed_funds(&self) -> Result<Coin, ContractError> {
         let resp: RewardsResponse = self
             .app
@@ -318,6 +724,43 @@ impl Suite {
         Ok(resp.rewards)
     }
 
+    pub fn simulate_distribution(
+        &self,
+        denom: Option<&str>,
+    ) -> StdResult<SimulateDistributionResponse> {
+        self.app.wrap().query_wasm_smart(
+            &self.contract,
+            &QueryMsg::SimulateDistribution {
+                denom: denom.map(str::to_owned),
+            },
+        )
+    }
+
+    pub fn distribution_params(&self) -> StdResult<DistributionParamsResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.contract, &QueryMsg::DistributionParams {})
+    }
+
+    pub fn simulate_slash(&self, addr: &str, portion: Decimal) -> StdResult<SimulateSlashResponse> {
+        self.app.wrap().query_wasm_smart(
+            &self.contract,
+            &QueryMsg::SimulateSlash {
+                addr: addr.to_owned(),
+                portion,
+            },
+        )
+    }
+
+    pub fn distribution_data(&self, denom: Option<&str>) -> StdResult<Option<Distribution>> {
+        self.app.wrap().query_wasm_smart(
+            &self.contract,
+            &QueryMsg::DistributionData {
+                denom: denom.map(str::to_owned),
+            },
+        )
+    }
+
     pub fn delegated(&self, owner: &str) -> Result<Addr, ContractError> {
         let resp: DelegatedResponse = self.app.wrap().query_wasm_smart(
             self.contract.clone(),
@@ -328,6 +771,23 @@ impl Suite {
         Ok(resp.delegated)
     }
 
+    pub fn delegations_to(
+        &self,
+        delegate: &str,
+        start_after: impl Into<Option<String>>,
+        limit: impl Into<Option<u32>>,
+    ) -> StdResult<Vec<Addr>> {
+        let resp: DelegationsResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::DelegationsTo {
+                delegate: delegate.to_owned(),
+                start_after: start_after.into(),
+                limit: limit.into(),
+            },
+        )?;
+        Ok(resp.owners)
+    }
+
     /// Shortcut for querying distributeable token balance of contract
     pub fn token_balance(&self, owner: &str) -> StdResult<u128> {
         let amount = self
@@ -338,6 +798,16 @@ impl Suite {
         Ok(amount.into())
     }
 
+    /// Shortcut for querying token balance in any denom
+    pub fn denom_balance(&self, owner: &str, denom: &str) -> StdResult<u128> {
+        let amount = self
+            .app
+            .wrap()
+            .query_balance(Addr::unchecked(owner), denom)?
+            .amount;
+        Ok(amount.into())
+    }
+
     pub fn members(&self) -> StdResult<Vec<Member>> {
         let resp: MemberListResponse = self.app.wrap().query_wasm_smart(
             self.contract.clone(),
@@ -349,6 +819,37 @@ impl Suite {
         Ok(resp.members)
     }
 
+    pub fn member_count(&self) -> StdResult<u64> {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.contract.clone(), &QueryMsg::MemberCount {})
+    }
+
+    pub fn is_member(&self, addr: &str) -> StdResult<bool> {
+        self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::IsMember {
+                addr: addr.to_owned(),
+            },
+        )
+    }
+
+    pub fn total_points(&self) -> StdResult<u64> {
+        let resp: TotalPointsResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(self.contract.clone(), &QueryMsg::TotalPoints {})?;
+        Ok(resp.points)
+    }
+
+    pub fn surviving_total_points(&self, halflives: u32) -> StdResult<u64> {
+        let resp: TotalPointsResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::SurvivingTotalPoints { halflives },
+        )?;
+        Ok(resp.points)
+    }
+
     /// Queries engagement contract for its halflife
     pub fn halflife(&self) -> StdResult<HalflifeResponse> {
         self.app
@@ -356,6 +857,54 @@ impl Suite {
             .query_wasm_smart(&self.contract, &QueryMsg::Halflife {})
     }
 
+    /// Queries engagement contract for its points decay
+    pub fn decay(&self) -> StdResult<DecayResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.contract, &QueryMsg::Decay {})
+    }
+
+    /// Queries engagement contract for points of the member after subsequent decays
+    pub fn projected_member(
+        &self,
+        addr: &str,
+        after_halflives: impl Into<Option<u32>>,
+    ) -> StdResult<ProjectedMemberResponse> {
+        self.app.wrap().query_wasm_smart(
+            &self.contract,
+            &QueryMsg::ProjectedMember {
+                addr: addr.to_owned(),
+                after_halflives: after_halflives.into(),
+            },
+        )
+    }
+
+    pub fn projected_points(
+        &self,
+        addr: &str,
+        at_time: Timestamp,
+    ) -> StdResult<ProjectedPointsResponse> {
+        self.app.wrap().query_wasm_smart(
+            &self.contract,
+            &QueryMsg::ProjectedPoints {
+                addr: addr.to_owned(),
+                at_time,
+            },
+        )
+    }
+
+    /// Rewrites contract storage to the single denom layout of 0.16. Only the code of the contract
+    /// changes, so `migrate` should be called to migrate the storage back.
+    pub fn downgrade_to_single_denom(&mut self) -> AnyResult<AppResponse> {
+        let code_id = self.app.store_code(contract_engagement_0_16());
+        self.app.migrate_contract(
+            self.owner.clone(),
+            self.contract.clone(),
+            &Empty {},
+            code_id,
+        )
+    }
+
     /// Migrates the contract to the same version (same code id), but possibly changing
     /// some cfg values via MigrateMsg.
     pub fn migrate(&mut self, addr: &str, msg: &MigrateMsg) -> AnyResult<AppResponse> {
@@ -2,9 +2,10 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::i128::Int128;
-use cosmwasm_std::{Addr, Timestamp, Uint128};
+use crate::msg::DecayMode;
+use cosmwasm_std::{Addr, Coin, Decimal, Order, StdResult, Storage, Timestamp, Uint128};
 use cw_storage_plus::{Item, Map};
-use tg_utils::Duration;
+use tg_utils::{members, Duration, TOTAL};
 pub use tg_utils::{PREAUTH_SLASHING, SLASHERS};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -12,13 +13,57 @@ pub struct Halflife {
     /// if set to None then there's no half life
     pub halflife: Option<Duration>,
 
+    /// Linear decay, used instead of half life. `halflife` is always None if this is set.
+    #[serde(default)]
+    pub linear: Option<LinearDecay>,
+
     pub last_applied: Timestamp,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct LinearDecay {
+    pub amount: u64,
+    pub interval: Duration,
+}
+
 impl Halflife {
+    pub fn new(decay: Option<DecayMode>, last_applied: Timestamp) -> Self {
+        match decay {
+            Some(DecayMode::Halflife(halflife)) => Self {
+                halflife: Some(halflife),
+                linear: None,
+                last_applied,
+            },
+            Some(DecayMode::Linear { amount, interval }) => Self {
+                halflife: None,
+                linear: Some(LinearDecay { amount, interval }),
+                last_applied,
+            },
+            None => Self {
+                halflife: None,
+                linear: None,
+                last_applied,
+            },
+        }
+    }
+
+    pub fn decay(&self) -> Option<DecayMode> {
+        match (&self.linear, self.halflife) {
+            (Some(linear), _) => Some(DecayMode::Linear {
+                amount: linear.amount,
+                interval: linear.interval,
+            }),
+            (None, Some(halflife)) => Some(DecayMode::Halflife(halflife)),
+            (None, None) => None,
+        }
+    }
+
     pub fn should_apply(&self, t: Timestamp) -> bool {
-        if let Some(halflife) = self.halflife {
-            halflife.after_time(self.last_applied).is_expired_time(t)
+        if let Some(decay) = self.decay() {
+            decay
+                .interval()
+                .after_time(self.last_applied)
+                .is_expired_time(t)
         } else {
             false
         }
@@ -36,10 +81,20 @@ impl Halflife {
 pub const SHARES_SHIFT: u8 = 32;
 
 pub const HALFLIFE: Item<Halflife> = Item::new("halflife");
+/// Points decay never reduces members below this many points. Missing means 1.
+pub const HALFLIFE_FLOOR: Item<u64> = Item::new("halflife_floor");
+
+/// Points below which points decay never reduces members
+pub fn halflife_floor(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(HALFLIFE_FLOOR.may_load(storage)?.unwrap_or(1))
+}
+
+/// Maximum number of distinct denoms which may be distributed by this contract
+pub const MAX_DENOMS: Item<u32> = Item::new("max_denoms");
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct Distribution {
-    /// Tokens can be distributed by this denom.
+    /// Denom of tokens distributed.
     pub denom: String,
     /// How many shares is single point worth
     pub shares_per_point: Uint128,
@@ -51,20 +106,200 @@ pub struct Distribution {
     pub withdrawable_total: Uint128,
 }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+impl Distribution {
+    pub fn new(denom: impl Into<String>) -> Self {
+        Self {
+            denom: denom.into(),
+            shares_per_point: Uint128::zero(),
+            shares_leftover: 0,
+            distributed_total: Uint128::zero(),
+            withdrawable_total: Uint128::zero(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, JsonSchema, Debug)]
 pub struct WithdrawAdjustment {
     /// How much points should be added/removed from calculated funds while withdrawal.
     pub shares_correction: Int128,
     /// How much funds addresses already withdrawn.
     pub withdrawn_rewards: Uint128,
-    /// User delegated for funds withdrawal
+}
+
+/// Portion of every distribution sent to `FEE_RECIPIENT` instead of being distributed. Missing
+/// means no fee.
+pub const DISTRIBUTION_FEE: Item<Decimal> = Item::new("distribution_fee");
+/// Address receiving the distribution fee (admin by default). Only set if `DISTRIBUTION_FEE` is
+/// not zero.
+pub const FEE_RECIPIENT: Item<Addr> = Item::new("fee_recipient");
+
+/// If set, all undistributed rewards are distributed at every end block. Missing means disabled.
+pub const AUTO_DISTRIBUTE: Item<bool> = Item::new("auto_distribute");
+
+/// Denom given on instantiation. Queries about a single denom refer to this one by default.
+pub const DENOM: Item<String> = Item::new("denom");
+/// Rewards distribution data, per denom
+pub const DISTRIBUTION: Map<&str, Distribution> = Map::new("distributions");
+/// Information how to exactly adjust rewards while withdrawal, per address and denom. Missing
+/// entry is equivalent to no adjustment.
+pub const WITHDRAW_ADJUSTMENT: Map<(&Addr, &str), WithdrawAdjustment> =
+    Map::new("withdraw_adjustments");
+/// Tags assigned to a member, sorted. Rewards distributed with `ExecuteMsg::DistributeRewardsTo`
+/// are shared only among members having the tag. Missing entry means no tags.
+pub const MEMBER_TAGS: Map<&Addr, Vec<String>> = Map::new("member_tags");
+/// Sum of reward points of members having given tag
+pub const TAG_TOTAL: Map<&str, u128> = Map::new("tag_totals");
+
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, JsonSchema, Debug)]
+pub struct TagDistribution {
+    /// How many shares is single point of a tagged member worth
+    pub shares_per_point: Uint128,
+    /// Shares which were not fully distributed on previous distributions to the tag
+    pub shares_leftover: u64,
+    /// Total rewards distributed to the tag
+    pub distributed_total: Uint128,
+}
+
+/// Rewards distribution data of tagged distributions, per tag and denom. Distributed tokens are
+/// accounted in `DISTRIBUTION` totals of the denom as well, so they are never distributed twice.
+pub const TAG_DISTRIBUTION: Map<(&str, &str), TagDistribution> = Map::new("tag_distributions");
+/// Withdraw adjustments of tagged distributions, per address, tag and denom. On withdrawal tagged
+/// rewards are moved to `WITHDRAW_ADJUSTMENT`, and counted as withdrawn here.
+pub const TAG_WITHDRAW_ADJUSTMENT: Map<(&Addr, &str, &str), WithdrawAdjustment> =
+    Map::new("tag_withdraw_adjustments");
+/// Address delegated for funds withdrawal. If missing, only the owner itself can withdraw.
+pub const DELEGATED: Map<&Addr, Addr> = Map::new("delegated");
+/// Reverse index of `DELEGATED`, keyed by delegated address and owner. Delegations of owners to
+/// themselves are not indexed.
+pub const DELEGATED_BY: Map<(&Addr, &Addr), ()> = Map::new("delegated_by");
+
+/// Rewards withdrawn by `WithdrawAndDistribute`, kept until the forward to the target contract
+/// is known not to fail.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct PendingForward {
+    pub owner: Addr,
+    pub rewards: Vec<Coin>,
+}
+
+/// Rewards forwarded by `WithdrawAndDistribute`, removed once the target contract replies.
+/// Restored to the owner if the target contract fails.
+pub const PENDING_FORWARD: Item<PendingForward> = Item::new("pending_forward");
+
+/// Maximum reward multiplier which can be set for a member
+pub const MAX_REWARD_MULTIPLIER: u64 = 10;
+/// Multipliers applied to members points in rewards distribution. Missing entry means 1.
+pub const REWARD_MULTIPLIERS: Map<&Addr, Decimal> = Map::new("reward_multipliers");
+/// Sum of members points weighted by their reward multipliers - rewards are distributed
+/// proportionally to it.
+pub const REWARD_TOTAL: Item<u128> = Item::new("reward_total");
+/// Number of entries in `members()`, so it can be queried without iterating over them
+pub const MEMBER_COUNT: Item<u64> = Item::new("member_count");
+
+/// Reward multiplier of given address, 1 if not set
+pub fn reward_multiplier(storage: &dyn Storage, addr: &Addr) -> StdResult<Decimal> {
+    Ok(REWARD_MULTIPLIERS
+        .may_load(storage, addr)?
+        .unwrap_or_else(Decimal::one))
+}
+
+/// Points of member weighted by its reward multiplier, as accounted in rewards distribution
+pub fn reward_points(points: u64, multiplier: Decimal) -> u128 {
+    (Uint128::from(points) * multiplier).u128()
+}
+
+/// Tags assigned to given address, empty if not set
+pub fn member_tags(storage: &dyn Storage, addr: &Addr) -> StdResult<Vec<String>> {
+    Ok(MEMBER_TAGS.may_load(storage, addr)?.unwrap_or_default())
+}
+
+/// Initializes `REWARD_TOTAL` with `TOTAL` if it is not set yet, which is the case for contracts
+/// created before reward multipliers were introduced (so none is set).
+pub fn init_reward_total(storage: &mut dyn Storage) -> StdResult<()> {
+    if REWARD_TOTAL.may_load(storage)?.is_none() {
+        let total = TOTAL.may_load(storage)?.unwrap_or_default();
+        REWARD_TOTAL.save(storage, &total.into())?;
+    }
+    Ok(())
+}
+
+/// Initializes `MEMBER_COUNT` by counting all members if it is not set yet, which is the case for
+/// contracts created before it was introduced.
+pub fn init_member_count(storage: &mut dyn Storage) -> StdResult<()> {
+    if MEMBER_COUNT.may_load(storage)?.is_none() {
+        let count = members()
+            .keys_raw(storage, None, None, Order::Ascending)
+            .count();
+        MEMBER_COUNT.save(storage, &(count as u64))?;
+    }
+    Ok(())
+}
+
+/// Indexes all `DELEGATED` entries in `DELEGATED_BY`, for contracts created before it was
+/// introduced. Already indexed entries are just overwritten.
+pub fn init_delegated_by(storage: &mut dyn Storage) -> StdResult<()> {
+    let delegations = DELEGATED
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (owner, delegated) in delegations {
+        if owner != delegated {
+            DELEGATED_BY.save(storage, (&delegated, &owner), &())?;
+        }
+    }
+    Ok(())
+}
+
+/// Withdraw adjustment as stored in the single denom layout of 0.16
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct LegacyWithdrawAdjustment {
+    pub shares_correction: Int128,
+    pub withdrawn_rewards: Uint128,
     pub delegated: Addr,
 }
 
-/// Rewards distribution data
-pub const DISTRIBUTION: Item<Distribution> = Item::new("distribution");
-/// Information how to exactly adjust rewards while withdrawal
-pub const WITHDRAW_ADJUSTMENT: Map<&Addr, WithdrawAdjustment> = Map::new("withdraw_adjustment");
+/// Rewards distribution data of the single denom layout of 0.16, replaced by `DISTRIBUTION`
+pub const LEGACY_DISTRIBUTION: Item<Distribution> = Item::new("distribution");
+/// Withdraw adjustments of the single denom layout of 0.16, replaced by `WITHDRAW_ADJUSTMENT`
+/// and `DELEGATED`
+pub const LEGACY_WITHDRAW_ADJUSTMENT: Map<&Addr, LegacyWithdrawAdjustment> =
+    Map::new("withdraw_adjustment");
+
+/// Moves rewards distribution data of contracts created before multiple denoms were supported
+/// to the per denom layout. The distributed denom becomes `DENOM`, and withdrawal delegations
+/// are moved to `DELEGATED`. Does nothing if the data is migrated already.
+pub fn migrate_single_denom_distribution(storage: &mut dyn Storage) -> StdResult<()> {
+    let distribution = match LEGACY_DISTRIBUTION.may_load(storage)? {
+        Some(distribution) => distribution,
+        None => return Ok(()),
+    };
+    let denom = distribution.denom.clone();
+    DISTRIBUTION.save(storage, &denom, &distribution)?;
+    DENOM.save(storage, &denom)?;
+    LEGACY_DISTRIBUTION.remove(storage);
+
+    let adjustments = LEGACY_WITHDRAW_ADJUSTMENT
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (addr, adjustment) in adjustments {
+        let new_adjustment = WithdrawAdjustment {
+            shares_correction: adjustment.shares_correction,
+            withdrawn_rewards: adjustment.withdrawn_rewards,
+        };
+        WITHDRAW_ADJUSTMENT.save(storage, (&addr, &denom), &new_adjustment)?;
+        if adjustment.delegated != addr {
+            DELEGATED.save(storage, &addr, &adjustment.delegated)?;
+        }
+        LEGACY_WITHDRAW_ADJUSTMENT.remove(storage, &addr);
+    }
+    Ok(())
+}
+
+/// Loads distribution data of all denoms
+pub fn load_distributions(storage: &dyn Storage) -> StdResult<Vec<Distribution>> {
+    DISTRIBUTION
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, distribution)| distribution))
+        .collect()
+}
 
 #[cfg(test)]
 mod tests {
@@ -75,18 +310,21 @@ mod tests {
         let epoch = 123456789;
         let hf = Halflife {
             halflife: None,
+            linear: None,
             last_applied: Timestamp::from_seconds(epoch),
         };
         assert!(!hf.should_apply(Timestamp::from_seconds(epoch)));
 
         let hf = Halflife {
             halflife: Some(Duration::new(epoch + 1)),
+            linear: None,
             last_applied: Timestamp::from_seconds(epoch),
         };
         assert!(!hf.should_apply(Timestamp::from_seconds(epoch)));
 
         let hf = Halflife {
             halflife: Some(Duration::new(epoch + 1)),
+            linear: None,
             last_applied: Timestamp::from_seconds(epoch),
         };
         // because halflife + last_applied + 1 = one second after half life is expected to be met
@@ -94,14 +332,30 @@ mod tests {
 
         let hf = Halflife {
             halflife: Some(Duration::new(epoch + 1)),
+            linear: None,
             last_applied: Timestamp::from_seconds(epoch + 2),
         };
         assert!(!hf.should_apply(Timestamp::from_seconds(epoch + 2)));
 
         let hf = Halflife {
             halflife: Some(Duration::new(epoch + 1)),
+            linear: None,
             last_applied: Timestamp::from_seconds(epoch + 2),
         };
         assert!(hf.should_apply(Timestamp::from_seconds(epoch * 2 + 3)));
     }
+
+    #[test]
+    fn linear_decay_should_apply() {
+        let epoch = 123456789;
+        let hf = Halflife::new(
+            Some(DecayMode::Linear {
+                amount: 5,
+                interval: Duration::new(100),
+            }),
+            Timestamp::from_seconds(epoch),
+        );
+        assert!(!hf.should_apply(Timestamp::from_seconds(epoch + 99)));
+        assert!(hf.should_apply(Timestamp::from_seconds(epoch + 100)));
+    }
 }
@@ -577,7 +577,8 @@ mod tests {
             tg4_stake::contract::execute,
             tg4_stake::contract::instantiate,
             tg4_stake::contract::query,
-        );
+        )
+        .with_reply(tg4_stake::contract::reply);
         Box::new(contract)
     }
 
@@ -591,7 +592,14 @@ mod tests {
             preauths_hooks: 1,
             preauths_slashing: 1,
             halflife: None,
+            decay: None,
             denom: STAKE_DENOM.to_owned(),
+            max_denoms: tg4_engagement::msg::default_max_denoms(),
+            distribution_fee: Decimal::zero(),
+            fee_recipient: None,
+            auto_distribute: false,
+            halflife_floor: 1,
+            initial_adjustments: vec![],
         };
         app.instantiate_contract(group_id, Addr::unchecked(OWNER), &msg, &[], "group", admin)
             .unwrap()
@@ -606,13 +614,19 @@ mod tests {
         let group_id = app.store_code(contract_staking());
         let msg = tg4_stake::msg::InstantiateMsg {
             denom: STAKE_DENOM.to_owned(),
-            tokens_per_point: Uint128::new(1),
+            tokens_per_point: Decimal::one(),
             min_bond: Uint128::new(100),
             unbonding_period: 3600,
             admin: admin.clone(),
             preauths_hooks: 1,
             preauths_slashing: 1,
             auto_return_limit: 0,
+            reject_zero_funds: false,
+            max_bond_per_tx: None,
+            min_stake_duration: None,
+            max_claims_per_addr: None,
+            slash_policy: tg4_stake::msg::SlashPolicy::Remove,
+            points_rounding: tg4_stake::msg::Rounding::Down,
         };
         let contract = app
             .instantiate_contract(
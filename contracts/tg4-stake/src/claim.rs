@@ -6,7 +6,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::{
-    Addr, BlockInfo, CustomQuery, Decimal, Deps, Order, StdResult, Storage, Uint128,
+    Addr, BlockInfo, CustomQuery, Decimal, Deps, Order, StdResult, Storage, Timestamp, Uint128,
 };
 use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, MultiIndex, PrefixBound};
 use tg_utils::Expiration;
@@ -15,16 +15,49 @@ use tg_utils::Expiration;
 const MAX_LIMIT: u32 = 100;
 const DEFAULT_LIMIT: u32 = 30;
 
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) struct TokenReleaseInfo {
     pub addr: Addr,
     pub amount: Uint128,
+    /// Creation heights of all claims contributing to this release, in ascending order
+    pub creation_heights: Vec<u64>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) struct ReleaseData {
+    /// Liquid tokens released to claim owners, not including redirected ones
     pub liquid_releases: Vec<TokenReleaseInfo>,
     pub vesting_releases: Vec<TokenReleaseInfo>,
+    /// Liquid tokens of claims with payout recipient set, addressed to the recipients
+    pub redirected_releases: Vec<TokenReleaseInfo>,
+    /// Number of claims released
+    pub released: u64,
+    /// Number of expired claims left unreleased because of the limit. Counted up to the limit, so
+    /// the actual backlog may be larger if it equals the limit
+    pub remaining_expired: u64,
+}
+
+/// Tokens released from claims of a single address
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct AddrReleaseData {
+    /// Liquid tokens released to the claim owner, not including redirected ones
+    pub liquid: Uint128,
+    pub vesting: Uint128,
+    /// Liquid tokens of claims with payout recipient set, addressed to the recipients
+    pub redirected: Vec<TokenReleaseInfo>,
+}
+
+impl AddrReleaseData {
+    pub fn is_empty(&self) -> bool {
+        self.liquid.is_zero()
+            && self.vesting.is_zero()
+            && self
+                .redirected
+                .iter()
+                .all(|release| release.amount.is_zero())
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -42,6 +75,32 @@ pub struct Claim {
     pub release_at: Expiration,
     /// Height of a blockchain in a moment of creation of this claim
     pub creation_height: u64,
+    /// If set, liquid tokens of this claim are paid to this address instead of the owner.
+    /// Vesting tokens are always returned to the owner
+    #[serde(default)]
+    pub recipient: Option<Addr>,
+}
+
+/// Liquid releases of claims with payout recipient set, one per claim
+fn redirected_releases(claims: &[Claim]) -> Vec<TokenReleaseInfo> {
+    claims
+        .iter()
+        .filter_map(|claim| {
+            claim.recipient.as_ref().map(|recipient| TokenReleaseInfo {
+                addr: recipient.clone(),
+                amount: claim.amount,
+                creation_heights: vec![claim.creation_height],
+            })
+        })
+        .collect()
+}
+
+/// Sorted, deduplicated creation heights of given claims
+fn creation_heights(claims: &[&Claim]) -> Vec<u64> {
+    let mut heights: Vec<_> = claims.iter().map(|claim| claim.creation_height).collect();
+    heights.sort_unstable();
+    heights.dedup();
+    heights
 }
 
 struct ClaimIndexes<'a> {
@@ -70,6 +129,16 @@ impl Claim {
             vesting_amount: Some(vesting_amount.into()),
             release_at: released,
             creation_height,
+            recipient: None,
+        }
+    }
+
+    /// Liquid amount paid to the claim owner
+    fn owner_amount(&self) -> Uint128 {
+        if self.recipient.is_some() {
+            Uint128::zero()
+        } else {
+            self.amount
         }
     }
 }
@@ -95,7 +164,9 @@ impl<'a> Claims<'a> {
     }
 
     /// This creates a claim, such that the given address can claim an amount of tokens after
-    /// the release date.
+    /// the release date. If `recipient` is given, liquid tokens are paid to it instead, which
+    /// also applies to the claim this one is merged with.
+    #[allow(clippy::too_many_arguments)]
     pub fn create_claim(
         &self,
         storage: &mut dyn Storage,
@@ -104,6 +175,7 @@ impl<'a> Claims<'a> {
         vesting_amount: Uint128,
         release_at: Expiration,
         creation_height: u64,
+        recipient: Option<Addr>,
     ) -> StdResult<()> {
         let addr = &addr;
         // Add a claim to this user to get their tokens after the unbonding period
@@ -116,6 +188,9 @@ impl<'a> Claims<'a> {
                         claim.amount += amount;
                         claim.vesting_amount =
                             Some(claim.vesting_amount.unwrap_or_default() + vesting_amount);
+                        if recipient.is_some() {
+                            claim.recipient = recipient;
+                        }
                         Ok(claim)
                     }
                     None => Ok(Claim {
@@ -124,6 +199,7 @@ impl<'a> Claims<'a> {
                         vesting_amount: Some(vesting_amount),
                         release_at,
                         creation_height,
+                        recipient,
                     }),
                 }
             },
@@ -132,15 +208,37 @@ impl<'a> Claims<'a> {
         Ok(())
     }
 
+    /// Checks if the address has `limit` claims already, so a new claim released at `release_at`
+    /// would exceed it. Claims are merged with existing ones of the same release time, so such
+    /// claim never exceeds the limit.
+    pub fn limit_reached(
+        &self,
+        storage: &dyn Storage,
+        addr: &Addr,
+        release_at: Expiration,
+        limit: u32,
+    ) -> bool {
+        if self.claims.has(storage, (addr, release_at.as_key())) {
+            return false;
+        }
+        let count = self
+            .claims
+            .prefix(addr)
+            .keys_raw(storage, None, None, Order::Ascending)
+            .take(limit as usize)
+            .count();
+        count >= limit as usize
+    }
+
     /// This iterates over all mature claims for the address, and removes them, up to an optional limit.
     /// It removes the finished claims and returns the total amount of tokens to be released.
-    pub fn claim_addr(
+    pub(crate) fn claim_addr(
         &self,
         storage: &mut dyn Storage,
         addr: &Addr,
         block: &BlockInfo,
         limit: impl Into<Option<u64>>,
-    ) -> StdResult<(Uint128, Uint128)> {
+    ) -> StdResult<AddrReleaseData> {
         let claims = self
             .claims
             .prefix(addr)
@@ -153,21 +251,28 @@ impl<'a> Claims<'a> {
             );
 
         let claims = self.collect_claims(claims, limit.into())?;
-        let amount = claims.iter().map(|claim| claim.amount).sum();
-        let vesting_amount = claims
+        let liquid = claims.iter().map(Claim::owner_amount).sum();
+        let vesting = claims
             .iter()
             .map(|claim| claim.vesting_amount.unwrap_or_default())
             .sum();
+        let redirected = redirected_releases(&claims);
 
         self.release_claims(storage, claims)?;
 
-        Ok((amount, vesting_amount))
+        Ok(AddrReleaseData {
+            liquid,
+            vesting,
+            redirected,
+        })
     }
 
     /// This iterates over all mature claims of any addresses, and removes them. Up to `limit`
     /// claims would be processed, starting from the oldest. It removes the finished claims and
     /// returns a pair of vectors representing the amounts of liquid and vesting tokens
-    /// to be released to particular addresses.
+    /// to be released to particular addresses, together with the number of released claims and
+    /// expired claims left for later. Claims left for later are counted up to `limit` only, so
+    /// the cost doesn't grow with the backlog.
     pub(crate) fn claim_expired(
         &self,
         storage: &mut dyn Storage,
@@ -186,7 +291,8 @@ impl<'a> Claims<'a> {
                 Order::Ascending,
             );
 
-        let mut claims = self.collect_claims(claims, limit.into())?;
+        let limit = limit.into();
+        let mut claims = self.collect_claims(claims, limit)?;
         claims.sort_by_key(|claim| claim.addr.clone());
 
         let liquid_releases = claims
@@ -195,9 +301,13 @@ impl<'a> Claims<'a> {
             // is stabilized [https://github.com/rust-lang/rust/issues/80552]
             .group_by(|claim| &claim.addr)
             .into_iter()
-            .map(|(addr, group)| TokenReleaseInfo {
-                addr: addr.clone(),
-                amount: group.map(|claim| claim.amount).sum(),
+            .map(|(addr, group)| {
+                let group: Vec<_> = group.collect();
+                TokenReleaseInfo {
+                    addr: addr.clone(),
+                    amount: group.iter().map(|claim| claim.owner_amount()).sum(),
+                    creation_heights: creation_heights(&group),
+                }
             })
             .collect();
 
@@ -207,19 +317,47 @@ impl<'a> Claims<'a> {
             // is stabilized [https://github.com/rust-lang/rust/issues/80552]
             .group_by(|claim| &claim.addr)
             .into_iter()
-            .map(|(addr, group)| TokenReleaseInfo {
-                addr: addr.clone(),
-                amount: group
-                    .map(|claim| claim.vesting_amount.unwrap_or_default())
-                    .sum(),
+            .map(|(addr, group)| {
+                let group: Vec<_> = group.collect();
+                TokenReleaseInfo {
+                    addr: addr.clone(),
+                    amount: group
+                        .iter()
+                        .map(|claim| claim.vesting_amount.unwrap_or_default())
+                        .sum(),
+                    creation_heights: creation_heights(&group),
+                }
             })
             .collect();
 
+        let redirected_releases = redirected_releases(&claims);
+        let released = claims.len() as u64;
+
         self.release_claims(storage, claims)?;
 
+        // without limit all expired claims are released
+        let remaining_expired = match limit {
+            Some(limit) => self
+                .claims
+                .idx
+                .release_at
+                .prefix_range_raw(
+                    storage,
+                    None,
+                    Some(PrefixBound::inclusive(block.time.nanos())),
+                    Order::Ascending,
+                )
+                .take(limit as usize)
+                .count() as u64,
+            None => 0,
+        };
+
         let release_data = ReleaseData {
             liquid_releases,
             vesting_releases,
+            redirected_releases,
+            released,
+            remaining_expired,
         };
 
         Ok(release_data)
@@ -258,6 +396,70 @@ impl<'a> Claims<'a> {
         Ok(())
     }
 
+    /// Merges claims of the address released within the same second into single claims.
+    /// Merged claim is released at the latest release time of its group, so no tokens are
+    /// released earlier than originally scheduled, and keeps the lowest creation height. Claims
+    /// with different payout recipients are never merged. Returns the number of removed claims.
+    pub fn compact_claims_for_addr(
+        &self,
+        storage: &mut dyn Storage,
+        addr: &Addr,
+    ) -> StdResult<u64> {
+        let mut claims = self
+            .claims
+            .prefix(addr)
+            .range(storage, None, None, Order::Ascending)
+            .map(|claim| claim.map(|(_, claim)| claim))
+            .collect::<StdResult<Vec<_>>>()?;
+        // stable sort, so claims in every group stay ordered by release time
+        claims.sort_by(|a, b| {
+            let a_key = (a.release_at.as_key() / NANOS_PER_SECOND, &a.recipient);
+            let b_key = (b.release_at.as_key() / NANOS_PER_SECOND, &b.recipient);
+            a_key.cmp(&b_key)
+        });
+
+        let mut removed = 0;
+        // TODO: use `slice::group_by` in place of `Itertools::group_by` when `slice_group_by`
+        // is stabilized [https://github.com/rust-lang/rust/issues/80552]
+        let groups = claims.iter().group_by(|claim| {
+            (
+                claim.release_at.as_key() / NANOS_PER_SECOND,
+                claim.recipient.clone(),
+            )
+        });
+        for (_, group) in &groups {
+            let group: Vec<_> = group.collect();
+            if group.len() < 2 {
+                continue;
+            }
+
+            let latest = group[group.len() - 1];
+            let merged = Claim {
+                addr: addr.clone(),
+                amount: group.iter().map(|claim| claim.amount).sum(),
+                vesting_amount: Some(
+                    group
+                        .iter()
+                        .map(|claim| claim.vesting_amount.unwrap_or_default())
+                        .sum(),
+                ),
+                release_at: latest.release_at,
+                creation_height: creation_heights(&group)[0],
+                recipient: latest.recipient.clone(),
+            };
+
+            for claim in &group {
+                self.claims
+                    .remove(storage, (addr, claim.release_at.as_key()))?;
+            }
+            self.claims
+                .save(storage, (addr, merged.release_at.as_key()), &merged)?;
+            removed += group.len() as u64 - 1;
+        }
+
+        Ok(removed)
+    }
+
     pub fn slash_claims_for_addr(
         &self,
         storage: &mut dyn Storage,
@@ -294,6 +496,84 @@ impl<'a> Claims<'a> {
         Ok((total_slashed, total_vesting_slashed))
     }
 
+    /// Sums liquid and vesting amounts of claims of all addresses into `buckets` consecutive
+    /// time buckets, `bucket_nanos` long each, starting at `start`. Claims which are already
+    /// expired at `start`, but not yet released, fall into the first bucket.
+    pub fn query_unlock_schedule<Q: CustomQuery>(
+        &self,
+        deps: Deps<Q>,
+        start: Timestamp,
+        bucket_nanos: u64,
+        buckets: usize,
+    ) -> StdResult<Vec<(Uint128, Uint128)>> {
+        let end = start
+            .nanos()
+            .saturating_add(bucket_nanos.saturating_mul(buckets as u64));
+
+        let mut schedule = vec![(Uint128::zero(), Uint128::zero()); buckets];
+        let claims = self.claims.idx.release_at.prefix_range_raw(
+            deps.storage,
+            None,
+            Some(PrefixBound::exclusive(end)),
+            Order::Ascending,
+        );
+        for claim in claims {
+            let (_, claim) = claim?;
+            let bucket = claim.release_at.as_key().saturating_sub(start.nanos()) / bucket_nanos;
+            let (liquid, vesting) = &mut schedule[bucket as usize];
+            *liquid += claim.amount;
+            *vesting += claim.vesting_amount.unwrap_or_default();
+        }
+
+        Ok(schedule)
+    }
+
+    /// Sums liquid and vesting amounts of claims of all addresses by their release time, for up
+    /// to `limit` release times after `start_after`
+    pub fn query_release_schedule<Q: CustomQuery>(
+        &self,
+        deps: Deps<Q>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<(u64, Uint128, Uint128)>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(PrefixBound::exclusive);
+
+        let mut schedule: Vec<(u64, Uint128, Uint128)> = vec![];
+        let claims = self.claims.idx.release_at.prefix_range_raw(
+            deps.storage,
+            start,
+            None,
+            Order::Ascending,
+        );
+        for claim in claims {
+            let (_, claim) = claim?;
+            let release_at = claim.release_at.as_key();
+            let vesting = claim.vesting_amount.unwrap_or_default();
+            if let Some((at, liquid_sum, vesting_sum)) = schedule.last_mut() {
+                if *at == release_at {
+                    *liquid_sum += claim.amount;
+                    *vesting_sum += vesting;
+                    continue;
+                }
+            }
+            if schedule.len() == limit {
+                break;
+            }
+            schedule.push((release_at, claim.amount, vesting));
+        }
+
+        Ok(schedule)
+    }
+
+    /// Sums liquid amounts of all pending claims
+    pub fn liquid_total(&self, storage: &dyn Storage) -> StdResult<Uint128> {
+        self.claims
+            .range_raw(storage, None, None, Order::Ascending)
+            .map(|claim| claim.map(|(_, claim)| claim.amount))
+            .sum()
+    }
+
     pub fn query_claims<Q: CustomQuery>(
         &self,
         deps: Deps<Q>,
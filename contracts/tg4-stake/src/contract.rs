@@ -2,9 +2,12 @@
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
     coin, coins, to_binary, Addr, BankMsg, Binary, Coin, CustomQuery, Decimal, Deps, DepsMut, Env,
-    MessageInfo, Order, StdError, StdResult, Storage, Uint128,
+    Event, MessageInfo, Order, Reply, StdError, StdResult, Storage, SubMsgResult, Timestamp,
+    Uint128, Uint256,
 };
 use std::cmp::min;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
 use std::ops::Sub;
 
 use cw2::set_contract_version;
@@ -18,16 +21,22 @@ use tg_bindings::{
     request_privileges, Privilege, PrivilegeChangeMsg, TgradeMsg, TgradeQuery, TgradeSudoMsg,
 };
 use tg_utils::{
-    members, validate_portion, Duration, ADMIN, HOOKS, PREAUTH_HOOKS, PREAUTH_SLASHING, SLASHERS,
-    TOTAL,
+    members, validate_portion, Duration, Expiration, ADMIN, HOOKS, PREAUTH_HOOKS, PREAUTH_SLASHING,
+    SLASHERS, TOTAL,
 };
 
 use crate::error::ContractError;
 use crate::msg::{
-    ClaimsResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PreauthResponse, QueryMsg,
-    StakedResponse, UnbondingPeriodResponse,
+    AutoRestakeResponse, ClaimReleasedHookMsg, ClaimsResponse, ExecuteMsg, InstantiateMsg,
+    InvariantsResponse, MigrateMsg, PreauthResponse, QueryMsg, ReleaseBucket,
+    ReleaseScheduleResponse, StakeDelegateResponse, StakedResponse, UnbondingPeriodResponse,
+    UnlockBucket, UnlockScheduleResponse,
+};
+use crate::state::{
+    claims, Config, RecentBond, Rounding, SlashPolicy, AUTO_RESTAKE, CLAIM_HOOKS, CONFIG,
+    CONTRACT_FROZEN, FROZEN, RECENT_BONDS, STAKE, STAKE_DELEGATE, STAKE_HISTORY_START,
+    STAKE_VESTING,
 };
-use crate::state::{claims, Config, CONFIG, STAKE, STAKE_VESTING};
 
 pub type Response = cosmwasm_std::Response<TgradeMsg>;
 pub type SubMsg = cosmwasm_std::SubMsg<TgradeMsg>;
@@ -36,16 +45,21 @@ pub type SubMsg = cosmwasm_std::SubMsg<TgradeMsg>;
 const CONTRACT_NAME: &str = "crates.io:tg4-stake";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Reply id of member changed hooks sent on slashing
+const SLASH_HOOK_REPLY_ID: u64 = 1;
+const CLAIM_HOOK_REPLY_ID: u64 = 2;
+
 // Note, you can use StdResult in some functions where you do not
 // make use of the custom errors
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     mut deps: DepsMut<TgradeQuery>,
-    _env: Env,
+    env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    STAKE_HISTORY_START.save(deps.storage, &env.block.height)?;
     let api = deps.api;
     ADMIN.set(deps.branch(), maybe_addr(api, msg.admin)?)?;
 
@@ -65,6 +79,12 @@ pub fn instantiate(
         min_bond,
         unbonding_period: Duration::new(msg.unbonding_period),
         auto_return_limit: msg.auto_return_limit,
+        reject_zero_funds: msg.reject_zero_funds,
+        max_bond_per_tx: msg.max_bond_per_tx.filter(|max| !max.is_zero()),
+        min_stake_duration: msg.min_stake_duration.map(Duration::new),
+        max_claims_per_addr: msg.max_claims_per_addr.filter(|max| *max > 0),
+        slash_policy: msg.slash_policy,
+        points_rounding: msg.points_rounding,
     };
     CONFIG.save(deps.storage, &config)?;
     TOTAL.save(deps.storage, &0)?;
@@ -82,20 +102,45 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     let api = deps.api;
+    if !matches!(msg, ExecuteMsg::FreezeContract { .. })
+        && CONTRACT_FROZEN.may_load(deps.storage)?.unwrap_or_default()
+    {
+        return Err(ContractError::ContractFrozen {});
+    }
+
     match msg {
         ExecuteMsg::UpdateAdmin { admin } => ADMIN
             .execute_update_admin(deps, info, maybe_addr(api, admin)?)
             .map_err(Into::into),
         ExecuteMsg::AddHook { addr } => execute_add_hook(deps, info, addr),
         ExecuteMsg::RemoveHook { addr } => execute_remove_hook(deps, info, addr),
+        ExecuteMsg::AddClaimHook { addr } => execute_add_claim_hook(deps, info, addr),
+        ExecuteMsg::RemoveClaimHook { addr } => execute_remove_claim_hook(deps, info, addr),
         ExecuteMsg::Bond { vesting_tokens } => execute_bond(deps, env, info, vesting_tokens),
         ExecuteMsg::Unbond {
             tokens: Coin { amount, denom },
-        } => execute_unbond(deps, env, info, amount, denom),
-        ExecuteMsg::Claim {} => execute_claim(deps, env, info),
+            owner,
+        } => execute_unbond(deps, env, info, amount, denom, owner),
+        ExecuteMsg::UnbondAll {} => execute_unbond_all(deps, env, info),
+        ExecuteMsg::Claim { receiver, owner } => execute_claim(deps, env, info, receiver, owner),
+        ExecuteMsg::DelegateStakeControl { delegate } => {
+            execute_delegate_stake_control(deps, info, delegate)
+        }
         ExecuteMsg::AddSlasher { addr } => execute_add_slasher(deps, info, addr),
         ExecuteMsg::RemoveSlasher { addr } => execute_remove_slasher(deps, info, addr),
-        ExecuteMsg::Slash { addr, portion } => execute_slash(deps, env, info, addr, portion),
+        ExecuteMsg::Slash {
+            addr,
+            portion,
+            include_claims,
+        } => execute_slash(deps, env, info, addr, portion, include_claims),
+        ExecuteMsg::SetAutoRestake { enabled } => execute_set_auto_restake(deps, info, enabled),
+        ExecuteMsg::ForceUnbond { addr, recipient } => {
+            execute_force_unbond(deps, env, info, addr, recipient)
+        }
+        ExecuteMsg::Freeze { addr, until } => execute_freeze(deps, info, addr, until),
+        ExecuteMsg::Unfreeze { addr } => execute_unfreeze(deps, info, addr),
+        ExecuteMsg::CompactClaims { addr } => execute_compact_claims(deps, info, addr),
+        ExecuteMsg::FreezeContract { frozen } => execute_freeze_contract(deps, info, frozen),
     }
 }
 
@@ -144,6 +189,51 @@ pub fn execute_remove_hook<Q: CustomQuery>(
     Ok(res)
 }
 
+pub fn execute_add_claim_hook<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    hook: String,
+) -> Result<Response, ContractError> {
+    // custom guard: using a preauth OR being admin
+    if !ADMIN.is_admin(deps.as_ref(), &info.sender)? {
+        PREAUTH_HOOKS.use_auth(deps.storage)?;
+    }
+
+    // add the hook
+    CLAIM_HOOKS.add_hook(deps.storage, deps.api.addr_validate(&hook)?)?;
+
+    // response
+    let res = Response::new()
+        .add_attribute("action", "add_claim_hook")
+        .add_attribute("hook", hook)
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
+pub fn execute_remove_claim_hook<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    hook: String,
+) -> Result<Response, ContractError> {
+    // custom guard: self-removal OR being admin
+    let hook_addr = deps.api.addr_validate(&hook)?;
+    if info.sender != hook_addr && !ADMIN.is_admin(deps.as_ref(), &info.sender)? {
+        return Err(ContractError::Unauthorized(
+            "Hook address is not same as sender's and sender is not an admin".to_owned(),
+        ));
+    }
+
+    // remove the hook
+    CLAIM_HOOKS.remove_hook(deps.storage, hook_addr)?;
+
+    // response
+    let res = Response::new()
+        .add_attribute("action", "remove_claim_hook")
+        .add_attribute("hook", hook)
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
 pub fn execute_bond<Q: CustomQuery>(
     deps: DepsMut<Q>,
     env: Env,
@@ -151,6 +241,15 @@ pub fn execute_bond<Q: CustomQuery>(
     vesting_tokens: Option<Coin>,
 ) -> Result<Response, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
+    if cfg.reject_zero_funds
+        && info
+            .funds
+            .iter()
+            .chain(&vesting_tokens)
+            .any(|coin| coin.amount.is_zero())
+    {
+        return Err(ContractError::ZeroFunds {});
+    }
     let amount = validate_funds(&info.funds, &cfg.denom)?;
     let vesting_amount = vesting_tokens
         .map(|v| validate_funds(&[v], &cfg.denom))
@@ -159,11 +258,35 @@ pub fn execute_bond<Q: CustomQuery>(
     if amount + vesting_amount == Uint128::zero() {
         return Err(ContractError::NoFunds {});
     }
+    if let Some(max) = cfg.max_bond_per_tx {
+        if amount + vesting_amount > max {
+            return Err(ContractError::BondTooLarge {
+                amount: amount + vesting_amount,
+                max,
+            });
+        }
+    }
 
     // update the sender's stake
-    let new_stake = STAKE.update(deps.storage, &info.sender, |stake| -> StdResult<_> {
-        Ok(stake.unwrap_or_default() + amount)
-    })?;
+    let new_stake = STAKE.update(
+        deps.storage,
+        &info.sender,
+        env.block.height,
+        |stake| -> StdResult<_> { Ok(stake.unwrap_or_default() + amount) },
+    )?;
+
+    // Track recently bonded tokens, which are locked for `min_stake_duration`
+    if let Some(min_stake_duration) = cfg.min_stake_duration {
+        RECENT_BONDS.update(deps.storage, &info.sender, |recent| -> StdResult<_> {
+            let locked = recent
+                .map(|recent| recent.locked(min_stake_duration, env.block.time))
+                .unwrap_or_default();
+            Ok(RecentBond {
+                amount: locked + amount + vesting_amount,
+                last_bond: env.block.time,
+            })
+        })?;
+    }
 
     let mut res = Response::new()
         .add_attribute("action", "bond")
@@ -171,10 +294,12 @@ pub fn execute_bond<Q: CustomQuery>(
         .add_attribute("sender", &info.sender);
 
     // Update the sender's vesting stake
-    let new_vesting_stake =
-        STAKE_VESTING.update(deps.storage, &info.sender, |stake| -> StdResult<_> {
-            Ok(stake.unwrap_or_default() + vesting_amount)
-        })?;
+    let new_vesting_stake = STAKE_VESTING.update(
+        deps.storage,
+        &info.sender,
+        env.block.height,
+        |stake| -> StdResult<_> { Ok(stake.unwrap_or_default() + vesting_amount) },
+    )?;
     // Delegate (stake to contract) to sender's vesting account
     if vesting_amount > Uint128::zero() {
         let msg = TgradeMsg::Delegate {
@@ -204,10 +329,12 @@ pub fn execute_unbond<Q: CustomQuery>(
     info: MessageInfo,
     amount: Uint128,
     denom: String,
+    owner: Option<String>,
 ) -> Result<Response, ContractError> {
     if amount.is_zero() {
         return Err(ContractError::ZeroAmount {});
     }
+    let owner = stake_owner(deps.as_ref(), &info.sender, owner)?;
 
     // provide them a claim
     let cfg = CONFIG.load(deps.storage)?;
@@ -216,44 +343,76 @@ pub fn execute_unbond<Q: CustomQuery>(
         return Err(ContractError::InvalidDenom {});
     }
 
+    if let Some(until) = FROZEN.may_load(deps.storage, &owner)? {
+        if !until.is_expired(&env.block) {
+            return Err(ContractError::Frozen { until });
+        }
+    }
+
     // Load stake first for comparison
-    let stake = STAKE
-        .may_load(deps.storage, &info.sender)?
-        .unwrap_or_default();
-    // Reduce the sender's stake - saturating if insufficient
-    let new_stake = STAKE.update(deps.storage, &info.sender, |stake| -> StdResult<_> {
-        Ok(stake.unwrap_or_default().saturating_sub(amount))
-    })?;
+    let stake = STAKE.may_load(deps.storage, &owner)?.unwrap_or_default();
+
+    if let Some(min_stake_duration) = cfg.min_stake_duration {
+        if let Some(recent) = RECENT_BONDS.may_load(deps.storage, &owner)? {
+            let locked = recent.locked(min_stake_duration, env.block.time);
+            let vesting_stake = STAKE_VESTING
+                .may_load(deps.storage, &owner)?
+                .unwrap_or_default();
+            let unbondable = (stake + vesting_stake).saturating_sub(locked);
+            if amount > unbondable {
+                return Err(ContractError::StakeTooNew {
+                    unbondable,
+                    until: recent.last_bond.plus_seconds(min_stake_duration.seconds()),
+                });
+            }
+        }
+    }
+    // Reduce the owner's stake - saturating if insufficient
+    let new_stake = STAKE.update(
+        deps.storage,
+        &owner,
+        env.block.height,
+        |stake| -> StdResult<_> { Ok(stake.unwrap_or_default().saturating_sub(amount)) },
+    )?;
 
     let mut res = Response::new()
         .add_attribute("action", "unbond")
         .add_attribute("amount", amount)
         .add_attribute("denom", &denom)
-        .add_attribute("sender", &info.sender);
+        .add_attribute("sender", &info.sender)
+        .add_attribute("owner", &owner);
 
-    // Reduce the sender's vesting stake - aborting if insufficient
+    // Reduce the owner's vesting stake - aborting if insufficient
     let vesting_amount = amount.saturating_sub(stake);
-    let new_vesting_stake =
-        STAKE_VESTING.update(deps.storage, &info.sender, |stake| -> StdResult<_> {
-            Ok(stake.unwrap_or_default().checked_sub(vesting_amount)?)
-        })?;
+    let new_vesting_stake = STAKE_VESTING.update(
+        deps.storage,
+        &owner,
+        env.block.height,
+        |stake| -> StdResult<_> { Ok(stake.unwrap_or_default().checked_sub(vesting_amount)?) },
+    )?;
 
     // Create claim for unbonded liquid and vesting amounts
     let completion = cfg.unbonding_period.after(&env.block);
+    if let Some(max) = cfg.max_claims_per_addr {
+        if claims().limit_reached(deps.storage, &owner, completion, max) {
+            return Err(ContractError::TooManyClaims {});
+        }
+    }
     claims().create_claim(
         deps.storage,
-        info.sender.clone(),
+        owner.clone(),
         min(stake, amount),
         vesting_amount,
         completion,
         env.block.height,
+        None,
     )?;
     res = res.add_attribute("completion_time", completion.time().nanos().to_string());
 
     // Update membership messages
     res = res.add_submessages(update_membership(
         deps.storage,
-        info.sender,
+        owner,
         new_stake + new_vesting_stake,
         &cfg,
         env.block.height,
@@ -262,6 +421,150 @@ pub fn execute_unbond<Q: CustomQuery>(
     Ok(res)
 }
 
+pub fn execute_unbond_all<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let stake = STAKE
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    let vesting_stake = STAKE_VESTING
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+
+    execute_unbond(deps, env, info, stake + vesting_stake, cfg.denom, None)
+}
+
+pub fn execute_force_unbond<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    addr: String,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let addr = deps.api.addr_validate(&addr)?;
+    let recipient = recipient
+        .map(|recipient| deps.api.addr_validate(&recipient))
+        .transpose()?;
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Whole stake is unbonded, ignoring freezes and `min_stake_duration`
+    let stake = STAKE.may_load(deps.storage, &addr)?.unwrap_or_default();
+    let vesting_stake = STAKE_VESTING
+        .may_load(deps.storage, &addr)?
+        .unwrap_or_default();
+    if stake.is_zero() && vesting_stake.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    STAKE.save(deps.storage, &addr, &Uint128::zero(), env.block.height)?;
+    STAKE_VESTING.save(deps.storage, &addr, &Uint128::zero(), env.block.height)?;
+    RECENT_BONDS.remove(deps.storage, &addr);
+
+    let completion = cfg.unbonding_period.after(&env.block);
+    claims().create_claim(
+        deps.storage,
+        addr.clone(),
+        stake,
+        vesting_stake,
+        completion,
+        env.block.height,
+        recipient.clone(),
+    )?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "force_unbond")
+        .add_attribute("addr", &addr)
+        .add_attribute("liquid_tokens", stake)
+        .add_attribute("vesting_tokens", vesting_stake)
+        .add_attribute("completion_time", completion.time().nanos().to_string())
+        .add_attribute("sender", &info.sender);
+    if let Some(recipient) = recipient {
+        res = res.add_attribute("recipient", recipient);
+    }
+
+    res = res.add_submessages(update_membership(
+        deps.storage,
+        addr,
+        Uint128::zero(),
+        &cfg,
+        env.block.height,
+    )?);
+
+    Ok(res)
+}
+
+pub fn execute_freeze<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    addr: String,
+    until: Expiration,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    FROZEN.save(deps.storage, &deps.api.addr_validate(&addr)?, &until)?;
+
+    let res = Response::new()
+        .add_attribute("action", "freeze")
+        .add_attribute("addr", addr)
+        .add_attribute("until", until.time().to_string())
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
+pub fn execute_compact_claims<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let addr = deps.api.addr_validate(&addr)?;
+    let removed = claims().compact_claims_for_addr(deps.storage, &addr)?;
+
+    let res = Response::new()
+        .add_attribute("action", "compact_claims")
+        .add_attribute("addr", addr)
+        .add_attribute("removed_claims", removed.to_string())
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
+pub fn execute_freeze_contract<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    frozen: bool,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    CONTRACT_FROZEN.save(deps.storage, &frozen)?;
+
+    let res = Response::new()
+        .add_attribute("action", "freeze_contract")
+        .add_attribute("frozen", frozen.to_string())
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
+pub fn execute_unfreeze<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    FROZEN.remove(deps.storage, &deps.api.addr_validate(&addr)?);
+
+    let res = Response::new()
+        .add_attribute("action", "unfreeze")
+        .add_attribute("addr", addr)
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
 pub fn execute_add_slasher<Q: CustomQuery>(
     deps: DepsMut<Q>,
     info: MessageInfo,
@@ -313,6 +616,7 @@ pub fn execute_slash<Q: CustomQuery>(
     info: MessageInfo,
     addr: String,
     portion: Decimal,
+    include_claims: bool,
 ) -> Result<Response, ContractError> {
     if !SLASHERS.is_slasher(deps.storage, &info.sender)? {
         return Err(ContractError::Unauthorized(
@@ -341,29 +645,45 @@ pub fn execute_slash<Q: CustomQuery>(
 
     // slash the liquid stake, if any
     let mut new_liquid_stake = Uint128::zero();
-    let mut liquid_slashed = Uint128::zero();
+    let mut liquid_stake_slashed = Uint128::zero();
     if let Some(liquid_stake) = liquid_stake {
-        liquid_slashed = liquid_stake * portion;
-        new_liquid_stake = STAKE.update(deps.storage, &addr, |stake| -> StdResult<_> {
-            Ok(stake.unwrap_or_default().sub(liquid_slashed))
-        })?;
+        liquid_stake_slashed = liquid_stake * portion;
+        new_liquid_stake = STAKE.update(
+            deps.storage,
+            &addr,
+            env.block.height,
+            |stake| -> StdResult<_> { Ok(stake.unwrap_or_default().sub(liquid_stake_slashed)) },
+        )?;
     }
 
     // slash the vesting stake, if any
     let mut new_vesting_stake = Uint128::zero();
-    let mut vesting_slashed = Uint128::zero();
+    let mut vesting_stake_slashed = Uint128::zero();
     if let Some(vesting_stake) = vesting_stake {
-        vesting_slashed = vesting_stake * portion;
-        new_vesting_stake = STAKE_VESTING.update(deps.storage, &addr, |stake| -> StdResult<_> {
-            Ok(stake.unwrap_or_default().sub(vesting_slashed))
-        })?;
+        vesting_stake_slashed = vesting_stake * portion;
+        new_vesting_stake = STAKE_VESTING.update(
+            deps.storage,
+            &addr,
+            env.block.height,
+            |stake| -> StdResult<_> { Ok(stake.unwrap_or_default().sub(vesting_stake_slashed)) },
+        )?;
     }
 
-    // slash the liquid and vesting claims
-    let (liquid_claims_slashed, vesting_claims_slashed) =
-        claims().slash_claims_for_addr(deps.storage, addr.clone(), portion)?;
-    liquid_slashed += liquid_claims_slashed;
-    vesting_slashed += vesting_claims_slashed;
+    // slash the liquid and vesting claims, unless only bonded stake is to be slashed
+    let (liquid_claims_slashed, vesting_claims_slashed) = if include_claims {
+        claims().slash_claims_for_addr(deps.storage, addr.clone(), portion)?
+    } else {
+        (Uint128::zero(), Uint128::zero())
+    };
+
+    // every source is reported separately for indexers, but burned together
+    res = res
+        .add_attribute("liquid_stake_slashed", liquid_stake_slashed)
+        .add_attribute("vesting_stake_slashed", vesting_stake_slashed)
+        .add_attribute("liquid_claims_slashed", liquid_claims_slashed)
+        .add_attribute("vesting_claims_slashed", vesting_claims_slashed);
+    let liquid_slashed = liquid_stake_slashed + liquid_claims_slashed;
+    let vesting_slashed = vesting_stake_slashed + vesting_claims_slashed;
 
     // burn the liquid slashed tokens
     if liquid_slashed > Uint128::zero() {
@@ -381,17 +701,72 @@ pub fn execute_slash<Q: CustomQuery>(
         res = res.add_message(burn_vesting_msg);
     }
 
-    res.messages.extend(update_membership(
+    // Depending on policy, member slashed below `min_bond` is kept at the membership floor
+    let mut new_stake = new_liquid_stake + new_vesting_stake;
+    if cfg.slash_policy == SlashPolicy::ClampAtMinBond
+        && !new_stake.is_zero()
+        && new_stake < cfg.min_bond
+        && members().may_load(deps.storage, &addr)?.is_some()
+    {
+        new_stake = cfg.min_bond;
+    }
+
+    // Failing hooks must not prevent slashing, so their errors are caught and only logged
+    let hooks = update_membership(
         deps.storage,
-        addr,
-        new_liquid_stake + new_vesting_stake,
+        addr.clone(),
+        new_stake,
         &cfg,
         env.block.height,
-    )?);
+    )?;
+    // zero if the address is not a member anymore
+    let new_points = members()
+        .may_load(deps.storage, &addr)?
+        .map(|member| member.points)
+        .unwrap_or_default();
+    res = res.add_attribute("new_points", new_points.to_string());
+    res.messages.extend(
+        hooks
+            .into_iter()
+            .map(|hook| SubMsg::reply_on_error(hook.msg, SLASH_HOOK_REPLY_ID)),
+    );
 
     Ok(res)
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(
+    _deps: DepsMut<TgradeQuery>,
+    _env: Env,
+    msg: Reply,
+) -> Result<Response, ContractError> {
+    match msg.id {
+        SLASH_HOOK_REPLY_ID => Ok(slash_hook_reply(msg.result)),
+        CLAIM_HOOK_REPLY_ID => Ok(claim_hook_reply(msg.result)),
+        _ => Err(ContractError::UnrecognisedReply(msg.id)),
+    }
+}
+
+/// Logs failure of claim hook sent on automatic claims release, without reverting the release
+fn claim_hook_reply(result: SubMsgResult) -> Response {
+    match result {
+        SubMsgResult::Err(err) => Response::new()
+            .add_attribute("action", "claim_hook_failed")
+            .add_attribute("error", err),
+        SubMsgResult::Ok(_) => Response::new(),
+    }
+}
+
+/// Logs failure of member changed hook sent on slashing, without reverting the slash
+fn slash_hook_reply(result: SubMsgResult) -> Response {
+    match result {
+        SubMsgResult::Err(err) => Response::new()
+            .add_attribute("action", "slash_hook_failed")
+            .add_attribute("error", err),
+        SubMsgResult::Ok(_) => Response::new(),
+    }
+}
+
 /// Validates funds sent with the message, that they are containing only a single denom. Returns
 /// amount of funds sent, or error if:
 /// * More than a single denom is sent (`ExtraDenoms` error)
@@ -401,8 +776,14 @@ pub fn validate_funds(funds: &[Coin], stake_denom: &str) -> Result<Uint128, Cont
     match funds {
         [] => Ok(Uint128::zero()),
         [Coin { denom, amount }] if denom == stake_denom => Ok(*amount),
-        [_] => Err(ContractError::MissingDenom(stake_denom.to_string())),
-        _ => Err(ContractError::ExtraDenoms(stake_denom.to_string())),
+        [Coin { denom, .. }] => Err(ContractError::MissingDenom {
+            expected: stake_denom.to_string(),
+            found: denom.clone(),
+        }),
+        _ => Err(ContractError::ExtraDenoms {
+            expected: stake_denom.to_string(),
+            found: funds.iter().map(|c| c.denom.clone()).collect(),
+        }),
     }
 }
 
@@ -442,55 +823,162 @@ fn update_membership(
 }
 
 fn calc_points(stake: Uint128, cfg: &Config) -> Option<u64> {
+    // `min_bond` cutoff is checked on the stake itself, so rounding up never grants points
+    // below it
     if stake < cfg.min_bond {
         None
     } else {
-        let p = stake.u128() / cfg.tokens_per_point.u128();
+        // Dividing by decimal is multiplying by its fractional and dividing by its atomics
+        let stake = stake.full_mul(Decimal::one().atomics());
+        let tokens_per_point = Uint256::from(cfg.tokens_per_point.atomics());
+        let p = stake / tokens_per_point;
+        let p = match cfg.points_rounding {
+            Rounding::Down => p,
+            Rounding::Nearest => {
+                let half = tokens_per_point - tokens_per_point / Uint256::from(2u8);
+                if stake % tokens_per_point >= half {
+                    p + Uint256::one()
+                } else {
+                    p
+                }
+            }
+        };
+        let p = Uint128::try_from(p).map_or(u128::MAX, |p| p.u128());
         Some(p as u64)
     }
 }
 
+/// Returns address whose stake is managed by `sender`: `owner` if `sender` is its stake delegate,
+/// `sender` itself if `owner` is not given
+fn stake_owner<Q: CustomQuery>(
+    deps: Deps<Q>,
+    sender: &Addr,
+    owner: Option<String>,
+) -> Result<Addr, ContractError> {
+    let owner = match owner {
+        Some(owner) => deps.api.addr_validate(&owner)?,
+        None => return Ok(sender.clone()),
+    };
+    if owner != *sender && STAKE_DELEGATE.may_load(deps.storage, &owner)?.as_ref() != Some(sender) {
+        return Err(ContractError::Unauthorized(
+            "Sender is not a stake delegate of the owner".to_owned(),
+        ));
+    }
+    Ok(owner)
+}
+
+pub fn execute_delegate_stake_control<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    delegate: String,
+) -> Result<Response, ContractError> {
+    let delegate = deps.api.addr_validate(&delegate)?;
+
+    if delegate == info.sender {
+        STAKE_DELEGATE.remove(deps.storage, &info.sender);
+    } else {
+        STAKE_DELEGATE.save(deps.storage, &info.sender, &delegate)?;
+    }
+
+    let res = Response::new()
+        .add_attribute("action", "delegate_stake_control")
+        .add_attribute("sender", &info.sender)
+        .add_attribute("delegate", delegate);
+    Ok(res)
+}
+
 pub fn execute_claim<Q: CustomQuery>(
     deps: DepsMut<Q>,
     env: Env,
     info: MessageInfo,
+    receiver: Option<String>,
+    owner: Option<String>,
 ) -> Result<Response, ContractError> {
-    let (release, vesting_release) =
-        claims().claim_addr(deps.storage, &info.sender, &env.block, None)?;
-    if release.is_zero() && vesting_release.is_zero() {
+    let owner = stake_owner(deps.as_ref(), &info.sender, owner)?;
+    // Delegate can't redirect tokens away from the owner
+    if owner != info.sender && receiver.is_some() {
+        return Err(ContractError::Unauthorized(
+            "Stake delegate cannot set claim receiver".to_owned(),
+        ));
+    }
+    let receiver = match receiver {
+        Some(receiver) => deps.api.addr_validate(&receiver)?,
+        None => owner.clone(),
+    };
+
+    let release_data = claims().claim_addr(deps.storage, &owner, &env.block, None)?;
+    if release_data.is_empty() {
         return Err(ContractError::NothingToClaim {});
     }
+    let release = release_data.liquid;
+    let vesting_release = release_data.vesting;
 
     let config = CONFIG.load(deps.storage)?;
 
     let mut res = Response::new()
         .add_attribute("action", "claim")
-        .add_attribute("sender", &info.sender);
+        .add_attribute("sender", &info.sender)
+        .add_attribute("owner", &owner);
 
     if !release.is_zero() {
         let amount = coin(release.into(), config.denom.clone());
         res = res
             .add_attribute("liquid_tokens", amount.to_string())
+            .add_attribute("receiver", &receiver)
+            .add_message(BankMsg::Send {
+                to_address: receiver.into(),
+                amount: vec![amount],
+            });
+    }
+
+    // Claims created by `ForceUnbond` with a recipient are paid to it, regardless of `receiver`
+    for redirected in release_data.redirected {
+        if redirected.amount.is_zero() {
+            continue;
+        }
+        let amount = coin(redirected.amount.into(), config.denom.clone());
+        res = res
+            .add_attribute("redirected_tokens", amount.to_string())
+            .add_attribute("recipient", &redirected.addr)
             .add_message(BankMsg::Send {
-                to_address: info.sender.clone().into(),
+                to_address: redirected.addr.into(),
                 amount: vec![amount],
             });
     }
 
     if !vesting_release.is_zero() {
         let vesting_amount = coin(vesting_release.into(), config.denom);
-        // Undelegate (unstake from contract) to sender's vesting account
+        // Undelegate (unstake from contract) to owner's vesting account. Undelegation cannot
+        // change the owner, so `receiver` is ignored here
         res = res
             .add_attribute("vesting_tokens", vesting_amount.to_string())
             .add_message(TgradeMsg::Undelegate {
                 funds: vesting_amount,
-                recipient: info.sender.to_string(),
+                recipient: owner.to_string(),
             });
     }
 
     Ok(res)
 }
 
+pub fn execute_set_auto_restake<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    if enabled {
+        AUTO_RESTAKE.save(deps.storage, &info.sender, &true)?;
+    } else {
+        AUTO_RESTAKE.remove(deps.storage, &info.sender);
+    }
+
+    let res = Response::new()
+        .add_attribute("action", "set_auto_restake")
+        .add_attribute("enabled", enabled.to_string())
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn sudo(
     deps: DepsMut<TgradeQuery>,
@@ -519,28 +1007,103 @@ fn privilege_promote<Q: CustomQuery>(deps: DepsMut<Q>) -> Result<Response, Contr
 }
 
 fn end_block<Q: CustomQuery>(deps: DepsMut<Q>, env: Env) -> Result<Response, ContractError> {
-    let mut resp = Response::new();
-
     let config = CONFIG.load(deps.storage)?;
-    if config.auto_return_limit > 0 {
-        let sub_msgs = release_expired_claims(deps, env, config)?;
-        resp = resp.add_submessages(sub_msgs);
+    let frozen = CONTRACT_FROZEN.may_load(deps.storage)?.unwrap_or_default();
+    if config.auto_return_limit > 0 && !frozen {
+        release_expired_claims(deps, env, config)
+    } else {
+        Ok(Response::new())
     }
+}
 
-    Ok(resp)
-}
-
+/// Releases up to `auto_return_limit` expired claims. Numbers of released claims and of expired
+/// claims left for later blocks are reported as `claims_released` and `claims_remaining_expired`
+/// attributes, so the limit can be tuned. Remaining claims are counted up to the limit only.
 fn release_expired_claims<Q: CustomQuery>(
     deps: DepsMut<Q>,
     env: Env,
     config: Config,
-) -> Result<Vec<SubMsg>, ContractError> {
+) -> Result<Response, ContractError> {
     let release_data =
         claims().claim_expired(deps.storage, &env.block, config.auto_return_limit)?;
 
-    let send_msgs = release_data
+    // Creation heights of released claims, so auto releases can be matched with unbonds
+    let events: Vec<_> = release_data
+        .liquid_releases
+        .iter()
+        .map(|release_info| {
+            let heights = release_info
+                .creation_heights
+                .iter()
+                .map(u64::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+            Event::new("claims_released")
+                .add_attribute("owner", &release_info.addr)
+                .add_attribute("creation_heights", heights)
+        })
+        .collect();
+
+    // Restaked claims are taken from the same `auto_return_limit` budget as returned ones, so the
+    // amount of work done here stays bounded
+    let (restake_liquid, liquid_releases): (Vec<_>, Vec<_>) = release_data
         .liquid_releases
         .into_iter()
+        .partition(|release_info| AUTO_RESTAKE.has(deps.storage, &release_info.addr));
+    let (restake_vesting, vesting_releases): (Vec<_>, Vec<_>) = release_data
+        .vesting_releases
+        .into_iter()
+        .partition(|release_info| AUTO_RESTAKE.has(deps.storage, &release_info.addr));
+
+    // Both releases are grouped by the same, sorted addresses, so they can be zipped together.
+    // Vesting tokens are still delegated to the contract, so they are just accounted as stake again
+    let mut restake_msgs = vec![];
+    for (liquid, vesting) in restake_liquid.into_iter().zip(restake_vesting) {
+        let new_stake = STAKE.update(
+            deps.storage,
+            &liquid.addr,
+            env.block.height,
+            |stake| -> StdResult<_> { Ok(stake.unwrap_or_default() + liquid.amount) },
+        )?;
+        let new_vesting_stake = STAKE_VESTING.update(
+            deps.storage,
+            &vesting.addr,
+            env.block.height,
+            |stake| -> StdResult<_> { Ok(stake.unwrap_or_default() + vesting.amount) },
+        )?;
+        restake_msgs.extend(update_membership(
+            deps.storage,
+            liquid.addr,
+            new_stake + new_vesting_stake,
+            &config,
+            env.block.height,
+        )?);
+    }
+
+    // Both releases are still grouped by the same addresses after partitioning
+    let mut hook_msgs = vec![];
+    for (liquid, vesting) in liquid_releases.iter().zip(&vesting_releases) {
+        if liquid.amount.is_zero() && vesting.amount.is_zero() {
+            continue;
+        }
+        let released = ClaimReleasedHookMsg {
+            addr: liquid.addr.clone(),
+            liquid: liquid.amount,
+            vesting: vesting.amount,
+        };
+        // Failing hooks must not block releasing claims, so their errors are caught and logged
+        hook_msgs.extend(CLAIM_HOOKS.prepare_hooks(deps.storage, |h| {
+            released
+                .clone()
+                .into_cosmos_msg(h)
+                .map(|msg| SubMsg::reply_on_error(msg, CLAIM_HOOK_REPLY_ID))
+        })?);
+    }
+
+    // Redirected claims are never restaked, they are always sent to their recipients
+    let send_msgs = liquid_releases
+        .into_iter()
+        .chain(release_data.redirected_releases)
         .filter(|release_info| !release_info.amount.is_zero())
         .map(|release_info| {
             let amount = coins(release_info.amount.into(), config.denom.clone());
@@ -551,8 +1114,7 @@ fn release_expired_claims<Q: CustomQuery>(
         })
         .collect::<StdResult<Vec<_>>>()?;
 
-    let undelegate_msgs = release_data
-        .vesting_releases
+    let undelegate_msgs = vesting_releases
         .into_iter()
         .filter(|release_info| !release_info.amount.is_zero())
         .map(|release_info| {
@@ -564,11 +1126,25 @@ fn release_expired_claims<Q: CustomQuery>(
         })
         .collect::<StdResult<Vec<_>>>()?;
 
-    Ok(send_msgs.into_iter().chain(undelegate_msgs).collect())
+    let msgs: Vec<_> = send_msgs
+        .into_iter()
+        .chain(undelegate_msgs)
+        .chain(restake_msgs)
+        .chain(hook_msgs)
+        .collect();
+    let resp = Response::new()
+        .add_submessages(msgs)
+        .add_events(events)
+        .add_attribute("claims_released", release_data.released.to_string())
+        .add_attribute(
+            "claims_remaining_expired",
+            release_data.remaining_expired.to_string(),
+        );
+    Ok(resp)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps<TgradeQuery>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps<TgradeQuery>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     use QueryMsg::*;
     match msg {
         Configuration {} => to_binary(&CONFIG.load(deps.storage)?),
@@ -593,12 +1169,16 @@ pub fn query(deps: Deps<TgradeQuery>, _env: Env, msg: QueryMsg) -> StdResult<Bin
                 start_after,
             )?,
         }),
-        Staked { address } => to_binary(&query_staked(deps, address)?),
+        Staked { address, at_height } => to_binary(&query_staked(deps, address, at_height)?),
         Admin {} => to_binary(&ADMIN.query_admin(deps)?),
         Hooks {} => {
             let hooks = HOOKS.list_hooks(deps.storage)?;
             to_binary(&HooksResponse { hooks })
         }
+        ClaimHooks {} => {
+            let hooks = CLAIM_HOOKS.list_hooks(deps.storage)?;
+            to_binary(&HooksResponse { hooks })
+        }
         Preauths {} => {
             let preauths_hooks = PREAUTH_HOOKS.get_auth(deps.storage)?;
             to_binary(&PreauthResponse { preauths_hooks })
@@ -614,7 +1194,151 @@ pub fn query(deps: Deps<TgradeQuery>, _env: Env, msg: QueryMsg) -> StdResult<Bin
             to_binary(&SLASHERS.is_slasher(deps.storage, &addr)?)
         }
         ListSlashers {} => to_binary(&SLASHERS.list_slashers(deps.storage)?),
+        StakeDelegate { owner } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            to_binary(&StakeDelegateResponse {
+                delegate: STAKE_DELEGATE.may_load(deps.storage, &owner)?,
+            })
+        }
+        AutoRestake { address } => {
+            let addr = deps.api.addr_validate(&address)?;
+            to_binary(&AutoRestakeResponse {
+                enabled: AUTO_RESTAKE.has(deps.storage, &addr),
+            })
+        }
+        IsFrozen { addr } => {
+            let addr = deps.api.addr_validate(&addr)?;
+            let frozen = FROZEN.may_load(deps.storage, &addr)?;
+            to_binary(&matches!(frozen, Some(until) if !until.is_expired(&env.block)))
+        }
+        IsContractFrozen {} => {
+            to_binary(&CONTRACT_FROZEN.may_load(deps.storage)?.unwrap_or_default())
+        }
+        GlobalUnlockSchedule {
+            bucket_seconds,
+            buckets,
+        } => to_binary(&query_global_unlock_schedule(
+            deps,
+            env,
+            bucket_seconds,
+            buckets,
+        )?),
+        ReleaseSchedule { start_after, limit } => {
+            to_binary(&query_release_schedule(deps, start_after, limit)?)
+        }
+        Invariants {} => to_binary(&query_invariants(deps, env)?),
+    }
+}
+
+fn query_invariants<Q: CustomQuery>(deps: Deps<Q>, env: Env) -> StdResult<InvariantsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let total = TOTAL.load(deps.storage)?;
+
+    let points_sum = members()
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, mi)| mi.points))
+        .sum::<StdResult<u64>>()?;
+
+    let mut stakes = BTreeMap::new();
+    for stake in STAKE.range(deps.storage, None, None, Order::Ascending) {
+        let (addr, stake) = stake?;
+        *stakes.entry(addr).or_insert_with(Uint128::zero) += stake;
+    }
+    for stake in STAKE_VESTING.range(deps.storage, None, None, Order::Ascending) {
+        let (addr, stake) = stake?;
+        *stakes.entry(addr).or_insert_with(Uint128::zero) += stake;
+    }
+    // Members slashed below `min_bond` may be kept at its points
+    let clamped_points = calc_points(config.min_bond, &config);
+    let mut stake_points = 0;
+    for (addr, stake) in stakes {
+        let points = members().may_load(deps.storage, &addr)?.map(|mi| mi.points);
+        let clamped = config.slash_policy == SlashPolicy::ClampAtMinBond
+            && !stake.is_zero()
+            && stake < config.min_bond
+            && points == clamped_points;
+        stake_points += if clamped {
+            points.unwrap_or_default()
+        } else {
+            calc_points(stake, &config).unwrap_or_default()
+        };
+    }
+
+    let liquid_stake = STAKE
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, stake)| stake))
+        .sum::<StdResult<Uint128>>()?;
+    let liquid_claims = claims().liquid_total(deps.storage)?;
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.denom)?
+        .amount;
+
+    Ok(InvariantsResponse {
+        config,
+        total_matches_sum_of_points: total == points_sum,
+        stake_totals_match_running_totals: total == stake_points,
+        no_negative_balances: balance >= liquid_stake + liquid_claims,
+    })
+}
+
+fn query_release_schedule<Q: CustomQuery>(
+    deps: Deps<Q>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ReleaseScheduleResponse> {
+    let denom = CONFIG.load(deps.storage)?.denom;
+    let buckets = claims()
+        .query_release_schedule(deps, start_after, limit)?
+        .into_iter()
+        .map(|(release_at, liquid, vesting)| ReleaseBucket {
+            release_at,
+            liquid: coin(liquid.u128(), &denom),
+            vesting: coin(vesting.u128(), &denom),
+        })
+        .collect();
+    Ok(ReleaseScheduleResponse { buckets })
+}
+
+fn query_global_unlock_schedule<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: Env,
+    bucket_seconds: u64,
+    buckets: u32,
+) -> StdResult<UnlockScheduleResponse> {
+    if bucket_seconds == 0 {
+        return Err(StdError::generic_err(
+            "Bucket length must be greater than zero",
+        ));
     }
+    if buckets == 0 {
+        return Err(StdError::generic_err(
+            "Number of buckets must be greater than zero",
+        ));
+    }
+    let buckets = min(buckets, MAX_LIMIT) as usize;
+    let bucket_nanos = bucket_seconds.saturating_mul(1_000_000_000);
+    let denom = CONFIG.load(deps.storage)?.denom;
+
+    let start = env.block.time;
+    let schedule = claims().query_unlock_schedule(deps, start, bucket_nanos, buckets)?;
+    let buckets = schedule
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (liquid, vesting))| {
+            let bucket_start = start
+                .nanos()
+                .saturating_add(bucket_nanos.saturating_mul(idx as u64));
+            UnlockBucket {
+                start: Timestamp::from_nanos(bucket_start),
+                end: Timestamp::from_nanos(bucket_start.saturating_add(bucket_nanos)),
+                liquid: coin(liquid.u128(), &denom),
+                vesting: coin(vesting.u128(), &denom),
+            }
+        })
+        .collect();
+
+    Ok(UnlockScheduleResponse { buckets })
 }
 
 fn query_total_points<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<TotalPointsResponse> {
@@ -622,12 +1346,35 @@ fn query_total_points<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<TotalPointsRes
     Ok(TotalPointsResponse { points })
 }
 
-pub fn query_staked<Q: CustomQuery>(deps: Deps<Q>, addr: String) -> StdResult<StakedResponse> {
+pub fn query_staked<Q: CustomQuery>(
+    deps: Deps<Q>,
+    addr: String,
+    height: Option<u64>,
+) -> StdResult<StakedResponse> {
     let addr = deps.api.addr_validate(&addr)?;
-    let stake = STAKE.may_load(deps.storage, &addr)?.unwrap_or_default();
-    let vesting = STAKE_VESTING
-        .may_load(deps.storage, &addr)?
-        .unwrap_or_default();
+    let (stake, vesting) = match height {
+        Some(h) => {
+            let start = STAKE_HISTORY_START
+                .may_load(deps.storage)?
+                .unwrap_or_default();
+            if h < start {
+                return Err(StdError::generic_err(format!(
+                    "Stake history is not available before height {}",
+                    start
+                )));
+            }
+            (
+                STAKE.may_load_at_height(deps.storage, &addr, h)?,
+                STAKE_VESTING.may_load_at_height(deps.storage, &addr, h)?,
+            )
+        }
+        None => (
+            STAKE.may_load(deps.storage, &addr)?,
+            STAKE_VESTING.may_load(deps.storage, &addr)?,
+        ),
+    };
+    let stake = stake.unwrap_or_default();
+    let vesting = vesting.unwrap_or_default();
     let config = CONFIG.load(deps.storage)?;
 
     Ok(StakedResponse {
@@ -725,15 +1472,20 @@ fn list_members_by_points<Q: CustomQuery>(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(
     deps: DepsMut<TgradeQuery>,
-    _env: Env,
+    env: Env,
     msg: MigrateMsg,
 ) -> Result<Response, ContractError> {
     ensure_from_older_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    // Stakes were not snapshotted before, their history starts now
+    if STAKE_HISTORY_START.may_load(deps.storage)?.is_none() {
+        STAKE_HISTORY_START.save(deps.storage, &env.block.height)?;
+    }
+
     CONFIG.update::<_, StdError>(deps.storage, |mut cfg| {
         if let Some(tokens_per_point) = msg.tokens_per_point {
-            let tokens_per_point = if tokens_per_point == Uint128::zero() {
-                Uint128::new(1)
+            let tokens_per_point = if tokens_per_point.is_zero() {
+                Decimal::one()
             } else {
                 tokens_per_point
             };
@@ -753,6 +1505,36 @@ pub fn migrate(
         if let Some(auto_return_limit) = msg.auto_return_limit {
             cfg.auto_return_limit = auto_return_limit;
         }
+        if let Some(reject_zero_funds) = msg.reject_zero_funds {
+            cfg.reject_zero_funds = reject_zero_funds;
+        }
+        if let Some(max_bond_per_tx) = msg.max_bond_per_tx {
+            cfg.max_bond_per_tx = if max_bond_per_tx.is_zero() {
+                None
+            } else {
+                Some(max_bond_per_tx)
+            };
+        }
+        if let Some(min_stake_duration) = msg.min_stake_duration {
+            cfg.min_stake_duration = if min_stake_duration == 0 {
+                None
+            } else {
+                Some(Duration::new(min_stake_duration))
+            };
+        }
+        if let Some(max_claims_per_addr) = msg.max_claims_per_addr {
+            cfg.max_claims_per_addr = if max_claims_per_addr == 0 {
+                None
+            } else {
+                Some(max_claims_per_addr)
+            };
+        }
+        if let Some(slash_policy) = msg.slash_policy {
+            cfg.slash_policy = slash_policy;
+        }
+        if let Some(points_rounding) = msg.points_rounding {
+            cfg.points_rounding = points_rounding;
+        }
         Ok(cfg)
     })?;
 
@@ -766,6 +1548,7 @@ mod tests {
     use cosmwasm_std::{
         from_slice, CosmosMsg, OverflowError, OverflowOperation, StdError, Storage,
     };
+    use cw_controllers::AdminError;
     use tg4::{member_key, TOTAL_KEY};
     use tg_utils::{Expiration, HookError, PreauthError, SlasherError};
 
@@ -779,7 +1562,8 @@ mod tests {
     const USER2: &str = "user2";
     const USER3: &str = "user3";
     const DENOM: &str = "stake";
-    const TOKENS_PER_POINT: Uint128 = Uint128::new(1_000);
+    // 1_000 tokens per point, in `Decimal` atomics
+    const TOKENS_PER_POINT: Decimal = Decimal::raw(1_000_000_000_000_000_000_000);
     const MIN_BOND: Uint128 = Uint128::new(5_000);
     const UNBONDING_DURATION: u64 = 100;
 
@@ -789,7 +1573,7 @@ mod tests {
 
     fn do_instantiate(
         deps: DepsMut<TgradeQuery>,
-        tokens_per_point: Uint128,
+        tokens_per_point: Decimal,
         min_bond: Uint128,
         unbonding_period: u64,
         auto_return_limit: u64,
@@ -803,6 +1587,12 @@ mod tests {
             preauths_hooks: 1,
             preauths_slashing: 1,
             auto_return_limit,
+            reject_zero_funds: false,
+            max_bond_per_tx: None,
+            min_stake_duration: None,
+            max_claims_per_addr: None,
+            slash_policy: SlashPolicy::Remove,
+            points_rounding: Rounding::Down,
         };
         let info = mock_info("creator", &[]);
         instantiate(deps, mock_env(), info, msg).unwrap();
@@ -875,6 +1665,7 @@ mod tests {
             if *stake != 0 {
                 let msg = ExecuteMsg::Unbond {
                     tokens: coin(*stake, DENOM),
+                    owner: None,
                 };
                 let info = mock_info(addr, &[]);
                 execute(deps.branch(), env.clone(), info, msg).unwrap();
@@ -904,15 +1695,50 @@ mod tests {
                 min_bond: MIN_BOND,
                 unbonding_period: Duration::new(UNBONDING_DURATION),
                 auto_return_limit: 0,
+                reject_zero_funds: false,
+                max_bond_per_tx: None,
+                min_stake_duration: None,
+                max_claims_per_addr: None,
+                slash_policy: SlashPolicy::Remove,
+                points_rounding: Rounding::Down,
             }
         );
 
         // query the admin's staked amount (just to confirm the query works)
-        let res = query_staked(deps.as_ref(), INIT_ADMIN.into()).unwrap();
+        let res = query_staked(deps.as_ref(), INIT_ADMIN.into(), None).unwrap();
         assert_eq!(coin(0, "stake"), res.liquid);
         assert_eq!(coin(0, "stake"), res.vesting);
     }
 
+    #[test]
+    fn staked_at_height_query_works() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+        let height = mock_env().block.height;
+
+        bond(deps.as_mut(), (12_000, 0), (0, 0), (0, 0), 1);
+        bond(deps.as_mut(), (3_000, 5_000), (0, 0), (0, 0), 3);
+
+        let staked_at = |deps: Deps<TgradeQuery>, h: u64| {
+            let res = query_staked(deps, USER1.into(), Some(h)).unwrap();
+            (res.liquid.amount.u128(), res.vesting.amount.u128())
+        };
+
+        // Stake changed at a height is visible since the next one
+        assert_eq!(staked_at(deps.as_ref(), height), (0, 0));
+        assert_eq!(staked_at(deps.as_ref(), height + 1), (0, 0));
+        assert_eq!(staked_at(deps.as_ref(), height + 2), (12_000, 0));
+        assert_eq!(staked_at(deps.as_ref(), height + 3), (12_000, 0));
+        assert_eq!(staked_at(deps.as_ref(), height + 4), (15_000, 5_000));
+
+        let res = query_staked(deps.as_ref(), USER1.into(), None).unwrap();
+        assert_eq!(coin(15_000, DENOM), res.liquid);
+        assert_eq!(coin(5_000, DENOM), res.vesting);
+
+        // History before instantiation is not available
+        query_staked(deps.as_ref(), USER1.into(), Some(height - 1)).unwrap_err();
+    }
+
     #[test]
     fn unbonding_period_query_works() {
         let mut deps = mock_deps_tgrade();
@@ -972,26 +1798,26 @@ mod tests {
     // this tests the member queries of liquid amounts
     #[track_caller]
     fn assert_stake_liquid(deps: Deps<TgradeQuery>, user1: u128, user2: u128, user3: u128) {
-        let stake1 = query_staked(deps, USER1.into()).unwrap();
+        let stake1 = query_staked(deps, USER1.into(), None).unwrap();
         assert_eq!(stake1.liquid, coin(user1, DENOM));
 
-        let stake2 = query_staked(deps, USER2.into()).unwrap();
+        let stake2 = query_staked(deps, USER2.into(), None).unwrap();
         assert_eq!(stake2.liquid, coin(user2, DENOM));
 
-        let stake3 = query_staked(deps, USER3.into()).unwrap();
+        let stake3 = query_staked(deps, USER3.into(), None).unwrap();
         assert_eq!(stake3.liquid, coin(user3, DENOM));
     }
 
     // this tests the member queries of illiquid amounts
     #[track_caller]
     fn assert_stake_vesting(deps: Deps<TgradeQuery>, user1: u128, user2: u128, user3: u128) {
-        let stake1 = query_staked(deps, USER1.into()).unwrap();
+        let stake1 = query_staked(deps, USER1.into(), None).unwrap();
         assert_eq!(stake1.vesting, coin(user1, DENOM));
 
-        let stake2 = query_staked(deps, USER2.into()).unwrap();
+        let stake2 = query_staked(deps, USER2.into(), None).unwrap();
         assert_eq!(stake2.vesting, coin(user2, DENOM));
 
-        let stake3 = query_staked(deps, USER3.into()).unwrap();
+        let stake3 = query_staked(deps, USER3.into(), None).unwrap();
         assert_eq!(stake3.vesting, coin(user3, DENOM));
     }
 
@@ -1025,6 +1851,153 @@ mod tests {
         // after second stake
     }
 
+    fn instantiate_with_rounding(deps: DepsMut<TgradeQuery>, points_rounding: Rounding) {
+        let msg = InstantiateMsg {
+            denom: DENOM.to_owned(),
+            tokens_per_point: TOKENS_PER_POINT,
+            min_bond: MIN_BOND,
+            unbonding_period: UNBONDING_DURATION,
+            admin: Some(INIT_ADMIN.into()),
+            preauths_hooks: 1,
+            preauths_slashing: 1,
+            auto_return_limit: 0,
+            reject_zero_funds: false,
+            max_bond_per_tx: None,
+            min_stake_duration: None,
+            max_claims_per_addr: None,
+            slash_policy: SlashPolicy::Remove,
+            points_rounding,
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn points_rounding_down() {
+        let mut deps = mock_deps_tgrade();
+        instantiate_with_rounding(deps.as_mut(), Rounding::Down);
+
+        bond_liquid(deps.as_mut(), 12_499, 7_500, 4_900, 1);
+        assert_users(deps.as_ref(), Some(12), Some(7), None, None);
+    }
+
+    #[test]
+    fn points_rounding_nearest() {
+        let mut deps = mock_deps_tgrade();
+        instantiate_with_rounding(deps.as_mut(), Rounding::Nearest);
+
+        // 4_900 would round up to 5 points, but it is still below `min_bond`
+        bond_liquid(deps.as_mut(), 12_499, 7_500, 4_900, 1);
+        assert_users(deps.as_ref(), Some(12), Some(8), None, None);
+
+        // Reaching `min_bond` grants points
+        bond_liquid(deps.as_mut(), 0, 0, 100, 2);
+        assert_users(deps.as_ref(), Some(12), Some(8), Some(5), None);
+    }
+
+    #[test]
+    fn fractional_tokens_per_point() {
+        let mut cfg = Config {
+            denom: DENOM.to_owned(),
+            tokens_per_point: Decimal::from_ratio(3u128, 2u128),
+            min_bond: Uint128::new(1),
+            unbonding_period: Duration::new(UNBONDING_DURATION),
+            auto_return_limit: 0,
+            reject_zero_funds: false,
+            max_bond_per_tx: None,
+            min_stake_duration: None,
+            max_claims_per_addr: None,
+            slash_policy: SlashPolicy::Remove,
+            points_rounding: Rounding::Down,
+        };
+        let points = |stake: u128, cfg: &Config| calc_points(Uint128::new(stake), cfg);
+
+        // 1.5 tokens per point
+        assert_eq!(points(0, &cfg), None);
+        assert_eq!(points(1, &cfg), Some(0));
+        assert_eq!(points(2, &cfg), Some(1));
+        assert_eq!(points(3, &cfg), Some(2));
+        assert_eq!(points(4, &cfg), Some(2));
+        assert_eq!(points(1_500_000, &cfg), Some(1_000_000));
+        assert_eq!(points(1_499_999, &cfg), Some(999_999));
+
+        cfg.points_rounding = Rounding::Nearest;
+        assert_eq!(points(0, &cfg), None);
+        assert_eq!(points(1, &cfg), Some(1));
+        assert_eq!(points(2, &cfg), Some(1));
+        assert_eq!(points(3, &cfg), Some(2));
+        assert_eq!(points(4, &cfg), Some(3));
+        assert_eq!(points(1_499_999, &cfg), Some(999_999));
+        assert_eq!(points(1_499_251, &cfg), Some(999_501));
+
+        // 0.4 tokens per point - 2.5 points per token, exactly half rounds up
+        cfg.tokens_per_point = Decimal::from_ratio(2u128, 5u128);
+        assert_eq!(points(1, &cfg), Some(3));
+        assert_eq!(points(2, &cfg), Some(5));
+        cfg.points_rounding = Rounding::Down;
+        assert_eq!(points(1, &cfg), Some(2));
+        assert_eq!(points(2, &cfg), Some(5));
+
+        // integer ratio behaves as before
+        cfg.tokens_per_point = TOKENS_PER_POINT;
+        assert_eq!(points(999, &cfg), Some(0));
+        assert_eq!(points(7_500, &cfg), Some(7));
+        assert_eq!(points(u128::MAX, &cfg), Some((u128::MAX / 1_000) as u64));
+    }
+
+    #[test]
+    fn fractional_tokens_per_point_membership() {
+        let mut deps = mock_deps_tgrade();
+        do_instantiate(
+            deps.as_mut(),
+            Decimal::from_ratio(3u128, 2u128),
+            Uint128::new(10),
+            UNBONDING_DURATION,
+            0,
+        );
+
+        bond_liquid(deps.as_mut(), 9, 10, 16, 1);
+        assert_users(deps.as_ref(), None, Some(6), Some(10), None);
+    }
+
+    #[test]
+    fn migrate_keeps_integer_tokens_per_point() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+
+        // Config as stored by older versions, with integer `tokens_per_point`
+        deps.storage.set(
+            b"config",
+            br#"{"denom":"stake","tokens_per_point":"1000","min_bond":"5000","unbonding_period":100,"auto_return_limit":0}"#,
+        );
+        let cfg = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(cfg.tokens_per_point, TOKENS_PER_POINT);
+
+        bond_liquid(deps.as_mut(), 12_000, 7_500, 0, 1);
+        assert_users(deps.as_ref(), Some(12), Some(7), None, None);
+
+        // Zero is still sanitized to one on migration
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.16.0").unwrap();
+        migrate(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg {
+                tokens_per_point: Some(Decimal::zero()),
+                min_bond: None,
+                unbonding_period: None,
+                auto_return_limit: None,
+                reject_zero_funds: None,
+                max_bond_per_tx: None,
+                min_stake_duration: None,
+                max_claims_per_addr: None,
+                slash_policy: None,
+                points_rounding: None,
+            },
+        )
+        .unwrap();
+        let cfg = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(cfg.tokens_per_point, Decimal::one());
+    }
+
     #[test]
     fn bond_stake_vesting_adds_membership() {
         let mut deps = mock_deps_tgrade();
@@ -1246,6 +2219,7 @@ mod tests {
         // Zero amount unbonds are rejected
         let msg = ExecuteMsg::Unbond {
             tokens: coin(0, DENOM),
+            owner: None,
         };
         let env = mock_env();
         let info = mock_info(USER1, &[]);
@@ -1255,6 +2229,7 @@ mod tests {
         // Invalid denom unbonds are rejected
         let msg = ExecuteMsg::Unbond {
             tokens: coin(1234, "INV"),
+            owner: None,
         };
         let env = mock_env();
         let info = mock_info(USER1, &[]);
@@ -1296,6 +2271,7 @@ mod tests {
         // error if try to unbond more than stake (USER2 has 5000 staked)
         let msg = ExecuteMsg::Unbond {
             tokens: coin(5100, DENOM),
+            owner: None,
         };
         let mut env = mock_env();
         env.block.height += 5;
@@ -1435,7 +2411,10 @@ mod tests {
             deps.as_mut(),
             env,
             mock_info(USER1, &[]),
-            ExecuteMsg::Claim {},
+            ExecuteMsg::Claim {
+                receiver: None,
+                owner: None,
+            },
         )
         .unwrap_err();
         assert_eq!(err, ContractError::NothingToClaim {});
@@ -1448,7 +2427,10 @@ mod tests {
             deps.as_mut(),
             env3.clone(),
             mock_info(USER1, &[]),
-            ExecuteMsg::Claim {},
+            ExecuteMsg::Claim {
+                receiver: None,
+                owner: None,
+            },
         )
         .unwrap();
         assert_eq!(
@@ -1470,7 +2452,10 @@ mod tests {
             deps.as_mut(),
             env3.clone(),
             mock_info(USER2, &[]),
-            ExecuteMsg::Claim {},
+            ExecuteMsg::Claim {
+                receiver: None,
+                owner: None,
+            },
         )
         .unwrap();
         assert_eq!(
@@ -1486,7 +2471,10 @@ mod tests {
             deps.as_mut(),
             env3,
             mock_info(USER3, &[]),
-            ExecuteMsg::Claim {},
+            ExecuteMsg::Claim {
+                receiver: None,
+                owner: None,
+            },
         )
         .unwrap_err();
         assert_eq!(err, ContractError::NothingToClaim {});
@@ -1531,7 +2519,10 @@ mod tests {
             deps.as_mut(),
             env4,
             mock_info(USER2, &[]),
-            ExecuteMsg::Claim {},
+            ExecuteMsg::Claim {
+                receiver: None,
+                owner: None,
+            },
         )
         .unwrap();
         assert_eq!(
@@ -1549,80 +2540,701 @@ mod tests {
     }
 
     #[test]
-    fn add_remove_hooks() {
-        // add will over-write and remove have no effect
+    fn claim_to_receiver() {
         let mut deps = mock_deps_tgrade();
         default_instantiate(deps.as_mut());
 
-        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
-        assert!(hooks.is_empty());
-
-        let contract1 = String::from("hook1");
-        let contract2 = String::from("hook2");
+        // 4_000 (liquid) and 500 (vesting) will be claimed for USER1
+        bond(deps.as_mut(), (4_000, 7_500), (0, 0), (0, 0), 1);
+        unbond(deps.as_mut(), 4_500, 0, 0, 2, 0);
 
-        let add_msg = ExecuteMsg::AddHook {
-            addr: contract1.clone(),
-        };
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION);
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(USER1, &[]),
+            ExecuteMsg::Claim {
+                receiver: Some(USER2.to_owned()),
+                owner: None,
+            },
+        )
+        .unwrap();
 
-        // anyone can add the first one, until preauth is consume
-        assert_eq!(1, PREAUTH_HOOKS.get_auth(&deps.storage).unwrap());
-        let user_info = mock_info(USER1, &[]);
-        let _ = execute(deps.as_mut(), mock_env(), user_info, add_msg.clone()).unwrap();
-        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
-        assert_eq!(hooks, vec![contract1.clone()]);
+        // Liquid tokens go to the receiver, vesting tokens back to the owner
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: USER2.into(),
+                    amount: coins(4_000, DENOM),
+                }),
+                SubMsg::new(TgradeMsg::Undelegate {
+                    funds: coin(500, DENOM),
+                    recipient: USER1.into(),
+                })
+            ]
+        );
+        assert_eq!(
+            get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None),
+            vec![]
+        );
+    }
 
-        // non-admin cannot add hook without preauth
-        assert_eq!(0, PREAUTH_HOOKS.get_auth(&deps.storage).unwrap());
-        let user_info = mock_info(USER1, &[]);
-        let err = execute(
-            deps.as_mut(),
+    fn query_stake_delegate(deps: Deps<TgradeQuery>, owner: &str) -> Option<Addr> {
+        let raw = query(
+            deps,
             mock_env(),
-            user_info.clone(),
-            add_msg.clone(),
+            QueryMsg::StakeDelegate {
+                owner: owner.to_owned(),
+            },
         )
-        .unwrap_err();
-        assert_eq!(err, PreauthError::NoPreauth {}.into());
-
-        // cannot remove a non-registered contract
-        let admin_info = mock_info(INIT_ADMIN, &[]);
-        let remove_msg = ExecuteMsg::RemoveHook {
-            addr: contract2.clone(),
-        };
-        let err = execute(deps.as_mut(), mock_env(), admin_info.clone(), remove_msg).unwrap_err();
-        assert_eq!(err, HookError::HookNotRegistered {}.into());
+        .unwrap();
+        let res: StakeDelegateResponse = from_slice(&raw).unwrap();
+        res.delegate
+    }
 
-        // admin can second contract, and it appears in the query
-        let add_msg2 = ExecuteMsg::AddHook {
-            addr: contract2.clone(),
-        };
-        execute(deps.as_mut(), mock_env(), admin_info.clone(), add_msg2).unwrap();
-        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
-        assert_eq!(hooks, vec![contract1.clone(), contract2.clone()]);
+    #[test]
+    fn stake_delegate_can_unbond_and_claim_for_owner() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
 
-        // cannot re-add an existing contract
-        let err = execute(deps.as_mut(), mock_env(), admin_info.clone(), add_msg).unwrap_err();
-        assert_eq!(err, HookError::HookAlreadyRegistered {}.into());
+        bond(deps.as_mut(), (4_000, 7_500), (0, 0), (0, 0), 1);
+        assert_eq!(query_stake_delegate(deps.as_ref(), USER1), None);
 
-        // non-admin cannot remove
-        let remove_msg = ExecuteMsg::RemoveHook { addr: contract1 };
-        let err = execute(deps.as_mut(), mock_env(), user_info, remove_msg.clone()).unwrap_err();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER1, &[]),
+            ExecuteMsg::DelegateStakeControl {
+                delegate: USER2.to_owned(),
+            },
+        )
+        .unwrap();
         assert_eq!(
-            err,
-            ContractError::Unauthorized(
-                "Hook address is not same as sender's and sender is not an admin".to_owned()
-            )
+            query_stake_delegate(deps.as_ref(), USER1),
+            Some(Addr::unchecked(USER2))
         );
 
-        // remove the original
-        execute(deps.as_mut(), mock_env(), admin_info, remove_msg).unwrap();
-        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
-        assert_eq!(hooks, vec![contract2.clone()]);
-
-        // contract can self-remove
-        let contract_info = mock_info(&contract2, &[]);
-        let remove_msg2 = ExecuteMsg::RemoveHook { addr: contract2 };
-        execute(deps.as_mut(), mock_env(), contract_info, remove_msg2).unwrap();
-        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
+        let mut env = mock_env();
+        env.block.height += 2;
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(USER2, &[]),
+            ExecuteMsg::Unbond {
+                tokens: coin(4_500, DENOM),
+                owner: Some(USER1.to_owned()),
+            },
+        )
+        .unwrap();
+
+        // Owner's stake is reduced, delegate has nothing staked
+        assert_stake_liquid(deps.as_ref(), 0, 0, 0);
+        assert_stake_vesting(deps.as_ref(), 7_000, 0, 0);
+        assert_eq!(
+            get_claims(deps.as_ref(), Addr::unchecked(USER2), None, None),
+            vec![]
+        );
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION);
+
+        // Delegate cannot redirect claimed tokens
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(USER2, &[]),
+            ExecuteMsg::Claim {
+                receiver: Some(USER2.to_owned()),
+                owner: Some(USER1.to_owned()),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized(_)));
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(USER2, &[]),
+            ExecuteMsg::Claim {
+                receiver: None,
+                owner: Some(USER1.to_owned()),
+            },
+        )
+        .unwrap();
+
+        // All released tokens are paid to the owner
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: USER1.into(),
+                    amount: coins(4_000, DENOM),
+                }),
+                SubMsg::new(TgradeMsg::Undelegate {
+                    funds: coin(500, DENOM),
+                    recipient: USER1.into(),
+                })
+            ]
+        );
+        assert_eq!(
+            get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn unauthorized_stake_delegate_rejected() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+
+        bond_liquid(deps.as_mut(), 12_000, 0, 0, 1);
+
+        let mut env = mock_env();
+        env.block.height += 2;
+        let unbond_msg = ExecuteMsg::Unbond {
+            tokens: coin(4_000, DENOM),
+            owner: Some(USER1.to_owned()),
+        };
+
+        // No delegate set at all
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(USER3, &[]),
+            unbond_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized(_)));
+
+        // Delegate set, but to somebody else
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER1, &[]),
+            ExecuteMsg::DelegateStakeControl {
+                delegate: USER2.to_owned(),
+            },
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(USER3, &[]),
+            unbond_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized(_)));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(USER3, &[]),
+            ExecuteMsg::Claim {
+                receiver: None,
+                owner: Some(USER1.to_owned()),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized(_)));
+
+        // Delegating to self revokes delegation
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER1, &[]),
+            ExecuteMsg::DelegateStakeControl {
+                delegate: USER1.to_owned(),
+            },
+        )
+        .unwrap();
+        assert_eq!(query_stake_delegate(deps.as_ref(), USER1), None);
+        let err = execute(deps.as_mut(), env, mock_info(USER2, &[]), unbond_msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized(_)));
+
+        assert_stake_liquid(deps.as_ref(), 12_000, 0, 0);
+    }
+
+    #[test]
+    fn unbond_all_exits_mixed_stake() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+
+        bond(deps.as_mut(), (4_000, 7_500), (6_000, 0), (0, 0), 1);
+        assert_users(deps.as_ref(), Some(11), Some(6), None, None);
+
+        let height_delta = 2;
+        let mut env = mock_env();
+        env.block.height += height_delta;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(USER1, &[]),
+            ExecuteMsg::UnbondAll {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes
+                .iter()
+                .find(|attr| attr.key == "amount")
+                .unwrap()
+                .value,
+            "11500"
+        );
+
+        // Both liquid and vesting stake are unbonded in a single claim
+        assert_stake_liquid(deps.as_ref(), 0, 6_000, 0);
+        assert_stake_vesting(deps.as_ref(), 0, 0, 0);
+        assert_users(deps.as_ref(), None, Some(6), None, None);
+        let expires = Duration::new(UNBONDING_DURATION).after(&env.block);
+        assert_eq!(
+            get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None),
+            vec![Claim::new(
+                Addr::unchecked(USER1),
+                4_000,
+                7_500,
+                expires,
+                env.block.height,
+            )]
+        );
+
+        // Nothing left to unbond
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info(USER1, &[]),
+            ExecuteMsg::UnbondAll {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ZeroAmount {});
+    }
+
+    #[test]
+    fn force_unbond_pays_recipient() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+
+        bond(deps.as_mut(), (4_000, 7_500), (6_000, 0), (0, 0), 1);
+        assert_users(deps.as_ref(), Some(11), Some(6), None, None);
+
+        // only admin can force unbond
+        let force_msg = ExecuteMsg::ForceUnbond {
+            addr: USER1.to_owned(),
+            recipient: Some(USER3.to_owned()),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER2, &[]),
+            force_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Admin(AdminError::NotAdmin {}));
+
+        let mut env = mock_env();
+        env.block.height += 2;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(INIT_ADMIN, &[]),
+            force_msg,
+        )
+        .unwrap();
+
+        // membership is dropped at once, whole stake is in the claim
+        assert_users(deps.as_ref(), None, Some(6), None, None);
+        assert_stake_liquid(deps.as_ref(), 0, 6_000, 0);
+        assert_stake_vesting(deps.as_ref(), 0, 0, 0);
+        let mut expected = Claim::new(
+            Addr::unchecked(USER1),
+            4_000,
+            7_500,
+            Duration::new(UNBONDING_DURATION).after(&env.block),
+            env.block.height,
+        );
+        expected.recipient = Some(Addr::unchecked(USER3));
+        assert_eq!(
+            get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None),
+            vec![expected]
+        );
+
+        // nothing left to force unbond
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info(INIT_ADMIN, &[]),
+            ExecuteMsg::ForceUnbond {
+                addr: USER1.to_owned(),
+                recipient: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ZeroAmount {});
+
+        // liquid tokens go to the recipient even if the owner claims with another receiver,
+        // vesting tokens back to the owner
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION);
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(USER1, &[]),
+            ExecuteMsg::Claim {
+                receiver: Some(USER2.to_owned()),
+                owner: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: USER3.into(),
+                    amount: coins(4_000, DENOM),
+                }),
+                SubMsg::new(TgradeMsg::Undelegate {
+                    funds: coin(7_500, DENOM),
+                    recipient: USER1.into(),
+                })
+            ]
+        );
+        assert_eq!(
+            get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn freeze_blocks_unbonding() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+
+        bond_liquid(deps.as_mut(), 12_000, 0, 0, 1);
+        unbond(deps.as_mut(), 2_000, 0, 0, 2, 0);
+
+        // only admin can freeze
+        let until = Duration::new(UNBONDING_DURATION * 2).after(&mock_env().block);
+        let freeze_msg = ExecuteMsg::Freeze {
+            addr: USER1.to_owned(),
+            until,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER1, &[]),
+            freeze_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Admin(AdminError::NotAdmin {}));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(INIT_ADMIN, &[]),
+            freeze_msg,
+        )
+        .unwrap();
+
+        let is_frozen = |deps: Deps<TgradeQuery>, env: Env| -> bool {
+            let msg = QueryMsg::IsFrozen {
+                addr: USER1.to_owned(),
+            };
+            from_slice(&query(deps, env, msg).unwrap()).unwrap()
+        };
+        assert!(is_frozen(deps.as_ref(), mock_env()));
+
+        // unbonding is not possible
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(USER1, &[]),
+            ExecuteMsg::Unbond {
+                tokens: coin(1_000, DENOM),
+                owner: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Frozen { until });
+
+        // bonding and claiming matured claims still works
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(USER1, &coins(1_000, DENOM)),
+            ExecuteMsg::Bond {
+                vesting_tokens: None,
+            },
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(USER1, &[]),
+            ExecuteMsg::Claim {
+                receiver: None,
+                owner: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: USER1.into(),
+                amount: coins(2_000, DENOM),
+            })]
+        );
+        assert_stake_liquid(deps.as_ref(), 11_000, 0, 0);
+
+        // after the freeze expires, unbonding is possible again
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION * 2);
+        assert!(!is_frozen(deps.as_ref(), env.clone()));
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(USER1, &[]),
+            ExecuteMsg::Unbond {
+                tokens: coin(1_000, DENOM),
+                owner: None,
+            },
+        )
+        .unwrap();
+        assert_stake_liquid(deps.as_ref(), 10_000, 0, 0);
+    }
+
+    #[test]
+    fn unfreeze_allows_unbonding() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+
+        bond_liquid(deps.as_mut(), 12_000, 0, 0, 1);
+
+        let until = Duration::new(UNBONDING_DURATION).after(&mock_env().block);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(INIT_ADMIN, &[]),
+            ExecuteMsg::Freeze {
+                addr: USER1.to_owned(),
+                until,
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(INIT_ADMIN, &[]),
+            ExecuteMsg::Unfreeze {
+                addr: USER1.to_owned(),
+            },
+        )
+        .unwrap();
+
+        unbond(deps.as_mut(), 2_000, 0, 0, 2, 0);
+        assert_stake_liquid(deps.as_ref(), 10_000, 0, 0);
+    }
+
+    #[test]
+    fn freeze_contract_blocks_execution() {
+        let mut deps = mock_deps_tgrade();
+        do_instantiate(
+            deps.as_mut(),
+            TOKENS_PER_POINT,
+            MIN_BOND,
+            UNBONDING_DURATION,
+            10,
+        );
+
+        bond_liquid(deps.as_mut(), 12_000, 0, 0, 1);
+        unbond(deps.as_mut(), 2_000, 0, 0, 2, 0);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(INIT_ADMIN, &[]),
+            ExecuteMsg::AddSlasher {
+                addr: "slasher".to_owned(),
+            },
+        )
+        .unwrap();
+
+        // only admin can freeze
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(USER1, &[]),
+            ExecuteMsg::FreezeContract { frozen: true },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Admin(AdminError::NotAdmin {}));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(INIT_ADMIN, &[]),
+            ExecuteMsg::FreezeContract { frozen: true },
+        )
+        .unwrap();
+
+        let is_frozen = |deps: Deps<TgradeQuery>| -> bool {
+            from_slice(&query(deps, mock_env(), QueryMsg::IsContractFrozen {}).unwrap()).unwrap()
+        };
+        assert!(is_frozen(deps.as_ref()));
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION);
+        let blocked = [
+            (
+                USER1,
+                coins(1_000, DENOM),
+                ExecuteMsg::Bond {
+                    vesting_tokens: None,
+                },
+            ),
+            (
+                USER1,
+                vec![],
+                ExecuteMsg::Unbond {
+                    tokens: coin(1_000, DENOM),
+                    owner: None,
+                },
+            ),
+            (
+                USER1,
+                vec![],
+                ExecuteMsg::Claim {
+                    receiver: None,
+                    owner: None,
+                },
+            ),
+            (
+                "slasher",
+                vec![],
+                ExecuteMsg::Slash {
+                    addr: USER1.to_owned(),
+                    portion: Decimal::percent(50),
+                    include_claims: true,
+                },
+            ),
+            (
+                INIT_ADMIN,
+                vec![],
+                ExecuteMsg::UpdateAdmin {
+                    admin: Some(USER2.to_owned()),
+                },
+            ),
+        ];
+        for (sender, funds, msg) in blocked.iter().cloned() {
+            let err =
+                execute(deps.as_mut(), env.clone(), mock_info(sender, &funds), msg).unwrap_err();
+            assert_eq!(err, ContractError::ContractFrozen {});
+        }
+
+        // matured claims are not released at end of block, but queries work
+        let res = end_block(deps.as_mut(), env.clone()).unwrap();
+        assert!(res.messages.is_empty());
+        assert_stake_liquid(deps.as_ref(), 10_000, 0, 0);
+        assert_eq!(
+            get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None).len(),
+            1
+        );
+
+        // unfreezing restores everything
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(INIT_ADMIN, &[]),
+            ExecuteMsg::FreezeContract { frozen: false },
+        )
+        .unwrap();
+        assert!(!is_frozen(deps.as_ref()));
+
+        let res = end_block(deps.as_mut(), env.clone()).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        // the claim is already released
+        let blocked = blocked
+            .iter()
+            .filter(|(_, _, msg)| !matches!(msg, ExecuteMsg::Claim { .. }))
+            .cloned();
+        for (sender, funds, msg) in blocked {
+            execute(deps.as_mut(), env.clone(), mock_info(sender, &funds), msg).unwrap();
+        }
+    }
+
+    #[test]
+    fn add_remove_hooks() {
+        // add will over-write and remove have no effect
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+
+        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
+        assert!(hooks.is_empty());
+
+        let contract1 = String::from("hook1");
+        let contract2 = String::from("hook2");
+
+        let add_msg = ExecuteMsg::AddHook {
+            addr: contract1.clone(),
+        };
+
+        // anyone can add the first one, until preauth is consume
+        assert_eq!(1, PREAUTH_HOOKS.get_auth(&deps.storage).unwrap());
+        let user_info = mock_info(USER1, &[]);
+        let _ = execute(deps.as_mut(), mock_env(), user_info, add_msg.clone()).unwrap();
+        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
+        assert_eq!(hooks, vec![contract1.clone()]);
+
+        // non-admin cannot add hook without preauth
+        assert_eq!(0, PREAUTH_HOOKS.get_auth(&deps.storage).unwrap());
+        let user_info = mock_info(USER1, &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            user_info.clone(),
+            add_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, PreauthError::NoPreauth {}.into());
+
+        // cannot remove a non-registered contract
+        let admin_info = mock_info(INIT_ADMIN, &[]);
+        let remove_msg = ExecuteMsg::RemoveHook {
+            addr: contract2.clone(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), admin_info.clone(), remove_msg).unwrap_err();
+        assert_eq!(err, HookError::HookNotRegistered {}.into());
+
+        // admin can second contract, and it appears in the query
+        let add_msg2 = ExecuteMsg::AddHook {
+            addr: contract2.clone(),
+        };
+        execute(deps.as_mut(), mock_env(), admin_info.clone(), add_msg2).unwrap();
+        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
+        assert_eq!(hooks, vec![contract1.clone(), contract2.clone()]);
+
+        // cannot re-add an existing contract
+        let err = execute(deps.as_mut(), mock_env(), admin_info.clone(), add_msg).unwrap_err();
+        assert_eq!(err, HookError::HookAlreadyRegistered {}.into());
+
+        // non-admin cannot remove
+        let remove_msg = ExecuteMsg::RemoveHook { addr: contract1 };
+        let err = execute(deps.as_mut(), mock_env(), user_info, remove_msg.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Unauthorized(
+                "Hook address is not same as sender's and sender is not an admin".to_owned()
+            )
+        );
+
+        // remove the original
+        execute(deps.as_mut(), mock_env(), admin_info, remove_msg).unwrap();
+        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
+        assert_eq!(hooks, vec![contract2.clone()]);
+
+        // contract can self-remove
+        let contract_info = mock_info(&contract2, &[]);
+        let remove_msg2 = ExecuteMsg::RemoveHook { addr: contract2 };
+        execute(deps.as_mut(), mock_env(), contract_info, remove_msg2).unwrap();
+        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
         assert_eq!(hooks, Vec::<String>::new());
     }
 
@@ -1671,6 +3283,7 @@ mod tests {
             let msg = ExecuteMsg::Slash {
                 addr: addr.to_string(),
                 portion,
+                include_claims: true,
             };
             let slasher_info = mock_info(slasher, &[]);
 
@@ -1865,48 +3478,209 @@ mod tests {
         }
 
         #[test]
-        fn slashing_bonded_mixed_tokens_works() {
+        fn slashing_bonded_mixed_tokens_works() {
+            let mut deps = mock_deps_tgrade();
+            default_instantiate(deps.as_mut());
+            let cfg = CONFIG.load(&deps.storage).unwrap();
+            let slasher = add_slasher(deps.as_mut());
+
+            bond_liquid(deps.as_mut(), 12_000, 1_500, 0, 1);
+            assert_stake_liquid(deps.as_ref(), 12_000, 1_500, 0);
+            bond_vesting(deps.as_mut(), 0, 6_000, 4_000, 1);
+            assert_stake_vesting(deps.as_ref(), 0, 6_000, 4_000);
+
+            // The slasher we added can slash
+            let res1 = slash(deps.as_mut(), &slasher, USER1, Decimal::percent(20)).unwrap();
+            let res2 = slash(deps.as_mut(), &slasher, USER3, Decimal::percent(50)).unwrap();
+            let res3 = slash(deps.as_mut(), &slasher, USER2, Decimal::percent(10)).unwrap();
+            assert_stake_liquid(deps.as_ref(), 9_600, 1_350, 0);
+            assert_stake_vesting(deps.as_ref(), 0, 5_400, 2_000);
+
+            // Tokens are burned
+            assert_burned(res1, &coins(2_400, &cfg.denom), &[]);
+            assert_burned(res2, &[], &coins(2_000, &cfg.denom));
+            assert_burned(res3, &coins(150, &cfg.denom), &coins(600, &cfg.denom));
+        }
+
+        #[test]
+        fn slashing_stake_update_membership() {
+            let mut deps = mock_deps_tgrade();
+            default_instantiate(deps.as_mut());
+            let slasher = add_slasher(deps.as_mut());
+
+            // ensure it rounds down, and respects cut-off
+            bond(deps.as_mut(), (0, 12_000), (7_000, 0), (3_000, 4_000), 1);
+            assert_users(deps.as_ref(), Some(12), Some(7), Some(7), None);
+
+            slash(deps.as_mut(), &slasher, USER1, Decimal::percent(50)).unwrap();
+            slash(deps.as_mut(), &slasher, USER2, Decimal::percent(10)).unwrap();
+            slash(deps.as_mut(), &slasher, USER3, Decimal::percent(20)).unwrap();
+
+            // Assert updated points
+            assert_stake_liquid(deps.as_ref(), 0, 6_300, 2_400);
+            assert_stake_vesting(deps.as_ref(), 6_000, 0, 3_200);
+            assert_users(deps.as_ref(), Some(6), Some(6), Some(5), None);
+        }
+
+        #[test]
+        fn slashing_below_min_bond_removes_member_by_default() {
+            let mut deps = mock_deps_tgrade();
+            default_instantiate(deps.as_mut());
+            let slasher = add_slasher(deps.as_mut());
+
+            bond(deps.as_mut(), (5_500, 0), (0, 6_000), (7_000, 0), 1);
+            assert_users(deps.as_ref(), Some(5), Some(6), Some(7), None);
+
+            // just below `min_bond`
+            slash(deps.as_mut(), &slasher, USER1, Decimal::percent(10)).unwrap();
+            slash(deps.as_mut(), &slasher, USER2, Decimal::percent(20)).unwrap();
+
+            assert_stake_liquid(deps.as_ref(), 4_950, 0, 7_000);
+            assert_stake_vesting(deps.as_ref(), 0, 4_800, 0);
+            assert_users(deps.as_ref(), None, None, Some(7), None);
+        }
+
+        #[test]
+        fn slashing_below_min_bond_clamps_member() {
+            let mut deps = mock_deps_tgrade();
+            let msg = InstantiateMsg {
+                denom: DENOM.to_owned(),
+                tokens_per_point: TOKENS_PER_POINT,
+                min_bond: MIN_BOND,
+                unbonding_period: UNBONDING_DURATION,
+                admin: Some(INIT_ADMIN.into()),
+                preauths_hooks: 1,
+                preauths_slashing: 1,
+                auto_return_limit: 0,
+                reject_zero_funds: false,
+                max_bond_per_tx: None,
+                min_stake_duration: None,
+                max_claims_per_addr: None,
+                slash_policy: SlashPolicy::ClampAtMinBond,
+                points_rounding: Rounding::Down,
+            };
+            instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let slasher = add_slasher(deps.as_mut());
+
+            bond(deps.as_mut(), (5_500, 0), (0, 6_000), (7_000, 0), 1);
+            assert_users(deps.as_ref(), Some(5), Some(6), Some(7), None);
+
+            // just below `min_bond`, members are kept at the `min_bond` points
+            slash(deps.as_mut(), &slasher, USER1, Decimal::percent(10)).unwrap();
+            slash(deps.as_mut(), &slasher, USER2, Decimal::percent(20)).unwrap();
+            // slashing the whole stake still removes the member
+            slash(deps.as_mut(), &slasher, USER3, Decimal::one()).unwrap();
+
+            assert_stake_liquid(deps.as_ref(), 4_950, 0, 0);
+            assert_stake_vesting(deps.as_ref(), 0, 4_800, 0);
+            assert_users(deps.as_ref(), Some(5), Some(5), None, None);
+
+            // further stake changes use the actual stake
+            unbond(deps.as_mut(), 100, 0, 0, 2, 0);
+            assert_users(deps.as_ref(), None, Some(5), None, None);
+        }
+
+        #[test]
+        fn invariants_hold_after_operations() {
             let mut deps = mock_deps_tgrade();
-            default_instantiate(deps.as_mut());
-            let cfg = CONFIG.load(&deps.storage).unwrap();
+            let msg = InstantiateMsg {
+                denom: DENOM.to_owned(),
+                tokens_per_point: TOKENS_PER_POINT,
+                min_bond: MIN_BOND,
+                unbonding_period: UNBONDING_DURATION,
+                admin: Some(INIT_ADMIN.into()),
+                preauths_hooks: 1,
+                preauths_slashing: 1,
+                auto_return_limit: 0,
+                reject_zero_funds: false,
+                max_bond_per_tx: None,
+                min_stake_duration: None,
+                max_claims_per_addr: None,
+                slash_policy: SlashPolicy::ClampAtMinBond,
+                points_rounding: Rounding::Down,
+            };
+            instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
             let slasher = add_slasher(deps.as_mut());
 
-            bond_liquid(deps.as_mut(), 12_000, 1_500, 0, 1);
-            assert_stake_liquid(deps.as_ref(), 12_000, 1_500, 0);
-            bond_vesting(deps.as_mut(), 0, 6_000, 4_000, 1);
-            assert_stake_vesting(deps.as_ref(), 0, 6_000, 4_000);
-
-            // The slasher we added can slash
-            let res1 = slash(deps.as_mut(), &slasher, USER1, Decimal::percent(20)).unwrap();
-            let res2 = slash(deps.as_mut(), &slasher, USER3, Decimal::percent(50)).unwrap();
-            let res3 = slash(deps.as_mut(), &slasher, USER2, Decimal::percent(10)).unwrap();
-            assert_stake_liquid(deps.as_ref(), 9_600, 1_350, 0);
-            assert_stake_vesting(deps.as_ref(), 0, 5_400, 2_000);
-
-            // Tokens are burned
-            assert_burned(res1, &coins(2_400, &cfg.denom), &[]);
-            assert_burned(res2, &[], &coins(2_000, &cfg.denom));
-            assert_burned(res3, &coins(150, &cfg.denom), &coins(600, &cfg.denom));
+            bond(deps.as_mut(), (12_000, 0), (5_500, 3_000), (7_000, 0), 1);
+            unbond(deps.as_mut(), 4_000, 0, 7_000, 2, 0);
+            slash(deps.as_mut(), &slasher, USER2, Decimal::percent(50)).unwrap();
+            slash(deps.as_mut(), &slasher, USER1, Decimal::percent(20)).unwrap();
+            // USER2 is clamped at `min_bond`
+            assert_users(deps.as_ref(), Some(6), Some(5), None, None);
+
+            // Liquid stakes: 6_400 + 2_750, liquid claims: 3_200 + 7_000
+            let contract = mock_env().contract.address;
+            deps.querier
+                .update_balance(&contract, coins(6_400 + 2_750 + 3_200 + 7_000, DENOM));
+
+            let invariants = query_invariants(deps.as_ref(), mock_env()).unwrap();
+            assert_eq!(invariants.config, CONFIG.load(&deps.storage).unwrap());
+            assert!(invariants.total_matches_sum_of_points);
+            assert!(invariants.stake_totals_match_running_totals);
+            assert!(invariants.no_negative_balances);
+
+            // Broken state is detected
+            deps.querier.update_balance(&contract, coins(1_000, DENOM));
+            TOTAL.save(&mut deps.storage, &12).unwrap();
+
+            let invariants = query_invariants(deps.as_ref(), mock_env()).unwrap();
+            assert!(!invariants.total_matches_sum_of_points);
+            assert!(!invariants.stake_totals_match_running_totals);
+            assert!(!invariants.no_negative_balances);
         }
 
         #[test]
-        fn slashing_stake_update_membership() {
+        fn slashing_hooks_failure_doesnt_revert_slash() {
             let mut deps = mock_deps_tgrade();
             default_instantiate(deps.as_mut());
             let slasher = add_slasher(deps.as_mut());
 
-            // ensure it rounds down, and respects cut-off
-            bond(deps.as_mut(), (0, 12_000), (7_000, 0), (3_000, 4_000), 1);
-            assert_users(deps.as_ref(), Some(12), Some(7), Some(7), None);
+            let hook = String::from("hook");
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(INIT_ADMIN, &[]),
+                ExecuteMsg::AddHook { addr: hook.clone() },
+            )
+            .unwrap();
 
-            slash(deps.as_mut(), &slasher, USER1, Decimal::percent(50)).unwrap();
-            slash(deps.as_mut(), &slasher, USER2, Decimal::percent(10)).unwrap();
-            slash(deps.as_mut(), &slasher, USER3, Decimal::percent(20)).unwrap();
+            bond(deps.as_mut(), (12_000, 0), (0, 0), (0, 0), 1);
+            let res = slash(deps.as_mut(), &slasher, USER1, Decimal::percent(50)).unwrap();
 
-            // Assert updated points
-            assert_stake_liquid(deps.as_ref(), 0, 6_300, 2_400);
-            assert_stake_vesting(deps.as_ref(), 6_000, 0, 3_200);
-            assert_users(deps.as_ref(), Some(6), Some(6), Some(5), None);
+            // Hook is only replied on error
+            let diff = MemberDiff::new(USER1, Some(12), Some(6));
+            let hook_msg = MemberChangedHookMsg::one(diff)
+                .into_cosmos_msg(hook)
+                .unwrap();
+            let hook_msgs: Vec<_> = res
+                .messages
+                .into_iter()
+                .filter(|msg| !matches!(msg.msg, CosmosMsg::Bank(BankMsg::Burn { .. })))
+                .collect();
+            assert_eq!(
+                hook_msgs,
+                [SubMsg::reply_on_error(hook_msg, SLASH_HOOK_REPLY_ID)]
+            );
+
+            // Reverting hook is logged, and the slash stays applied
+            let res = reply(
+                deps.as_mut(),
+                mock_env(),
+                Reply {
+                    id: SLASH_HOOK_REPLY_ID,
+                    result: SubMsgResult::Err("hook failed".to_owned()),
+                },
+            )
+            .unwrap();
+            assert_eq!(
+                res,
+                Response::new()
+                    .add_attribute("action", "slash_hook_failed")
+                    .add_attribute("error", "hook failed")
+            );
+            assert_stake_liquid(deps.as_ref(), 6_000, 0, 0);
+            assert_users(deps.as_ref(), Some(6), None, None, None);
         }
 
         #[test]
@@ -1952,6 +3726,82 @@ mod tests {
             assert_burned(res, &coins(2_400, &cfg.denom), &coins(200, &cfg.denom));
         }
 
+        #[test]
+        fn slashing_without_claims_keeps_claims_intact() {
+            let mut deps = mock_deps_tgrade();
+            default_instantiate(deps.as_mut());
+            let cfg = CONFIG.load(&deps.storage).unwrap();
+            let slasher = add_slasher(deps.as_mut());
+
+            bond_liquid(deps.as_mut(), 12_000, 7_500, 4_000, 1);
+            bond_vesting(deps.as_mut(), 1_000, 750, 40, 1);
+            let height_delta = 2;
+            unbond(deps.as_mut(), 3_000, 2_600, 0, height_delta, 0);
+            let mut env = mock_env();
+            env.block.height += height_delta;
+
+            let expires = Duration::new(UNBONDING_DURATION).after(&env.block);
+            let claims = vec![Claim::new(
+                Addr::unchecked(USER1),
+                3_000,
+                0,
+                expires,
+                env.block.height,
+            )];
+            assert_eq!(
+                get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None),
+                claims
+            );
+
+            let msg = ExecuteMsg::Slash {
+                addr: USER1.to_owned(),
+                portion: Decimal::percent(20),
+                include_claims: false,
+            };
+            let res = execute(deps.as_mut(), env, mock_info(&slasher, &[]), msg).unwrap();
+
+            // claims retain their full amounts, only bonded stake is slashed
+            assert_eq!(
+                get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None),
+                claims
+            );
+            assert_stake_liquid(deps.as_ref(), 7_200, 4_900, 4_000);
+            assert_stake_vesting(deps.as_ref(), 800, 750, 40);
+            assert_burned(res, &coins(1_800, &cfg.denom), &coins(200, &cfg.denom));
+        }
+
+        #[test]
+        fn slashing_reports_every_source() {
+            let mut deps = mock_deps_tgrade();
+            default_instantiate(deps.as_mut());
+            let slasher = add_slasher(deps.as_mut());
+
+            // liquid stake is unbonded first, so claim gets some of the vesting as well
+            bond(deps.as_mut(), (12_000, 6_000), (0, 0), (0, 0), 1);
+            unbond(deps.as_mut(), 14_000, 0, 0, 2, 0);
+            bond_liquid(deps.as_mut(), 10_000, 0, 0, 3);
+            assert_stake_liquid(deps.as_ref(), 10_000, 0, 0);
+            assert_stake_vesting(deps.as_ref(), 4_000, 0, 0);
+            assert_users(deps.as_ref(), Some(14), None, None, None);
+
+            let res = slash(deps.as_mut(), &slasher, USER1, Decimal::percent(10)).unwrap();
+
+            let attr = |key: &str| {
+                res.attributes
+                    .iter()
+                    .find(|attr| attr.key == key)
+                    .map(|attr| attr.value.as_str())
+            };
+            assert_eq!(attr("liquid_stake_slashed"), Some("1000"));
+            assert_eq!(attr("vesting_stake_slashed"), Some("400"));
+            assert_eq!(attr("liquid_claims_slashed"), Some("1200"));
+            assert_eq!(attr("vesting_claims_slashed"), Some("200"));
+            assert_eq!(attr("new_points"), Some("12"));
+            assert_users(deps.as_ref(), Some(12), None, None, None);
+
+            assert_burned(res, &coins(2_200, DENOM), &coins(600, DENOM));
+        }
+
         #[test]
         fn random_user_cannot_slash() {
             let mut deps = mock_deps_tgrade();
@@ -2077,6 +3927,7 @@ mod tests {
         // check firing on unbond
         let msg = ExecuteMsg::Unbond {
             tokens: coin(7_300, DENOM),
+            owner: None,
         };
         let info = mock_info(USER1, &[]);
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -2098,6 +3949,334 @@ mod tests {
         assert_eq!(res.messages, vec![msg1, msg2]);
     }
 
+    #[test]
+    fn zero_amount_coins_accepted_by_default() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+
+        // zero amount coin alone is just no funds
+        let info = mock_info(USER1, &[coin(0, DENOM)]);
+        let msg = ExecuteMsg::Bond {
+            vesting_tokens: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::NoFunds {});
+
+        // zero amount coin is ignored when anything else is bonded
+        let info = mock_info(USER1, &[coin(0, DENOM)]);
+        let msg = ExecuteMsg::Bond {
+            vesting_tokens: Some(coin(500, DENOM)),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query_staked(deps.as_ref(), USER1.into(), None).unwrap();
+        assert_eq!(coin(0, DENOM), res.liquid);
+        assert_eq!(coin(500, DENOM), res.vesting);
+    }
+
+    #[test]
+    fn zero_amount_coins_rejected_in_strict_mode() {
+        let mut deps = mock_deps_tgrade();
+        let msg = InstantiateMsg {
+            denom: DENOM.to_owned(),
+            tokens_per_point: TOKENS_PER_POINT,
+            min_bond: MIN_BOND,
+            unbonding_period: UNBONDING_DURATION,
+            admin: Some(INIT_ADMIN.into()),
+            preauths_hooks: 1,
+            preauths_slashing: 1,
+            auto_return_limit: 0,
+            reject_zero_funds: true,
+            max_bond_per_tx: None,
+            min_stake_duration: None,
+            max_claims_per_addr: None,
+            slash_policy: SlashPolicy::Remove,
+            points_rounding: Rounding::Down,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // zero amount liquid coin
+        let info = mock_info(USER1, &[coin(0, DENOM)]);
+        let msg = ExecuteMsg::Bond {
+            vesting_tokens: Some(coin(500, DENOM)),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::ZeroFunds {});
+
+        // zero amount vesting coin
+        let info = mock_info(USER1, &[coin(500, DENOM)]);
+        let msg = ExecuteMsg::Bond {
+            vesting_tokens: Some(coin(0, DENOM)),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::ZeroFunds {});
+
+        // non-zero coins are fine
+        let info = mock_info(USER1, &[coin(500, DENOM)]);
+        let msg = ExecuteMsg::Bond {
+            vesting_tokens: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query_staked(deps.as_ref(), USER1.into(), None).unwrap();
+        assert_eq!(coin(500, DENOM), res.liquid);
+    }
+
+    #[test]
+    fn bond_limited_per_tx() {
+        let mut deps = mock_deps_tgrade();
+        let msg = InstantiateMsg {
+            denom: DENOM.to_owned(),
+            tokens_per_point: TOKENS_PER_POINT,
+            min_bond: MIN_BOND,
+            unbonding_period: UNBONDING_DURATION,
+            admin: Some(INIT_ADMIN.into()),
+            preauths_hooks: 1,
+            preauths_slashing: 1,
+            auto_return_limit: 0,
+            reject_zero_funds: false,
+            max_bond_per_tx: Some(Uint128::new(1000)),
+            min_stake_duration: None,
+            max_claims_per_addr: None,
+            slash_policy: SlashPolicy::Remove,
+            points_rounding: Rounding::Down,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // liquid above the limit
+        let info = mock_info(USER1, &[coin(1001, DENOM)]);
+        let msg = ExecuteMsg::Bond {
+            vesting_tokens: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::BondTooLarge {
+                amount: Uint128::new(1001),
+                max: Uint128::new(1000)
+            }
+        );
+
+        // liquid and vesting combined above the limit
+        let info = mock_info(USER1, &[coin(600, DENOM)]);
+        let msg = ExecuteMsg::Bond {
+            vesting_tokens: Some(coin(500, DENOM)),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::BondTooLarge {
+                amount: Uint128::new(1100),
+                max: Uint128::new(1000)
+            }
+        );
+
+        // exactly at the limit
+        let info = mock_info(USER1, &[coin(500, DENOM)]);
+        let msg = ExecuteMsg::Bond {
+            vesting_tokens: Some(coin(500, DENOM)),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // limit is per transaction, not total
+        let info = mock_info(USER1, &[coin(1000, DENOM)]);
+        let msg = ExecuteMsg::Bond {
+            vesting_tokens: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query_staked(deps.as_ref(), USER1.into(), None).unwrap();
+        assert_eq!(coin(1500, DENOM), res.liquid);
+        assert_eq!(coin(500, DENOM), res.vesting);
+    }
+
+    #[test]
+    fn zero_bond_limit_means_unlimited() {
+        let mut deps = mock_deps_tgrade();
+        let msg = InstantiateMsg {
+            denom: DENOM.to_owned(),
+            tokens_per_point: TOKENS_PER_POINT,
+            min_bond: MIN_BOND,
+            unbonding_period: UNBONDING_DURATION,
+            admin: Some(INIT_ADMIN.into()),
+            preauths_hooks: 1,
+            preauths_slashing: 1,
+            auto_return_limit: 0,
+            reject_zero_funds: false,
+            max_bond_per_tx: Some(Uint128::zero()),
+            min_stake_duration: None,
+            max_claims_per_addr: None,
+            slash_policy: SlashPolicy::Remove,
+            points_rounding: Rounding::Down,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(CONFIG.load(&deps.storage).unwrap().max_bond_per_tx, None);
+
+        let info = mock_info(USER1, &[coin(5000, DENOM)]);
+        let msg = ExecuteMsg::Bond {
+            vesting_tokens: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn unbond_limited_by_min_stake_duration() {
+        let mut deps = mock_deps_tgrade();
+        let msg = InstantiateMsg {
+            denom: DENOM.to_owned(),
+            tokens_per_point: TOKENS_PER_POINT,
+            min_bond: MIN_BOND,
+            unbonding_period: UNBONDING_DURATION,
+            admin: Some(INIT_ADMIN.into()),
+            preauths_hooks: 1,
+            preauths_slashing: 1,
+            auto_return_limit: 0,
+            reject_zero_funds: false,
+            max_bond_per_tx: None,
+            min_stake_duration: Some(100),
+            max_claims_per_addr: None,
+            slash_policy: SlashPolicy::Remove,
+            points_rounding: Rounding::Down,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let mut env = mock_env();
+        let start = env.block.time;
+        let bond_msg = ExecuteMsg::Bond {
+            vesting_tokens: None,
+        };
+        let unbond_msg = |amount| ExecuteMsg::Unbond {
+            tokens: coin(amount, DENOM),
+            owner: None,
+        };
+
+        let info = mock_info(USER1, &[coin(1000, DENOM)]);
+        execute(deps.as_mut(), env.clone(), info, bond_msg.clone()).unwrap();
+
+        // cannot unbond before the minimum duration passes
+        env.block.time = start.plus_seconds(50);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(USER1, &[]),
+            unbond_msg(1),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::StakeTooNew {
+                unbondable: Uint128::zero(),
+                until: start.plus_seconds(100),
+            }
+        );
+
+        // after the duration the stake matured, so bonding more locks only the new tokens
+        env.block.time = start.plus_seconds(100);
+        let info = mock_info(USER1, &[coin(500, DENOM)]);
+        execute(deps.as_mut(), env.clone(), info, bond_msg).unwrap();
+
+        // partial unbond of the older stake is allowed
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(USER1, &[]),
+            unbond_msg(600),
+        )
+        .unwrap();
+
+        // but not of the recently bonded one
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(USER1, &[]),
+            unbond_msg(401),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::StakeTooNew {
+                unbondable: Uint128::new(400),
+                until: start.plus_seconds(200),
+            }
+        );
+
+        // once the new stake matures it can be unbonded as well
+        env.block.time = start.plus_seconds(200);
+        execute(deps.as_mut(), env, mock_info(USER1, &[]), unbond_msg(900)).unwrap();
+
+        let res = query_staked(deps.as_ref(), USER1.into(), None).unwrap();
+        assert_eq!(coin(0, DENOM), res.liquid);
+    }
+
+    #[test]
+    fn unbond_limited_by_max_claims_per_addr() {
+        let mut deps = mock_deps_tgrade();
+        let msg = InstantiateMsg {
+            denom: DENOM.to_owned(),
+            tokens_per_point: TOKENS_PER_POINT,
+            min_bond: MIN_BOND,
+            unbonding_period: UNBONDING_DURATION,
+            admin: Some(INIT_ADMIN.into()),
+            preauths_hooks: 1,
+            preauths_slashing: 1,
+            auto_return_limit: 0,
+            reject_zero_funds: false,
+            max_bond_per_tx: None,
+            min_stake_duration: None,
+            max_claims_per_addr: Some(2),
+            slash_policy: SlashPolicy::Remove,
+            points_rounding: Rounding::Down,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        bond_liquid(deps.as_mut(), 1_000, 0, 0, 1);
+
+        let mut env = mock_env();
+        let start = env.block.time;
+        let unbond_msg = ExecuteMsg::Unbond {
+            tokens: coin(100, DENOM),
+            owner: None,
+        };
+        let unbond = |deps: DepsMut<TgradeQuery>, env: &Env| {
+            execute(deps, env.clone(), mock_info(USER1, &[]), unbond_msg.clone())
+        };
+
+        unbond(deps.as_mut(), &env).unwrap();
+        env.block.time = start.plus_seconds(10);
+        unbond(deps.as_mut(), &env).unwrap();
+
+        // claims released at the same time are merged, so they don't count toward the limit
+        unbond(deps.as_mut(), &env).unwrap();
+        assert_eq!(
+            get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None).len(),
+            2
+        );
+
+        env.block.time = start.plus_seconds(20);
+        let err = unbond(deps.as_mut(), &env).unwrap_err();
+        assert_eq!(err, ContractError::TooManyClaims {});
+
+        // releasing a mature claim frees a slot
+        env.block.time = start.plus_seconds(UNBONDING_DURATION);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(USER1, &[]),
+            ExecuteMsg::Claim {
+                receiver: None,
+                owner: None,
+            },
+        )
+        .unwrap();
+        unbond(deps.as_mut(), &env).unwrap();
+        assert_eq!(
+            get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None).len(),
+            2
+        );
+        env.block.time = start.plus_seconds(UNBONDING_DURATION + 10);
+        let err = unbond(deps.as_mut(), &env).unwrap_err();
+        assert_eq!(err, ContractError::TooManyClaims {});
+    }
+
     #[test]
     fn only_bond_valid_coins() {
         let mut deps = mock_deps_tgrade();
@@ -2127,7 +4306,13 @@ mod tests {
             },
         )
         .unwrap_err();
-        assert_eq!(err, ContractError::MissingDenom(DENOM.to_string()));
+        assert_eq!(
+            err,
+            ContractError::MissingDenom {
+                expected: DENOM.to_string(),
+                found: "FOO".to_string(),
+            }
+        );
 
         // cannot bond with 2 coins (even if one is correct)
         let info = mock_info(USER1, &[coin(1234, DENOM), coin(5000, "BAR")]);
@@ -2140,7 +4325,13 @@ mod tests {
             },
         )
         .unwrap_err();
-        assert_eq!(err, ContractError::ExtraDenoms(DENOM.to_string()));
+        assert_eq!(
+            err,
+            ContractError::ExtraDenoms {
+                expected: DENOM.to_string(),
+                found: vec![DENOM.to_string(), "BAR".to_string()],
+            }
+        );
 
         // can bond with just the proper denom
         // cannot bond with incorrect denom
@@ -2160,7 +4351,13 @@ mod tests {
     fn ensure_bonding_edge_cases_liquid() {
         // use min_bond 0, tokens_per_points 100
         let mut deps = mock_deps_tgrade();
-        do_instantiate(deps.as_mut(), Uint128::new(100), Uint128::zero(), 5, 0);
+        do_instantiate(
+            deps.as_mut(),
+            Decimal::from_ratio(100u128, 1u128),
+            Uint128::zero(),
+            5,
+            0,
+        );
 
         // setting 50 tokens, gives us Some(0) points
         // even setting to 1 token
@@ -2176,7 +4373,13 @@ mod tests {
     fn ensure_bonding_edge_cases_vesting() {
         // use min_bond 0, tokens_per_points 100
         let mut deps = mock_deps_tgrade();
-        do_instantiate(deps.as_mut(), Uint128::new(100), Uint128::zero(), 5, 0);
+        do_instantiate(
+            deps.as_mut(),
+            Decimal::from_ratio(100u128, 1u128),
+            Uint128::zero(),
+            5,
+            0,
+        );
 
         // setting 50 tokens, gives us Some(0) points
         // even setting to 1 token
@@ -2192,7 +4395,13 @@ mod tests {
     fn ensure_bonding_edge_cases_mixed() {
         // use min_bond 0, tokens_per_points 100
         let mut deps = mock_deps_tgrade();
-        do_instantiate(deps.as_mut(), Uint128::new(100), Uint128::zero(), 5, 0);
+        do_instantiate(
+            deps.as_mut(),
+            Decimal::from_ratio(100u128, 1u128),
+            Uint128::zero(),
+            5,
+            0,
+        );
 
         // setting 25 liquid tokens, gives us Some(0) points
         // even setting to 1 token
@@ -2227,6 +4436,7 @@ mod tests {
             env.block.time = env.block.time.plus_seconds(10);
             let msg = ExecuteMsg::Unbond {
                 tokens: coin(10, DENOM),
+                owner: None,
             };
             execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
         }
@@ -2252,6 +4462,257 @@ mod tests {
         assert_eq!(concatenated, all_claims);
     }
 
+    #[test]
+    fn compact_near_simultaneous_claims() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+        bond(deps.as_mut(), (500, 100), (0, 0), (0, 0), 1);
+
+        // three claims released within the same second, and one a few seconds later
+        let mut env = mock_env();
+        env.block.height += 2;
+        env.block.time = Timestamp::from_seconds(env.block.time.seconds());
+        let info = mock_info(USER1, &[]);
+        for (nanos, amount) in [
+            (0, 100),
+            (200_000_000, 150),
+            (700_000_000, 50),
+            (5_000_000_000, 10),
+        ] {
+            let mut env = env.clone();
+            env.block.time = env.block.time.plus_nanos(nanos);
+            let msg = ExecuteMsg::Unbond {
+                tokens: coin(amount, DENOM),
+                owner: None,
+            };
+            execute(deps.as_mut(), env, info.clone(), msg).unwrap();
+        }
+        assert_eq!(
+            get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None).len(),
+            4
+        );
+
+        // only admin can compact claims
+        let msg = ExecuteMsg::CompactClaims {
+            addr: USER1.to_owned(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Admin(AdminError::NotAdmin {}));
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info(INIT_ADMIN, &[]), msg).unwrap();
+        assert_eq!(
+            res.attributes
+                .iter()
+                .find(|attr| attr.key == "removed_claims")
+                .unwrap()
+                .value,
+            "2"
+        );
+
+        // merged claim is released at the latest release time of the group
+        let release = |nanos: u64| {
+            Expiration::at_timestamp(
+                env.block
+                    .time
+                    .plus_seconds(UNBONDING_DURATION)
+                    .plus_nanos(nanos),
+            )
+        };
+        assert_eq!(
+            get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None),
+            vec![
+                Claim::new(
+                    Addr::unchecked(USER1),
+                    300,
+                    0,
+                    release(700_000_000),
+                    env.block.height,
+                ),
+                Claim::new(
+                    Addr::unchecked(USER1),
+                    10,
+                    0,
+                    release(5_000_000_000),
+                    env.block.height,
+                ),
+            ]
+        );
+
+        // total releasable amount is unchanged
+        let mut later = env.clone();
+        later.block.time = later.block.time.plus_seconds(UNBONDING_DURATION + 10);
+        let res = execute(
+            deps.as_mut(),
+            later,
+            info,
+            ExecuteMsg::Claim {
+                receiver: None,
+                owner: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: USER1.into(),
+                amount: coins(310, DENOM),
+            })]
+        );
+    }
+
+    #[test]
+    fn compacting_keeps_claims_of_different_recipients() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+        bond(deps.as_mut(), (500, 0), (0, 0), (0, 0), 1);
+
+        let mut env = mock_env();
+        env.block.height += 2;
+        let msg = ExecuteMsg::Unbond {
+            tokens: coin(100, DENOM),
+            owner: None,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(USER1, &[]), msg).unwrap();
+        env.block.time = env.block.time.plus_nanos(100);
+        let msg = ExecuteMsg::ForceUnbond {
+            addr: USER1.to_owned(),
+            recipient: Some(USER2.to_owned()),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(INIT_ADMIN, &[]), msg).unwrap();
+
+        let claims = get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None);
+        assert_eq!(claims.len(), 2);
+
+        let msg = ExecuteMsg::CompactClaims {
+            addr: USER1.to_owned(),
+        };
+        execute(deps.as_mut(), env, mock_info(INIT_ADMIN, &[]), msg).unwrap();
+        assert_eq!(
+            get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None),
+            claims
+        );
+    }
+
+    fn get_unlock_schedule(
+        deps: Deps<TgradeQuery>,
+        time_delta: u64,
+        bucket_seconds: u64,
+        buckets: u32,
+    ) -> StdResult<Vec<UnlockBucket>> {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(time_delta);
+        let raw = query(
+            deps,
+            env,
+            QueryMsg::GlobalUnlockSchedule {
+                bucket_seconds,
+                buckets,
+            },
+        )?;
+        let res: UnlockScheduleResponse = from_slice(&raw)?;
+        Ok(res.buckets)
+    }
+
+    #[test]
+    fn global_unlock_schedule_query() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+        bond(deps.as_mut(), (500, 0), (100, 400), (0, 0), 1);
+
+        // claims of USER1 and USER2 released at the same time fall into the same bucket
+        unbond(deps.as_mut(), 100, 0, 0, 2, 0);
+        unbond(deps.as_mut(), 50, 300, 0, 3, 30);
+        unbond(deps.as_mut(), 0, 150, 0, 4, 250);
+
+        let start = mock_env().block.time;
+        let bucket = |from: u64, to: u64, liquid: u128, vesting: u128| UnlockBucket {
+            start: start.plus_seconds(from),
+            end: start.plus_seconds(to),
+            liquid: coin(liquid, DENOM),
+            vesting: coin(vesting, DENOM),
+        };
+
+        // claims are released after UNBONDING_DURATION (100s) since unbonding
+        let schedule = get_unlock_schedule(deps.as_ref(), 90, 25, 3).unwrap();
+        assert_eq!(
+            schedule,
+            [
+                bucket(90, 115, 100, 0),
+                bucket(115, 140, 150, 200),
+                bucket(140, 165, 0, 0),
+            ]
+        );
+
+        // expired, but not yet released claims are accounted in the first bucket
+        let schedule = get_unlock_schedule(deps.as_ref(), 200, 100, 2).unwrap();
+        assert_eq!(
+            schedule,
+            [bucket(200, 300, 250, 200), bucket(300, 400, 0, 150)]
+        );
+
+        // number of buckets is limited
+        let schedule = get_unlock_schedule(deps.as_ref(), 0, 1, 1000).unwrap();
+        assert_eq!(schedule.len(), MAX_LIMIT as usize);
+
+        get_unlock_schedule(deps.as_ref(), 0, 0, 10).unwrap_err();
+        // no buckets to account expired claims in
+        get_unlock_schedule(deps.as_ref(), 200, 100, 0).unwrap_err();
+    }
+
+    fn get_release_schedule(
+        deps: Deps<TgradeQuery>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> Vec<ReleaseBucket> {
+        let raw = query(
+            deps,
+            mock_env(),
+            QueryMsg::ReleaseSchedule { start_after, limit },
+        )
+        .unwrap();
+        let res: ReleaseScheduleResponse = from_slice(&raw).unwrap();
+        res.buckets
+    }
+
+    #[test]
+    fn release_schedule_query() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+        bond(deps.as_mut(), (500, 0), (100, 400), (0, 0), 1);
+
+        // claims of USER1 and USER2 released at the same time are summed up
+        unbond(deps.as_mut(), 100, 0, 0, 2, 0);
+        unbond(deps.as_mut(), 50, 300, 0, 3, 30);
+        unbond(deps.as_mut(), 0, 150, 0, 4, 250);
+
+        let release_at = |secs: u64| {
+            mock_env()
+                .block
+                .time
+                .plus_seconds(secs + UNBONDING_DURATION)
+                .nanos()
+        };
+        let bucket = |secs: u64, liquid: u128, vesting: u128| ReleaseBucket {
+            release_at: release_at(secs),
+            liquid: coin(liquid, DENOM),
+            vesting: coin(vesting, DENOM),
+        };
+
+        let schedule = get_release_schedule(deps.as_ref(), None, None);
+        assert_eq!(
+            schedule,
+            [bucket(0, 100, 0), bucket(30, 150, 200), bucket(250, 0, 150)]
+        );
+
+        // paginated by release time
+        let schedule = get_release_schedule(deps.as_ref(), None, Some(2));
+        assert_eq!(schedule, [bucket(0, 100, 0), bucket(30, 150, 200)]);
+        let schedule = get_release_schedule(deps.as_ref(), Some(release_at(30)), Some(2));
+        assert_eq!(schedule, [bucket(250, 0, 150)]);
+        let schedule = get_release_schedule(deps.as_ref(), Some(release_at(250)), None);
+        assert_eq!(schedule, []);
+    }
+
     mod auto_release_claims {
         // Because of tests framework limitations at the point of implementing this test, it is
         // difficult to actually test reaction for tgrade sudo messages. Instead to check the
@@ -2468,6 +4929,146 @@ mod tests {
             assert_sends_undelegates(resp, vec![(USER1, 1000)], vec![(USER1, 1)]);
         }
 
+        #[test]
+        fn release_events_list_creation_heights() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 5);
+
+            bond_liquid(deps.as_mut(), 12_000, 7_500, 4_000, 1);
+
+            unbond(deps.as_mut(), 1000, 500, 0, 2, 0);
+            unbond(deps.as_mut(), 2000, 0, 0, 5, 10);
+            let mut env = mock_env();
+            env.block.height += 6;
+            env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION + 10);
+
+            let resp = end_block(deps.as_mut(), env).unwrap();
+            let height = mock_env().block.height;
+            assert_eq!(
+                resp.events,
+                vec![
+                    Event::new("claims_released")
+                        .add_attribute("owner", USER1)
+                        .add_attribute(
+                            "creation_heights",
+                            format!("{},{}", height + 2, height + 5)
+                        ),
+                    Event::new("claims_released")
+                        .add_attribute("owner", USER2)
+                        .add_attribute("creation_heights", (height + 2).to_string()),
+                ]
+            );
+            assert_sends(resp, vec![(USER1, 3000), (USER2, 500)]);
+        }
+
+        #[test]
+        fn force_unbonded_claim_released_to_recipient() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 2);
+
+            bond_liquid(deps.as_mut(), 12_000, 7_500, 4_000, 1);
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(INIT_ADMIN, &[]),
+                ExecuteMsg::ForceUnbond {
+                    addr: USER1.to_owned(),
+                    recipient: Some(USER3.to_owned()),
+                },
+            )
+            .unwrap();
+
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION);
+            let resp = end_block(deps.as_mut(), env).unwrap();
+            assert_sends(resp, vec![(USER3, 12_000)]);
+        }
+
+        #[test]
+        fn release_attributes_report_backlog() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 2);
+
+            bond_liquid(deps.as_mut(), 12_000, 7_500, 4_000, 1);
+            unbond(deps.as_mut(), 1000, 500, 200, 2, 0);
+            let mut env = mock_env();
+            env.block.height += 2;
+            env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION);
+
+            let claims_attrs = |resp: &Response| -> Vec<(String, String)> {
+                resp.attributes
+                    .iter()
+                    .filter(|attr| attr.key.starts_with("claims_"))
+                    .map(|attr| (attr.key.clone(), attr.value.clone()))
+                    .collect()
+            };
+
+            // Only 2 out of 3 expired claims are released
+            let resp = end_block(deps.as_mut(), env.clone()).unwrap();
+            assert_eq!(
+                claims_attrs(&resp),
+                vec![
+                    ("claims_released".to_owned(), "2".to_owned()),
+                    ("claims_remaining_expired".to_owned(), "1".to_owned()),
+                ]
+            );
+
+            // Remaining one is released in next block
+            env.block.height += 1;
+            let resp = end_block(deps.as_mut(), env.clone()).unwrap();
+            assert_eq!(
+                claims_attrs(&resp),
+                vec![
+                    ("claims_released".to_owned(), "1".to_owned()),
+                    ("claims_remaining_expired".to_owned(), "0".to_owned()),
+                ]
+            );
+
+            // Nothing to release
+            env.block.height += 1;
+            let resp = end_block(deps.as_mut(), env).unwrap();
+            assert_eq!(
+                claims_attrs(&resp),
+                vec![
+                    ("claims_released".to_owned(), "0".to_owned()),
+                    ("claims_remaining_expired".to_owned(), "0".to_owned()),
+                ]
+            );
+        }
+
+        #[test]
+        fn remaining_expired_claims_counted_up_to_limit() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 2);
+
+            bond_liquid(deps.as_mut(), 12_000, 7_500, 4_000, 1);
+            unbond(deps.as_mut(), 1000, 500, 200, 2, 0);
+            unbond(deps.as_mut(), 1000, 500, 0, 3, 10);
+            let mut env = mock_env();
+            env.block.height += 3;
+            env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION + 10);
+
+            let remaining = |resp: &Response| {
+                resp.attributes
+                    .iter()
+                    .find(|attr| attr.key == "claims_remaining_expired")
+                    .map(|attr| attr.value.clone())
+                    .unwrap()
+            };
+
+            // 3 out of 5 expired claims are left, but counting stops at the limit
+            let resp = end_block(deps.as_mut(), env.clone()).unwrap();
+            assert_eq!(remaining(&resp), "2");
+
+            env.block.height += 1;
+            let resp = end_block(deps.as_mut(), env.clone()).unwrap();
+            assert_eq!(remaining(&resp), "1");
+
+            env.block.height += 1;
+            let resp = end_block(deps.as_mut(), env).unwrap();
+            assert_eq!(remaining(&resp), "0");
+        }
+
         #[test]
         fn multiple_users_claims_liquid() {
             let mut deps = mock_deps_tgrade();
@@ -2823,6 +5424,7 @@ mod tests {
 
             let msg = ExecuteMsg::Unbond {
                 tokens: coin(5_000, "invalid"),
+                owner: None,
             };
             let info = mock_info(USER1, &[]);
             let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
@@ -2843,11 +5445,168 @@ mod tests {
 
             let msg = ExecuteMsg::Unbond {
                 tokens: coin(5_000, "invalid"),
+                owner: None,
             };
             let info = mock_info(USER1, &[]);
             let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
 
             assert_eq!(ContractError::InvalidDenom {}, err);
         }
+
+        fn set_auto_restake(deps: DepsMut<TgradeQuery>, addr: &str, enabled: bool) {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(addr, &[]),
+                ExecuteMsg::SetAutoRestake { enabled },
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn auto_restake_claims() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 4);
+
+            bond_liquid(deps.as_mut(), 11_000, 7_500, 0, 1);
+            bond_vesting(deps.as_mut(), 1_000, 0, 0, 2);
+            set_auto_restake(deps.as_mut(), USER1, true);
+
+            let raw = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::AutoRestake {
+                    address: USER1.to_owned(),
+                },
+            )
+            .unwrap();
+            let resp: AutoRestakeResponse = from_slice(&raw).unwrap();
+            assert!(resp.enabled);
+
+            let height_delta = 3;
+            unbond(deps.as_mut(), 11_500, 2_500, 0, height_delta, 0);
+            assert_stake_liquid(deps.as_ref(), 0, 5_000, 0);
+            assert_stake_vesting(deps.as_ref(), 500, 0, 0);
+            assert_users(deps.as_ref(), None, Some(5), None, None);
+
+            let mut env = mock_env();
+            env.block.height += height_delta;
+            env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION);
+
+            // Only the claim of the user who didn't opt in is returned
+            let resp = end_block(deps.as_mut(), env).unwrap();
+            assert_sends(resp, vec![(USER2, 2_500)]);
+
+            // Claim of the opted in user is bonded again, liquid and vesting tokens alike
+            assert_stake_liquid(deps.as_ref(), 11_000, 5_000, 0);
+            assert_stake_vesting(deps.as_ref(), 1_000, 0, 0);
+            assert_users(deps.as_ref(), Some(12), Some(5), None, None);
+            assert_eq!(
+                get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None),
+                vec![]
+            );
+        }
+
+        #[test]
+        fn auto_restake_disabled() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 2);
+
+            bond_liquid(deps.as_mut(), 12_000, 0, 0, 1);
+            set_auto_restake(deps.as_mut(), USER1, true);
+            set_auto_restake(deps.as_mut(), USER1, false);
+
+            let height_delta = 2;
+            unbond(deps.as_mut(), 1_000, 0, 0, height_delta, 0);
+            let mut env = mock_env();
+            env.block.height += height_delta;
+            env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION);
+
+            let resp = end_block(deps.as_mut(), env).unwrap();
+            assert_sends(resp, vec![(USER1, 1_000)]);
+            assert_stake_liquid(deps.as_ref(), 11_000, 0, 0);
+        }
+
+        #[test]
+        fn claim_hooks_fire_on_release() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 4);
+
+            let hook = String::from("hook");
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(INIT_ADMIN, &[]),
+                ExecuteMsg::AddClaimHook { addr: hook.clone() },
+            )
+            .unwrap();
+            let raw = query(deps.as_ref(), mock_env(), QueryMsg::ClaimHooks {}).unwrap();
+            let hooks: HooksResponse = from_slice(&raw).unwrap();
+            assert_eq!(hooks.hooks, vec![hook.clone()]);
+
+            bond_liquid(deps.as_mut(), 1_000, 7_500, 0, 1);
+            bond_vesting(deps.as_mut(), 11_000, 7_500, 0, 2);
+            set_auto_restake(deps.as_mut(), USER2, true);
+
+            let height_delta = 3;
+            unbond(deps.as_mut(), 1_001, 2_500, 0, height_delta, 0);
+            let mut env = mock_env();
+            env.block.height += height_delta;
+            env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION);
+
+            let resp = end_block(deps.as_mut(), env).unwrap();
+            // Restaked claims are not reported
+            let expected = ClaimReleasedHookMsg {
+                addr: Addr::unchecked(USER1),
+                liquid: Uint128::new(1_000),
+                vesting: Uint128::new(1),
+            }
+            .into_cosmos_msg(hook.clone())
+            .map(|msg| SubMsg::reply_on_error(msg, CLAIM_HOOK_REPLY_ID))
+            .unwrap();
+            let hook_msgs: Vec<_> = resp
+                .messages
+                .iter()
+                .filter(|msg| matches!(msg.msg, CosmosMsg::Wasm(_)))
+                .cloned()
+                .collect();
+            assert_eq!(hook_msgs, vec![expected]);
+
+            // Reverting hook is logged, and claims stay released
+            let res = reply(
+                deps.as_mut(),
+                mock_env(),
+                Reply {
+                    id: CLAIM_HOOK_REPLY_ID,
+                    result: SubMsgResult::Err("hook failed".to_owned()),
+                },
+            )
+            .unwrap();
+            assert_eq!(
+                res,
+                Response::new()
+                    .add_attribute("action", "claim_hook_failed")
+                    .add_attribute("error", "hook failed")
+            );
+
+            // Removed hook is not called anymore
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(INIT_ADMIN, &[]),
+                ExecuteMsg::RemoveClaimHook { addr: hook },
+            )
+            .unwrap();
+            unbond(deps.as_mut(), 1_000, 0, 0, height_delta, 0);
+            let mut env = mock_env();
+            env.block.height += 2 * height_delta;
+            env.block.time = env.block.time.plus_seconds(2 * UNBONDING_DURATION);
+            let resp = end_block(deps.as_mut(), env).unwrap();
+            assert_undelegates(resp.clone(), vec![(USER1, 1_000)]);
+            assert!(!resp
+                .messages
+                .iter()
+                .any(|msg| matches!(msg.msg, CosmosMsg::Wasm(_))));
+        }
     }
 }
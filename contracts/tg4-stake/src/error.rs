@@ -1,8 +1,8 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Timestamp, Uint128};
 use thiserror::Error;
 
 use cw_controllers::AdminError;
-use tg_utils::{HookError, PreauthError, SlasherError};
+use tg_utils::{Expiration, HookError, PreauthError, SlasherError};
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
@@ -27,11 +27,14 @@ pub enum ContractError {
     #[error("No claims that can be released currently")]
     NothingToClaim {},
 
-    #[error("Must send '{0}' to stake")]
-    MissingDenom(String),
+    #[error("Must send '{expected}' to stake, got '{found}'")]
+    MissingDenom { expected: String, found: String },
 
-    #[error("Sent unsupported denoms, must send '{0}' to stake")]
-    ExtraDenoms(String),
+    #[error("Sent unsupported denoms {found:?}, must send only '{expected}' to stake")]
+    ExtraDenoms {
+        expected: String,
+        found: Vec<String>,
+    },
 
     #[error("Must send valid amount to unbond")]
     ZeroAmount {},
@@ -42,6 +45,30 @@ pub enum ContractError {
     #[error("No funds sent")]
     NoFunds {},
 
+    #[error("Zero amount coins are not accepted")]
+    ZeroFunds {},
+
+    #[error("Bond of {amount} exceeds maximum of {max} per transaction")]
+    BondTooLarge { amount: Uint128, max: Uint128 },
+
+    #[error("Stake is too new - only {unbondable} can be unbonded before {until}")]
+    StakeTooNew {
+        unbondable: Uint128,
+        until: Timestamp,
+    },
+
+    #[error("Too many pending claims - wait for some of them to be released before unbonding")]
+    TooManyClaims {},
+
     #[error("Unrecognized sudo message")]
     UnknownSudoMsg {},
+
+    #[error("Unrecognised reply id: {0}")]
+    UnrecognisedReply(u64),
+
+    #[error("Unbonding is frozen until {}", until.time())]
+    Frozen { until: Expiration },
+
+    #[error("Contract is frozen")]
+    ContractFrozen {},
 }
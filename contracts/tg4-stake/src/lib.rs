@@ -2,4 +2,6 @@ pub mod claim;
 pub mod contract;
 pub mod error;
 pub mod msg;
+#[cfg(test)]
+mod multitest;
 pub mod state;
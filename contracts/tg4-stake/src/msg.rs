@@ -1,20 +1,30 @@
-use cosmwasm_std::{Coin, Decimal, Uint128};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, StdResult, Timestamp, Uint128, WasmMsg,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use tg_bindings::TgradeMsg;
 use tg_utils::{Duration, Expiration};
 
 pub use crate::claim::Claim;
+use crate::state::Config;
+pub use crate::state::{Rounding, SlashPolicy};
 use tg4::Member;
 
 const fn default_auto_return_limit() -> u64 {
     20
 }
 
+const fn default_include_claims() -> bool {
+    true
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct InstantiateMsg {
     /// Denom of the token to stake
     pub denom: String,
-    pub tokens_per_point: Uint128,
+    /// Amount of tokens giving one point, may be fractional (e.g. "1.5")
+    pub tokens_per_point: Decimal,
     pub min_bond: Uint128,
     /// Unbounding period in seconds
     pub unbonding_period: u64,
@@ -31,6 +41,31 @@ pub struct InstantiateMsg {
     /// Setting this to 0 disables auto returning claims.
     #[serde(default = "default_auto_return_limit")]
     pub auto_return_limit: u64,
+    /// If set, `Bond` fails when any coin sent (or `vesting_tokens`) has zero amount, instead of
+    /// treating it as no funds. Disabled by default.
+    #[serde(default)]
+    pub reject_zero_funds: bool,
+    /// If set, `Bond` fails when liquid and vesting tokens bonded in a single message exceed
+    /// this amount. Unlimited by default, zero is treated as no limit.
+    #[serde(default)]
+    pub max_bond_per_tx: Option<Uint128>,
+    /// If set, tokens can't be unbonded until this many seconds passed since the last `Bond`
+    /// of the address. Only tokens bonded within this time are locked, older stake can still be
+    /// unbonded. Disabled by default.
+    #[serde(default)]
+    pub min_stake_duration: Option<u64>,
+    /// If set, `Unbond` fails when the address already has this many outstanding claims with
+    /// distinct release times. Unlimited by default, zero is treated as no limit.
+    #[serde(default)]
+    pub max_claims_per_addr: Option<u32>,
+    /// Decides if members slashed below `min_bond` are removed (default) or kept at the points
+    /// of `min_bond` stake
+    #[serde(default)]
+    pub slash_policy: SlashPolicy,
+    /// Rounding of points calculated from stake, down by default. Stake below `min_bond` never
+    /// gets any points, regardless of rounding
+    #[serde(default)]
+    pub points_rounding: Rounding,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -44,10 +79,33 @@ pub enum ExecuteMsg {
     /// back to his wallet after `unbonding_period`.
     /// Tokens will be unbonded from the liquid stake first, and then from the vesting stake
     /// if available.
-    Unbond { tokens: Coin },
+    Unbond {
+        tokens: Coin,
+        /// Address whose tokens are unbonded; `sender` by default. `sender` has to be the stake
+        /// delegate of `owner` (`owner` has to call `DelegateStakeControl { delegate: sender }`
+        /// before)
+        #[serde(default)]
+        owner: Option<String>,
+    },
+    /// Unbonds the whole liquid and vesting stake of the sender at once, as `Unbond` would with
+    /// their full stake amount
+    UnbondAll {},
     /// Claim is used to claim your native and vesting tokens that you previously "unbonded"
     /// after the contract-defined waiting period (eg. 1 week)
-    Claim {},
+    Claim {
+        /// Address to send the liquid tokens to, defaults to the owner. Vesting tokens are
+        /// always undelegated back to the owner's vesting account, ignoring this field. Can't be
+        /// set when claiming as a stake delegate.
+        #[serde(default)]
+        receiver: Option<String>,
+        /// Address whose claims are released; `sender` by default. `sender` has to be the stake
+        /// delegate of `owner`, and tokens are always returned to `owner`
+        #[serde(default)]
+        owner: Option<String>,
+    },
+    /// Authorizes `delegate` to unbond and claim on behalf of the sender. Only one delegate can
+    /// be set, setting it to own address removes the delegation.
+    DelegateStakeControl { delegate: String },
 
     /// Change the admin
     UpdateAdmin { admin: Option<String> },
@@ -55,6 +113,11 @@ pub enum ExecuteMsg {
     AddHook { addr: String },
     /// Remove a hook. Must be called by Admin
     RemoveHook { addr: String },
+    /// Add a new hook to be informed of claims released automatically at end of block. Must be
+    /// called by Admin, or use a preauth
+    AddClaimHook { addr: String },
+    /// Remove a claim hook. Must be called by Admin, or by the hook itself
+    RemoveClaimHook { addr: String },
     /// Add a new slasher. Must be called by Admin
     AddSlasher { addr: String },
     /// Remove a slasher. Must be called by Admin
@@ -63,7 +126,36 @@ pub enum ExecuteMsg {
         addr: String,
         // between (0.0, 1.0]
         portion: Decimal,
+        /// If false, pending claims are left untouched and only bonded stake is slashed.
+        /// Defaults to true.
+        #[serde(default = "default_include_claims")]
+        include_claims: bool,
     },
+    /// Opts the sender in or out of automatic restaking. When enabled, claims maturing at end of
+    /// block are bonded again instead of being returned to the sender. Only claims processed
+    /// automatically (see `auto_return_limit`) are affected.
+    SetAutoRestake { enabled: bool },
+    /// Unbonds the whole stake of the address, regardless of freezes and `min_stake_duration`,
+    /// creating a claim released after the unbonding period. If `recipient` is set, liquid
+    /// tokens of the claim are paid to it instead of the address. Meant for recovering stake
+    /// of compromised keys. Must be called by Admin
+    ForceUnbond {
+        addr: String,
+        recipient: Option<String>,
+    },
+    /// Prevents the address from unbonding until given expiration. Bonding and claiming already
+    /// unbonded tokens is still possible. Must be called by Admin
+    Freeze { addr: String, until: Expiration },
+    /// Lifts the unbonding freeze of the address. Must be called by Admin
+    Unfreeze { addr: String },
+    /// Merges claims of the address released within the same second into single claims, which
+    /// keeps the claims map small. Total released amounts are not affected, merged claims are
+    /// released at the latest release time of claims they replace. Must be called by Admin
+    CompactClaims { addr: String },
+    /// Emergency freeze of the whole contract. While frozen, all execute messages other than this
+    /// one fail, and claims are not released at end of block. Queries are still available.
+    /// Must be called by Admin
+    FreezeContract { frozen: bool },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -77,9 +169,14 @@ pub enum QueryMsg {
         limit: Option<u32>,
         start_after: Option<Expiration>,
     },
-    /// Shows the number of liquid and vesting tokens currently staked by this address.
-    /// Returns StakedResponse.
-    Staked { address: String },
+    /// Shows the number of liquid and vesting tokens staked by this address, currently or at
+    /// given height. Stake history is available since the contract instantiation, or since its
+    /// migration to the version recording it. Returns StakedResponse.
+    Staked {
+        address: String,
+        #[serde(default)]
+        at_height: Option<u64>,
+    },
     /// Returns the unbonding period of this contract.
     /// Returns UnbondingPeriodResponse.
     UnbondingPeriod {},
@@ -106,12 +203,43 @@ pub enum QueryMsg {
     },
     /// Shows all registered hooks. Returns HooksResponse.
     Hooks {},
+    /// Shows all registered claim hooks. Returns HooksResponse.
+    ClaimHooks {},
     /// Return the current number of preauths. Returns PreauthResponse.
     Preauths {},
     /// Returns information (bool) about whether a given address is an active slasher
     IsSlasher { addr: String },
     /// Returns all active slashers as a vector of addresses.
     ListSlashers {},
+    /// Returns the address authorized to unbond and claim on behalf of `owner`.
+    /// Returns StakeDelegateResponse.
+    StakeDelegate { owner: String },
+    /// Returns whether matured claims of this address are automatically restaked.
+    /// Returns AutoRestakeResponse.
+    AutoRestake { address: String },
+    /// Returns information (bool) about whether a given address is currently not allowed to
+    /// unbond
+    IsFrozen { addr: String },
+    /// Returns information (bool) about whether the whole contract is frozen with
+    /// `FreezeContract`
+    IsContractFrozen {},
+    /// Aggregates pending claims of all addresses into `buckets` consecutive time buckets,
+    /// `bucket_seconds` long each, starting at the current block time. Claims which are already
+    /// expired, but not yet released, are accounted in the first bucket. At most 100 buckets
+    /// are returned, and at least one must be requested. Returns UnlockScheduleResponse.
+    GlobalUnlockSchedule { bucket_seconds: u64, buckets: u32 },
+    /// Sums pending claims of all addresses by their release time (nanoseconds), ascending.
+    /// Paginated by the release time of the last returned bucket.
+    /// Returns ReleaseScheduleResponse.
+    ReleaseSchedule {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the config together with consistency checks of the contract state. All members,
+    /// stakes and claims are scanned, so the gas cost grows with the number of stakers - it is
+    /// meant for periodic monitoring, not to be called from other contracts.
+    /// Returns InvariantsResponse.
+    Invariants {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -120,6 +248,53 @@ pub struct StakedResponse {
     pub vesting: Coin,
 }
 
+/// Sent to claim hooks for every address whose matured claims were released at end of block.
+/// Claims restaked with `SetAutoRestake` are not reported.
+/// ClaimReleasedHookMsg should be de/serialized under `ClaimReleasedHook()` variant in a
+/// ExecuteMsg.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct ClaimReleasedHookMsg {
+    pub addr: Addr,
+    /// Liquid tokens sent back to the address
+    pub liquid: Uint128,
+    /// Vesting tokens undelegated back to the address
+    pub vesting: Uint128,
+}
+
+impl ClaimReleasedHookMsg {
+    /// serializes the message
+    pub fn into_binary(self) -> StdResult<Binary> {
+        let msg = ClaimReleasedExecuteMsg::ClaimReleasedHook(self);
+        to_binary(&msg)
+    }
+
+    /// creates a cosmos_msg sending this struct to the named contract
+    pub fn into_cosmos_msg<T: Into<String>>(
+        self,
+        contract_addr: T,
+    ) -> StdResult<CosmosMsg<TgradeMsg>> {
+        let msg = self.into_binary()?;
+        let execute = WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg,
+            funds: vec![],
+        };
+        Ok(execute.into())
+    }
+}
+
+// This is just a helper to properly serialize the above message
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+enum ClaimReleasedExecuteMsg {
+    ClaimReleasedHook(ClaimReleasedHookMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct StakeDelegateResponse {
+    pub delegate: Option<Addr>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct PreauthResponse {
     pub preauths_hooks: u64,
@@ -135,13 +310,72 @@ pub struct ClaimsResponse {
     pub claims: Vec<Claim>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct UnlockBucket {
+    /// Beginning of the bucket (inclusive)
+    pub start: Timestamp,
+    /// End of the bucket (exclusive)
+    pub end: Timestamp,
+    /// Sum of liquid tokens unlocked within the bucket
+    pub liquid: Coin,
+    /// Sum of vesting tokens unlocked within the bucket
+    pub vesting: Coin,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct UnlockScheduleResponse {
+    pub buckets: Vec<UnlockBucket>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ReleaseBucket {
+    /// Release time of claims in the bucket, in nanoseconds
+    pub release_at: u64,
+    /// Sum of liquid tokens released at this time
+    pub liquid: Coin,
+    /// Sum of vesting tokens released at this time
+    pub vesting: Coin,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ReleaseScheduleResponse {
+    pub buckets: Vec<ReleaseBucket>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct InvariantsResponse {
+    pub config: Config,
+    /// Total points equal the sum of all members points
+    pub total_matches_sum_of_points: bool,
+    /// Total points equal the sum of points calculated from every address stake (liquid and
+    /// vesting), taking `slash_policy` into account
+    pub stake_totals_match_running_totals: bool,
+    /// Contract balance covers all liquid stakes and pending liquid claims
+    pub no_negative_balances: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct AutoRestakeResponse {
+    pub enabled: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct MigrateMsg {
-    pub tokens_per_point: Option<Uint128>,
+    /// Zero is replaced with one
+    pub tokens_per_point: Option<Decimal>,
     pub min_bond: Option<Uint128>,
     pub unbonding_period: Option<u64>,
     pub auto_return_limit: Option<u64>,
+    pub reject_zero_funds: Option<bool>,
+    /// Zero removes the limit
+    pub max_bond_per_tx: Option<Uint128>,
+    /// Zero removes the minimum
+    pub min_stake_duration: Option<u64>,
+    /// Zero removes the limit
+    pub max_claims_per_addr: Option<u32>,
+    pub slash_policy: Option<SlashPolicy>,
+    pub points_rounding: Option<Rounding>,
 }
 
 #[cfg(test)]
@@ -0,0 +1,169 @@
+use cosmwasm_std::{
+    coins, Addr, Binary, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+    StdResult, Uint128,
+};
+use cw_multi_test::{AppBuilder, BasicApp, Contract, ContractWrapper, Executor};
+use tg4::{MemberResponse, Tg4QueryMsg};
+use tg_bindings::{TgradeMsg, TgradeQuery};
+
+use crate::msg::{ExecuteMsg, InstantiateMsg, Rounding, SlashPolicy};
+
+const DENOM: &str = "stake";
+const OWNER: &str = "owner";
+const USER: &str = "user";
+const SLASHER: &str = "slasher";
+
+fn contract_stake() -> Box<dyn Contract<TgradeMsg, TgradeQuery>> {
+    let contract = ContractWrapper::new(
+        crate::contract::execute,
+        crate::contract::instantiate,
+        crate::contract::query,
+    )
+    .with_reply(crate::contract::reply);
+    Box::new(contract)
+}
+
+fn failing_hook_execute(
+    _deps: DepsMut<TgradeQuery>,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response<TgradeMsg>> {
+    Err(StdError::generic_err("hook failed"))
+}
+
+fn failing_hook_instantiate(
+    _deps: DepsMut<TgradeQuery>,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response<TgradeMsg>> {
+    Ok(Response::new())
+}
+
+fn failing_hook_query(_deps: Deps<TgradeQuery>, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    Ok(Binary::default())
+}
+
+/// Member changed hook which reverts on every call
+fn contract_failing_hook() -> Box<dyn Contract<TgradeMsg, TgradeQuery>> {
+    let contract = ContractWrapper::new(
+        failing_hook_execute,
+        failing_hook_instantiate,
+        failing_hook_query,
+    );
+    Box::new(contract)
+}
+
+fn instantiate_stake(app: &mut BasicApp<TgradeMsg, TgradeQuery>) -> Addr {
+    let stake_id = app.store_code(contract_stake());
+    let msg = InstantiateMsg {
+        denom: DENOM.to_owned(),
+        tokens_per_point: Decimal::one(),
+        min_bond: Uint128::new(100),
+        unbonding_period: 3600,
+        admin: Some(OWNER.to_owned()),
+        preauths_hooks: 0,
+        preauths_slashing: 0,
+        auto_return_limit: 0,
+        reject_zero_funds: false,
+        max_bond_per_tx: None,
+        min_stake_duration: None,
+        max_claims_per_addr: None,
+        slash_policy: SlashPolicy::Remove,
+        points_rounding: Rounding::Down,
+    };
+    app.instantiate_contract(
+        stake_id,
+        Addr::unchecked(OWNER),
+        &msg,
+        &[],
+        "stake",
+        Some(OWNER.to_owned()),
+    )
+    .unwrap()
+}
+
+#[test]
+fn failing_slash_hook_doesnt_revert_slash() {
+    let mut app = AppBuilder::new_custom().build(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &Addr::unchecked(USER), coins(1000, DENOM))
+            .unwrap();
+    });
+    let stake = instantiate_stake(&mut app);
+
+    app.execute_contract(
+        Addr::unchecked(USER),
+        stake.clone(),
+        &ExecuteMsg::Bond {
+            vesting_tokens: None,
+        },
+        &coins(1000, DENOM),
+    )
+    .unwrap();
+
+    let hook_id = app.store_code(contract_failing_hook());
+    let hook = app
+        .instantiate_contract(
+            hook_id,
+            Addr::unchecked(OWNER),
+            &Empty {},
+            &[],
+            "hook",
+            None,
+        )
+        .unwrap();
+    let owner = Addr::unchecked(OWNER);
+    app.execute_contract(
+        owner.clone(),
+        stake.clone(),
+        &ExecuteMsg::AddHook {
+            addr: hook.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        owner,
+        stake.clone(),
+        &ExecuteMsg::AddSlasher {
+            addr: SLASHER.to_owned(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res = app
+        .execute_contract(
+            Addr::unchecked(SLASHER),
+            stake.clone(),
+            &ExecuteMsg::Slash {
+                addr: USER.to_owned(),
+                portion: Decimal::percent(50),
+                include_claims: true,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Hook failure is logged
+    assert!(res.events.iter().any(|ev| ev
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "action" && attr.value == "slash_hook_failed")));
+
+    // Slash is applied anyway
+    let member: MemberResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &stake,
+            &Tg4QueryMsg::Member {
+                addr: USER.to_owned(),
+                at_height: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(member.points, Some(500));
+}
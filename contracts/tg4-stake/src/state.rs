@@ -2,9 +2,9 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::claim::Claims;
-use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::{Item, Map};
-use tg_utils::Duration;
+use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotMap, Strategy};
+use tg_utils::{Duration, Expiration, Hooks};
 
 /// Builds a claims map as it cannot be done in const time
 pub fn claims() -> Claims<'static> {
@@ -15,14 +15,119 @@ pub fn claims() -> Claims<'static> {
 pub struct Config {
     /// denom of the token to stake
     pub denom: String,
-    pub tokens_per_point: Uint128,
+    /// may be fractional; serialized the same way as integer amounts before, so configs stored
+    /// by older versions are still loaded properly
+    pub tokens_per_point: Decimal,
     pub min_bond: Uint128,
     /// time in seconds
     pub unbonding_period: Duration,
     /// limits of how much claims can be automatically returned at end of block
     pub auto_return_limit: u64,
+    /// if set, bonding with any zero-amount coin is rejected instead of being ignored
+    #[serde(default)]
+    pub reject_zero_funds: bool,
+    /// if set, single bond (liquid and vesting combined) can't exceed this amount
+    #[serde(default)]
+    pub max_bond_per_tx: Option<Uint128>,
+    /// if set, tokens bonded within this duration since the last bond can't be unbonded
+    #[serde(default)]
+    pub min_stake_duration: Option<Duration>,
+    /// if set, unbonding fails when the address has this many claims pending already
+    #[serde(default)]
+    pub max_claims_per_addr: Option<u32>,
+    /// how members slashed below `min_bond` are handled
+    #[serde(default)]
+    pub slash_policy: SlashPolicy,
+    /// how stake not divisible by `tokens_per_point` is converted to points
+    #[serde(default)]
+    pub points_rounding: Rounding,
+}
+
+/// Membership handling of members whose stake falls below `min_bond` after being slashed
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SlashPolicy {
+    /// Member is removed, the same as after unbonding below `min_bond`
+    Remove,
+    /// Member keeps the points of `min_bond` stake until their stake changes again. Members
+    /// slashed to zero stake are still removed.
+    ClampAtMinBond,
+}
+
+impl Default for SlashPolicy {
+    fn default() -> Self {
+        Self::Remove
+    }
+}
+
+/// Rounding of points calculated from stake
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Rounding {
+    /// Remainder of `tokens_per_point` is ignored
+    Down,
+    /// Remainder of at least half of `tokens_per_point` counts as a whole point
+    Nearest,
+}
+
+impl Default for Rounding {
+    fn default() -> Self {
+        Self::Down
+    }
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
-pub const STAKE: Map<&Addr, Uint128> = Map::new("stake");
-pub const STAKE_VESTING: Map<&Addr, Uint128> = Map::new("vesting_stake");
+pub const STAKE: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "stake",
+    "stake__checkpoints",
+    "stake__changelog",
+    Strategy::EveryBlock,
+);
+pub const STAKE_VESTING: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "vesting_stake",
+    "vesting_stake__checkpoints",
+    "vesting_stake__changelog",
+    Strategy::EveryBlock,
+);
+
+/// Height since which stake history is recorded - the instantiation height, or the height of
+/// migration to the version snapshotting stakes
+pub const STAKE_HISTORY_START: Item<u64> = Item::new("stake_history_start");
+
+/// Addresses which opted in to have their matured claims bonded again at end of block,
+/// instead of having them returned
+pub const AUTO_RESTAKE: Map<&Addr, bool> = Map::new("auto_restake");
+
+/// Addresses authorized to unbond and claim on behalf of stake owners
+pub const STAKE_DELEGATE: Map<&Addr, Addr> = Map::new("stake_delegate");
+
+/// Addresses which are not allowed to unbond until given expiration
+pub const FROZEN: Map<&Addr, Expiration> = Map::new("frozen");
+
+/// Whether the whole contract is frozen in an emergency
+pub const CONTRACT_FROZEN: Item<bool> = Item::new("contract_frozen");
+
+/// Tokens bonded by an address since its stake last matured, with the time of the last bond.
+/// Only tracked when `min_stake_duration` is set.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct RecentBond {
+    pub amount: Uint128,
+    pub last_bond: Timestamp,
+}
+
+impl RecentBond {
+    /// Amount which still can't be unbonded at `now`. All recently bonded tokens are released at
+    /// once, when `min_stake_duration` passes since the last bond.
+    pub fn locked(&self, min_stake_duration: Duration, now: Timestamp) -> Uint128 {
+        if self.last_bond.plus_seconds(min_stake_duration.seconds()) > now {
+            self.amount
+        } else {
+            Uint128::zero()
+        }
+    }
+}
+
+pub const RECENT_BONDS: Map<&Addr, RecentBond> = Map::new("recent_bonds");
+
+/// Contracts notified about claims released automatically at end of block
+pub const CLAIM_HOOKS: Hooks = Hooks::new("claim_hooks");
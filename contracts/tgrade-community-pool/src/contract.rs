@@ -136,6 +136,7 @@ pub fn execute_withdraw_engagement_rewards<Q: CustomQuery>(
         &tg4_engagement::msg::ExecuteMsg::WithdrawRewards {
             owner: None,
             receiver: None,
+            as_vesting: false,
         },
     )?)?;
 
@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Result as AnyResult};
 
-use cosmwasm_std::{coin, Addr, CosmosMsg, StdResult};
+use cosmwasm_std::{coin, Addr, CosmosMsg, Decimal, StdResult};
 use cw_multi_test::{AppResponse, Contract, ContractWrapper, CosmosRouter, Executor};
 use tg4::{Member, Tg4ExecuteMsg};
 use tg_bindings::{TgradeMsg, TgradeQuery};
@@ -87,7 +87,14 @@ impl SuiteBuilder {
                     preauths_hooks: 0,
                     preauths_slashing: 1,
                     halflife: None,
+                    decay: None,
                     denom: "ENGAGEMENT".to_owned(),
+                    max_denoms: tg4_engagement::msg::default_max_denoms(),
+                    distribution_fee: Decimal::zero(),
+                    fee_recipient: None,
+                    auto_distribute: false,
+                    halflife_floor: 1,
+                    initial_adjustments: vec![],
                 },
                 &[],
                 "engagement",
@@ -106,7 +113,14 @@ impl SuiteBuilder {
                     preauths_hooks: 0,
                     preauths_slashing: 1,
                     halflife: None,
+                    decay: None,
                     denom: self.group_token.clone(),
+                    max_denoms: tg4_engagement::msg::default_max_denoms(),
+                    distribution_fee: Decimal::zero(),
+                    fee_recipient: None,
+                    auto_distribute: false,
+                    halflife_floor: 1,
+                    initial_adjustments: vec![],
                 },
                 &[],
                 "group",
@@ -205,7 +219,10 @@ impl Suite {
         self.app.execute_contract(
             self.owner.clone(),
             self.group_contract.clone(),
-            &tg4_engagement::ExecuteMsg::DistributeRewards { sender: None },
+            &tg4_engagement::ExecuteMsg::DistributeRewards {
+                sender: None,
+                denom: None,
+            },
             &[coin(amount, self.group_token.clone())],
         )
     }
@@ -90,7 +90,14 @@ impl SuiteBuilder {
                     preauths_hooks: 0,
                     preauths_slashing: 1,
                     halflife: None,
+                    decay: None,
                     denom: "ENGAGEMENT".to_owned(),
+                    max_denoms: tg4_engagement::msg::default_max_denoms(),
+                    distribution_fee: Decimal::zero(),
+                    fee_recipient: None,
+                    auto_distribute: false,
+                    halflife_floor: 1,
+                    initial_adjustments: vec![],
                 },
                 &[],
                 "engagement",
@@ -109,7 +116,14 @@ impl SuiteBuilder {
                     preauths_hooks: 0,
                     preauths_slashing: 1,
                     halflife: None,
+                    decay: None,
                     denom: "GROUP".to_owned(),
+                    max_denoms: tg4_engagement::msg::default_max_denoms(),
+                    distribution_fee: Decimal::zero(),
+                    fee_recipient: None,
+                    auto_distribute: false,
+                    halflife_floor: 1,
+                    initial_adjustments: vec![],
                 },
                 &[],
                 "group",
@@ -1,12 +1,13 @@
-use std::cmp::{max, min};
-use std::collections::BTreeSet;
+use std::cmp::{max, min, Reverse};
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::{TryFrom, TryInto};
 
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, BlockInfo, Coin, CustomQuery, Decimal, Deps, DepsMut, Env,
-    MessageInfo, Order, QueryRequest, Reply, StdError, StdResult, Timestamp, WasmMsg,
+    to_binary, Addr, Binary, BlockInfo, Coin, CustomQuery, Decimal, Deps, DepsMut, Env, Event,
+    MessageInfo, Order, QueryRequest, Reply, StdError, StdResult, Storage, Timestamp, Uint128,
+    WasmMsg,
 };
 
 use cw2::set_contract_version;
@@ -16,24 +17,34 @@ use cw_utils::{ensure_from_older_version, maybe_addr, parse_reply_instantiate_da
 
 use tg4::{Member, Tg4Contract};
 use tg_bindings::{
-    request_privileges, Ed25519Pubkey, Evidence, EvidenceType, Privilege, PrivilegeChangeMsg,
-    Pubkey, TgradeMsg, TgradeQuery, TgradeSudoMsg, ToAddress, ValidatorDiff, ValidatorUpdate,
-    ValidatorVoteResponse,
+    request_privileges, Evidence, EvidenceType, Privilege, PrivilegeChangeMsg, Pubkey, TgradeMsg,
+    TgradeQuery, TgradeSudoMsg, ValidatorDiff, ValidatorUpdate, ValidatorVoteResponse,
 };
 use tg_utils::{Duration, JailingDuration, SlashMsg, ADMIN};
 
 use crate::error::ContractError;
 use crate::msg::{
-    EpochResponse, ExecuteMsg, InstantiateMsg, InstantiateResponse, JailingEnd, JailingPeriod,
+    validate_epoch_reward, validate_probation_scaling, DistributionContractReward,
+    DistributionContractStats, DistributionContractStatsResponse, DistributionSchema,
+    DoubleSignInfoResponse, DoubleSignSlashing, Eligibility, EligibilityResponse, EpochResponse,
+    EpochsSinceSignedResponse, ExecuteMsg, InstantiateMsg, InstantiateResponse,
+    IsTombstonedResponse, JailingEnd, JailingHistoryResponse, JailingPeriod, JailingRecord,
     ListActiveValidatorsResponse, ListValidatorResponse, ListValidatorSlashingResponse, MigrateMsg,
-    OperatorResponse, QueryMsg, RewardsDistribution, RewardsInstantiateMsg, ValidatorMetadata,
-    ValidatorResponse,
+    OperatorByConsensusKeyResponse, OperatorResponse, PowerChange, PowerChangesResponse, QueryMsg,
+    RewardAddressResponse, RewardsDistribution, RewardsInstantiateMsg, SimulateRewardsResponse,
+    StakeQueryMsg, StakedResponse, TieBreak, UnvalidatedDistributionContract,
+    UnvalidatedDistributionContracts, UpcomingEpoch, UpcomingEpochsResponse,
+    ValidatePubkeyResponse, ValidatorAtRisk, ValidatorFullProfileResponse, ValidatorMetadata,
+    ValidatorResponse, ValidatorReward, ValidatorStatus, ValidatorsAtRiskResponse,
 };
-use crate::rewards::pay_block_rewards;
+use crate::rewards::{calculate_rewards, pay_block_rewards, reset_epoch_uptime, validators_uptime};
 use crate::state::{
-    export, import, operators, Config, DistributionContract, EpochInfo, OperatorInfo,
-    ValidatorInfo, ValidatorSlashing, ValsetState, BLOCK_SIGNERS, CONFIG, EPOCH, JAIL, VALIDATORS,
-    VALIDATOR_SLASHING, VALIDATOR_START_HEIGHT,
+    export, import, operators, Config, DistributionContract, EpochInfo, EpochOffset, OperatorInfo,
+    OperatorPubkey, SlashingCause, ValidatorInfo, ValidatorSlashing, ValsetState, ACTIVE_SINCE,
+    BLOCK_SIGNERS, BOUNDARY_SIGNERS, CONFIG, DISTRIBUTED_REWARDS, EPOCH, EPOCH_BLOCKS,
+    EPOCH_PROCESSING_PAUSED, EPOCH_SIGNED_BLOCKS, IMMEDIATE_JAILS, JAIL, JAILING_HISTORY,
+    OPERATOR_REGISTRATION_EPOCH, REWARD_ADDRESS, SKIPPED_EPOCH, TOMBSTONED, UNJAILED_AT,
+    VALIDATORS, VALIDATORS_HISTORY, VALIDATOR_SLASHING, VALIDATOR_START_HEIGHT,
 };
 
 // version info for migration info
@@ -41,10 +52,14 @@ pub(crate) const CONTRACT_NAME: &str = "crates.io:tgrade-valset";
 pub(crate) const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const REWARDS_INIT_REPLY_ID: u64 = 1;
+const SLASH_REPLY_ID: u64 = 2;
 
 /// Missed blocks interval a validator can be jailed for.
 pub const MISSED_BLOCKS: u64 = 1000;
 
+/// Minimum number of most recent epochs kept in `VALIDATORS_HISTORY`, older ones are pruned.
+pub const HISTORY_RETENTION: u64 = 100;
+
 /// We use this custom message everywhere
 pub type Response = cosmwasm_std::Response<TgradeMsg>;
 pub type SubMsg = cosmwasm_std::SubMsg<TgradeMsg>;
@@ -82,6 +97,23 @@ pub fn instantiate(
         validator_group: Addr::unchecked(""),
         verify_validators: msg.verify_validators,
         offline_jail_duration: msg.offline_jail_duration,
+        offline_jail_immunity: msg.offline_jail_immunity,
+        min_active_validators: msg.min_active_validators,
+        delay_new_operators: msg.delay_new_operators,
+        active_set_retention: msg.active_set_retention,
+        min_self_bond: msg.min_self_bond,
+        probation_scaling: msg.probation_scaling,
+        probation_epochs: msg.probation_epochs,
+        metadata_update_cooldown: msg.metadata_update_cooldown,
+        uptime_rewards: msg.uptime_rewards,
+        tie_break: msg.tie_break,
+        downtime_slash_ratio: msg.downtime_slash_ratio,
+        governance_slash_ratio: msg.governance_slash_ratio,
+        max_power: msg.max_power,
+        max_churn: msg.max_churn,
+        reward_vesting: msg.reward_vesting,
+        verification_grace_blocks: msg.verification_grace_blocks,
+        max_epoch_reward: msg.max_epoch_reward,
     };
     CONFIG.save(deps.storage, &cfg)?;
 
@@ -90,6 +122,7 @@ pub fn instantiate(
         current_epoch: 0,
         last_update_time: 0,
         last_update_height: 0,
+        epoch_offset: EpochOffset::default(),
     };
     EPOCH.save(deps.storage, &epoch)?;
 
@@ -97,12 +130,14 @@ pub fn instantiate(
 
     for op in msg.initial_keys.into_iter() {
         let oper = deps.api.addr_validate(&op.operator)?;
-        let pubkey: Ed25519Pubkey = op.validator_pubkey.try_into()?;
+        let pubkey: OperatorPubkey = op.validator_pubkey.try_into()?;
         op.metadata.validate()?;
         let info = OperatorInfo {
             pubkey,
             metadata: op.metadata,
             active_validator: false,
+            probation_start_epoch: None,
+            metadata_updated_at: None,
         };
         operators().save(deps.storage, &oper, &info)?;
     }
@@ -171,8 +206,27 @@ pub fn execute(
             distribution_contracts,
             verify_validators,
             offline_jail_duration,
+            offline_jail_immunity,
+            min_active_validators,
+            delay_new_operators,
+            active_set_retention,
+            min_self_bond,
+            probation_scaling,
+            probation_epochs,
+            metadata_update_cooldown,
+            uptime_rewards,
+            tie_break,
+            downtime_slash_ratio,
+            governance_slash_ratio,
+            max_power,
+            max_churn,
+            epoch_length,
+            reward_vesting,
+            verification_grace_blocks,
+            max_epoch_reward,
         } => execute_update_config(
             deps,
+            env,
             info,
             min_points,
             max_validators,
@@ -184,17 +238,53 @@ pub fn execute(
             distribution_contracts,
             verify_validators,
             offline_jail_duration,
+            offline_jail_immunity,
+            min_active_validators,
+            delay_new_operators,
+            active_set_retention,
+            min_self_bond,
+            probation_scaling,
+            probation_epochs,
+            metadata_update_cooldown,
+            uptime_rewards,
+            tie_break,
+            downtime_slash_ratio,
+            governance_slash_ratio,
+            max_power,
+            max_churn,
+            epoch_length,
+            reward_vesting,
+            verification_grace_blocks,
+            max_epoch_reward,
         ),
 
+        ExecuteMsg::AddDistributionContract { contract, ratio } => {
+            execute_add_distribution_contract(deps, info, contract, ratio)
+        }
+        ExecuteMsg::RemoveDistributionContract { contract } => {
+            execute_remove_distribution_contract(deps, info, contract)
+        }
         ExecuteMsg::RegisterValidatorKey { pubkey, metadata } => {
             execute_register_validator_key(deps, env, info, pubkey, metadata)
         }
         ExecuteMsg::UpdateMetadata(metadata) => execute_update_metadata(deps, env, info, metadata),
-        ExecuteMsg::Jail { operator, duration } => {
-            execute_jail(deps, env, info, operator, duration)
+        ExecuteMsg::Deregister {} => execute_deregister(deps, info),
+        ExecuteMsg::SetRewardAddress { address } => execute_set_reward_address(deps, info, address),
+        ExecuteMsg::Jail {
+            operator,
+            duration,
+            immediate,
+        } => execute_jail(deps, env, info, operator, duration, immediate),
+        ExecuteMsg::Tombstone { operator } => execute_tombstone(deps, env, info, operator),
+        ExecuteMsg::SetEpochProcessingPaused { paused } => {
+            execute_set_epoch_processing_paused(deps, info, paused)
         }
         ExecuteMsg::Unjail { operator } => execute_unjail(deps, env, info, operator),
-        ExecuteMsg::Slash { addr, portion } => execute_slash(deps, env, info, addr, portion),
+        ExecuteMsg::Slash {
+            addr,
+            portion,
+            reason,
+        } => execute_slash(deps, env, info, addr, portion, reason),
         #[cfg(debug_assertions)]
         ExecuteMsg::SimulateValidatorSet { validators } => {
             execute_simulate_validators(deps, info, validators)
@@ -205,6 +295,7 @@ pub fn execute(
 #[allow(clippy::too_many_arguments)]
 fn execute_update_config<Q: CustomQuery>(
     deps: DepsMut<Q>,
+    env: Env,
     info: MessageInfo,
     min_points: Option<u64>,
     max_validators: Option<u32>,
@@ -216,10 +307,39 @@ fn execute_update_config<Q: CustomQuery>(
     distribution_contracts: Option<Vec<DistributionContract>>,
     verify_validators: Option<bool>,
     offline_jail_duration: Option<Duration>,
+    offline_jail_immunity: Option<Duration>,
+    min_active_validators: Option<u32>,
+    delay_new_operators: Option<bool>,
+    active_set_retention: Option<u64>,
+    min_self_bond: Option<Uint128>,
+    probation_scaling: Option<Decimal>,
+    probation_epochs: Option<u64>,
+    metadata_update_cooldown: Option<Duration>,
+    uptime_rewards: Option<bool>,
+    tie_break: Option<TieBreak>,
+    downtime_slash_ratio: Option<Decimal>,
+    governance_slash_ratio: Option<Decimal>,
+    max_power: Option<u64>,
+    max_churn: Option<u32>,
+    epoch_length: Option<u64>,
+    reward_vesting: Option<bool>,
+    verification_grace_blocks: Option<u64>,
+    max_epoch_reward: Option<Coin>,
 ) -> Result<Response, ContractError> {
     ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
 
-    CONFIG.update::<_, StdError>(deps.storage, |mut cfg| {
+    if let Some(probation_scaling) = probation_scaling {
+        validate_probation_scaling(probation_scaling)?;
+    }
+    if epoch_length == Some(0) {
+        return Err(ContractError::InvalidEpoch {});
+    }
+    // The cap has to be changed separately, so a single message can't raise the reward above it
+    if epoch_reward.is_some() && max_epoch_reward.is_some() {
+        return Err(ContractError::EpochRewardAndCapUpdate {});
+    }
+
+    CONFIG.update::<_, ContractError>(deps.storage, |mut cfg| {
         if let Some(min_points) = min_points {
             cfg.min_points = min_points;
         }
@@ -250,9 +370,79 @@ fn execute_update_config<Q: CustomQuery>(
         if let Some(offline_jail_duration) = offline_jail_duration {
             cfg.offline_jail_duration = offline_jail_duration;
         }
+        if let Some(offline_jail_immunity) = offline_jail_immunity {
+            cfg.offline_jail_immunity = offline_jail_immunity;
+        }
+        if let Some(min_active_validators) = min_active_validators {
+            cfg.min_active_validators = min_active_validators;
+        }
+        if let Some(delay_new_operators) = delay_new_operators {
+            cfg.delay_new_operators = delay_new_operators;
+        }
+        if let Some(active_set_retention) = active_set_retention {
+            cfg.active_set_retention = active_set_retention;
+        }
+        if let Some(min_self_bond) = min_self_bond {
+            cfg.min_self_bond = min_self_bond;
+        }
+        if let Some(probation_scaling) = probation_scaling {
+            cfg.probation_scaling = probation_scaling;
+        }
+        if let Some(probation_epochs) = probation_epochs {
+            cfg.probation_epochs = probation_epochs;
+        }
+        if let Some(metadata_update_cooldown) = metadata_update_cooldown {
+            cfg.metadata_update_cooldown = metadata_update_cooldown;
+        }
+        if let Some(uptime_rewards) = uptime_rewards {
+            cfg.uptime_rewards = uptime_rewards;
+        }
+        if let Some(tie_break) = tie_break {
+            cfg.tie_break = tie_break;
+        }
+        if let Some(downtime_slash_ratio) = downtime_slash_ratio {
+            cfg.downtime_slash_ratio = downtime_slash_ratio;
+        }
+        if let Some(governance_slash_ratio) = governance_slash_ratio {
+            // Zero unsets the ratio, so `portion` passed with the message is used again
+            cfg.governance_slash_ratio = Some(governance_slash_ratio).filter(|r| !r.is_zero());
+        }
+        if let Some(max_power) = max_power {
+            // Zero removes the cap
+            cfg.max_power = Some(max_power).filter(|max| *max > 0);
+        }
+        if let Some(max_churn) = max_churn {
+            // Zero removes the limit
+            cfg.max_churn = Some(max_churn).filter(|max| *max > 0);
+        }
+        if let Some(reward_vesting) = reward_vesting {
+            cfg.reward_vesting = reward_vesting;
+        }
+        if let Some(verification_grace_blocks) = verification_grace_blocks {
+            cfg.verification_grace_blocks = verification_grace_blocks;
+        }
+        if let Some(max_epoch_reward) = max_epoch_reward {
+            // Zero amount removes the cap
+            cfg.max_epoch_reward = Some(max_epoch_reward).filter(|max| !max.amount.is_zero());
+        }
+        // Checked against the updated config, so lowering the cap below current reward fails too
+        validate_epoch_reward(&cfg.epoch_reward, cfg.max_epoch_reward.as_ref())?;
         Ok(cfg)
     })?;
 
+    if let Some(epoch_length) = epoch_length {
+        EPOCH.update::<_, StdError>(deps.storage, |mut epoch| {
+            // Epochs up to the current one are kept as they are, the new length applies
+            // since now
+            epoch.epoch_offset = EpochOffset {
+                epoch: current_epoch(&env.block, &epoch),
+                time: env.block.time.seconds(),
+            };
+            epoch.epoch_length = epoch_length;
+            Ok(epoch)
+        })?;
+    }
+
     let res = Response::new()
         .add_attribute("action", "update_config")
         .add_attribute("operator", &info.sender);
@@ -262,48 +452,100 @@ fn execute_update_config<Q: CustomQuery>(
 
 fn execute_register_validator_key<Q: CustomQuery>(
     deps: DepsMut<Q>,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     pubkey: Pubkey,
     metadata: ValidatorMetadata,
 ) -> Result<Response, ContractError> {
     metadata.validate()?;
 
-    let pubkey: Ed25519Pubkey = pubkey.try_into()?;
+    let pubkey: OperatorPubkey = pubkey.try_into()?;
     let moniker = metadata.moniker.clone();
 
     let operator = OperatorInfo {
         pubkey,
         metadata,
         active_validator: false,
+        probation_start_epoch: None,
+        metadata_updated_at: None,
     };
     match operators().may_load(deps.storage, &info.sender)? {
         Some(_) => return Err(ContractError::OperatorRegistered {}),
         None => operators().save(deps.storage, &info.sender, &operator)?,
     };
 
+    let epoch = EPOCH.load(deps.storage)?;
+    OPERATOR_REGISTRATION_EPOCH.save(
+        deps.storage,
+        &info.sender,
+        &current_epoch(&env.block, &epoch),
+    )?;
+
     let res = Response::new()
         .add_attribute("action", "register_validator_key")
         .add_attribute("operator", &info.sender)
-        .add_attribute("pubkey_type", "ed25519")
+        .add_attribute("pubkey_type", operator.pubkey.key_type())
         .add_attribute("pubkey_value", operator.pubkey.to_base64())
         .add_attribute("moniker", moniker);
 
     Ok(res)
 }
 
+fn execute_deregister<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let operator = operators()
+        .may_load(deps.storage, &info.sender)?
+        .ok_or_else(|| ContractError::Unauthorized("No operator info found".to_owned()))?;
+
+    // Operators jailed forever have to stay registered, so their key cannot be reused
+    if is_tombstoned(deps.storage, &info.sender)? {
+        return Err(ContractError::Tombstoned(info.sender.to_string()));
+    }
+
+    // The key stays reserved while the operator is in the active set, so the same key is never
+    // updated twice in a single validator diff
+    if operator.active_validator {
+        return Err(ContractError::ActiveValidator(info.sender.to_string()));
+    }
+
+    // Jailing is kept, so it still applies if the operator registers again
+    operators().remove(deps.storage, &info.sender)?;
+    OPERATOR_REGISTRATION_EPOCH.remove(deps.storage, &info.sender);
+    REWARD_ADDRESS.remove(deps.storage, &info.sender);
+
+    let res = Response::new()
+        .add_attribute("action", "deregister")
+        .add_attribute("operator", &info.sender)
+        .add_attribute("pubkey_type", operator.pubkey.key_type())
+        .add_attribute("pubkey_value", operator.pubkey.to_base64());
+
+    Ok(res)
+}
+
 fn execute_update_metadata<Q: CustomQuery>(
     deps: DepsMut<Q>,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     metadata: ValidatorMetadata,
 ) -> Result<Response, ContractError> {
     metadata.validate()?;
     let moniker = metadata.moniker.clone();
 
+    let cooldown = CONFIG.load(deps.storage)?.metadata_update_cooldown;
+    let is_admin = ADMIN.is_admin(deps.as_ref(), &info.sender)?;
+
     operators().update(deps.storage, &info.sender, |info| match info {
         Some(mut old) => {
+            if let Some(updated_at) = old.metadata_updated_at {
+                let retry_at = updated_at.plus_seconds(cooldown.seconds());
+                if !is_admin && env.block.time < retry_at {
+                    return Err(ContractError::MetadataUpdateTooSoon { retry_at });
+                }
+            }
             old.metadata = metadata;
+            old.metadata_updated_at = Some(env.block.time);
             Ok(old)
         }
         None => Err(ContractError::Unauthorized(
@@ -318,32 +560,234 @@ fn execute_update_metadata<Q: CustomQuery>(
     Ok(res)
 }
 
+fn execute_add_distribution_contract<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    contract: String,
+    ratio: Decimal,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let mut cfg = CONFIG.load(deps.storage)?;
+    let addr = deps.api.addr_validate(&contract)?;
+    if cfg
+        .distribution_contracts
+        .iter()
+        .any(|c| c.contract == addr)
+    {
+        return Err(ContractError::DistributionContractExists(contract));
+    }
+
+    let mut contracts: Vec<_> = cfg
+        .distribution_contracts
+        .into_iter()
+        .map(|c| UnvalidatedDistributionContract {
+            contract: c.contract.into(),
+            ratio: c.ratio,
+            schema: c.schema,
+        })
+        .collect();
+    contracts.push(UnvalidatedDistributionContract {
+        contract: contract.clone(),
+        ratio,
+        schema: DistributionSchema::default(),
+    });
+    cfg.distribution_contracts =
+        UnvalidatedDistributionContracts { inner: contracts }.validate(deps.api)?;
+    CONFIG.save(deps.storage, &cfg)?;
+
+    let res = Response::new()
+        .add_attribute("action", "add_distribution_contract")
+        .add_attribute("contract", contract)
+        .add_attribute("ratio", ratio.to_string());
+    Ok(res)
+}
+
+fn execute_remove_distribution_contract<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    contract: String,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let mut cfg = CONFIG.load(deps.storage)?;
+    let addr = deps.api.addr_validate(&contract)?;
+    let idx = cfg
+        .distribution_contracts
+        .iter()
+        .position(|c| c.contract == addr)
+        .ok_or(ContractError::NoDistributionContract {})?;
+    cfg.distribution_contracts.remove(idx);
+    CONFIG.save(deps.storage, &cfg)?;
+
+    let res = Response::new()
+        .add_attribute("action", "remove_distribution_contract")
+        .add_attribute("contract", contract);
+    Ok(res)
+}
+
+fn execute_set_reward_address<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    let address = deps.api.addr_validate(&address)?;
+
+    if !operators().has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized(
+            "No operator info found".to_owned(),
+        ));
+    }
+
+    if address == info.sender {
+        REWARD_ADDRESS.remove(deps.storage, &info.sender);
+    } else {
+        REWARD_ADDRESS.save(deps.storage, &info.sender, &address)?;
+    }
+
+    let res = Response::new()
+        .add_attribute("action", "set_reward_address")
+        .add_attribute("operator", &info.sender)
+        .add_attribute("address", address);
+    Ok(res)
+}
+
 fn execute_jail<Q: CustomQuery>(
     deps: DepsMut<Q>,
     env: Env,
     info: MessageInfo,
     operator: String,
     duration: JailingDuration,
+    immediate: bool,
 ) -> Result<Response, ContractError> {
     ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
 
+    let addr = deps.api.addr_validate(&operator)?;
+    // Tombstoned validators are jailed forever, which cannot be shortened
+    if TOMBSTONED.has(deps.storage, &addr) {
+        return Err(ContractError::Tombstoned(operator));
+    }
+
     let expiration = JailingPeriod::from_duration(duration, &env.block);
 
-    JAIL.save(
-        deps.storage,
-        &deps.api.addr_validate(&operator)?,
-        &expiration,
-    )?;
+    jail(deps.storage, &addr, &expiration)?;
+
+    if immediate {
+        let mut jails = IMMEDIATE_JAILS.may_load(deps.storage)?.unwrap_or_default();
+        jails.push(addr);
+        IMMEDIATE_JAILS.save(deps.storage, &jails)?;
+    }
 
     let until_attr = match expiration.end {
         JailingEnd::Until(expires) => Timestamp::from(expires).to_string(),
+        JailingEnd::UntilHeight(height) => format!("height {}", height),
         JailingEnd::Forever {} => "forever".to_owned(),
     };
 
     let res = Response::new()
         .add_attribute("action", "jail")
         .add_attribute("operator", &operator)
-        .add_attribute("until", &until_attr);
+        .add_attribute("until", &until_attr)
+        .add_attribute("immediate", immediate.to_string());
+
+    Ok(res)
+}
+
+fn execute_tombstone<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let addr = deps.api.addr_validate(&operator)?;
+    tombstone(deps.storage, &env, &addr)?;
+
+    let res = Response::new()
+        .add_attribute("action", "tombstone")
+        .add_attribute("operator", &operator);
+
+    Ok(res)
+}
+
+/// Jails the operator forever and marks it as tombstoned, so it is never again considered for
+/// the validator set.
+fn tombstone(storage: &mut dyn Storage, env: &Env, operator: &Addr) -> StdResult<()> {
+    jail(
+        storage,
+        operator,
+        &JailingPeriod::from_duration(JailingDuration::Forever {}, &env.block),
+    )?;
+    if !TOMBSTONED.has(storage, operator) {
+        TOMBSTONED.save(storage, operator, &env.block.height)?;
+    }
+    Ok(())
+}
+
+/// Latest record in the operator jailing history.
+fn last_jailing(storage: &dyn Storage, operator: &Addr) -> StdResult<Option<JailingRecord>> {
+    JAILING_HISTORY
+        .prefix(operator)
+        .range(storage, None, None, Order::Descending)
+        .next()
+        .transpose()
+        .map(|record| record.map(|(_, record)| record))
+}
+
+/// Jails the operator, recording the period in its jailing history.
+fn jail(storage: &mut dyn Storage, operator: &Addr, period: &JailingPeriod) -> StdResult<()> {
+    JAIL.save(storage, operator, period)?;
+    let id = last_jailing(storage, operator)?.map_or(0, |record| record.id + 1);
+    let record = JailingRecord {
+        id,
+        period: period.clone(),
+        unjailed_at: None,
+    };
+    JAILING_HISTORY.save(storage, (operator, id), &record)
+}
+
+/// Unjails the operator, recording the time in its jailing history.
+fn unjail(storage: &mut dyn Storage, operator: &Addr, time: Timestamp) -> StdResult<()> {
+    JAIL.remove(storage, operator);
+    UNJAILED_AT.save(storage, operator, &time)?;
+    if let Some(mut record) = last_jailing(storage, operator)? {
+        record.unjailed_at = Some(time);
+        JAILING_HISTORY.save(storage, (operator, record.id), &record)?;
+    }
+    Ok(())
+}
+
+/// Marks the operator as not active anymore. Operators who deregistered are skipped.
+fn deactivate_operator(storage: &mut dyn Storage, operator: &Addr) -> StdResult<()> {
+    if let Some(mut info) = operators().may_load(storage, operator)? {
+        info.active_validator = false;
+        operators().save(storage, operator, &info)?;
+    }
+    Ok(())
+}
+
+/// Tombstoned operators are either marked explicitly, or jailed forever.
+fn is_tombstoned(storage: &dyn Storage, operator: &Addr) -> StdResult<bool> {
+    if TOMBSTONED.has(storage, operator) {
+        return Ok(true);
+    }
+    let jail = JAIL.may_load(storage, operator)?;
+    Ok(matches!(jail, Some(jail) if jail.is_forever()))
+}
+
+fn execute_set_epoch_processing_paused<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    EPOCH_PROCESSING_PAUSED.save(deps.storage, &paused)?;
+
+    let res = Response::new()
+        .add_attribute("action", "set_epoch_processing_paused")
+        .add_attribute("paused", paused.to_string());
 
     Ok(res)
 }
@@ -371,7 +815,17 @@ fn execute_unjail<Q: CustomQuery>(
         }
 
         if expiration.is_expired(&env.block) || is_admin {
-            JAIL.remove(deps.storage, operator);
+            unjail(deps.storage, operator, env.block.time)?;
+
+            // Validator rejoins at scaled down power from the next epoch
+            let cfg = CONFIG.load(deps.storage)?;
+            if cfg.probation_epochs > 0 {
+                if let Some(mut op) = operators().may_load(deps.storage, operator)? {
+                    let epoch = EPOCH.load(deps.storage)?;
+                    op.probation_start_epoch = Some(current_epoch(&env.block, &epoch) + 1);
+                    operators().save(deps.storage, operator, &op)?;
+                }
+            }
         } else {
             return Err(ContractError::JailDidNotExpire {});
         }
@@ -389,6 +843,7 @@ fn store_slashing_event<Q: CustomQuery>(
     env: &Env,
     addr: Addr,
     portion: Decimal,
+    cause: SlashingCause,
 ) -> Result<(), ContractError> {
     let mut slashing = VALIDATOR_SLASHING
         .may_load(deps.storage, &addr)?
@@ -396,6 +851,8 @@ fn store_slashing_event<Q: CustomQuery>(
     slashing.push(ValidatorSlashing {
         slash_height: env.block.height,
         portion,
+        cause: Some(cause),
+        amount: None,
     });
     VALIDATOR_SLASHING.save(deps.storage, &addr, &slashing)?;
     Ok(())
@@ -407,14 +864,20 @@ fn execute_slash<Q: CustomQuery>(
     info: MessageInfo,
     operator: String,
     portion: Decimal,
+    reason: SlashingCause,
 ) -> Result<Response, ContractError> {
     ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
 
+    let config = CONFIG.load(deps.storage)?;
+    let portion = match reason {
+        SlashingCause::DoubleSign => config.double_sign_slash_ratio,
+        SlashingCause::Downtime => config.downtime_slash_ratio,
+        SlashingCause::Governance => config.governance_slash_ratio.unwrap_or(portion),
+    };
+
     // Store slashing event
     let addr = Addr::unchecked(&operator);
-    store_slashing_event(deps.branch(), &env, addr, portion)?;
-
-    let config = CONFIG.load(deps.storage)?;
+    store_slashing_event(deps.branch(), &env, addr, portion, reason)?;
 
     let slash_msg = SlashMsg::Slash {
         addr: operator,
@@ -428,7 +891,7 @@ fn execute_slash<Q: CustomQuery>(
         funds: vec![],
     };
 
-    let resp = Response::new().add_submessage(SubMsg::new(slash_msg));
+    let resp = Response::new().add_submessage(SubMsg::reply_on_success(slash_msg, SLASH_REPLY_ID));
 
     Ok(resp)
 }
@@ -462,6 +925,7 @@ pub fn query(deps: Deps<TgradeQuery>, env: Env, msg: QueryMsg) -> Result<Binary,
     match msg {
         Configuration {} => Ok(to_binary(&CONFIG.load(deps.storage)?)?),
         Epoch {} => Ok(to_binary(&query_epoch(deps, env)?)?),
+        UpcomingEpochs { count } => Ok(to_binary(&query_upcoming_epochs(deps, env, count)?)?),
         Validator { operator } => Ok(to_binary(&query_validator_key(deps, env, operator)?)?),
         ListValidators { start_after, limit } => Ok(to_binary(&list_validator_keys(
             deps,
@@ -480,10 +944,55 @@ pub fn query(deps: Deps<TgradeQuery>, env: Env, msg: QueryMsg) -> Result<Binary,
             start_after,
             limit,
         )?)?),
+        ListValidatorsByStatus {
+            status,
+            start_after,
+            limit,
+        } => Ok(to_binary(&list_validators_by_status(
+            deps,
+            env,
+            status,
+            start_after,
+            limit,
+        )?)?),
         SimulateActiveValidators {} => Ok(to_binary(&simulate_active_validators(deps, env)?)?),
+        SimulateActiveValidatorsWith { overrides } => Ok(to_binary(
+            &simulate_active_validators_with(deps, env, overrides)?,
+        )?),
         ListValidatorSlashing { operator } => {
             Ok(to_binary(&list_validator_slashing(deps, env, operator)?)?)
         }
+        JailingHistory {
+            operator,
+            start_after,
+            limit,
+        } => Ok(to_binary(&query_jailing_history(
+            deps,
+            operator,
+            start_after,
+            limit,
+        )?)?),
+        ValidatorFullProfile { operator } => Ok(to_binary(&query_validator_full_profile(
+            deps, env, operator,
+        )?)?),
+        PowerChanges { since_height } => Ok(to_binary(&query_power_changes(deps, since_height)?)?),
+        OperatorByConsensusKey { pubkey } => {
+            Ok(to_binary(&query_operator_by_consensus_key(deps, pubkey)?)?)
+        }
+        DoubleSignInfo {} => Ok(to_binary(&query_double_sign_info(deps)?)?),
+        ActiveValidatorsAtEpoch { epoch } => {
+            Ok(to_binary(&query_active_validators_at_epoch(deps, epoch)?)?)
+        }
+        ValidatePubkey { pubkey } => Ok(to_binary(&query_validate_pubkey(pubkey))?),
+        EpochsSinceSigned { operator } => {
+            Ok(to_binary(&query_epochs_since_signed(deps, operator)?)?)
+        }
+        SimulateRewards {} => Ok(to_binary(&simulate_rewards(deps, env)?)?),
+        RewardAddress { operator } => Ok(to_binary(&query_reward_address(deps, operator)?)?),
+        DistributionContractStats {} => Ok(to_binary(&query_distribution_contract_stats(deps)?)?),
+        IsTombstoned { operator } => Ok(to_binary(&query_is_tombstoned(deps, operator)?)?),
+        ValidatorsAtRisk {} => Ok(to_binary(&query_validators_at_risk(deps, env)?)?),
+        Eligibility { operator } => Ok(to_binary(&query_eligibility(deps, env, operator)?)?),
         Admin {} => Ok(to_binary(&ADMIN.query_admin(deps)?)?),
     }
 }
@@ -491,7 +1000,7 @@ pub fn query(deps: Deps<TgradeQuery>, env: Env, msg: QueryMsg) -> Result<Binary,
 fn query_epoch<Q: CustomQuery>(deps: Deps<Q>, env: Env) -> Result<EpochResponse, ContractError> {
     let epoch = EPOCH.load(deps.storage)?;
     let mut next_update_time =
-        Timestamp::from_seconds((epoch.current_epoch + 1) * epoch.epoch_length);
+        Timestamp::from_seconds(epoch_start_time(epoch.current_epoch + 1, &epoch));
     if env.block.time > next_update_time {
         next_update_time = env.block.time;
     }
@@ -502,10 +1011,31 @@ fn query_epoch<Q: CustomQuery>(deps: Deps<Q>, env: Env) -> Result<EpochResponse,
         last_update_time: epoch.last_update_time,
         last_update_height: epoch.last_update_height,
         next_update_time: next_update_time.nanos() / 1_000_000_000,
+        epoch_processing_paused: EPOCH_PROCESSING_PAUSED
+            .may_load(deps.storage)?
+            .unwrap_or_default(),
     };
     Ok(resp)
 }
 
+fn query_upcoming_epochs<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: Env,
+    count: u32,
+) -> Result<UpcomingEpochsResponse, ContractError> {
+    let info = EPOCH.load(deps.storage)?;
+    let next_epoch = current_epoch(&env.block, &info) + 1;
+
+    let epochs = (next_epoch..)
+        .take(count.min(MAX_LIMIT) as usize)
+        .map(|epoch| UpcomingEpoch {
+            epoch,
+            update_time: epoch_start_time(epoch, &info),
+        })
+        .collect();
+    Ok(UpcomingEpochsResponse { epochs })
+}
+
 fn query_validator_key<Q: CustomQuery>(
     deps: Deps<Q>,
     env: Env,
@@ -585,9 +1115,11 @@ fn list_active_validators<Q: CustomQuery>(
             i += 1;
         }
     }
+    let total_power = validators.iter().map(|v| v.power).sum();
     let validators = &validators[i..min(i + limit, validators.len())];
     Ok(ListActiveValidatorsResponse {
         validators: Vec::from(validators),
+        total_power,
     })
 }
 
@@ -606,7 +1138,10 @@ fn list_jailed_validators<Q: CustomQuery>(
         .range(deps.storage, start, None, Order::Ascending)
         .map(|jail| {
             let (addr, jailing_period) = jail?;
-            if !(cfg.auto_unjail && jailing_period.is_expired(&env.block)) {
+            // Operators who deregistered while jailed have no info to report
+            if !(cfg.auto_unjail && jailing_period.is_expired(&env.block))
+                && operators().has(deps.storage, &addr)
+            {
                 Ok(Some((addr, jailing_period)))
             } else {
                 Ok(None)
@@ -631,12 +1166,60 @@ fn list_jailed_validators<Q: CustomQuery>(
     Ok(ListValidatorResponse { validators })
 }
 
+fn list_validators_by_status<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: Env,
+    status: ValidatorStatus,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<ListValidatorResponse, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = maybe_addr(deps.api, start_after)?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let validators = operators()
+        .range(deps.storage, start, None, Order::Ascending)
+        .map(|r| {
+            let (operator, info) = r?;
+            let jailed_until = JAIL
+                .may_load(deps.storage, &operator)?
+                .filter(|expires| !(cfg.auto_unjail && expires.is_expired(&env.block)));
+
+            let matches = match (status, &jailed_until) {
+                (ValidatorStatus::Active, _) => info.active_validator,
+                (ValidatorStatus::Jailed, Some(jailing)) => !jailing.is_forever(),
+                (ValidatorStatus::Tombstoned, Some(jailing)) => jailing.is_forever(),
+                (_, None) => false,
+            };
+
+            Ok(matches.then(|| OperatorResponse::from_info(info, operator.into(), jailed_until)))
+        })
+        .filter_map(Result::transpose)
+        .take(limit)
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    Ok(ListValidatorResponse { validators })
+}
+
 fn simulate_active_validators<Q: CustomQuery>(
     deps: Deps<Q>,
     env: Env,
 ) -> Result<ListActiveValidatorsResponse, ContractError> {
-    let (validators, _) = calculate_validators(deps, &env)?;
-    Ok(ListActiveValidatorsResponse { validators })
+    let selection = calculate_validators(deps, &env, &[])?;
+    Ok(ListActiveValidatorsResponse::new(selection.validators))
+}
+
+fn simulate_active_validators_with<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: Env,
+    overrides: Vec<Member>,
+) -> Result<ListActiveValidatorsResponse, ContractError> {
+    for member in &overrides {
+        deps.api.addr_validate(&member.addr)?;
+    }
+    let selection = calculate_validators(deps, &env, &overrides)?;
+    Ok(ListActiveValidatorsResponse::new(selection.validators))
 }
 
 fn list_validator_slashing<Q: CustomQuery>(
@@ -652,49 +1235,442 @@ fn list_validator_slashing<Q: CustomQuery>(
     let slashing = VALIDATOR_SLASHING
         .may_load(deps.storage, &addr)?
         .unwrap_or_default();
-    let (jailed_until, tombstoned) = match JAIL.may_load(deps.storage, &addr)?.map(|j| j.end) {
-        Some(JailingEnd::Forever {}) => (None, true),
-        Some(JailingEnd::Until(u)) => (Some(u), false),
-        None => (None, false),
-    };
+    let (jailed_until, jailed_until_height) =
+        match JAIL.may_load(deps.storage, &addr)?.map(|j| j.end) {
+            Some(JailingEnd::Until(u)) => (Some(u), None),
+            Some(JailingEnd::UntilHeight(height)) => (None, Some(height)),
+            _ => (None, None),
+        };
+    let tombstoned = is_tombstoned(deps.storage, &addr)?;
     Ok(ListValidatorSlashingResponse {
         addr: operator,
         start_height,
         slashing,
         tombstoned,
         jailed_until,
+        jailed_until_height,
     })
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn sudo(
-    deps: DepsMut<TgradeQuery>,
-    env: Env,
-    msg: TgradeSudoMsg<ValsetState>,
-) -> Result<Response, ContractError> {
-    match msg {
-        TgradeSudoMsg::PrivilegeChange(change) => Ok(privilege_change(deps, change)),
-        TgradeSudoMsg::EndWithValidatorUpdate {} => end_block(deps, env),
-        TgradeSudoMsg::BeginBlock { evidence } => begin_block(deps, env, evidence),
-        TgradeSudoMsg::Export {} => export(deps.as_ref()),
-        TgradeSudoMsg::Import(state) => import(deps, state),
-        _ => Err(ContractError::UnsupportedSudoType {}),
-    }
-}
+fn query_jailing_history<Q: CustomQuery>(
+    deps: Deps<Q>,
+    operator: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<JailingHistoryResponse, ContractError> {
+    let addr = deps.api.addr_validate(&operator)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
 
-fn privilege_change<Q: CustomQuery>(_deps: DepsMut<Q>, change: PrivilegeChangeMsg) -> Response {
-    match change {
-        PrivilegeChangeMsg::Promoted {} => {
-            let msgs = request_privileges(&[
-                Privilege::ValidatorSetUpdater,
-                Privilege::TokenMinter,
-                Privilege::BeginBlocker,
-                Privilege::StateExporterImporter,
-            ]);
-            Response::new().add_submessages(msgs)
-        }
-        PrivilegeChangeMsg::Demoted {} => Response::new(),
-    }
+    let periods = JAILING_HISTORY
+        .prefix(&addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .map(|record| record.map(|(_, record)| record))
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    Ok(JailingHistoryResponse { periods })
+}
+
+fn query_validator_full_profile<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: Env,
+    operator: String,
+) -> Result<ValidatorFullProfileResponse, ContractError> {
+    let addr = deps.api.addr_validate(&operator)?;
+    let validator = query_validator_key(deps, env, operator)?
+        .validator
+        .ok_or_else(|| StdError::not_found("OperatorInfo"))?;
+
+    let rank = VALIDATORS
+        .load(deps.storage)?
+        .iter()
+        .position(|v| v.operator == addr)
+        .map(|rank| rank as u32);
+    let start_height = VALIDATOR_START_HEIGHT.may_load(deps.storage, &addr)?;
+
+    let mut slashing = VALIDATOR_SLASHING
+        .may_load(deps.storage, &addr)?
+        .unwrap_or_default();
+    // Keep only the most recent events
+    let skip = slashing.len().saturating_sub(MAX_LIMIT as usize);
+    slashing.drain(..skip);
+
+    let tombstoned = is_tombstoned(deps.storage, &addr)?;
+
+    let last_signed_height = match OperatorPubkey::try_from(&validator.pubkey) {
+        Ok(pubkey) => BLOCK_SIGNERS.may_load(deps.storage, &pubkey.to_address())?,
+        Err(_) => None,
+    };
+
+    Ok(ValidatorFullProfileResponse {
+        validator,
+        rank,
+        start_height,
+        slashing,
+        tombstoned,
+        last_signed_height,
+    })
+}
+
+fn query_is_tombstoned<Q: CustomQuery>(
+    deps: Deps<Q>,
+    operator: String,
+) -> Result<IsTombstonedResponse, ContractError> {
+    let addr = deps.api.addr_validate(&operator)?;
+    let tombstoned = is_tombstoned(deps.storage, &addr)?;
+    Ok(IsTombstonedResponse { tombstoned })
+}
+
+fn query_eligibility<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: Env,
+    operator: String,
+) -> Result<EligibilityResponse, ContractError> {
+    let addr = deps.api.addr_validate(&operator)?;
+    let eligibility = eligibility(deps, &env, &addr)?;
+    Ok(EligibilityResponse { eligibility })
+}
+
+/// Diagnoses selection of the operator, applying the same filters as `select_validators`
+fn eligibility<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: &Env,
+    operator: &Addr,
+) -> Result<Eligibility, ContractError> {
+    if is_tombstoned(deps.storage, operator)? {
+        return Ok(Eligibility::Tombstoned);
+    }
+    if !operators().has(deps.storage, operator) {
+        return Ok(Eligibility::NotRegistered);
+    }
+
+    let cfg = CONFIG.load(deps.storage)?;
+    match JAIL.may_load(deps.storage, operator)? {
+        Some(jail) if !(cfg.auto_unjail && jail.is_expired(&env.block)) => {
+            return Ok(Eligibility::Jailed)
+        }
+        _ => (),
+    }
+
+    let selection = calculate_validators(deps, env, &[])?;
+    if let Some(v) = selection
+        .validators
+        .iter()
+        .find(|v| &v.operator == operator)
+    {
+        return Ok(Eligibility::Eligible {
+            projected_power: v.power,
+        });
+    }
+
+    if cfg.delay_new_operators {
+        let epoch = current_epoch(&env.block, &EPOCH.load(deps.storage)?);
+        let registered = OPERATOR_REGISTRATION_EPOCH.may_load(deps.storage, operator)?;
+        if matches!(registered, Some(registered) if registered + 2 > epoch) {
+            return Ok(Eligibility::RegistrationPending);
+        }
+    }
+    if !cfg.min_self_bond.is_zero() && self_bond(deps, &cfg, operator) < cfg.min_self_bond {
+        return Ok(Eligibility::BelowMinSelfBond);
+    }
+    let points = cfg
+        .membership
+        .is_member(&deps.querier, operator)?
+        .unwrap_or_default();
+    if points < max(cfg.min_points, 1) {
+        return Ok(Eligibility::BelowMinPoints);
+    }
+
+    Ok(Eligibility::OutsideTopN)
+}
+
+fn query_epochs_since_signed<Q: CustomQuery>(
+    deps: Deps<Q>,
+    operator: String,
+) -> Result<EpochsSinceSignedResponse, ContractError> {
+    let addr = deps.api.addr_validate(&operator)?;
+    let info = operators().load(deps.storage, &addr)?;
+
+    let signed_epoch = BOUNDARY_SIGNERS.may_load(deps.storage, &info.pubkey.to_address())?;
+    let current_epoch = EPOCH.load(deps.storage)?.current_epoch;
+
+    Ok(EpochsSinceSignedResponse {
+        epochs: signed_epoch.map(|epoch| current_epoch.saturating_sub(epoch)),
+    })
+}
+
+fn query_validators_at_risk<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: Env,
+) -> Result<ValidatorsAtRiskResponse, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    if !cfg.verify_validators {
+        return Ok(ValidatorsAtRiskResponse { validators: vec![] });
+    }
+
+    let mut validators = vec![];
+    for v in VALIDATORS.load(deps.storage)? {
+        if !is_offline(deps.storage, &cfg, &env.block, &v)? {
+            continue;
+        }
+        let last_signed_height = match OperatorPubkey::try_from(&v.validator_pubkey) {
+            Ok(pubkey) => BLOCK_SIGNERS.may_load(deps.storage, &pubkey.to_address())?,
+            Err(_) => None,
+        };
+        validators.push(ValidatorAtRisk {
+            operator: v.operator,
+            last_signed_height,
+        });
+    }
+
+    Ok(ValidatorsAtRiskResponse { validators })
+}
+
+fn query_reward_address<Q: CustomQuery>(
+    deps: Deps<Q>,
+    operator: String,
+) -> Result<RewardAddressResponse, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+    let address = REWARD_ADDRESS
+        .may_load(deps.storage, &operator)?
+        .unwrap_or(operator);
+    Ok(RewardAddressResponse { address })
+}
+
+fn query_distribution_contract_stats<Q: CustomQuery>(
+    deps: Deps<Q>,
+) -> Result<DistributionContractStatsResponse, ContractError> {
+    let contracts = CONFIG
+        .load(deps.storage)?
+        .distribution_contracts
+        .into_iter()
+        .map(|c| {
+            let distributed = DISTRIBUTED_REWARDS
+                .may_load(deps.storage, &c.contract)?
+                .unwrap_or_default();
+            Ok(DistributionContractStats {
+                contract: c.contract,
+                ratio: c.ratio,
+                distributed,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(DistributionContractStatsResponse { contracts })
+}
+
+fn simulate_rewards<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: Env,
+) -> Result<SimulateRewardsResponse, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    let epoch = EPOCH.load(deps.storage)?;
+    let pay_epochs = max(
+        current_epoch(&env.block, &epoch).saturating_sub(epoch.current_epoch),
+        1,
+    );
+
+    let validators = VALIDATORS.load(deps.storage)?;
+    let uptimes = if cfg.verify_validators && cfg.uptime_rewards {
+        Some(validators_uptime(deps, &validators)?)
+    } else {
+        None
+    };
+    let split = calculate_rewards(
+        deps,
+        &env,
+        pay_epochs,
+        &cfg,
+        &validators,
+        uptimes.as_deref(),
+    )?;
+
+    let distribution_contracts = cfg
+        .distribution_contracts
+        .iter()
+        .map(|dc| DistributionContractReward {
+            contract: dc.contract.clone(),
+            reward: split
+                .distributions
+                .iter()
+                .find(|(contract, _)| *contract == dc.contract)
+                .map(|(_, reward)| *reward)
+                .unwrap_or_default(),
+        })
+        .collect();
+    let validators = split
+        .validators_shares
+        .iter()
+        .map(|(operator, reward)| ValidatorReward {
+            operator: operator.clone(),
+            commission: split
+                .commissions
+                .iter()
+                .find(|(addr, _)| addr == operator)
+                .map(|(_, commission)| *commission)
+                .unwrap_or_default(),
+            reward: *reward,
+        })
+        .collect();
+
+    Ok(SimulateRewardsResponse {
+        denom: split.denom,
+        total: split.total,
+        minted: split.minted,
+        distribution_contracts,
+        validators_reward: split.validators_reward,
+        validators,
+    })
+}
+
+fn query_operator_by_consensus_key<Q: CustomQuery>(
+    deps: Deps<Q>,
+    pubkey: Pubkey,
+) -> Result<OperatorByConsensusKeyResponse, ContractError> {
+    let pubkey: OperatorPubkey = pubkey.try_into()?;
+    // The pubkey index is maintained by `operators()` on every save, so it is always in sync
+    let operator = operators()
+        .idx
+        .pubkey
+        .item(deps.storage, pubkey.to_vec())?
+        .map(|(operator, _)| String::from_utf8(operator).map(Addr::unchecked))
+        .transpose()
+        .map_err(StdError::from)?;
+
+    Ok(OperatorByConsensusKeyResponse { operator })
+}
+
+fn query_validate_pubkey(pubkey: Pubkey) -> ValidatePubkeyResponse {
+    match OperatorPubkey::try_from(pubkey) {
+        Ok(pubkey) => ValidatePubkeyResponse {
+            valid: true,
+            normalized: Some(pubkey.into()),
+            error: None,
+        },
+        Err(err) => ValidatePubkeyResponse {
+            valid: false,
+            normalized: None,
+            error: Some(err.to_string()),
+        },
+    }
+}
+
+fn query_double_sign_info<Q: CustomQuery>(
+    deps: Deps<Q>,
+) -> Result<DoubleSignInfoResponse, ContractError> {
+    let slash_ratio = CONFIG.load(deps.storage)?.double_sign_slash_ratio;
+
+    let mut slashed = vec![];
+    for item in VALIDATOR_SLASHING.range(deps.storage, None, None, Order::Ascending) {
+        let (operator, slashing) = item?;
+        slashed.extend(
+            slashing
+                .into_iter()
+                .filter(|slashing| slashing.cause == Some(SlashingCause::DoubleSign))
+                .map(|slashing| DoubleSignSlashing {
+                    operator: operator.clone(),
+                    slash_height: slashing.slash_height,
+                    portion: slashing.portion,
+                    amount: slashing.amount,
+                }),
+        );
+    }
+    // Keep only the most recent events
+    slashed.sort_by_key(|slashing| Reverse(slashing.slash_height));
+    slashed.truncate(MAX_LIMIT as usize);
+
+    Ok(DoubleSignInfoResponse {
+        slash_ratio,
+        slashed,
+    })
+}
+
+fn query_active_validators_at_epoch<Q: CustomQuery>(
+    deps: Deps<Q>,
+    epoch: u64,
+) -> Result<ListActiveValidatorsResponse, ContractError> {
+    let retention = CONFIG.load(deps.storage)?.active_set_retention;
+    let current_epoch = EPOCH.load(deps.storage)?.current_epoch;
+    if retention == 0 || epoch + retention <= current_epoch {
+        return Err(ContractError::EpochNotRetained(epoch));
+    }
+
+    // The latest set recorded in the epoch, including removals by immediate jailing
+    let validators = VALIDATORS_HISTORY
+        .prefix(epoch)
+        .range(deps.storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map(|(_, validators)| validators)
+        .ok_or(ContractError::EpochNotRetained(epoch))?;
+
+    Ok(ListActiveValidatorsResponse::new(validators))
+}
+
+fn query_power_changes<Q: CustomQuery>(
+    deps: Deps<Q>,
+    since_height: u64,
+) -> Result<PowerChangesResponse, ContractError> {
+    let old_validators = VALIDATORS_HISTORY
+        .range(deps.storage, None, None, Order::Descending)
+        .find(|item| !matches!(item, Ok(((_, height), _)) if *height > since_height))
+        .transpose()?
+        .map(|(_, validators)| validators)
+        .ok_or(ContractError::HeightNotRetained(since_height))?;
+    let validators = VALIDATORS.load(deps.storage)?;
+
+    // (old, new) power per operator
+    let mut powers: BTreeMap<Addr, (u64, u64)> = old_validators
+        .into_iter()
+        .map(|v| (v.operator, (v.power, 0)))
+        .collect();
+    for v in validators {
+        powers.entry(v.operator).or_default().1 = v.power;
+    }
+
+    let changes = powers
+        .into_iter()
+        .filter(|(_, (old_power, new_power))| old_power != new_power)
+        .map(|(operator, (old_power, new_power))| PowerChange {
+            operator,
+            old_power,
+            new_power,
+        })
+        .collect();
+
+    Ok(PowerChangesResponse { changes })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(
+    deps: DepsMut<TgradeQuery>,
+    env: Env,
+    msg: TgradeSudoMsg<ValsetState>,
+) -> Result<Response, ContractError> {
+    match msg {
+        TgradeSudoMsg::PrivilegeChange(change) => Ok(privilege_change(deps, change)),
+        TgradeSudoMsg::EndWithValidatorUpdate {} => end_block(deps, env),
+        TgradeSudoMsg::BeginBlock { evidence } => begin_block(deps, env, evidence),
+        TgradeSudoMsg::Export {} => export(deps.as_ref()),
+        TgradeSudoMsg::Import(state) => import(deps, state),
+        _ => Err(ContractError::UnsupportedSudoType {}),
+    }
+}
+
+fn privilege_change<Q: CustomQuery>(_deps: DepsMut<Q>, change: PrivilegeChangeMsg) -> Response {
+    match change {
+        PrivilegeChangeMsg::Promoted {} => {
+            let msgs = request_privileges(&[
+                Privilege::ValidatorSetUpdater,
+                Privilege::TokenMinter,
+                Privilege::BeginBlocker,
+                Privilege::StateExporterImporter,
+                // Paying rewards to vesting accounts
+                Privilege::Delegator,
+            ]);
+            Response::new().add_submessages(msgs)
+        }
+        PrivilegeChangeMsg::Demoted {} => Response::new(),
+    }
 }
 
 /// returns true if this is an initial block, maybe part of InitGenesis processing,
@@ -705,9 +1681,17 @@ fn is_genesis_block(block: &BlockInfo) -> bool {
     block.height < 2
 }
 
-fn end_block(deps: DepsMut<TgradeQuery>, env: Env) -> Result<Response, ContractError> {
+fn end_block(mut deps: DepsMut<TgradeQuery>, env: Env) -> Result<Response, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
 
+    let mut epoch = EPOCH.load(deps.storage)?;
+    let cur_epoch = current_epoch(&env.block, &epoch);
+    let is_boundary = cur_epoch > epoch.current_epoch || is_genesis_block(&env.block);
+    // while paused, the epoch is not updated, so boundary is hit on every block
+    let paused = EPOCH_PROCESSING_PAUSED
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+
     if cfg.verify_validators {
         // Update the block signers height at each block
         deps.querier
@@ -715,17 +1699,40 @@ fn end_block(deps: DepsMut<TgradeQuery>, env: Env) -> Result<Response, ContractE
             .votes
             .iter()
             .filter(|&v| v.voted)
-            .try_for_each(|v| {
-                BLOCK_SIGNERS.save(deps.storage, v.address.as_slice(), &env.block.height)
+            .try_for_each(|v| -> StdResult<_> {
+                BLOCK_SIGNERS.save(deps.storage, v.address.as_slice(), &env.block.height)?;
+                // only boundaries actually processed are recorded
+                if is_boundary && !paused {
+                    BOUNDARY_SIGNERS.save(deps.storage, v.address.as_slice(), &cur_epoch)?;
+                }
+                if cfg.uptime_rewards {
+                    EPOCH_SIGNED_BLOCKS.update(deps.storage, v.address.as_slice(), |signed| {
+                        StdResult::Ok(signed.unwrap_or_default() + 1)
+                    })?;
+                }
+                Ok(())
             })?;
+        if cfg.uptime_rewards {
+            let blocks = EPOCH_BLOCKS.may_load(deps.storage)?.unwrap_or_default();
+            EPOCH_BLOCKS.save(deps.storage, &(blocks + 1))?;
+        }
     }
 
     // check if needed and quit early if we didn't hit epoch boundary
-    let mut epoch = EPOCH.load(deps.storage)?;
-    let cur_epoch = env.block.time.nanos() / (1_000_000_000 * epoch.epoch_length);
+    if !is_boundary {
+        return remove_immediately_jailed(deps, &env, &cfg);
+    }
 
-    if cur_epoch <= epoch.current_epoch && !is_genesis_block(&env.block) {
-        return Ok(Response::default());
+    // skipped epoch is reported only once
+    if paused {
+        let mut res = remove_immediately_jailed(deps.branch(), &env, &cfg)?;
+        if SKIPPED_EPOCH.may_load(deps.storage)? != Some(cur_epoch) {
+            SKIPPED_EPOCH.save(deps.storage, &cur_epoch)?;
+            res = res.add_event(
+                Event::new("epoch_skipped").add_attribute("epoch", cur_epoch.to_string()),
+            );
+        }
+        return Ok(res);
     }
     // we don't pay the first epoch, as this may be huge if contract starts at non-zero height
     let pay_epochs = if epoch.current_epoch == 0 {
@@ -737,6 +1744,8 @@ fn end_block(deps: DepsMut<TgradeQuery>, env: Env) -> Result<Response, ContractE
     // ensure to update this so we wait until next epoch to run this again
     epoch.current_epoch = cur_epoch;
     EPOCH.save(deps.storage, &epoch)?;
+    // jailed validators are excluded by the recalculation anyway
+    IMMEDIATE_JAILS.remove(deps.storage);
 
     if cfg.verify_validators {
         let expiration = JailingPeriod::from_duration(
@@ -744,49 +1753,65 @@ fn end_block(deps: DepsMut<TgradeQuery>, env: Env) -> Result<Response, ContractE
             &env.block,
         );
 
-        VALIDATORS
-            .load(deps.storage)?
-            .iter()
-            .flat_map(|v| match Ed25519Pubkey::try_from(&v.validator_pubkey) {
-                Ok(pubkey) => Some((v, pubkey)),
-                _ => None, // Silently ignore wrong / different type pubkeys
-            })
-            .try_for_each(|(v, ed25519_pubkey)| {
-                let operator_addr = &v.operator;
-                let validator_addr = ed25519_pubkey.to_address();
-                let mut height = BLOCK_SIGNERS.may_load(deps.storage, &validator_addr)?;
-                if height.is_none() {
-                    // Not a block signer yet, check their validator start height instead
-                    height = VALIDATOR_START_HEIGHT.may_load(deps.storage, operator_addr)?;
-                }
-                match height {
-                    Some(h) if h > env.block.height.saturating_sub(MISSED_BLOCKS) => Ok(()),
-                    _ => {
-                        // validator is inactive for at least MISSED_BLOCKS, jail!
-                        JAIL.save(deps.storage, operator_addr, &expiration)
-                    }
-                }
-            })?;
+        for v in VALIDATORS.load(deps.storage)? {
+            if is_offline(deps.storage, &cfg, &env.block, &v)? {
+                // validator is inactive for at least MISSED_BLOCKS, jail!
+                jail(deps.storage, &v.operator, &expiration)?;
+            }
+        }
     }
 
     // calculate and store new validator set
-    let (validators, auto_unjail) = calculate_validators(deps.as_ref(), &env)?;
+    let Selection {
+        validators,
+        auto_unjail,
+        padded,
+        low_self_bond,
+        capped,
+    } = calculate_validators(deps.as_ref(), &env, &[])?;
 
     // auto unjailing
     for addr in &auto_unjail {
-        JAIL.remove(deps.storage, addr)
+        unjail(deps.storage, addr, env.block.time)?;
     }
 
     let old_validators = VALIDATORS.load(deps.storage)?;
 
+    let (validators, deferred) = match cfg.max_churn {
+        Some(max_churn) if !old_validators.is_empty() => limit_churn(
+            deps.as_ref(),
+            &cfg,
+            max_churn as usize,
+            validators,
+            &old_validators,
+        )?,
+        _ => (validators, 0),
+    };
+
     // determine the diff to send back to tendermint
-    let (diff, add, remove) = calculate_diff(validators.clone(), old_validators);
+    let (diff, add, remove) = calculate_diff(validators.clone(), old_validators.clone());
     let update_members = RewardsDistribution::UpdateMembers {
         add: add.clone(),
         remove: remove.clone(),
     };
 
     VALIDATORS.save(deps.storage, &validators)?;
+    VALIDATORS_HISTORY.save(deps.storage, (cur_epoch, env.block.height), &validators)?;
+    // Prune sets older than retained epochs
+    let retention = max(cfg.active_set_retention, HISTORY_RETENTION);
+    if let Some(first_retained) = (cur_epoch + 1).checked_sub(retention) {
+        let expired = VALIDATORS_HISTORY
+            .keys(
+                deps.storage,
+                None,
+                Some(Bound::exclusive((first_retained, 0))),
+                Order::Ascending,
+            )
+            .collect::<StdResult<Vec<_>>>()?;
+        for key in expired {
+            VALIDATORS_HISTORY.remove(deps.storage, key);
+        }
+    }
 
     // update operators list with info about whether or not they're active validators
     for op in add {
@@ -797,11 +1822,7 @@ fn end_block(deps: DepsMut<TgradeQuery>, env: Env) -> Result<Response, ContractE
         })?;
     }
     for op in remove {
-        operators().update::<_, StdError>(deps.storage, &Addr::unchecked(op), |op| {
-            let mut op = op.ok_or_else(|| StdError::generic_err("operator doesn't exist"))?;
-            op.active_validator = false;
-            Ok(op)
-        })?;
+        deactivate_operator(deps.storage, &Addr::unchecked(op))?;
     }
 
     // Store starting heights of new validators
@@ -824,10 +1845,69 @@ fn end_block(deps: DepsMut<TgradeQuery>, env: Env) -> Result<Response, ContractE
         _ => unreachable!(),
     }
 
+    // Track when validators (re)joined the active set, for the verification grace period
+    for v in &validators {
+        if !old_validators.iter().any(|old| old.operator == v.operator) {
+            ACTIVE_SINCE.save(deps.storage, &v.operator, &(env.block.height + 1))?;
+        }
+    }
+    for old in &old_validators {
+        if !validators.iter().any(|v| v.operator == old.operator) {
+            ACTIVE_SINCE.remove(deps.storage, &old.operator);
+        }
+    }
+
     // provide payment if there are rewards to give
     let mut res = Response::new().set_data(to_binary(&diff)?);
+    if let Some(event) = validator_diff_event(cur_epoch, &validators, &old_validators) {
+        res = res.add_event(event);
+    }
+    if padded > 0 {
+        res = res.add_event(
+            Event::new("validators_padded")
+                .add_attribute("padded", padded.to_string())
+                .add_attribute(
+                    "min_active_validators",
+                    cfg.min_active_validators.to_string(),
+                ),
+        );
+    }
+    res = res.add_events(low_self_bond.into_iter().map(|operator| {
+        Event::new("validator_excluded")
+            .add_attribute("operator", operator)
+            .add_attribute("reason", "insufficient_self_bond")
+    }));
+    if let Some(max_power) = cfg.max_power {
+        res = res.add_events(capped.into_iter().map(|(operator, power)| {
+            Event::new("validator_power_capped")
+                .add_attribute("operator", operator)
+                .add_attribute("power", power.to_string())
+                .add_attribute("max_power", max_power.to_string())
+        }));
+    }
+    if deferred > 0 {
+        res = res.add_event(
+            Event::new("validator_changes_deferred")
+                .add_attribute("deferred", deferred.to_string())
+                .add_attribute("max_churn", cfg.max_churn.unwrap_or_default().to_string()),
+        );
+    }
+    let uptimes = if cfg.verify_validators && cfg.uptime_rewards {
+        Some(validators_uptime(deps.as_ref(), &old_validators)?)
+    } else {
+        None
+    };
+    reset_epoch_uptime(deps.storage)?;
+
     if pay_epochs > 0 {
-        res.messages = pay_block_rewards(deps, env, pay_epochs, &cfg)?
+        res.messages = pay_block_rewards(
+            deps,
+            env,
+            pay_epochs,
+            &cfg,
+            &old_validators,
+            uptimes.as_deref(),
+        )?
     };
 
     let res = res.add_submessage(SubMsg::new(WasmMsg::Execute {
@@ -839,70 +1919,426 @@ fn end_block(deps: DepsMut<TgradeQuery>, env: Env) -> Result<Response, ContractE
     Ok(res)
 }
 
+/// Checks if the validator didn't sign any block for at least `MISSED_BLOCKS`, so it is to be
+/// jailed on the epoch boundary. Validators which recently joined the active set are immune for
+/// `verification_grace_blocks`, and validators recently unjailed for `offline_jail_immunity`.
+/// Validators with unsupported keys are never offline.
+fn is_offline(
+    storage: &dyn Storage,
+    cfg: &Config,
+    block: &BlockInfo,
+    v: &ValidatorInfo,
+) -> StdResult<bool> {
+    let validator_addr = match OperatorPubkey::try_from(&v.validator_pubkey) {
+        Ok(pubkey) => pubkey.to_address(),
+        _ => return Ok(false), // Silently ignore wrong / different type pubkeys
+    };
+
+    let mut height = BLOCK_SIGNERS.may_load(storage, &validator_addr)?;
+    if height.is_none() {
+        // Not a block signer yet, check their validator start height instead
+        height = VALIDATOR_START_HEIGHT.may_load(storage, &v.operator)?;
+    }
+    // recently joined validators have a grace period to start signing
+    if let Some(active_since) = ACTIVE_SINCE.may_load(storage, &v.operator)? {
+        if active_since + cfg.verification_grace_blocks > block.height {
+            return Ok(false);
+        }
+    }
+    // recently unjailed validators are immune to offline jailing for a while
+    if let Some(unjailed_at) = UNJAILED_AT.may_load(storage, &v.operator)? {
+        if unjailed_at.plus_seconds(cfg.offline_jail_immunity.seconds()) > block.time {
+            return Ok(false);
+        }
+    }
+    Ok(!matches!(height, Some(h) if h > block.height.saturating_sub(MISSED_BLOCKS)))
+}
+
+/// Removes validators jailed with `immediate` flag from the active set, without waiting for the
+/// epoch boundary. Other validators are not recalculated.
+fn remove_immediately_jailed(
+    deps: DepsMut<TgradeQuery>,
+    env: &Env,
+    cfg: &Config,
+) -> Result<Response, ContractError> {
+    let jailed = match IMMEDIATE_JAILS.may_load(deps.storage)? {
+        Some(jailed) => jailed,
+        None => return Ok(Response::new()),
+    };
+    IMMEDIATE_JAILS.remove(deps.storage);
+
+    let old_validators = VALIDATORS.load(deps.storage)?;
+    let validators: Vec<_> = old_validators
+        .iter()
+        // could be unjailed in the meantime
+        .filter(|v| !(jailed.contains(&v.operator) && JAIL.has(deps.storage, &v.operator)))
+        .cloned()
+        .collect();
+    // Tendermint doesn't accept an empty validator set
+    if validators.len() == old_validators.len() || validators.is_empty() {
+        return Ok(Response::new());
+    }
+
+    let (diff, _, remove) = calculate_diff(validators.clone(), old_validators.clone());
+    VALIDATORS.save(deps.storage, &validators)?;
+    let epoch = EPOCH.load(deps.storage)?.current_epoch;
+    VALIDATORS_HISTORY.save(deps.storage, (epoch, env.block.height), &validators)?;
+    for op in &remove {
+        deactivate_operator(deps.storage, &Addr::unchecked(op))?;
+    }
+
+    let update_members = RewardsDistribution::UpdateMembers {
+        add: vec![],
+        remove,
+    };
+    let epoch = current_epoch(&env.block, &EPOCH.load(deps.storage)?);
+    let res = Response::new()
+        .set_data(to_binary(&diff)?)
+        .add_events(validator_diff_event(epoch, &validators, &old_validators))
+        .add_submessage(SubMsg::new(WasmMsg::Execute {
+            contract_addr: cfg.validator_group.to_string(),
+            msg: to_binary(&update_members)?,
+            funds: vec![],
+        }));
+    Ok(res)
+}
+
 const QUERY_LIMIT: Option<u32> = Some(30);
 
-/// Selects validators to be used for incoming epoch. Returns vector of validators info paired
-/// with vector of addresses to be un-jailed (always empty if auto un-jailing is disabled).
+/// Maximum number of operators listed in every attribute of `validator_diff` event
+const DIFF_EVENT_LIMIT: usize = 10;
+
+/// Summarizes changes between `old_validators` and `validators` active sets in a `validator_diff`
+/// event, or returns `None` if the set didn't change. Counts of added, removed and updated
+/// operators are always complete, but at most `DIFF_EVENT_LIMIT` operators are listed for each.
+fn validator_diff_event(
+    epoch: u64,
+    validators: &[ValidatorInfo],
+    old_validators: &[ValidatorInfo],
+) -> Option<Event> {
+    let power_in = |set: &[ValidatorInfo], v: &ValidatorInfo| {
+        set.iter()
+            .find(|other| other.operator == v.operator)
+            .map(|other| other.power)
+    };
+
+    let added: Vec<_> = validators
+        .iter()
+        .filter(|v| power_in(old_validators, v).is_none())
+        .map(|v| v.operator.to_string())
+        .collect();
+    let removed: Vec<_> = old_validators
+        .iter()
+        .filter(|v| power_in(validators, v).is_none())
+        .map(|v| v.operator.to_string())
+        .collect();
+    let updated: Vec<_> = validators
+        .iter()
+        .filter_map(|v| match power_in(old_validators, v) {
+            Some(old_power) if old_power != v.power => Some(format!("{}:{}", v.operator, v.power)),
+            _ => None,
+        })
+        .collect();
+
+    if added.is_empty() && removed.is_empty() && updated.is_empty() {
+        return None;
+    }
+
+    let mut event = Event::new("validator_diff").add_attribute("epoch", epoch.to_string());
+    for (key, items) in [("added", added), ("removed", removed), ("updated", updated)] {
+        event = event.add_attribute(format!("{}_count", key), items.len().to_string());
+        // Empty attribute values are not allowed
+        if !items.is_empty() {
+            let listed: Vec<_> = items.into_iter().take(DIFF_EVENT_LIMIT).collect();
+            event = event.add_attribute(key, listed.join(","));
+        }
+    }
+    Some(event)
+}
+
+/// Validators selected for the incoming epoch by `calculate_validators`
+struct Selection {
+    validators: Vec<ValidatorInfo>,
+    /// Addresses to be un-jailed (always empty if auto un-jailing is disabled)
+    auto_unjail: Vec<Addr>,
+    /// Number of validators below `min_points` included to satisfy `min_active_validators`
+    padded: usize,
+    /// Operators which would be selected but were excluded for bonding less than
+    /// `min_self_bond`
+    low_self_bond: Vec<Addr>,
+    /// Operators whose power was capped to `max_power`, with their uncapped power
+    capped: Vec<(Addr, u64)>,
+}
+
+/// Selects validators to be used for incoming epoch. Points of `overrides` members replace their
+/// points in the membership, for simulation purposes.
 fn calculate_validators<Q: CustomQuery>(
     deps: Deps<Q>,
     env: &Env,
-) -> Result<(Vec<ValidatorInfo>, Vec<Addr>), ContractError> {
+    overrides: &[Member],
+) -> Result<Selection, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
 
     let min_points = max(cfg.min_points, 1);
-    let scaling: u64 = cfg.scaling.unwrap_or(1).into();
 
     // get all validators from the contract, filtered
     let mut validators = vec![];
-    let mut batch = cfg
-        .membership
-        .list_members_by_points(&deps.querier, None, QUERY_LIMIT)?;
     let mut auto_unjail = vec![];
+    let mut low_self_bond = vec![];
+    select_validators(
+        deps,
+        env,
+        &cfg,
+        |points| points >= min_points,
+        cfg.max_validators as usize,
+        overrides,
+        &mut validators,
+        &mut auto_unjail,
+        &mut low_self_bond,
+    )?;
+
+    // pad the set with members below `min_points` if there are not enough validators
+    let min_active = min(cfg.min_active_validators, cfg.max_validators) as usize;
+    let selected = validators.len();
+    if selected < min_active {
+        select_validators(
+            deps,
+            env,
+            &cfg,
+            |points| points > 0 && points < min_points,
+            min_active,
+            overrides,
+            &mut validators,
+            &mut auto_unjail,
+            &mut low_self_bond,
+        )?;
+    }
+    let padded = validators.len() - selected;
+
+    // cap validators power, after scaling
+    let mut capped = vec![];
+    if let Some(max_power) = cfg.max_power {
+        for v in validators.iter_mut().filter(|v| v.power > max_power) {
+            capped.push((v.operator.clone(), v.power));
+            v.power = max_power;
+        }
+    }
+
+    Ok(Selection {
+        validators,
+        auto_unjail,
+        padded,
+        low_self_bond,
+        capped,
+    })
+}
+
+/// Limits the number of validators entering and leaving the active set to `max_churn`. Entering
+/// validators are ranked by their new power, and leaving ones by their old power - only the
+/// highest ranked changes are applied, the rest is deferred. Entering validator is deferred if
+/// the set is full and there is no leaving validator to take its place. Jailed and tombstoned
+/// validators always leave, consuming the limit. Validators whose leaving is deferred keep their
+/// old power, as it is not recalculated for validators which are not selected. Returns the
+/// limited set with the number of deferred changes.
+fn limit_churn<Q: CustomQuery>(
+    deps: Deps<Q>,
+    cfg: &Config,
+    max_churn: usize,
+    validators: Vec<ValidatorInfo>,
+    old_validators: &[ValidatorInfo],
+) -> StdResult<(Vec<ValidatorInfo>, usize)> {
+    let is_active = |set: &[ValidatorInfo], v: &ValidatorInfo| {
+        set.iter().any(|active| active.operator == v.operator)
+    };
+
+    let (staying, mut entering): (Vec<_>, Vec<_>) = validators
+        .into_iter()
+        .partition(|v| is_active(old_validators, v));
+    let mut leaving = vec![];
+    let mut forced = 0;
+    for v in old_validators.iter().filter(|v| !is_active(&staying, v)) {
+        if JAIL.has(deps.storage, &v.operator) || TOMBSTONED.has(deps.storage, &v.operator) {
+            forced += 1;
+        } else {
+            leaving.push(v.clone());
+        }
+    }
+
+    let by_power = |a: &ValidatorInfo, b: &ValidatorInfo| {
+        b.power
+            .cmp(&a.power)
+            .then_with(|| a.validator_pubkey.cmp(&b.validator_pubkey))
+    };
+    entering.sort_by(by_power);
+    leaving.sort_by(by_power);
+
+    let mut budget = max_churn.saturating_sub(forced);
+    let mut size = staying.len() + leaving.len();
+    let capacity = cfg.max_validators as usize;
+    let mut entered = vec![];
+    // Leaving validators still in the set, highest power last
+    let mut remaining: Vec<_> = leaving.into_iter().rev().collect();
+    let mut entering = entering.into_iter().peekable();
+
+    while budget > 0 {
+        let enter_next = match (entering.peek(), remaining.last()) {
+            (Some(enter), Some(leave)) => by_power(enter, leave).is_le(),
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => break,
+        };
+
+        if !enter_next {
+            remaining.pop();
+            size -= 1;
+            budget -= 1;
+        } else if size < capacity {
+            entered.extend(entering.next());
+            size += 1;
+            budget -= 1;
+        } else if budget >= 2 && !remaining.is_empty() {
+            // Set is full, the validator takes the place of one leaving
+            remaining.pop();
+            entered.extend(entering.next());
+            budget -= 2;
+        } else {
+            break;
+        }
+    }
+
+    let deferred = entering.count() + remaining.len();
+    let validators = staying
+        .into_iter()
+        .chain(entered)
+        .chain(remaining.into_iter().rev())
+        .collect();
+    Ok((validators, deferred))
+}
+
+/// Operator eligible to be a validator, before the tie at the last slot is broken
+struct Candidate {
+    points: u64,
+    info: ValidatorInfo,
+    /// Whether the operator is to be auto unjailed if selected
+    auto_unjail: bool,
+}
+
+/// Goes through members by points descending, adding those with points accepted by `filter` to
+/// `validators`, until there are `limit` validators. Ties at the last slot are broken according
+/// to `cfg.tie_break`. Operators with not enough self-bond are added to `low_self_bond` instead.
+/// Non-empty `overrides` replace points of members, see `members_with_overrides`.
+#[allow(clippy::too_many_arguments)]
+fn select_validators<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: &Env,
+    cfg: &Config,
+    filter: impl Fn(u64) -> bool,
+    limit: usize,
+    overrides: &[Member],
+    validators: &mut Vec<ValidatorInfo>,
+    auto_unjail: &mut Vec<Addr>,
+    low_self_bond: &mut Vec<Addr>,
+) -> Result<(), ContractError> {
+    let scaling: u64 = cfg.scaling.unwrap_or(1).into();
+    let epoch = current_epoch(&env.block, &EPOCH.load(deps.storage)?);
+    // Operators registered in this or the previous epoch are not eligible yet
+    let last_eligible_epoch = if cfg.delay_new_operators {
+        epoch.checked_sub(2)
+    } else {
+        None
+    };
+    let is_eligible =
+        |registered: u64| matches!(last_eligible_epoch, Some(last) if registered <= last);
+
+    let free = limit.saturating_sub(validators.len());
+    if free == 0 {
+        return Ok(());
+    }
+
+    let mut candidate = |m: Member| -> Option<StdResult<Candidate>> {
+        // why do we allow Addr::unchecked here?
+        // all valid keys for `operators()` are already validated before insertion
+        // we have 3 cases:
+        // 1. There is a match with operators().load(), this means it is a valid address and
+        //    has a pubkey registered -> count in our group
+        // 2. The address is valid, but has no pubkey registered in operators() -> skip
+        // 3. The address is invalid -> skip
+        //
+        // All 3 cases are handled properly below (operators.load() returns an Error on
+        // both 2 and 3), so we do not need to perform N addr_validate calls here
+        let m_addr = Addr::unchecked(&m.addr);
+
+        // tombstoned addresses never become validators again
+        if TOMBSTONED.has(deps.storage, &m_addr) {
+            return None;
+        }
+
+        // check if address is jailed
+        let auto_unjail = match JAIL.may_load(deps.storage, &m_addr) {
+            Err(err) => return Some(Err(err)),
+            // address not jailed, proceed
+            Ok(None) => false,
+            // address jailed, but period expired and auto unjailing enabled, to be
+            // added to auto_unjail list if selected
+            Ok(Some(expires)) if cfg.auto_unjail && expires.is_expired(&env.block) => true,
+            // address jailed and cannot be unjailed - filter validator out
+            _ => return None,
+        };
+
+        if cfg.delay_new_operators {
+            match OPERATOR_REGISTRATION_EPOCH.may_load(deps.storage, &m_addr) {
+                Err(err) => return Some(Err(err)),
+                Ok(Some(registered)) if !is_eligible(registered) => return None,
+                _ => (),
+            }
+        }
+
+        let op = operators().load(deps.storage, &m_addr).ok()?;
+
+        if !cfg.min_self_bond.is_zero() && self_bond(deps, cfg, &m_addr) < cfg.min_self_bond {
+            low_self_bond.push(m_addr);
+            return None;
+        }
+
+        let power = probation_power(cfg, &op, epoch, m.points * scaling);
+        Some(Ok(Candidate {
+            points: m.points,
+            info: ValidatorInfo {
+                operator: m_addr,
+                validator_pubkey: op.pubkey.into(),
+                power,
+            },
+            auto_unjail,
+        }))
+    };
+
+    // Candidates with the same points as the one at the last free slot are all collected, to
+    // break the tie between them
+    let mut candidates: Vec<Candidate> = vec![];
+    // Overrides may reorder members, so the whole membership is processed as a single batch
+    let overridden = !overrides.is_empty();
+    let mut batch = if overridden {
+        members_with_overrides(deps, cfg, overrides)?
+    } else {
+        cfg.membership
+            .list_members_by_points(&deps.querier, None, QUERY_LIMIT)?
+    };
 
-    while !batch.is_empty() && validators.len() < cfg.max_validators as usize {
+    'members: while !batch.is_empty() {
         let last = Some(batch.last().unwrap().clone());
 
-        let filtered: Vec<_> = batch
-            .into_iter()
-            .filter(|m| m.points >= min_points)
-            .filter_map(|m| -> Option<StdResult<_>> {
-                // why do we allow Addr::unchecked here?
-                // all valid keys for `operators()` are already validated before insertion
-                // we have 3 cases:
-                // 1. There is a match with operators().load(), this means it is a valid address and
-                //    has a pubkey registered -> count in our group
-                // 2. The address is valid, but has no pubkey registered in operators() -> skip
-                // 3. The address is invalid -> skip
-                //
-                // All 3 cases are handled properly below (operators.load() returns an Error on
-                // both 2 and 3), so we do not need to perform N addr_validate calls here
-                let m_addr = Addr::unchecked(&m.addr);
-
-                // check if address is jailed
-                match JAIL.may_load(deps.storage, &m_addr) {
-                    Err(err) => return Some(Err(err)),
-                    // address not jailed, proceed
-                    Ok(None) => (),
-                    // address jailed, but period expired and auto unjailing enabled, add to
-                    // auto_unjail list
-                    Ok(Some(expires)) if cfg.auto_unjail && expires.is_expired(&env.block) => {
-                        auto_unjail.push(m_addr.clone())
-                    }
-                    // address jailed and cannot be unjailed - filter validator out
-                    _ => return None,
-                };
-
-                operators().load(deps.storage, &m_addr).ok().map(|op| {
-                    Ok(ValidatorInfo {
-                        operator: m_addr,
-                        validator_pubkey: op.pubkey.into(),
-                        power: m.points * scaling,
-                    })
-                })
-            })
-            .take(cfg.max_validators as usize - validators.len() as usize)
-            .collect::<Result<_, _>>()?;
-        validators.extend_from_slice(&filtered);
+        for m in batch.into_iter().filter(|m| filter(m.points)) {
+            if candidates.len() >= free && m.points < candidates[free - 1].points {
+                break 'members;
+            }
+            if let Some(candidate) = candidate(m).transpose()? {
+                candidates.push(candidate);
+            }
+        }
+
+        if overridden {
+            break;
+        }
 
         // and get the next page
         batch = cfg
@@ -910,7 +2346,131 @@ fn calculate_validators<Q: CustomQuery>(
             .list_members_by_points(&deps.querier, last, QUERY_LIMIT)?;
     }
 
-    Ok((validators, auto_unjail))
+    if candidates.len() > free {
+        let cutoff = candidates[free - 1].points;
+        let tied_at = candidates
+            .iter()
+            .position(|c| c.points == cutoff)
+            .unwrap_or_default();
+        let mut tied = candidates.split_off(tied_at);
+        break_tie(deps, cfg.tie_break, &mut tied)?;
+        tied.truncate(free - candidates.len());
+        candidates.extend(tied);
+    }
+
+    for candidate in candidates {
+        if candidate.auto_unjail {
+            auto_unjail.push(candidate.info.operator.clone());
+        }
+        validators.push(candidate.info);
+    }
+
+    Ok(())
+}
+
+/// Loads all members of `cfg.membership` with points of `overrides` members replaced (or added if
+/// not members yet), sorted by points descending like `list_members_by_points`.
+fn members_with_overrides<Q: CustomQuery>(
+    deps: Deps<Q>,
+    cfg: &Config,
+    overrides: &[Member],
+) -> StdResult<Vec<Member>> {
+    let mut members = vec![];
+    let mut batch = cfg
+        .membership
+        .list_members_by_points(&deps.querier, None, QUERY_LIMIT)?;
+    while let Some(last) = batch.last().cloned() {
+        members.append(&mut batch);
+        batch = cfg
+            .membership
+            .list_members_by_points(&deps.querier, Some(last), QUERY_LIMIT)?;
+    }
+
+    for over in overrides {
+        match members.iter_mut().find(|m| m.addr == over.addr) {
+            Some(member) => member.points = over.points,
+            None => members.push(over.clone()),
+        }
+    }
+
+    members.sort_by(|a, b| b.points.cmp(&a.points).then_with(|| b.addr.cmp(&a.addr)));
+    Ok(members)
+}
+
+/// Sorts operators with equal points according to the `tie_break` strategy. Lexicographic order
+/// of pubkeys decides between operators equal otherwise.
+fn break_tie<Q: CustomQuery>(
+    deps: Deps<Q>,
+    tie_break: TieBreak,
+    tied: &mut Vec<Candidate>,
+) -> StdResult<()> {
+    let by_pubkey =
+        |a: &Candidate, b: &Candidate| a.info.validator_pubkey.cmp(&b.info.validator_pubkey);
+    if tie_break == TieBreak::PubkeyLex {
+        tied.sort_by(by_pubkey);
+        return Ok(());
+    }
+
+    // Operators who were never validators are treated as the newest
+    let mut by_height = tied
+        .drain(..)
+        .map(|c| {
+            let height = VALIDATOR_START_HEIGHT.may_load(deps.storage, &c.info.operator)?;
+            Ok((height.unwrap_or(u64::MAX), c))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    by_height.sort_by(|(height_a, a), (height_b, b)| {
+        let order = match tie_break {
+            TieBreak::NewestFirst => height_b.cmp(height_a),
+            _ => height_a.cmp(height_b),
+        };
+        order.then_with(|| by_pubkey(a, b))
+    });
+    tied.extend(by_height.into_iter().map(|(_, c)| c));
+    Ok(())
+}
+
+/// Scales down `power` of a validator on probation after being unjailed. Scaling starts at
+/// `probation_scaling` and grows linearly to full power over `probation_epochs` epochs.
+fn probation_power(cfg: &Config, op: &OperatorInfo, epoch: u64, power: u64) -> u64 {
+    let elapsed = match op.probation_start_epoch {
+        Some(start) if epoch < start + cfg.probation_epochs => epoch.saturating_sub(start),
+        _ => return power,
+    };
+
+    let power = Uint128::from(power);
+    let scaled = power * cfg.probation_scaling;
+    let ramp = (power - scaled).multiply_ratio(elapsed, cfg.probation_epochs);
+    // Validator on probation is not removed from the set
+    max((scaled + ramp).u128() as u64, 1)
+}
+
+/// Tokens bonded by the operator in the `membership` contract, liquid and vesting combined.
+/// Zero if the query fails, which is the case for membership contracts other than `tg4-stake` -
+/// the operator is then not eligible, but the epoch is still processed.
+fn self_bond<Q: CustomQuery>(deps: Deps<Q>, cfg: &Config, operator: &Addr) -> Uint128 {
+    deps.querier
+        .query_wasm_smart(
+            cfg.membership.addr(),
+            &StakeQueryMsg::Staked {
+                address: operator.to_string(),
+            },
+        )
+        .map(|staked: StakedResponse| staked.liquid.amount + staked.vesting.amount)
+        .unwrap_or_default()
+}
+
+/// Index of the epoch the block belongs to.
+fn current_epoch(block: &BlockInfo, info: &EpochInfo) -> u64 {
+    let offset = &info.epoch_offset;
+    let elapsed = block.time.seconds().saturating_sub(offset.time);
+    offset.epoch + elapsed / info.epoch_length
+}
+
+/// Time the epoch starts at, in seconds.
+fn epoch_start_time(epoch: u64, info: &EpochInfo) -> u64 {
+    let offset = &info.epoch_offset;
+    offset.time + epoch.saturating_sub(offset.epoch) * info.epoch_length
 }
 
 /// Computes validator differences.
@@ -1004,18 +2564,20 @@ pub fn migrate(
         Ok(cfg)
     })?;
 
-    Ok(Response::new())
+    // Contracts promoted before reward vesting was introduced lack the privilege to pay vested
+    // rewards
+    let msgs = request_privileges(&[Privilege::Delegator]);
+    Ok(Response::new().add_submessages(msgs))
 }
 
 mod evidence {
     use super::*;
 
-    use tg_bindings::{ToAddress, Validator};
+    use tg_bindings::Validator;
 
-    /// Validator struct contains only hash of first 20 bytes of validator's pub key
-    /// (sha256), while contract keeps only pub keys. To match potential reported
-    /// suspect, this function computes sha256 hashes for all existing validator and
-    /// compares result with suspect. It is acceptable approach, since it shouldn't
+    /// Validator struct contains only the Tendermint address of validator's pub key, while
+    /// contract keeps only pub keys. To match potential reported suspect, this function
+    /// computes addresses for all existing validator and compares result with suspect. It is acceptable approach, since it shouldn't
     /// happen too often.
     pub fn find_matching_validator<Q: CustomQuery>(
         deps: Deps<Q>,
@@ -1030,9 +2592,12 @@ mod evidence {
                     if start_height >= evidence_height {
                         return Ok(None);
                     }
-                    let operator = operators().load(deps.storage, &addr)?;
-                    let hash = Binary::from(operator.pubkey.to_address());
-                    if hash == suspect.address {
+                    // Operators who deregistered have no key to match
+                    let operator = match operators().may_load(deps.storage, &addr)? {
+                        Some(operator) => operator,
+                        None => return Ok(None),
+                    };
+                    if operator.pubkey.to_address() == suspect.address.as_slice() {
                         return Ok(Some(addr));
                     }
                     Ok(None)
@@ -1049,11 +2614,14 @@ mod evidence {
         };
         let slash_msg = to_binary(&slash_msg)?;
 
-        Ok(SubMsg::new(WasmMsg::Execute {
-            contract_addr: config.membership.addr().to_string(),
-            msg: slash_msg,
-            funds: vec![],
-        }))
+        Ok(SubMsg::reply_on_success(
+            WasmMsg::Execute {
+                contract_addr: config.membership.addr().to_string(),
+                msg: slash_msg,
+                funds: vec![],
+            },
+            SLASH_REPLY_ID,
+        ))
     }
 }
 
@@ -1091,13 +2659,10 @@ fn begin_block(
                     &env,
                     validator.clone(),
                     config.double_sign_slash_ratio,
+                    SlashingCause::DoubleSign,
                 )?;
 
-                JAIL.save(
-                    deps.storage,
-                    &validator,
-                    &JailingPeriod::from_duration(JailingDuration::Forever {}, &env.block),
-                )?;
+                tombstone(deps.storage, &env, &validator)?;
 
                 response = response
                     .clone()
@@ -1116,6 +2681,7 @@ fn begin_block(
 pub fn reply(deps: DepsMut<TgradeQuery>, env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg.id {
         REWARDS_INIT_REPLY_ID => rewards_instantiate_reply(deps, env, msg),
+        SLASH_REPLY_ID => slash_reply(deps, env, msg),
         _ => Err(ContractError::UnrecognisedReply(msg.id)),
     }
 }
@@ -1147,6 +2713,65 @@ pub fn rewards_instantiate_reply<Q: CustomQuery>(
     Ok(resp)
 }
 
+/// Records the amount burned by the membership contract on the slashing event stored in this
+/// block. Amounts are taken from the tg4-stake `slash` events, other membership contracts don't
+/// report them.
+fn slash_reply<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    msg: Reply,
+) -> Result<Response, ContractError> {
+    let id = msg.id;
+    let events = msg
+        .result
+        .into_result()
+        .map_err(|err| ContractError::ReplyParseFailure { id, err })?
+        .events;
+
+    let mut slashed: BTreeMap<String, Uint128> = BTreeMap::new();
+    for event in events.iter().filter(|event| event.ty == "wasm") {
+        let attribute = |key: &str| {
+            event
+                .attributes
+                .iter()
+                .find(|attr| attr.key == key)
+                .map(|attr| attr.value.as_str())
+        };
+        let addr = match attribute("addr") {
+            Some(addr) if attribute("action") == Some("slash") => addr,
+            _ => continue,
+        };
+        let keys = [
+            "liquid_stake_slashed",
+            "vesting_stake_slashed",
+            "liquid_claims_slashed",
+            "vesting_claims_slashed",
+        ];
+        for amount in keys.iter().filter_map(|key| attribute(key)) {
+            let amount: Uint128 = amount.parse()?;
+            *slashed.entry(addr.to_owned()).or_default() += amount;
+        }
+    }
+
+    for (addr, amount) in slashed {
+        let addr = Addr::unchecked(addr);
+        let mut slashing = match VALIDATOR_SLASHING.may_load(deps.storage, &addr)? {
+            Some(slashing) => slashing,
+            None => continue,
+        };
+        // Replies come in the order slashing events were stored
+        if let Some(event) = slashing
+            .iter_mut()
+            .find(|event| event.slash_height == env.block.height && event.amount.is_none())
+        {
+            event.amount = Some(amount);
+            VALIDATOR_SLASHING.save(deps.storage, &addr, &slashing)?;
+        }
+    }
+
+    Ok(Response::new())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
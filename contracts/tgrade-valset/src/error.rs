@@ -1,8 +1,8 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Timestamp};
 use thiserror::Error;
 
 use cw_controllers::AdminError;
-use tg_bindings::Ed25519PubkeyConversionError;
+use tg_bindings::{Ed25519PubkeyConversionError, Secp256k1PubkeyConversionError};
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
@@ -36,9 +36,32 @@ pub enum ContractError {
     #[error("Scaling must be unset or greater than zero")]
     InvalidScaling {},
 
-    #[error("Tendermint pubkey must be 32 bytes long")]
+    #[error("Probation scaling must be greater than zero and at most 1")]
+    InvalidProbationScaling {},
+
+    #[error("Max power must be unset or greater than zero")]
+    InvalidMaxPower {},
+
+    #[error("Max churn must be unset or greater than zero")]
+    InvalidMaxChurn {},
+
+    #[error("Epoch reward exceeds the configured maximum")]
+    EpochRewardTooHigh {},
+
+    #[error("Epoch reward and its maximum cannot be updated in the same message")]
+    EpochRewardAndCapUpdate {},
+
+    #[error("Metadata was updated too recently, retry at {retry_at}")]
+    MetadataUpdateTooSoon { retry_at: Timestamp },
+
+    #[error(
+        "Tendermint pubkey must be 32 bytes long ed25519 or 33 bytes long compressed secp256k1 key"
+    )]
     InvalidPubkey {},
 
+    #[error("Unsupported Tendermint pubkey type - only ed25519 and secp256k1 are supported")]
+    UnsupportedPubkeyType {},
+
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
 
@@ -51,6 +74,9 @@ pub enum ContractError {
     #[error("No distribution contract")]
     NoDistributionContract {},
 
+    #[error("Distribution contract {0} already configured")]
+    DistributionContractExists(String),
+
     #[error("Failure response from submsg: {0}")]
     SubmsgFailure(String),
 
@@ -60,9 +86,21 @@ pub enum ContractError {
     #[error("Unrecognised reply id: {0}")]
     UnrecognisedReply(u64),
 
+    #[error("Active validator set of epoch {0} is not retained")]
+    EpochNotRetained(u64),
+
+    #[error("No active validator set retained at or before height {0}")]
+    HeightNotRetained(u64),
+
     #[error("Never a validator: {0}")]
     NeverAValidator(String),
 
+    #[error("Operator {0} is tombstoned")]
+    Tombstoned(String),
+
+    #[error("Operator {0} is an active validator")]
+    ActiveValidator(String),
+
     #[error("Cannot unjail validator who's been jailed forever")]
     UnjailFromJailForeverForbidden {},
 
@@ -78,6 +116,9 @@ pub enum ContractError {
 
     #[error("Invalid metadata - website needs to start with http:// or https://")]
     InvalidMetadataWebsitePrefix {},
+
+    #[error("Invalid commission - has to be in range 0-1")]
+    InvalidCommission {},
 }
 
 impl From<Ed25519PubkeyConversionError> for ContractError {
@@ -85,3 +126,9 @@ impl From<Ed25519PubkeyConversionError> for ContractError {
         ContractError::InvalidPubkey {}
     }
 }
+
+impl From<Secp256k1PubkeyConversionError> for ContractError {
+    fn from(_err: Secp256k1PubkeyConversionError) -> Self {
+        ContractError::InvalidPubkey {}
+    }
+}
@@ -4,12 +4,17 @@ use std::convert::TryFrom;
 use std::ops::Add;
 
 use tg4::Member;
-use tg_bindings::{Ed25519Pubkey, Pubkey};
+use tg_bindings::Pubkey;
 use tg_utils::{Duration, Expiration, JailingDuration};
 
 use crate::error::ContractError;
-use crate::state::{DistributionContract, OperatorInfo, ValidatorInfo, ValidatorSlashing};
-use cosmwasm_std::{Addr, Api, BlockInfo, Coin, Decimal, Timestamp};
+use crate::state::{
+    DistributionContract, OperatorInfo, OperatorPubkey, SlashingCause, ValidatorInfo,
+    ValidatorSlashing,
+};
+use cosmwasm_std::{
+    to_binary, Addr, Api, Binary, BlockInfo, Coin, Decimal, StdResult, Timestamp, Uint128,
+};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct InstantiateMsg {
@@ -24,8 +29,7 @@ pub struct InstantiateMsg {
     pub min_points: u64,
     /// The maximum number of validators that can be included in the Tendermint validator set.
     /// If there are more validators than slots, we select the top N by membership points
-    /// descending. (In case of ties at the last slot, select by "first" Tendermint pubkey,
-    /// lexicographically sorted).
+    /// descending. Ties at the last slot are broken according to `tie_break`.
     pub max_validators: u32,
     /// Number of seconds in one epoch. We update the Tendermint validator set only once per epoch.
     /// Epoch # is env.block.time/epoch_length (round down). The first block with a new epoch number
@@ -99,6 +103,99 @@ pub struct InstantiateMsg {
     /// The duration to jail a validator for in case they don't sign their first epoch
     /// boundary block. After the period, they have to pass verification again, ad infinitum.
     pub offline_jail_duration: Duration,
+
+    /// The period after being unjailed during which a validator cannot be jailed again for being
+    /// offline. This prevents validators from flapping between jailed and unjailed states.
+    /// Jailing by the admin is not affected. Zero (no immunity) by default.
+    #[serde(default = "default_offline_jail_immunity")]
+    pub offline_jail_immunity: Duration,
+
+    /// The minimum number of active validators to keep. If fewer members than this meet
+    /// `min_points`, the set is padded with the highest ranked members below `min_points`
+    /// (0-point members are never included). Capped at `max_validators`. 0 (disabled) by default.
+    #[serde(default)]
+    pub min_active_validators: u32,
+
+    /// If set, operators registering validator key are not eligible to be active validators
+    /// until the epoch following the one in which they registered has passed. This prevents
+    /// injecting validators right before an epoch boundary. Disabled by default.
+    #[serde(default)]
+    pub delay_new_operators: bool,
+
+    /// Number of most recent epochs for which the active validator set is kept, to be queried
+    /// with `ActiveValidatorsAtEpoch`. Older sets are pruned. 0 (not kept) by default.
+    #[serde(default)]
+    pub active_set_retention: u64,
+
+    /// Minimum amount of tokens an operator has to bond by themselves (liquid and vesting
+    /// combined) to be eligible as an active validator. If non-zero, `membership` has to be
+    /// a tg4-stake contract supporting the `Staked` query. 0 (disabled) by default.
+    #[serde(default)]
+    pub min_self_bond: Uint128,
+
+    /// Initial power scaling of validators unjailed with `Unjail`. Power is scaled by it in the
+    /// first epoch after unjailing, and ramps back linearly to full power over `probation_epochs`
+    /// epochs. Has to be in the (0, 1] range, 1 (no scaling) by default.
+    #[serde(default = "default_probation_scaling")]
+    pub probation_scaling: Decimal,
+
+    /// Number of epochs it takes for an unjailed validator to get back to full power.
+    /// 0 (no probation) by default.
+    #[serde(default)]
+    pub probation_epochs: u64,
+
+    /// Minimal time between two `UpdateMetadata` calls of a single operator. The admin is not
+    /// limited. Zero (no limit) by default.
+    #[serde(default = "default_metadata_update_cooldown")]
+    pub metadata_update_cooldown: Duration,
+
+    /// If set together with `verify_validators`, validators rewards are scaled by the part of
+    /// epoch blocks they signed. Forfeited rewards are added to the next epoch reward.
+    /// Disabled by default.
+    #[serde(default)]
+    pub uptime_rewards: bool,
+
+    /// Strategy of selecting validators among operators with equal points at the last slot of
+    /// the validator set. Lexicographic order of Tendermint pubkeys by default.
+    #[serde(default)]
+    pub tie_break: TieBreak,
+
+    /// Ratio of bonded tokens slashed by `Slash` message with `downtime` reason. 0 by default.
+    #[serde(default)]
+    pub downtime_slash_ratio: Decimal,
+
+    /// Ratio of bonded tokens slashed by `Slash` message with `governance` reason. If not set,
+    /// `portion` passed with the message is used.
+    #[serde(default)]
+    pub governance_slash_ratio: Option<Decimal>,
+
+    /// Maximum Tendermint power of a single validator, applied after `scaling`. Validators with
+    /// higher power are capped to it, the excess is not redistributed. Not capped by default.
+    #[serde(default)]
+    pub max_power: Option<u64>,
+
+    /// Maximum number of validators entering and leaving the active set in a single epoch.
+    /// Changes above it with the lowest power are deferred to the next epoch. Not limited by
+    /// default.
+    #[serde(default)]
+    pub max_churn: Option<u32>,
+
+    /// If set, rewards paid directly to validators (commissions and shares of validators with
+    /// reward address set) are sent to vesting accounts of their receivers, instead of being
+    /// transferred as liquid tokens.
+    #[serde(default)]
+    pub reward_vesting: bool,
+
+    /// Number of blocks after joining the active validator set during which a validator is
+    /// not jailed for being offline, if `verify_validators` is enabled. Gives operators time to
+    /// bring their nodes up. 0 (disabled) by default.
+    #[serde(default)]
+    pub verification_grace_blocks: u64,
+
+    /// Upper bound of `epoch_reward`, protecting against minting an absurd amount by mistake.
+    /// Has to be in the same denom as `epoch_reward`. Not limited by default.
+    #[serde(default)]
+    pub max_epoch_reward: Option<Coin>,
 }
 
 impl InstantiateMsg {
@@ -115,6 +212,14 @@ impl InstantiateMsg {
         if self.scaling == Some(0) {
             return Err(ContractError::InvalidScaling {});
         }
+        if self.max_power == Some(0) {
+            return Err(ContractError::InvalidMaxPower {});
+        }
+        if self.max_churn == Some(0) {
+            return Err(ContractError::InvalidMaxChurn {});
+        }
+        validate_probation_scaling(self.probation_scaling)?;
+        validate_epoch_reward(&self.epoch_reward, self.max_epoch_reward.as_ref())?;
         // Current denom regexp in the SDK is [a-zA-Z][a-zA-Z0-9/]{2,127}
         if self.epoch_reward.denom.len() < 2 || self.epoch_reward.denom.len() > 127 {
             return Err(ContractError::InvalidRewardDenom {});
@@ -128,6 +233,7 @@ impl InstantiateMsg {
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     /// Change the admin
     UpdateAdmin {
@@ -176,22 +282,118 @@ pub enum ExecuteMsg {
         /// if `verify_validators` is enabled.
         /// After the jailing period, they will be jailed again if not signing blocks, ad infinitum.
         offline_jail_duration: Option<Duration>,
+
+        /// The period after being unjailed during which a validator cannot be jailed again for
+        /// being offline.
+        offline_jail_immunity: Option<Duration>,
+
+        /// The minimum number of active validators to keep, padding the set with members below
+        /// `min_points` if needed.
+        min_active_validators: Option<u32>,
+
+        /// Whether newly registered operators have to wait an epoch before becoming eligible
+        /// to be active validators.
+        delay_new_operators: Option<bool>,
+
+        /// Number of most recent epochs for which the active validator set is kept.
+        active_set_retention: Option<u64>,
+
+        /// Minimum amount of tokens an operator has to bond by themselves to be eligible as
+        /// an active validator.
+        min_self_bond: Option<Uint128>,
+
+        /// Initial power scaling of validators unjailed with `Unjail`.
+        probation_scaling: Option<Decimal>,
+
+        /// Number of epochs it takes for an unjailed validator to get back to full power.
+        probation_epochs: Option<u64>,
+
+        /// Minimal time between two `UpdateMetadata` calls of a single operator.
+        metadata_update_cooldown: Option<Duration>,
+
+        /// Whether validators rewards are scaled by their uptime.
+        uptime_rewards: Option<bool>,
+
+        /// Strategy of selecting validators with equal points at the last slot.
+        tie_break: Option<TieBreak>,
+
+        /// Ratio of bonded tokens slashed by `Slash` message with `downtime` reason.
+        downtime_slash_ratio: Option<Decimal>,
+
+        /// Ratio of bonded tokens slashed by `Slash` message with `governance` reason. Zero unsets
+        /// it, so the passed `portion` is used.
+        governance_slash_ratio: Option<Decimal>,
+
+        /// Maximum Tendermint power of a single validator, applied after `scaling`. Zero removes
+        /// the cap.
+        max_power: Option<u64>,
+
+        /// Maximum number of validators entering and leaving the active set in a single epoch.
+        /// Zero removes the limit.
+        max_churn: Option<u32>,
+
+        /// Number of seconds in one epoch. The epoch in progress is extended or shortened, so
+        /// that the next one starts `epoch_length` after the change.
+        epoch_length: Option<u64>,
+
+        /// Whether rewards paid directly to validators are sent to vesting accounts.
+        reward_vesting: Option<bool>,
+
+        /// Number of blocks after joining the active set during which validators are not
+        /// jailed for being offline.
+        verification_grace_blocks: Option<u64>,
+
+        /// Upper bound of `epoch_reward`. The epoch reward after the update must not exceed it.
+        /// Zero amount removes the bound. Cannot be updated together with `epoch_reward`.
+        max_epoch_reward: Option<Coin>,
+    },
+    /// Adds a distribution contract receiving `ratio` of the reward, keeping the other ones.
+    /// Fails if the sum of ratios would exceed 1. Only the admin can do this.
+    AddDistributionContract {
+        contract: String,
+        ratio: Decimal,
+    },
+    /// Removes the distribution contract, keeping the other ones. Only the admin can do this.
+    RemoveDistributionContract {
+        contract: String,
     },
     /// Links info.sender (operator) to this Tendermint consensus key.
     /// The operator cannot re-register another key.
     /// No two operators may have the same consensus_key.
+    /// Ed25519 and compressed secp256k1 keys are supported.
     RegisterValidatorKey {
         pubkey: Pubkey,
         /// Additional metadata assigned to this validator
         metadata: ValidatorMetadata,
     },
     UpdateMetadata(ValidatorMetadata),
+    /// Removes info.sender (operator) validator key and metadata, so the key can be registered
+    /// by another operator. Fails for active validators - the operator has to leave the active
+    /// set first, for example by unbonding. Fails for tombstoned operators (including ones
+    /// jailed forever).
+    Deregister {},
     /// Jails validator. Can be executed only by the admin.
     Jail {
         /// Operator which should be jailed
         operator: String,
         /// Duration for how long validator is jailed
         duration: JailingDuration,
+        /// If set, an active validator is removed from the validator set at the end of the
+        /// current block, instead of on the next epoch boundary
+        #[serde(default)]
+        immediate: bool,
+    },
+    /// Tombstones validator: jails it forever, and permanently excludes it from the validator
+    /// set. Can be executed only by the admin.
+    Tombstone {
+        /// Operator which should be tombstoned
+        operator: String,
+    },
+    /// Pauses or resumes epoch processing, for maintenance. While paused, the active validator
+    /// set is not recalculated and rewards are not paid on epoch boundaries. Rewards of skipped
+    /// epochs are paid on the first boundary after resuming. Can be executed only by the admin.
+    SetEpochProcessingPaused {
+        paused: bool,
     },
     /// Unjails validator. Admin can unjail anyone anytime, others can unjail only themselves and
     /// only if the jail period passed.
@@ -201,10 +403,19 @@ pub enum ExecuteMsg {
         operator: Option<String>,
     },
     /// To be called by admin only. Slashes a given address (by forwarding slash to both rewards
-    /// contract and engagement contract)
+    /// contract and engagement contract). The slashed portion is the ratio configured for the
+    /// `reason`; `portion` is used only for `governance` reason without configured ratio.
     Slash {
         addr: String,
         portion: Decimal,
+        #[serde(default)]
+        reason: SlashingCause,
+    },
+    /// Sets the address receiving validation rewards of info.sender (operator): its commission
+    /// and its share of the validators reward, which is then paid directly instead of through
+    /// the validator group. Setting it to the operator address clears it.
+    SetRewardAddress {
+        address: String,
     },
 
     /// This will update the validator set with the passed list.
@@ -222,6 +433,9 @@ pub enum QueryMsg {
     Configuration {},
     /// Returns EpochResponse - get info on current and next epochs
     Epoch {},
+    /// Returns UpcomingEpochsResponse - numbers and start times of the next `count` epochs,
+    /// computed from the current block time. `count` is capped at 100.
+    UpcomingEpochs { count: u32 },
 
     /// Returns the validator key and associated metadata (if present) for the given operator.
     /// Returns ValidatorResponse
@@ -245,19 +459,141 @@ pub enum QueryMsg {
         limit: Option<u32>,
     },
 
+    /// Paginate over operators in given status, using operator address as pagination.
+    /// Returns ListValidatorResponse
+    ListValidatorsByStatus {
+        status: ValidatorStatus,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
     /// This will calculate who the new validators would be if
     /// we recalculated end block right now.
     /// Also returns ListActiveValidatorsResponse
     SimulateActiveValidators {},
 
+    /// Like `SimulateActiveValidators`, but with points of `overrides` members replacing their
+    /// current points in the membership (members are added if missing). Nothing is stored.
+    /// Returns ListActiveValidatorsResponse
+    SimulateActiveValidatorsWith { overrides: Vec<Member> },
+
     /// Returns a list of validator slashing events.
     /// Returns ListValidatorSlashingResponse
     ListValidatorSlashing { operator: String },
 
+    /// Returns past and current jailing periods of the operator, oldest first, with the time
+    /// they ended by unjailing. Paginated by record `id`.
+    /// Returns JailingHistoryResponse
+    JailingHistory {
+        operator: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Returns everything known about the operator in one response: metadata, jailing and
+    /// active status, rank, slashing record and signing info.
+    /// Returns ValidatorFullProfileResponse
+    ValidatorFullProfile { operator: String },
+
+    /// Lists operators whose power in the active validator set differs from the last epoch
+    /// snapshot recorded at or before `since_height`, with old and new values. Operators which
+    /// joined or left the set are reported with zero old or new power respectively. Fails if
+    /// there is no such snapshot, as history older than `HISTORY_RETENTION` epochs (or
+    /// `active_set_retention`, if larger) is pruned.
+    /// Returns PowerChangesResponse
+    PowerChanges { since_height: u64 },
+
+    /// Returns operator which registered given consensus pubkey, if any.
+    /// Returns OperatorByConsensusKeyResponse
+    OperatorByConsensusKey { pubkey: Pubkey },
+
+    /// Returns the slash ratio applied on double signing, and the most recent operators
+    /// slashed and tombstoned for double signing with the burned stake amounts.
+    /// Returns DoubleSignInfoResponse
+    DoubleSignInfo {},
+
+    /// Returns active validator set as it was at the end of the given epoch (including removals
+    /// by immediate jailing). Only the last `active_set_retention` epochs are available.
+    /// Returns ListActiveValidatorsResponse
+    ActiveValidatorsAtEpoch { epoch: u64 },
+
+    /// Checks if given consensus pubkey would be accepted by `RegisterValidatorKey`, without
+    /// checking if it is already registered.
+    /// Returns ValidatePubkeyResponse
+    ValidatePubkey { pubkey: Pubkey },
+
+    /// Returns how many epoch boundaries passed since the operator last signed an epoch
+    /// boundary block. Only tracked with `verify_validators` enabled.
+    /// Returns EpochsSinceSignedResponse
+    EpochsSinceSigned { operator: String },
+
+    /// Simulates how the reward would be split if the epoch boundary was reached now, using the
+    /// current fees, active validators and their uptime. Rewards of at least one epoch are
+    /// simulated. As distribution contracts ratios are applied using decimal multiplication,
+    /// indivisible tokens end up with the validators, see `InstantiateMsg::distribution_contracts`.
+    /// Returns SimulateRewardsResponse
+    SimulateRewards {},
+
+    /// Returns the address receiving validation rewards of the operator, which is the operator
+    /// itself unless set with `SetRewardAddress`.
+    /// Returns RewardAddressResponse
+    RewardAddress { operator: String },
+
+    /// Returns configured distribution contracts together with the total rewards sent to them.
+    /// Returns DistributionContractStatsResponse
+    DistributionContractStats {},
+
+    /// Returns if the operator is tombstoned, either with `Tombstone` or for double signing.
+    /// Returns IsTombstonedResponse
+    IsTombstoned { operator: String },
+
+    /// Lists active validators which didn't sign any block for at least `MISSED_BLOCKS`, and
+    /// are to be jailed on the next epoch boundary unless they sign one before it. Always empty
+    /// with `verify_validators` disabled.
+    /// Returns ValidatorsAtRiskResponse
+    ValidatorsAtRisk {},
+
+    /// Checks if the operator would be selected to the active validator set if the epoch ended
+    /// now, and if not, gives the reason.
+    /// Returns EligibilityResponse
+    Eligibility { operator: String },
+
     /// Returns cw_controllers::AdminResponse
     Admin {},
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidatorStatus {
+    /// Operator is a member of the current active validator set
+    Active,
+    /// Operator is jailed for a limited period of time
+    Jailed,
+    /// Operator is jailed forever
+    Tombstoned,
+}
+
+/// Strategy of selecting validators among operators with equal points, when not all of them fit
+/// in `max_validators`
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum TieBreak {
+    /// Operators with lexicographically first Tendermint pubkey win
+    PubkeyLex,
+    /// Operators who became validators first (lowest `start_height`) win. Operators who were
+    /// never validators come last.
+    OldestFirst,
+    /// Operators who became validators last (highest `start_height`) win. Operators who were
+    /// never validators come first.
+    NewestFirst,
+}
+
+impl Default for TieBreak {
+    fn default() -> Self {
+        Self::PubkeyLex
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct UnvalidatedDistributionContract {
     /// The unvalidated address of the contract to which part of the reward tokens is sent to.
@@ -265,6 +601,10 @@ pub struct UnvalidatedDistributionContract {
     /// The ratio of total reward tokens for an epoch to be sent to that contract for further
     /// distribution.
     pub ratio: Decimal,
+    /// Message sent to the contract along with its reward. Message accepted by tg4-engagement
+    /// by default.
+    #[serde(default)]
+    pub schema: DistributionSchema,
 }
 
 impl UnvalidatedDistributionContract {
@@ -272,10 +612,37 @@ impl UnvalidatedDistributionContract {
         Ok(DistributionContract {
             contract: api.addr_validate(&self.contract)?,
             ratio: self.ratio,
+            schema: self.schema,
         })
     }
 }
 
+/// Message sent to a distribution contract to distribute its part of the reward, which is sent
+/// as the message funds.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DistributionSchema {
+    /// `DistributionMsg::DistributeRewards`, as accepted by tg4-engagement
+    Engagement {},
+    /// Arbitrary message, sent as is, for distribution contracts with different interface
+    Custom { msg: Binary },
+}
+
+impl Default for DistributionSchema {
+    fn default() -> Self {
+        Self::Engagement {}
+    }
+}
+
+impl DistributionSchema {
+    pub fn distribute_msg(&self) -> StdResult<Binary> {
+        match self {
+            Self::Engagement {} => to_binary(&DistributionMsg::DistributeRewards {}),
+            Self::Custom { msg } => Ok(msg.clone()),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug, Default)]
 #[serde(transparent)]
 pub struct UnvalidatedDistributionContracts {
@@ -312,6 +679,36 @@ pub fn default_double_sign_slash() -> Decimal {
     Decimal::percent(50)
 }
 
+pub fn default_offline_jail_immunity() -> Duration {
+    Duration::new(0)
+}
+
+pub fn default_metadata_update_cooldown() -> Duration {
+    Duration::new(0)
+}
+
+pub fn default_probation_scaling() -> Decimal {
+    Decimal::one()
+}
+
+pub fn validate_probation_scaling(probation_scaling: Decimal) -> Result<(), ContractError> {
+    if probation_scaling.is_zero() || probation_scaling > Decimal::one() {
+        return Err(ContractError::InvalidProbationScaling {});
+    }
+    Ok(())
+}
+
+pub fn validate_epoch_reward(
+    epoch_reward: &Coin,
+    max_epoch_reward: Option<&Coin>,
+) -> Result<(), ContractError> {
+    match max_epoch_reward {
+        Some(max) if max.denom != epoch_reward.denom => Err(ContractError::InvalidRewardDenom {}),
+        Some(max) if epoch_reward.amount > max.amount => Err(ContractError::EpochRewardTooHigh {}),
+        _ => Ok(()),
+    }
+}
+
 /// Validator Metadata modeled after the Cosmos SDK staking module
 #[derive(
     Serialize, Deserialize, Clone, Eq, PartialEq, Ord, PartialOrd, JsonSchema, Debug, Default,
@@ -331,6 +728,11 @@ pub struct ValidatorMetadata {
 
     /// The validator's (optional) details
     pub details: Option<String>,
+
+    /// Portion of the validator's share of the epoch reward, sent directly to the operator
+    /// before the reward is split. Has to be at most 1, 0 by default.
+    #[serde(default)]
+    pub commission: Decimal,
 }
 
 pub const MIN_MONIKER_LENGTH: usize = 3;
@@ -384,6 +786,9 @@ impl ValidatorMetadata {
                 });
             }
         }
+        if self.commission > Decimal::one() {
+            return Err(ContractError::InvalidCommission {});
+        }
         Ok(())
     }
 }
@@ -398,7 +803,7 @@ pub struct OperatorInitInfo {
 
 impl OperatorInitInfo {
     pub fn validate(&self) -> Result<(), ContractError> {
-        Ed25519Pubkey::try_from(&self.validator_pubkey)?;
+        OperatorPubkey::try_from(&self.validator_pubkey)?;
         self.metadata.validate()
     }
 }
@@ -414,6 +819,23 @@ pub struct EpochResponse {
     pub last_update_height: u64,
     /// Seconds (UTC UNIX time) of next timestamp that will trigger a validator recalculation
     pub next_update_time: u64,
+    /// Whether epoch processing is paused with `SetEpochProcessingPaused`
+    #[serde(default)]
+    pub epoch_processing_paused: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct UpcomingEpochsResponse {
+    pub epochs: Vec<UpcomingEpoch>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct UpcomingEpoch {
+    /// The epoch #
+    pub epoch: u64,
+    /// Seconds (UTC UNIX time) of timestamp that will trigger validator recalculation for
+    /// this epoch
+    pub update_time: u64,
 }
 
 // data behind one operator
@@ -452,6 +874,8 @@ pub struct JailingPeriod {
 #[serde(rename_all = "snake_case")]
 pub enum JailingEnd {
     Until(Expiration),
+    /// Jailing expires at given block height
+    UntilHeight(u64),
     Forever {},
 }
 
@@ -461,6 +885,7 @@ impl JailingPeriod {
             start: block.time,
             end: match duration {
                 JailingDuration::Duration(duration) => JailingEnd::Until(duration.after(block)),
+                JailingDuration::Blocks(blocks) => JailingEnd::UntilHeight(block.height + blocks),
                 JailingDuration::Forever {} => JailingEnd::Forever {},
             },
         }
@@ -474,6 +899,7 @@ impl JailingPeriod {
         match self.end {
             JailingEnd::Forever {} => false,
             JailingEnd::Until(expires) => expires.is_expired(block),
+            JailingEnd::UntilHeight(height) => block.height >= height,
         }
     }
 }
@@ -492,6 +918,19 @@ pub struct ListValidatorResponse {
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct ListActiveValidatorsResponse {
     pub validators: Vec<ValidatorInfo>,
+    /// Sum of power of all validators in the set, not only the returned page
+    pub total_power: u64,
+}
+
+impl ListActiveValidatorsResponse {
+    /// Response with the whole validators set
+    pub fn new(validators: Vec<ValidatorInfo>) -> Self {
+        let total_power = validators.iter().map(|v| v.power).sum();
+        Self {
+            validators,
+            total_power,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -507,6 +946,193 @@ pub struct ListValidatorSlashingResponse {
     pub tombstoned: bool,
     /// If validator is jailed, it will show expiration time
     pub jailed_until: Option<Expiration>,
+    /// If validator is jailed for a number of blocks, it will show expiration height
+    pub jailed_until_height: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct JailingHistoryResponse {
+    /// Jailing records, oldest first
+    pub periods: Vec<JailingRecord>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct JailingRecord {
+    /// Sequence number of the jailing for the operator, starting from 0
+    pub id: u64,
+    /// Jailing period as it was imposed. Early unjailing does not shorten it.
+    pub period: JailingPeriod,
+    /// Time the operator was unjailed at. Unset if still jailed, or if the period was replaced
+    /// by a later jailing.
+    pub unjailed_at: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct ValidatorFullProfileResponse {
+    /// Validator key, metadata, jailing period and active status, as returned by
+    /// `QueryMsg::Validator`
+    pub validator: OperatorResponse,
+    /// Position in the active validator set sorted by power descending, starting from 0.
+    /// Unset if not in the active validator set
+    pub rank: Option<u32>,
+    /// Block height of first validator addition to validators set. Unset if never a validator
+    pub start_height: Option<u64>,
+    /// Slashing events, capped to the most recent 100
+    pub slashing: Vec<ValidatorSlashing>,
+    /// Whether or not a validator has been tombstoned (killed out of
+    /// validator set)
+    pub tombstoned: bool,
+    /// Height of the last block signed by the validator, if any
+    pub last_signed_height: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct PowerChange {
+    pub operator: Addr,
+    pub old_power: u64,
+    pub new_power: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct DoubleSignSlashing {
+    pub operator: Addr,
+    /// Block height of slashing event
+    pub slash_height: u64,
+    /// Portion of the operator stake which was slashed
+    pub portion: Decimal,
+    /// Stake tokens burned, if reported by the membership contract
+    pub amount: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct DoubleSignInfoResponse {
+    /// Currently configured `double_sign_slash_ratio`
+    pub slash_ratio: Decimal,
+    /// Most recent double sign slashing events, latest first
+    pub slashed: Vec<DoubleSignSlashing>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct PowerChangesResponse {
+    /// Changed operators, sorted by address
+    pub changes: Vec<PowerChange>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct OperatorByConsensusKeyResponse {
+    pub operator: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct ValidatePubkeyResponse {
+    pub valid: bool,
+    /// The pubkey in the form stored by this contract, if valid
+    pub normalized: Option<Pubkey>,
+    /// Reason of rejecting the pubkey, if invalid
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct EpochsSinceSignedResponse {
+    /// Number of epoch boundaries passed since the last signed boundary block. Unset if the
+    /// operator never signed one, or its key can't be verified
+    pub epochs: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct SimulateRewardsResponse {
+    pub denom: String,
+    /// Whole reward: minted tokens, collected fees and rewards forfeited in the previous payment
+    pub total: Uint128,
+    /// Part of `total` to be minted
+    pub minted: Uint128,
+    /// Rewards of distribution contracts, in `distribution_contracts` order
+    pub distribution_contracts: Vec<DistributionContractReward>,
+    /// Remainder sent to the validator group
+    pub validators_reward: Uint128,
+    /// Rewards of the active validators
+    pub validators: Vec<ValidatorReward>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct RewardAddressResponse {
+    pub address: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct DistributionContractStatsResponse {
+    /// Stats of distribution contracts, in `distribution_contracts` order
+    pub contracts: Vec<DistributionContractStats>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct DistributionContractStats {
+    pub contract: Addr,
+    pub ratio: Decimal,
+    /// Total rewards sent to the contract, in the epoch reward denom
+    pub distributed: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct IsTombstonedResponse {
+    pub tombstoned: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct ValidatorAtRisk {
+    pub operator: Addr,
+    /// Height of the last block signed by the validator. Unset if it never signed one
+    pub last_signed_height: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct ValidatorsAtRiskResponse {
+    pub validators: Vec<ValidatorAtRisk>,
+}
+
+/// Result of selecting the operator to the active validator set. Reasons are reported in order
+/// of the checks: the first failing one is returned.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Eligibility {
+    /// Operator would be an active validator with given power
+    Eligible { projected_power: u64 },
+    /// Operator is tombstoned and never becomes a validator again
+    Tombstoned,
+    /// Operator has no validator key registered
+    NotRegistered,
+    /// Operator is jailed, and is not to be unjailed automatically
+    Jailed,
+    /// Operator registered too recently, see `InstantiateMsg::delay_new_operators`
+    RegistrationPending,
+    /// Operator self bond is below `min_self_bond`
+    BelowMinSelfBond,
+    /// Operator has less than `min_points` points, and is not needed to pad the active set up
+    /// to `min_active_validators`
+    BelowMinPoints,
+    /// Operator passes all the filters, but `max_validators` operators rank higher
+    OutsideTopN,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct EligibilityResponse {
+    pub eligibility: Eligibility,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct DistributionContractReward {
+    pub contract: Addr,
+    pub reward: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct ValidatorReward {
+    pub operator: Addr,
+    /// Commission sent directly to the operator reward address
+    pub commission: Uint128,
+    /// Share of the validators reward, sent directly to the reward address if it is set,
+    /// distributed by the validator group otherwise
+    pub reward: Uint128,
 }
 
 /// Messages sent by this contract to an external contract
@@ -518,6 +1144,19 @@ pub enum DistributionMsg {
     DistributeRewards {},
 }
 
+/// Queries sent by this contract to the `membership` contract, if it is a tg4-stake contract
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum StakeQueryMsg {
+    Staked { address: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct StakedResponse {
+    pub liquid: Coin,
+    pub vesting: Coin,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct RewardsInstantiateMsg {
@@ -534,6 +1173,10 @@ pub enum RewardsDistribution {
         add: Vec<Member>,
     },
     DistributeRewards {},
+    SetRewardMultiplier {
+        addr: String,
+        multiplier: Decimal,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -583,6 +1226,23 @@ mod test {
             validator_group_code_id: 0,
             verify_validators: false,
             offline_jail_duration: Duration::new(0),
+            offline_jail_immunity: Duration::new(0),
+            min_active_validators: 0,
+            delay_new_operators: false,
+            active_set_retention: 0,
+            min_self_bond: Uint128::zero(),
+            probation_scaling: Decimal::one(),
+            probation_epochs: 0,
+            metadata_update_cooldown: Duration::new(0),
+            uptime_rewards: false,
+            tie_break: TieBreak::PubkeyLex,
+            downtime_slash_ratio: Decimal::zero(),
+            governance_slash_ratio: None,
+            max_power: None,
+            max_churn: None,
+            reward_vesting: false,
+            verification_grace_blocks: 0,
+            max_epoch_reward: None,
         };
         proper.validate().unwrap();
 
@@ -597,6 +1257,27 @@ mod test {
         let err = invalid.validate().unwrap_err();
         assert_eq!(err, ContractError::InvalidScaling {});
 
+        // fails on 0 max power
+        let mut invalid = proper.clone();
+        invalid.max_power = Some(0);
+        let err = invalid.validate().unwrap_err();
+        assert_eq!(err, ContractError::InvalidMaxPower {});
+
+        // fails on 0 max churn
+        let mut invalid = proper.clone();
+        invalid.max_churn = Some(0);
+        let err = invalid.validate().unwrap_err();
+        assert_eq!(err, ContractError::InvalidMaxChurn {});
+
+        // fails on probation scaling out of (0, 1] range
+        let mut invalid = proper.clone();
+        invalid.probation_scaling = Decimal::zero();
+        let err = invalid.validate().unwrap_err();
+        assert_eq!(err, ContractError::InvalidProbationScaling {});
+        invalid.probation_scaling = Decimal::percent(101);
+        let err = invalid.validate().unwrap_err();
+        assert_eq!(err, ContractError::InvalidProbationScaling {});
+
         // fails on 0 min points
         let mut invalid = proper.clone();
         invalid.min_points = 0;
@@ -626,6 +1307,23 @@ mod test {
         let err = invalid.validate().unwrap_err();
         assert_eq!(err, ContractError::InvalidPubkey {});
 
+        // fails if reward exceeds its cap
+        let mut invalid = proper.clone();
+        invalid.max_epoch_reward = Some(coin(7776, "foobar"));
+        let err = invalid.validate().unwrap_err();
+        assert_eq!(err, ContractError::EpochRewardTooHigh {});
+
+        // allows reward equal to its cap
+        let mut capped = proper.clone();
+        capped.max_epoch_reward = Some(coin(7777, "foobar"));
+        capped.validate().unwrap();
+
+        // fails if cap is in other denom than reward
+        let mut invalid = proper.clone();
+        invalid.max_epoch_reward = Some(coin(10000, "other"));
+        let err = invalid.validate().unwrap_err();
+        assert_eq!(err, ContractError::InvalidRewardDenom {});
+
         // fails if no denom set for reward
         let mut invalid = proper;
         invalid.epoch_reward.denom = "".into();
@@ -641,6 +1339,7 @@ mod test {
             website: Some((0..MAX_METADATA_SIZE + 1).map(|_| "X").collect::<String>()),
             security_contact: Some((0..MAX_METADATA_SIZE + 1).map(|_| "X").collect::<String>()),
             details: Some((0..MAX_METADATA_SIZE + 1).map(|_| "X").collect::<String>()),
+            commission: Decimal::zero(),
         };
         let resp = meta.validate().unwrap_err();
         assert_eq!(
@@ -759,5 +1458,20 @@ mod test {
         };
         let resp = meta.validate().unwrap_err();
         assert_eq!(ContractError::InvalidMetadataWebsitePrefix {}, resp);
+
+        let meta = ValidatorMetadata {
+            website: Some("https://website".to_owned()),
+            details: Some("details".to_owned()),
+            commission: Decimal::percent(101),
+            ..meta
+        };
+        let resp = meta.validate().unwrap_err();
+        assert_eq!(ContractError::InvalidCommission {}, resp);
+
+        let meta = ValidatorMetadata {
+            commission: Decimal::one(),
+            ..meta
+        };
+        meta.validate().unwrap();
     }
 }
@@ -1,13 +1,27 @@
+mod active_set_history;
 mod admin;
 mod contract;
+mod delay_new_operators;
 mod double_sign;
+mod eligibility;
 mod export_import;
 mod helpers;
 mod jailing;
+mod max_churn;
+mod max_power;
 mod migration;
+mod min_active_validators;
+mod min_self_bond;
+mod pause_epochs;
+mod power_changes;
+mod probation;
 mod rewards_split;
 mod slashing;
 mod stake;
 mod suite;
+mod tie_break;
+mod tombstone;
 mod update_config;
+mod uptime_rewards;
+mod validator_diff;
 mod verify_online;
@@ -0,0 +1,91 @@
+use cosmwasm_std::Addr;
+use tg_utils::Duration;
+
+use super::helpers::{assert_active_validators, members_init};
+use super::suite::SuiteBuilder;
+use crate::msg::PowerChange;
+
+#[test]
+fn active_set_not_retained_by_default() {
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_operators(&members)
+        .build();
+
+    suite.advance_epoch().unwrap();
+    let epoch = suite.epoch().unwrap().current_epoch;
+
+    suite.active_validators_at_epoch(epoch).unwrap_err();
+}
+
+#[test]
+fn active_set_queried_by_epoch() {
+    let members = ["member1", "member2", "member3"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members[..2], &[2, 3]))
+        .with_operators(&members)
+        .with_active_set_retention(2)
+        .build();
+
+    suite.advance_epoch().unwrap();
+    let first_epoch = suite.epoch().unwrap().current_epoch;
+
+    suite
+        .update_members(&[(members[1], 7), (members[2], 4)], &[members[0]])
+        .unwrap();
+    suite.advance_epoch().unwrap();
+    let second_epoch = suite.epoch().unwrap().current_epoch;
+
+    assert_active_validators(
+        &suite.active_validators_at_epoch(first_epoch).unwrap(),
+        &[(members[0], 2), (members[1], 3)],
+    );
+    assert_active_validators(
+        &suite.active_validators_at_epoch(second_epoch).unwrap(),
+        &[(members[1], 7), (members[2], 4)],
+    );
+
+    // Sets older than retention are pruned
+    suite.advance_epoch().unwrap();
+    suite.active_validators_at_epoch(first_epoch).unwrap_err();
+    assert_active_validators(
+        &suite.active_validators_at_epoch(second_epoch).unwrap(),
+        &[(members[1], 7), (members[2], 4)],
+    );
+}
+
+#[test]
+fn immediate_jailing_reflected_in_epoch_set() {
+    let members = ["member1", "member2", "member3"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5]))
+        .with_operators(&members)
+        .with_active_set_retention(2)
+        .with_epoch_length(1000)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    suite.advance_epoch().unwrap();
+    let epoch = suite.epoch().unwrap().current_epoch;
+    let epoch_height = suite.block_info().height;
+    suite.advance_seconds(1).unwrap();
+
+    suite
+        .jail_immediately(&admin, members[1], Duration::new(3600))
+        .unwrap();
+    suite.next_block().unwrap();
+
+    assert_active_validators(
+        &suite.active_validators_at_epoch(epoch).unwrap(),
+        &[(members[0], 2), (members[2], 5)],
+    );
+    assert_eq!(
+        suite.power_changes(epoch_height).unwrap(),
+        [PowerChange {
+            operator: Addr::unchecked(members[1]),
+            old_power: 3,
+            new_power: 0,
+        }]
+    );
+}
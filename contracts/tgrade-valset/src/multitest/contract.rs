@@ -1,13 +1,16 @@
 use crate::error::ContractError;
 use crate::msg::{
-    EpochResponse, ValidatorMetadata, MAX_METADATA_SIZE, MIN_METADATA_SIZE, MIN_MONIKER_LENGTH,
+    EpochResponse, TieBreak, UpcomingEpoch, ValidatorMetadata, MAX_METADATA_SIZE,
+    MIN_METADATA_SIZE, MIN_MONIKER_LENGTH,
 };
 use crate::state::Config;
+use crate::test_helpers::{mock_metadata, mock_pubkey, mock_secp256k1_pubkey};
 
 use super::helpers::{addr_to_pubkey, assert_active_validators, assert_operators, members_init};
 use super::suite::SuiteBuilder;
 use assert_matches::assert_matches;
-use cosmwasm_std::{coin, Decimal};
+use cosmwasm_std::{coin, Addr, Binary, Decimal, Uint128};
+use tg_bindings::{Pubkey, ValidatorUpdate};
 use tg_utils::Duration;
 
 #[test]
@@ -43,6 +46,23 @@ fn initialization() {
             validator_group: config.validator_group.clone(),
             verify_validators: false,
             offline_jail_duration: Duration::new(0),
+            offline_jail_immunity: Duration::new(0),
+            min_active_validators: 0,
+            delay_new_operators: false,
+            active_set_retention: 0,
+            min_self_bond: Uint128::zero(),
+            probation_scaling: Decimal::one(),
+            probation_epochs: 0,
+            metadata_update_cooldown: Duration::new(0),
+            uptime_rewards: false,
+            tie_break: TieBreak::PubkeyLex,
+            downtime_slash_ratio: Decimal::zero(),
+            governance_slash_ratio: None,
+            max_power: None,
+            max_churn: None,
+            reward_vesting: false,
+            verification_grace_blocks: 0,
+            max_epoch_reward: None,
         }
     );
 
@@ -76,6 +96,49 @@ fn initialization() {
     }
 }
 
+#[test]
+fn upcoming_epochs() {
+    let members = vec!["member1", "member2"];
+
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_operators(&members)
+        .with_epoch_length(3600)
+        .build();
+
+    let next_update_time = suite.epoch().unwrap().next_update_time;
+    let upcoming = suite.upcoming_epochs(3).unwrap();
+    assert_eq!(
+        upcoming,
+        vec![
+            UpcomingEpoch {
+                epoch: next_update_time / 3600,
+                update_time: next_update_time,
+            },
+            UpcomingEpoch {
+                epoch: next_update_time / 3600 + 1,
+                update_time: next_update_time + 3600,
+            },
+            UpcomingEpoch {
+                epoch: next_update_time / 3600 + 2,
+                update_time: next_update_time + 7200,
+            },
+        ]
+    );
+
+    // Moving within an epoch doesn't change upcoming epochs
+    suite.advance_seconds(1).unwrap();
+    assert_eq!(suite.upcoming_epochs(3).unwrap(), upcoming);
+
+    // Passing a boundary shifts them
+    suite.advance_epoch().unwrap();
+    assert_eq!(suite.upcoming_epochs(2).unwrap(), upcoming[1..]);
+
+    // Count is capped
+    assert_eq!(suite.upcoming_epochs(1000).unwrap().len(), 100);
+    assert!(suite.upcoming_epochs(0).unwrap().is_empty());
+}
+
 #[test]
 fn validators_query_pagination() {
     let members = vec!["member1", "member2", "member3", "member4", "member5"];
@@ -122,6 +185,43 @@ fn validators_query_pagination() {
     );
 }
 
+#[test]
+fn total_power_independent_of_pagination() {
+    let members = vec!["member1", "member2", "member3", "member4", "member5"];
+
+    let suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5, 8, 4]))
+        .with_operators(&members)
+        .with_scaling(10)
+        .build();
+
+    let mut pages = vec![];
+    let mut start_after = None;
+    loop {
+        let page = suite.list_active_validators_page(start_after, 2).unwrap();
+        let last = match page.validators.last() {
+            Some(last) => last.operator.to_string(),
+            None => break,
+        };
+        start_after = Some(last);
+        pages.push(page);
+    }
+
+    assert_eq!(pages.len(), 3);
+    let summed: u64 = pages
+        .iter()
+        .flat_map(|page| &page.validators)
+        .map(|v| v.power)
+        .sum();
+    assert_eq!(summed, 220);
+    for page in pages {
+        assert_eq!(page.total_power, summed);
+    }
+
+    let simulated = suite.simulate_active_validators_response().unwrap();
+    assert_eq!(simulated.total_power, summed);
+}
+
 #[test]
 fn simulate_validators() {
     let members = vec![
@@ -146,6 +246,46 @@ fn simulate_validators() {
     );
 }
 
+#[test]
+fn simulate_validators_with_overrides() {
+    let members = vec![
+        "member1", "member2", "member3", "member4", "member5", "member6",
+    ];
+
+    let suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5, 8, 13, 21]))
+        .with_operators(&members)
+        .with_max_validators(2)
+        .with_min_points(5)
+        .build();
+
+    // Boosting member below `min_points` brings it into the set
+    assert_active_validators(
+        &suite
+            .simulate_active_validators_with(&[(members[0], 30)])
+            .unwrap(),
+        &[(members[0], 30), (members[5], 21)],
+    );
+
+    // Lowering points pushes member out of the set
+    assert_active_validators(
+        &suite
+            .simulate_active_validators_with(&[(members[5], 1)])
+            .unwrap(),
+        &[(members[3], 8), (members[4], 13)],
+    );
+
+    // Nothing is changed by simulation
+    assert_active_validators(
+        &suite.simulate_active_validators().unwrap(),
+        &[(members[4], 13), (members[5], 21)],
+    );
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[4], 13), (members[5], 21)],
+    );
+}
+
 #[test]
 fn update_metadata() {
     let members = vec!["member1"];
@@ -160,6 +300,7 @@ fn update_metadata() {
         website: Some("https://www.funny.boy.rs".to_owned()),
         security_contact: Some("funny@boy.rs".to_owned()),
         details: Some("Comedian".to_owned()),
+        commission: Decimal::zero(),
     };
 
     suite.update_metadata(members[0], &meta).unwrap();
@@ -173,6 +314,7 @@ fn update_metadata() {
         website: Some("https://www.empty.one.rs".to_owned()),
         security_contact: Some("empty@one.rs".to_owned()),
         details: Some("Ghost".to_owned()),
+        commission: Decimal::zero(),
     };
 
     // Update with invalid meta (empty moniker) fails
@@ -200,6 +342,62 @@ fn update_metadata() {
     );
 }
 
+#[test]
+fn update_metadata_rate_limited() {
+    let members = vec!["member1"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2]))
+        .with_operators(&members)
+        .with_metadata_update_cooldown(100)
+        .build();
+
+    // First update is never limited
+    let first_update = suite.block_info().time;
+    suite
+        .update_metadata(members[0], &mock_metadata("first"))
+        .unwrap();
+
+    let err = suite
+        .update_metadata(members[0], &mock_metadata("second"))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MetadataUpdateTooSoon {
+            retry_at: first_update.plus_seconds(100)
+        },
+        err.downcast().unwrap()
+    );
+
+    suite.advance_seconds(99).unwrap();
+    suite
+        .update_metadata(members[0], &mock_metadata("second"))
+        .unwrap_err();
+
+    // Cooldown resets after the update
+    suite.advance_seconds(1).unwrap();
+    suite
+        .update_metadata(members[0], &mock_metadata("second"))
+        .unwrap();
+    let resp = suite.validator(members[0]).unwrap();
+    assert_eq!(resp.validator.unwrap().metadata, mock_metadata("second"));
+
+    suite.advance_seconds(50).unwrap();
+    suite
+        .update_metadata(members[0], &mock_metadata("third"))
+        .unwrap_err();
+
+    // Admin operator is not limited
+    let admin = suite.admin().to_owned();
+    suite
+        .register_validator_key(&admin, mock_pubkey(admin.as_bytes()), mock_metadata(&admin))
+        .unwrap();
+    suite
+        .update_metadata(&admin, &mock_metadata("first"))
+        .unwrap();
+    suite
+        .update_metadata(&admin, &mock_metadata("second"))
+        .unwrap();
+}
+
 #[test]
 fn list_validators() {
     let members = vec!["member1", "member2", "member3", "member4"];
@@ -289,6 +487,7 @@ fn register_key_invalid_metadata() {
         website: Some((0..MAX_METADATA_SIZE + 1).map(|_| "X").collect::<String>()),
         security_contact: Some((0..MAX_METADATA_SIZE + 1).map(|_| "X").collect::<String>()),
         details: Some((0..MAX_METADATA_SIZE + 1).map(|_| "X").collect::<String>()),
+        commission: Decimal::zero(),
     };
     let pubkey = addr_to_pubkey(members[0]);
     let resp = suite
@@ -323,6 +522,224 @@ fn register_key_invalid_metadata() {
     );
 }
 
+#[test]
+fn operator_by_consensus_key() {
+    let members = ["member1", "member2"];
+
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_operators(&members[..1])
+        .build();
+
+    // Operator registered on instantiation
+    assert_eq!(
+        suite
+            .operator_by_consensus_key(mock_pubkey(members[0].as_bytes()))
+            .unwrap(),
+        Some(Addr::unchecked(members[0]))
+    );
+
+    let pubkey = mock_pubkey(members[1].as_bytes());
+    assert_eq!(
+        suite.operator_by_consensus_key(pubkey.clone()).unwrap(),
+        None
+    );
+
+    suite
+        .register_validator_key(members[1], pubkey.clone(), mock_metadata(members[1]))
+        .unwrap();
+    assert_eq!(
+        suite.operator_by_consensus_key(pubkey).unwrap(),
+        Some(Addr::unchecked(members[1]))
+    );
+
+    // Malformed keys are rejected
+    suite
+        .operator_by_consensus_key(Pubkey::Ed25519(Binary(vec![1, 2, 3])))
+        .unwrap_err();
+}
+
+#[test]
+fn validate_pubkey() {
+    let members = ["member1"];
+
+    let suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2]))
+        .build();
+
+    let pubkey = mock_pubkey(b"valid");
+    let resp = suite.validate_pubkey(pubkey.clone()).unwrap();
+    assert!(resp.valid);
+    assert_eq!(resp.normalized, Some(pubkey));
+    assert_eq!(resp.error, None);
+
+    let pubkey = mock_secp256k1_pubkey(b"valid");
+    let resp = suite.validate_pubkey(pubkey.clone()).unwrap();
+    assert!(resp.valid);
+    assert_eq!(resp.normalized, Some(pubkey));
+
+    // ed25519 key of wrong length
+    let resp = suite
+        .validate_pubkey(Pubkey::Ed25519(Binary(vec![1, 2, 3])))
+        .unwrap();
+    assert!(!resp.valid);
+    assert_eq!(resp.normalized, None);
+    assert_eq!(
+        resp.error,
+        Some(ContractError::InvalidPubkey {}.to_string())
+    );
+
+    // uncompressed secp256k1 key
+    let resp = suite
+        .validate_pubkey(Pubkey::Secp256k1(Binary(vec![4; 33])))
+        .unwrap();
+    assert!(!resp.valid);
+    assert_eq!(
+        resp.error,
+        Some(ContractError::InvalidPubkey {}.to_string())
+    );
+
+    // unsupported key type
+    let resp = suite
+        .validate_pubkey(Pubkey::Sr25519(Binary(vec![1; 32])))
+        .unwrap();
+    assert!(!resp.valid);
+    assert_eq!(
+        resp.error,
+        Some(ContractError::UnsupportedPubkeyType {}.to_string())
+    );
+}
+
+#[test]
+fn deregister_frees_consensus_key() {
+    let members = ["member1", "member2", "member3"];
+
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5]))
+        .with_operators(&members[..2])
+        .build();
+    suite.advance_epoch().unwrap();
+
+    // Only operators can deregister
+    let err = suite.deregister(members[2]).unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized("No operator info found".to_owned()),
+        err.downcast().unwrap()
+    );
+
+    // Active validator keeps its key until it leaves the active set
+    let err = suite.deregister(members[0]).unwrap_err();
+    assert_eq!(
+        ContractError::ActiveValidator(members[0].to_owned()),
+        err.downcast().unwrap()
+    );
+
+    let pubkey = mock_pubkey(members[0].as_bytes());
+    let admin = suite.admin().to_owned();
+    suite.jail(&admin, members[0], Duration::new(3600)).unwrap();
+    let diff = suite.advance_epoch().unwrap().unwrap();
+    assert_eq!(
+        diff.diffs,
+        [ValidatorUpdate {
+            pubkey: pubkey.clone(),
+            power: 0
+        }]
+    );
+
+    suite.deregister(members[0]).unwrap();
+    assert_eq!(suite.validator(members[0]).unwrap().validator, None);
+    assert_eq!(
+        suite.operator_by_consensus_key(pubkey.clone()).unwrap(),
+        None
+    );
+
+    // Freed key can be registered by another operator in the same epoch
+    suite
+        .register_validator_key(members[2], pubkey.clone(), mock_metadata(members[2]))
+        .unwrap();
+    assert_eq!(
+        suite.operator_by_consensus_key(pubkey.clone()).unwrap(),
+        Some(Addr::unchecked(members[2]))
+    );
+
+    // Key is updated only once
+    let diff = suite.advance_epoch().unwrap().unwrap();
+    assert_eq!(diff.diffs, [ValidatorUpdate { pubkey, power: 5 }]);
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[2], 5), (members[1], 3)],
+    );
+}
+
+#[test]
+fn register_secp256k1_key() {
+    let members = ["member1", "member2", "member3"];
+
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5]))
+        .with_operators(&members[..1])
+        .build();
+    suite.advance_epoch().unwrap();
+
+    let pubkey = mock_secp256k1_pubkey(members[1].as_bytes());
+    let resp = suite
+        .register_validator_key(members[1], pubkey.clone(), mock_metadata(members[1]))
+        .unwrap();
+    let register = resp.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+    assert!(register
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "pubkey_type" && attr.value == "secp256k1"));
+    assert_eq!(
+        suite.operator_by_consensus_key(pubkey.clone()).unwrap(),
+        Some(Addr::unchecked(members[1]))
+    );
+
+    // Validator diff contains secp256k1 key
+    let diff = suite.advance_epoch().unwrap().unwrap();
+    assert_eq!(
+        diff.diffs,
+        [ValidatorUpdate {
+            pubkey: pubkey.clone(),
+            power: 3
+        }]
+    );
+
+    // Keys are unique no matter of the key type
+    suite
+        .register_validator_key(members[2], pubkey, mock_metadata(members[2]))
+        .unwrap_err();
+    suite
+        .register_validator_key(
+            members[2],
+            mock_pubkey(members[0].as_bytes()),
+            mock_metadata(members[2]),
+        )
+        .unwrap_err();
+
+    // Only compressed secp256k1 keys are accepted
+    let err = suite
+        .register_validator_key(
+            members[2],
+            Pubkey::Secp256k1(Binary(vec![4; 33])),
+            mock_metadata(members[2]),
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidPubkey {}, err.downcast().unwrap());
+
+    let err = suite
+        .register_validator_key(
+            members[2],
+            Pubkey::Sr25519(Binary(vec![1; 32])),
+            mock_metadata(members[2]),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::UnsupportedPubkeyType {},
+        err.downcast().unwrap()
+    );
+}
+
 #[test]
 fn update_metadata_invalid_metadata() {
     let members = vec!["member1"];
@@ -339,6 +756,7 @@ fn update_metadata_invalid_metadata() {
         website: Some((0..MAX_METADATA_SIZE + 1).map(|_| "X").collect::<String>()),
         security_contact: Some((0..MAX_METADATA_SIZE + 1).map(|_| "X").collect::<String>()),
         details: Some((0..MAX_METADATA_SIZE + 1).map(|_| "X").collect::<String>()),
+        commission: Decimal::zero(),
     };
     let resp = suite.update_metadata(members[0], &meta).unwrap_err();
     assert_eq!(
@@ -376,8 +794,8 @@ mod instantiate {
 
     use crate::error::ContractError;
     use crate::msg::{
-        InstantiateMsg, OperatorInitInfo, UnvalidatedDistributionContracts, ValidatorMetadata,
-        MAX_METADATA_SIZE, MIN_METADATA_SIZE,
+        InstantiateMsg, OperatorInitInfo, TieBreak, UnvalidatedDistributionContracts,
+        ValidatorMetadata, MAX_METADATA_SIZE, MIN_METADATA_SIZE,
     };
     use crate::multitest::suite::{contract_stake, contract_valset};
     use crate::test_helpers::mock_pubkey;
@@ -391,13 +809,19 @@ mod instantiate {
         let admin = "steakhouse owner".to_owned();
         let msg = tg4_stake::msg::InstantiateMsg {
             denom: "james bond denom".to_owned(),
-            tokens_per_point: Uint128::new(10),
+            tokens_per_point: Decimal::from_ratio(10u128, 1u128),
             min_bond: Uint128::new(1),
             unbonding_period: 1234,
             admin: Some(admin.clone()),
             preauths_hooks: 0,
             preauths_slashing: 1,
             auto_return_limit: 0,
+            reject_zero_funds: false,
+            max_bond_per_tx: None,
+            min_stake_duration: None,
+            max_claims_per_addr: None,
+            slash_policy: tg4_stake::msg::SlashPolicy::Remove,
+            points_rounding: tg4_stake::msg::Rounding::Down,
         };
         let stake_addr = app
             .instantiate_contract(
@@ -437,6 +861,23 @@ mod instantiate {
             validator_group_code_id: 1,
             verify_validators: false,
             offline_jail_duration: Duration::new(0),
+            offline_jail_immunity: Duration::new(0),
+            min_active_validators: 0,
+            delay_new_operators: false,
+            active_set_retention: 0,
+            min_self_bond: Uint128::zero(),
+            probation_scaling: Decimal::one(),
+            probation_epochs: 0,
+            metadata_update_cooldown: Duration::new(0),
+            uptime_rewards: false,
+            tie_break: TieBreak::PubkeyLex,
+            downtime_slash_ratio: Decimal::zero(),
+            governance_slash_ratio: None,
+            max_power: None,
+            max_churn: None,
+            reward_vesting: false,
+            verification_grace_blocks: 0,
+            max_epoch_reward: None,
         };
 
         let err = app
@@ -0,0 +1,75 @@
+use super::helpers::{assert_active_validators, members_init};
+use super::suite::SuiteBuilder;
+use crate::test_helpers::{mock_metadata, mock_pubkey};
+
+#[test]
+fn new_operator_eligible_immediately_by_default() {
+    let members = ["member1", "member2", "member3"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5]))
+        .with_operators(&members[..2])
+        .build();
+
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[1], 3)],
+    );
+
+    suite
+        .register_validator_key(
+            members[2],
+            mock_pubkey(members[2].as_bytes()),
+            mock_metadata(members[2]),
+        )
+        .unwrap();
+
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[1], 3), (members[2], 5)],
+    );
+}
+
+#[test]
+fn new_operator_delayed_by_an_epoch() {
+    let members = ["member1", "member2", "member3"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5]))
+        .with_operators(&members[..2])
+        .with_delay_new_operators()
+        .build();
+
+    // Operators registered on instantiation are not delayed
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[1], 3)],
+    );
+
+    suite
+        .register_validator_key(
+            members[2],
+            mock_pubkey(members[2].as_bytes()),
+            mock_metadata(members[2]),
+        )
+        .unwrap();
+
+    // Registered in the current epoch, not eligible yet
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[1], 3)],
+    );
+    assert_active_validators(
+        &suite.simulate_active_validators().unwrap(),
+        &[(members[0], 2), (members[1], 3)],
+    );
+
+    // Included in the next one
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[1], 3), (members[2], 5)],
+    );
+}
@@ -1,12 +1,12 @@
-use cosmwasm_std::coin;
-use cosmwasm_std::{Binary, Decimal};
-use tg_bindings::{Ed25519Pubkey, Evidence, EvidenceType, ToAddress, Validator};
+use cosmwasm_std::{coin, coins};
+use cosmwasm_std::{Addr, Binary, Decimal, Uint128};
+use tg_bindings::{Ed25519Pubkey, Evidence, EvidenceType, Secp256k1Pubkey, ToAddress, Validator};
 
 use super::helpers::{addr_to_pubkey, assert_operators};
 use super::suite::SuiteBuilder;
-use crate::msg::{JailingEnd, ValidatorMetadata};
+use crate::msg::{DoubleSignInfoResponse, DoubleSignSlashing, JailingEnd, ValidatorMetadata};
 use crate::multitest::helpers::members_init;
-use crate::test_helpers::mock_pubkey;
+use crate::test_helpers::{mock_metadata, mock_pubkey, mock_secp256k1_pubkey};
 
 use std::convert::TryFrom;
 
@@ -105,17 +105,22 @@ fn evidence_doesnt_affect_engagement_rewards() {
         ],
     );
 
+    // Validation reward of jailed validator is paid out when it is removed from the validators
+    // group
     suite.advance_epoch().unwrap();
+    assert_eq!(suite.token_balance(members[0].0).unwrap(), 1500);
+    assert_eq!(suite.token_balance(members[1].0).unwrap(), 0);
+
     suite.withdraw_distribution_reward(members[0].0, 0).unwrap();
     suite.withdraw_distribution_reward(members[1].0, 0).unwrap();
-    assert_eq!(suite.token_balance(members[0].0).unwrap(), 1500);
+    assert_eq!(suite.token_balance(members[0].0).unwrap(), 3000);
     assert_eq!(suite.token_balance(members[1].0).unwrap(), 1500);
 
     // Both validators get equal engagement reward
     suite.advance_epoch().unwrap();
     suite.withdraw_distribution_reward(members[0].0, 0).unwrap();
     suite.withdraw_distribution_reward(members[1].0, 0).unwrap();
-    assert_eq!(suite.token_balance(members[0].0).unwrap(), 2250);
+    assert_eq!(suite.token_balance(members[0].0).unwrap(), 3750);
     assert_eq!(suite.token_balance(members[1].0).unwrap(), 2250);
 }
 
@@ -183,6 +188,97 @@ fn multiple_evidences() {
     );
 }
 
+#[test]
+fn double_sign_info() {
+    let member_addrs = [
+        "reallylongaddresstofit32charact1",
+        "reallylongaddresstofit32charact2",
+    ];
+    let members = members_init(&member_addrs, &[10, 10]);
+
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members)
+        .with_operators_pubkeys(&member_addrs)
+        .build();
+
+    // Slashing by the admin is not reported
+    let admin = suite.admin().to_owned();
+    suite
+        .slash(&admin, members[1].0, Decimal::percent(10))
+        .unwrap();
+    assert_eq!(
+        suite.double_sign_info().unwrap(),
+        DoubleSignInfoResponse {
+            slash_ratio: Decimal::percent(50),
+            slashed: vec![],
+        }
+    );
+
+    let evidence = create_evidence_for_member(members[0], suite.height() + 1);
+    suite.next_block_with_evidence(vec![evidence]).unwrap();
+    let slash_height = suite.height();
+
+    assert_eq!(
+        suite.double_sign_info().unwrap(),
+        DoubleSignInfoResponse {
+            slash_ratio: Decimal::percent(50),
+            slashed: vec![DoubleSignSlashing {
+                operator: Addr::unchecked(members[0].0),
+                slash_height,
+                portion: Decimal::percent(50),
+                // Engagement contract doesn't report slashed amounts
+                amount: None,
+            }],
+        }
+    );
+}
+
+#[test]
+fn double_sign_info_reports_burned_stake() {
+    let member_addrs = [
+        "reallylongaddresstofit32charact1",
+        "reallylongaddresstofit32charact2",
+    ];
+    let mut suite = SuiteBuilder::new()
+        .with_stake("tgrade", 10u128)
+        .with_operators_pubkeys(&member_addrs)
+        .with_funds(&[
+            (member_addrs[0], &coins(301, "tgrade")),
+            (member_addrs[1], &coins(100, "tgrade")),
+        ])
+        .with_min_points(1)
+        .with_epoch_reward(coin(0, "usdc"))
+        .build();
+
+    suite
+        .bond(&Addr::unchecked(member_addrs[0]), &coins(301, "tgrade"))
+        .unwrap();
+    suite
+        .bond(&Addr::unchecked(member_addrs[1]), &coins(100, "tgrade"))
+        .unwrap();
+    suite.advance_epoch().unwrap();
+    // Validators start validating in the block following the epoch change
+    suite.advance_blocks(1).unwrap();
+
+    let evidence = create_evidence_for_member((member_addrs[0], 30), suite.height() + 1);
+    suite.next_block_with_evidence(vec![evidence]).unwrap();
+    let slash_height = suite.height();
+
+    assert_eq!(
+        suite.double_sign_info().unwrap(),
+        DoubleSignInfoResponse {
+            slash_ratio: Decimal::percent(50),
+            slashed: vec![DoubleSignSlashing {
+                operator: Addr::unchecked(member_addrs[0]),
+                slash_height,
+                portion: Decimal::percent(50),
+                // Rounded down by the stake contract
+                amount: Some(Uint128::new(150)),
+            }],
+        }
+    );
+}
+
 #[test]
 fn evidence_with_not_matching_date() {
     let member_addrs = vec![
@@ -206,6 +302,7 @@ fn evidence_with_not_matching_date() {
         website: Some("https://www.funny.boy.rs".to_owned()),
         security_contact: Some("funny@boy.rs".to_owned()),
         details: Some("Comedian".to_owned()),
+        commission: Decimal::zero(),
     };
     let pubkey = addr_to_pubkey(members[2].0);
     suite
@@ -233,3 +330,41 @@ fn evidence_with_not_matching_date() {
         ],
     );
 }
+
+#[test]
+fn evidence_matches_secp256k1_validator() {
+    let members = ["member1", "member2"];
+
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[10, 10]))
+        .with_operators(&members[..1])
+        .build();
+
+    let pubkey = mock_secp256k1_pubkey(members[1].as_bytes());
+    suite
+        .register_validator_key(members[1], pubkey.clone(), mock_metadata(members[1]))
+        .unwrap();
+    suite.advance_epoch().unwrap();
+    suite.next_block().unwrap();
+
+    let address = Secp256k1Pubkey::try_from(pubkey).unwrap().to_address();
+    let evidence = Evidence {
+        evidence_type: EvidenceType::DuplicateVote,
+        validator: Validator {
+            address: Binary::from(address.to_vec()),
+            power: 10,
+        },
+        height: suite.height() + 1,
+        time: 3,
+        total_voting_power: 20,
+    };
+    suite.next_block_with_evidence(vec![evidence]).unwrap();
+
+    assert_operators(
+        &suite.list_validators(None, None).unwrap(),
+        &[
+            (members[0], None),
+            (members[1], Some(JailingEnd::Forever {})),
+        ],
+    );
+}
@@ -0,0 +1,159 @@
+use cosmwasm_std::{coin, coins, Addr};
+use tg_utils::{Duration, JailingDuration};
+
+use super::helpers::members_init;
+use super::suite::SuiteBuilder;
+use crate::msg::Eligibility;
+use crate::test_helpers::{mock_metadata, mock_pubkey};
+
+const BOND_DENOM: &str = "tgrade";
+
+#[test]
+fn selected_operator_is_eligible() {
+    let members = ["member1", "member2", "member3"];
+    let suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5]))
+        .with_operators(&members)
+        .with_max_validators(2)
+        .build();
+
+    assert_eq!(
+        suite.eligibility(members[2]).unwrap(),
+        Eligibility::Eligible { projected_power: 5 }
+    );
+    assert_eq!(
+        suite.eligibility(members[1]).unwrap(),
+        Eligibility::Eligible { projected_power: 3 }
+    );
+    assert_eq!(
+        suite.eligibility(members[0]).unwrap(),
+        Eligibility::OutsideTopN
+    );
+}
+
+#[test]
+fn unregistered_and_low_points_operators_not_eligible() {
+    let members = ["member1", "member2", "member3", "member4"];
+    let suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5, 0]))
+        .with_operators(&[members[0], members[1], members[3]])
+        .with_min_points(3)
+        .build();
+
+    assert_eq!(
+        suite.eligibility(members[1]).unwrap(),
+        Eligibility::Eligible { projected_power: 3 }
+    );
+    assert_eq!(
+        suite.eligibility(members[0]).unwrap(),
+        Eligibility::BelowMinPoints
+    );
+    assert_eq!(
+        suite.eligibility(members[2]).unwrap(),
+        Eligibility::NotRegistered
+    );
+    assert_eq!(
+        suite.eligibility(members[3]).unwrap(),
+        Eligibility::BelowMinPoints
+    );
+    assert_eq!(
+        suite.eligibility("somebody").unwrap(),
+        Eligibility::NotRegistered
+    );
+}
+
+#[test]
+fn jailed_and_tombstoned_operators_not_eligible() {
+    let members = ["member1", "member2", "member3"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5]))
+        .with_operators(&members)
+        .with_auto_unjail()
+        .build();
+    let admin = suite.admin().to_owned();
+
+    suite.jail(&admin, members[0], Duration::new(3600)).unwrap();
+    suite.tombstone(&admin, members[1]).unwrap();
+    suite
+        .jail(&admin, members[2], JailingDuration::Forever {})
+        .unwrap();
+
+    assert_eq!(suite.eligibility(members[0]).unwrap(), Eligibility::Jailed);
+    assert_eq!(
+        suite.eligibility(members[1]).unwrap(),
+        Eligibility::Tombstoned
+    );
+    assert_eq!(
+        suite.eligibility(members[2]).unwrap(),
+        Eligibility::Tombstoned
+    );
+
+    // expired jailing doesn't matter with auto unjailing enabled
+    suite.advance_seconds(3800).unwrap();
+    assert_eq!(
+        suite.eligibility(members[0]).unwrap(),
+        Eligibility::Eligible { projected_power: 2 }
+    );
+}
+
+#[test]
+fn recently_registered_operator_not_eligible() {
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_operators(&members[..1])
+        .with_delay_new_operators()
+        .build();
+
+    suite
+        .register_validator_key(
+            members[1],
+            mock_pubkey(members[1].as_bytes()),
+            mock_metadata(members[1]),
+        )
+        .unwrap();
+    assert_eq!(
+        suite.eligibility(members[1]).unwrap(),
+        Eligibility::RegistrationPending
+    );
+
+    suite.advance_epoch().unwrap();
+    suite.advance_epoch().unwrap();
+    assert_eq!(
+        suite.eligibility(members[1]).unwrap(),
+        Eligibility::Eligible { projected_power: 3 }
+    );
+}
+
+#[test]
+fn operator_with_low_self_bond_not_eligible() {
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_stake(BOND_DENOM, 10u128)
+        .with_operators(&members)
+        .with_funds(&[
+            (members[0], &coins(100, BOND_DENOM)),
+            (members[1], &coins(60, BOND_DENOM)),
+        ])
+        .with_epoch_reward(coin(0, "usdc"))
+        .with_min_self_bond(80)
+        .build();
+
+    suite
+        .bond(&Addr::unchecked(members[0]), &coins(100, BOND_DENOM))
+        .unwrap();
+    suite
+        .bond(&Addr::unchecked(members[1]), &coins(60, BOND_DENOM))
+        .unwrap();
+
+    assert_eq!(
+        suite.eligibility(members[0]).unwrap(),
+        Eligibility::Eligible {
+            projected_power: 10
+        }
+    );
+    assert_eq!(
+        suite.eligibility(members[1]).unwrap(),
+        Eligibility::BelowMinSelfBond
+    );
+}
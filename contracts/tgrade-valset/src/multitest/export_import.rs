@@ -1,15 +1,22 @@
 use crate::contract::{CONTRACT_NAME, CONTRACT_VERSION};
-use crate::msg::OperatorResponse;
+use crate::msg::{JailingEnd, JailingPeriod, JailingRecord, OperatorResponse, TieBreak};
 use crate::multitest::helpers::addr_to_pubkey;
 use crate::multitest::suite::{Suite, SuiteBuilder};
 use crate::state::{
-    Config, EpochInfo, SlashingResponse, StartHeightResponse, ValidatorInfo, ValidatorSlashing,
-    ValsetState,
+    Config, EpochInfo, EpochOffset, OperatorValue, SlashingCause, SlashingResponse,
+    StartHeightResponse, ValidatorInfo, ValidatorSlashing, ValsetState,
 };
-use cosmwasm_std::{coin, Addr, Decimal};
+use cosmwasm_std::{coin, Addr, Decimal, Timestamp, Uint128};
 use cw2::ContractVersion;
 use tg4::Tg4Contract;
-use tg_utils::Duration;
+use tg_utils::{Duration, Expiration};
+
+fn value<T>(operator: &str, value: T) -> OperatorValue<T> {
+    OperatorValue {
+        operator: operator.to_owned(),
+        value,
+    }
+}
 
 #[test]
 fn export_contains_all_state() {
@@ -68,7 +75,24 @@ fn export_works() {
             distribution_contracts: vec![],
             validator_group: suite.validator_group.clone(),
             verify_validators: false,
-            offline_jail_duration: Duration::new(0)
+            offline_jail_duration: Duration::new(0),
+            offline_jail_immunity: Duration::new(0),
+            min_active_validators: 0,
+            delay_new_operators: false,
+            active_set_retention: 0,
+            min_self_bond: Uint128::zero(),
+            probation_scaling: Decimal::one(),
+            probation_epochs: 0,
+            metadata_update_cooldown: Duration::new(0),
+            uptime_rewards: false,
+            tie_break: TieBreak::PubkeyLex,
+            downtime_slash_ratio: Decimal::zero(),
+            governance_slash_ratio: None,
+            max_power: None,
+            max_churn: None,
+            reward_vesting: false,
+            verification_grace_blocks: 0,
+            max_epoch_reward: None,
         }
     );
 
@@ -79,6 +103,7 @@ fn export_works() {
             current_epoch: suite.epoch().unwrap().current_epoch,
             last_update_time: 0,
             last_update_height: 0,
+            epoch_offset: EpochOffset::default(),
         }
     );
 
@@ -117,12 +142,30 @@ fn import_works() {
             validator_group: Addr::unchecked("validator_group"),
             verify_validators: true,
             offline_jail_duration: Duration::new(86400),
+            offline_jail_immunity: Duration::new(0),
+            min_active_validators: 0,
+            delay_new_operators: false,
+            active_set_retention: 0,
+            min_self_bond: Uint128::zero(),
+            probation_scaling: Decimal::one(),
+            probation_epochs: 0,
+            metadata_update_cooldown: Duration::new(0),
+            uptime_rewards: false,
+            tie_break: TieBreak::PubkeyLex,
+            downtime_slash_ratio: Decimal::zero(),
+            governance_slash_ratio: None,
+            max_power: None,
+            max_churn: None,
+            reward_vesting: false,
+            verification_grace_blocks: 0,
+            max_epoch_reward: None,
         },
         epoch: EpochInfo {
             epoch_length: 1000,
             current_epoch: 1234,
             last_update_time: 1,
             last_update_height: 2,
+            epoch_offset: EpochOffset::default(),
         },
         operators: vec![OperatorResponse {
             operator: member_addr.to_owned(),
@@ -145,8 +188,28 @@ fn import_works() {
             slashing: vec![ValidatorSlashing {
                 slash_height: 1234,
                 portion: Decimal::percent(25),
+                cause: Some(SlashingCause::Governance),
+                amount: Some(Uint128::new(500)),
             }],
         }],
+        probation_start_epochs: vec![value(member_addr, 1235)],
+        metadata_updated_at: vec![value(member_addr, Timestamp::from_seconds(100))],
+        tombstoned: vec![value(member_addr, 1200)],
+        unjailed_at: vec![value(member_addr, Timestamp::from_seconds(200))],
+        reward_addresses: vec![value(member_addr, Addr::unchecked("reward_address"))],
+        registration_epochs: vec![value(member_addr, 1000)],
+        jailing_history: vec![value(
+            member_addr,
+            JailingRecord {
+                id: 0,
+                period: JailingPeriod {
+                    start: Timestamp::from_seconds(50),
+                    end: JailingEnd::Until(Expiration::at_timestamp(Timestamp::from_seconds(150))),
+                },
+                unjailed_at: Some(Timestamp::from_seconds(200)),
+            },
+        )],
+        active_since: vec![value(member_addr, 1100)],
     };
 
     suite.import(imp.clone()).unwrap();
@@ -164,6 +227,10 @@ fn import_deletes_existing_entries() {
     let mut suite = SuiteBuilder::new()
         .with_operators(&[member_addr_ori])
         .build();
+    let admin = suite.admin().to_owned();
+    suite
+        .jail(&admin, member_addr_ori, Duration::new(3600))
+        .unwrap();
 
     let imp = ValsetState {
         contract_version: ContractVersion {
@@ -184,12 +251,30 @@ fn import_deletes_existing_entries() {
             validator_group: Addr::unchecked("validator_group"),
             verify_validators: true,
             offline_jail_duration: Duration::new(86400),
+            offline_jail_immunity: Duration::new(0),
+            min_active_validators: 0,
+            delay_new_operators: false,
+            active_set_retention: 0,
+            min_self_bond: Uint128::zero(),
+            probation_scaling: Decimal::one(),
+            probation_epochs: 0,
+            metadata_update_cooldown: Duration::new(0),
+            uptime_rewards: false,
+            tie_break: TieBreak::PubkeyLex,
+            downtime_slash_ratio: Decimal::zero(),
+            governance_slash_ratio: None,
+            max_power: None,
+            max_churn: None,
+            reward_vesting: false,
+            verification_grace_blocks: 0,
+            max_epoch_reward: None,
         },
         epoch: EpochInfo {
             epoch_length: 1000,
             current_epoch: 1234,
             last_update_time: 1,
             last_update_height: 2,
+            epoch_offset: EpochOffset::default(),
         },
         operators: vec![OperatorResponse {
             operator: member_addr_new.to_owned(),
@@ -201,6 +286,14 @@ fn import_deletes_existing_entries() {
         validators: vec![],
         validators_start_height: vec![],
         validators_slashing: vec![],
+        probation_start_epochs: vec![],
+        metadata_updated_at: vec![],
+        tombstoned: vec![],
+        unjailed_at: vec![],
+        reward_addresses: vec![],
+        registration_epochs: vec![],
+        jailing_history: vec![],
+        active_since: vec![],
     };
 
     suite.import(imp.clone()).unwrap();
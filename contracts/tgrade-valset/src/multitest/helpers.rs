@@ -1,5 +1,7 @@
+use std::convert::TryInto;
+
 use cosmwasm_std::Binary;
-use tg_bindings::Pubkey;
+use tg_bindings::{Ed25519Pubkey, Pubkey, ToAddress};
 
 use crate::msg::{JailingEnd, OperatorResponse};
 use crate::state::ValidatorInfo;
@@ -10,6 +12,13 @@ pub fn addr_to_pubkey(addr: &str) -> Pubkey {
     Pubkey::Ed25519(Binary((*addr).as_bytes().to_vec()))
 }
 
+// Converts address to the validator address used in votes
+// Requires addr to be exactly 32 bytes long, panics otherwise
+pub fn addr_to_vote_addr(addr: &str) -> Binary {
+    let pubkey: Ed25519Pubkey = addr_to_pubkey(addr).try_into().unwrap();
+    Binary(pubkey.to_address().to_vec())
+}
+
 pub fn members_init<'m>(members: &[&'m str], points: &[u64]) -> Vec<(&'m str, u64)> {
     members
         .iter()
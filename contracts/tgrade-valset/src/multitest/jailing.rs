@@ -1,10 +1,14 @@
 use crate::error::ContractError;
-use crate::msg::{JailingEnd, ValidatorResponse};
+use crate::msg::{JailingEnd, ValidatorResponse, ValidatorStatus};
+use crate::test_helpers::mock_pubkey;
 
-use super::helpers::{assert_active_validators, assert_operators, members_init};
-use super::suite::SuiteBuilder;
-use cosmwasm_std::{StdResult, Timestamp};
+use std::convert::TryInto;
+
+use super::helpers::{addr_to_pubkey, assert_active_validators, assert_operators, members_init};
+use super::suite::{Suite, SuiteBuilder};
+use cosmwasm_std::{Binary, Decimal, StdResult, Timestamp};
 use cw_controllers::AdminError;
+use tg_bindings::{Ed25519Pubkey, ToAddress, ValidatorUpdate, ValidatorVote};
 use tg_utils::{Duration, Expiration, JailingDuration};
 
 #[test]
@@ -179,6 +183,49 @@ fn anyone_can_unjail_self_after_period() {
     )
 }
 
+#[test]
+fn unjail_self_after_blocks() {
+    let members = vec!["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_operators(&members)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    let height = suite.block_info().height;
+    suite
+        .jail(&admin, members[0], JailingDuration::Blocks(10))
+        .unwrap();
+
+    assert_operators(
+        &suite.list_validators(None, None).unwrap(),
+        &[
+            (members[0], Some(JailingEnd::UntilHeight(height + 10))),
+            (members[1], None),
+        ],
+    );
+    let slashing = suite.list_validator_slashing(members[0]).unwrap();
+    assert_eq!(slashing.jailed_until, None);
+    assert_eq!(slashing.jailed_until_height, Some(height + 10));
+
+    // Plenty of time passes, but not enough blocks for jailing to expire
+    suite.app().update_block(|block| {
+        block.time = block.time.plus_seconds(86400);
+        block.height += 9;
+    });
+
+    let err = suite.unjail(members[0], None).unwrap_err();
+    assert_eq!(ContractError::JailDidNotExpire {}, err.downcast().unwrap(),);
+
+    suite.app().update_block(|block| block.height += 1);
+    suite.unjail(members[0], None).unwrap();
+
+    assert_operators(
+        &suite.list_validators(None, None).unwrap(),
+        &[(members[0], None), (members[1], None)],
+    );
+}
+
 #[test]
 fn jailed_validators_are_ignored_on_selection() {
     let members = vec!["member1", "member2", "member3", "member4"];
@@ -412,6 +459,61 @@ fn list_jailed_validators_with_pagination() {
     assert_eq!(operators[1].operator, members[4]);
 }
 
+#[test]
+fn list_validators_by_status() {
+    let members = vec!["member1", "member2", "member3", "member4"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5, 8]))
+        .with_operators(&members)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    suite.jail(&admin, members[0], Duration::new(3600)).unwrap();
+    suite
+        .jail(&admin, members[2], JailingDuration::Forever {})
+        .unwrap();
+    suite.advance_epoch().unwrap();
+
+    fn operators(suite: &Suite, status: ValidatorStatus) -> Vec<String> {
+        suite
+            .list_validators_by_status(status, None, None)
+            .unwrap()
+            .into_iter()
+            .map(|op| op.operator)
+            .collect()
+    }
+    assert_eq!(
+        operators(&suite, ValidatorStatus::Active),
+        vec![members[1], members[3]]
+    );
+    assert_eq!(operators(&suite, ValidatorStatus::Jailed), vec![members[0]]);
+    assert_eq!(
+        operators(&suite, ValidatorStatus::Tombstoned),
+        vec![members[2]]
+    );
+
+    // Pagination skips operators in other statuses
+    let page = suite
+        .list_validators_by_status(ValidatorStatus::Active, None, 1)
+        .unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].operator, members[1]);
+    let page = suite
+        .list_validators_by_status(ValidatorStatus::Active, page[0].operator.clone(), None)
+        .unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].operator, members[3]);
+
+    // Unjailed operator is not listed as jailed anymore
+    suite.advance_seconds(4000).unwrap();
+    suite.unjail(&admin, members[0]).unwrap();
+    assert!(operators(&suite, ValidatorStatus::Jailed).is_empty());
+    assert_eq!(
+        operators(&suite, ValidatorStatus::Tombstoned),
+        vec![members[2]]
+    );
+}
+
 #[test]
 fn jailing_duration_start_is_provided() {
     let members = vec!["member1", "member2"];
@@ -436,3 +538,217 @@ fn jailing_duration_start_is_provided() {
     assert_eq!(time1, jail_start(suite.validator(members[0])));
     assert_eq!(time2, jail_start(suite.validator(members[1])));
 }
+
+#[test]
+fn full_profile_matches_individual_queries() {
+    let members = vec![
+        "member1member1member1member1memb",
+        "member2member2member2member2memb",
+        "member3member3member3member3memb",
+    ];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5]))
+        .with_operators(&members)
+        .with_verify_validators(600)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    let pubkey: Ed25519Pubkey = addr_to_pubkey(members[0]).try_into().unwrap();
+    suite
+        .set_votes(&[ValidatorVote {
+            address: Binary(pubkey.to_address().to_vec()),
+            power: 2,
+            voted: true,
+        }])
+        .unwrap();
+    suite.next_block().unwrap();
+    let signed_height = suite.height();
+
+    suite
+        .slash(&admin, members[0], Decimal::percent(50))
+        .unwrap();
+    suite.jail(&admin, members[0], Duration::new(3600)).unwrap();
+
+    let profile = suite.validator_full_profile(members[0]).unwrap();
+    let validator = suite.validator(members[0]).unwrap().validator.unwrap();
+    let slashing = suite.list_validator_slashing(members[0]).unwrap();
+    let rank = suite
+        .list_active_validators(None, None)
+        .unwrap()
+        .iter()
+        .position(|v| v.operator == members[0])
+        .map(|rank| rank as u32);
+
+    assert!(profile.validator.jailed_until.is_some());
+    assert_eq!(profile.validator, validator);
+    assert_eq!(profile.rank, rank);
+    assert_eq!(profile.rank, Some(2));
+    assert_eq!(profile.start_height, Some(slashing.start_height));
+    assert_eq!(profile.slashing.len(), 1);
+    assert_eq!(profile.slashing, slashing.slashing);
+    assert_eq!(profile.tombstoned, slashing.tombstoned);
+    assert_eq!(profile.last_signed_height, Some(signed_height));
+
+    // Once jailed validator drops out of the active set, it has no rank anymore
+    suite.advance_epoch().unwrap();
+    let profile = suite.validator_full_profile(members[0]).unwrap();
+    assert_eq!(profile.rank, None);
+
+    // Validators never signing a block have no signing info
+    let profile = suite.validator_full_profile(members[1]).unwrap();
+    assert_eq!(profile.last_signed_height, None);
+    assert!(profile.slashing.is_empty());
+}
+
+#[test]
+fn immediate_jailing_removes_validator_in_same_block() {
+    let members = vec!["member1", "member2", "member3"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5]))
+        .with_operators(&members)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    suite.advance_epoch().unwrap();
+    suite.advance_seconds(1).unwrap();
+
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[1], 3), (members[2], 5)],
+    );
+
+    suite
+        .jail_immediately(&admin, members[1], Duration::new(3600))
+        .unwrap();
+    let jailed_until = JailingEnd::Until(Duration::new(3600).after(&suite.app().block_info()));
+
+    // Validator is removed at the end of the block, without waiting for the epoch boundary
+    let diff = suite.next_block().unwrap().unwrap();
+    assert_eq!(
+        diff.diffs,
+        vec![ValidatorUpdate {
+            pubkey: mock_pubkey(members[1].as_bytes()),
+            power: 0,
+        }]
+    );
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[2], 5)],
+    );
+    assert_operators(
+        &suite.list_validators(None, None).unwrap(),
+        &[
+            (members[0], None),
+            (members[1], Some(jailed_until)),
+            (members[2], None),
+        ],
+    );
+
+    // Nothing more changes, also on the epoch boundary
+    assert_eq!(suite.next_block().unwrap(), None);
+    let diff = suite.advance_epoch().unwrap().unwrap();
+    assert!(diff.diffs.is_empty());
+}
+
+#[test]
+fn regular_jailing_waits_for_epoch_boundary() {
+    let members = vec!["member1", "member2", "member3"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5]))
+        .with_operators(&members)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    suite.advance_epoch().unwrap();
+    suite.advance_seconds(1).unwrap();
+
+    suite.jail(&admin, members[1], Duration::new(3600)).unwrap();
+
+    assert_eq!(suite.next_block().unwrap(), None);
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[1], 3), (members[2], 5)],
+    );
+
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[2], 5)],
+    );
+}
+
+#[test]
+fn jailing_history_records_all_periods() {
+    let members = vec!["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_operators(&members)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    assert_eq!(
+        suite
+            .jailing_history(members[0], None, None)
+            .unwrap()
+            .periods,
+        vec![]
+    );
+
+    // First jailing, served until expiration
+    let first_start = suite.block_info().time;
+    suite.jail(&admin, members[0], Duration::new(3600)).unwrap();
+    suite.advance_seconds(3600).unwrap();
+    let first_unjail = suite.block_info().time;
+    suite.unjail(members[0], None).unwrap();
+
+    // Second jailing, cut short by admin
+    let second_start = suite.block_info().time;
+    suite.jail(&admin, members[0], Duration::new(7200)).unwrap();
+    suite.unjail(&admin, members[0]).unwrap();
+
+    // Re-jailed forever, still in jail
+    suite.advance_seconds(100).unwrap();
+    let third_start = suite.block_info().time;
+    suite
+        .jail(&admin, members[0], JailingDuration::Forever {})
+        .unwrap();
+
+    let periods = suite
+        .jailing_history(members[0], None, None)
+        .unwrap()
+        .periods;
+    assert_eq!(periods.len(), 3);
+    assert_eq!(periods[0].id, 0);
+    assert_eq!(periods[0].period.start, first_start);
+    assert_eq!(
+        periods[0].period.end,
+        JailingEnd::Until(Expiration::at_timestamp(first_start.plus_seconds(3600)))
+    );
+    assert_eq!(periods[0].unjailed_at, Some(first_unjail));
+    assert_eq!(periods[1].id, 1);
+    assert_eq!(periods[1].period.start, second_start);
+    assert_eq!(
+        periods[1].period.end,
+        JailingEnd::Until(Expiration::at_timestamp(second_start.plus_seconds(7200)))
+    );
+    assert_eq!(periods[1].unjailed_at, Some(second_start));
+    assert_eq!(periods[2].id, 2);
+    assert_eq!(periods[2].period.start, third_start);
+    assert_eq!(periods[2].period.end, JailingEnd::Forever {});
+    assert_eq!(periods[2].unjailed_at, None);
+
+    // Paginated by record id
+    let page = suite.jailing_history(members[0], 0, 1).unwrap().periods;
+    assert_eq!(page, periods[1..2]);
+    let page = suite.jailing_history(members[0], 1, None).unwrap().periods;
+    assert_eq!(page, periods[2..]);
+
+    // Other operators are not affected
+    assert_eq!(
+        suite
+            .jailing_history(members[1], None, None)
+            .unwrap()
+            .periods,
+        vec![]
+    );
+}
@@ -0,0 +1,189 @@
+use tg_utils::Duration;
+
+use super::helpers::{assert_active_validators, members_init};
+use super::suite::SuiteBuilder;
+
+#[test]
+fn large_points_shift_applied_across_epochs() {
+    let members = [
+        "member1", "member2", "member3", "member4", "member5", "member6", "member7", "member8",
+    ];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members[..4], &[1, 2, 3, 4]))
+        .with_operators(&members)
+        .with_max_validators(4)
+        .with_max_churn(4)
+        .build();
+
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[
+            (members[0], 1),
+            (members[1], 2),
+            (members[2], 3),
+            (members[3], 4),
+        ],
+    );
+
+    // All validators are to be replaced
+    suite
+        .update_members(
+            &[
+                (members[4], 10),
+                (members[5], 20),
+                (members[6], 30),
+                (members[7], 40),
+            ],
+            &[],
+        )
+        .unwrap();
+
+    // Only two strongest validators take places of the two strongest leaving ones
+    let diff = suite.advance_epoch().unwrap().unwrap();
+    assert_eq!(diff.diffs.len(), 4);
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[
+            (members[0], 1),
+            (members[1], 2),
+            (members[6], 30),
+            (members[7], 40),
+        ],
+    );
+
+    // The rest is swapped in the next epoch
+    let diff = suite.advance_epoch().unwrap().unwrap();
+    assert_eq!(diff.diffs.len(), 4);
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[
+            (members[4], 10),
+            (members[5], 20),
+            (members[6], 30),
+            (members[7], 40),
+        ],
+    );
+}
+
+#[test]
+fn limit_can_be_removed() {
+    let members = ["member1", "member2", "member3", "member4"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members[..2], &[1, 2]))
+        .with_operators(&members)
+        .with_max_validators(2)
+        .with_max_churn(2)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    suite.update_max_churn(&admin, 0).unwrap();
+    assert_eq!(suite.config().unwrap().max_churn, None);
+
+    // Both validators are replaced at once
+    suite
+        .update_members(&[(members[2], 10), (members[3], 20)], &[])
+        .unwrap();
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[2], 10), (members[3], 20)],
+    );
+}
+
+#[test]
+fn deferred_changes_reported() {
+    let members = ["member1", "member2", "member3"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[1, 2, 3]))
+        .with_operators(&members)
+        .with_epoch_length(100)
+        .with_max_churn(1)
+        .build();
+
+    suite
+        .update_members(&[], &[members[1], members[2]])
+        .unwrap();
+
+    suite.app().advance_seconds(100);
+    let (responses, _) = suite.app().end_block().unwrap();
+    suite.app().begin_block(vec![]).unwrap();
+
+    let deferred: Vec<_> = responses
+        .iter()
+        .flat_map(|resp| &resp.events)
+        .filter(|ev| ev.ty == "wasm-validator_changes_deferred")
+        .collect();
+    assert_eq!(deferred.len(), 1);
+    let attr = |key: &str| {
+        deferred[0]
+            .attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.as_str())
+    };
+    assert_eq!(attr("deferred"), Some("1"));
+    assert_eq!(attr("max_churn"), Some("1"));
+
+    // Validator with higher power leaves first
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 1), (members[1], 2)],
+    );
+
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 1)],
+    );
+}
+
+#[test]
+fn jailed_validators_always_leave() {
+    let members = ["member1", "member2", "member3", "member4"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members[..3], &[1, 2, 3]))
+        .with_operators(&members)
+        .with_max_churn(1)
+        .build();
+
+    let admin = suite.admin().to_owned();
+    suite.jail(&admin, members[1], Duration::new(3600)).unwrap();
+    suite.jail(&admin, members[2], Duration::new(3600)).unwrap();
+    suite.update_members(&[(members[3], 4)], &[]).unwrap();
+
+    // Both jailed validators are removed, exceeding the limit, so the new one has to wait
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 1)],
+    );
+
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 1), (members[3], 4)],
+    );
+}
+
+#[test]
+fn deferred_leaving_validator_keeps_old_power() {
+    let members = ["member1", "member2", "member3"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members[..2], &[5, 6]))
+        .with_operators(&members)
+        .with_max_validators(2)
+        .with_max_churn(1)
+        .build();
+
+    // member1 drops out in favour of member3, but replacing it takes two changes
+    suite
+        .update_members(&[(members[0], 1), (members[1], 7), (members[2], 10)], &[])
+        .unwrap();
+    suite.advance_epoch().unwrap();
+
+    // Power of the staying validator is updated, the deferred one keeps the old power
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 5), (members[1], 7)],
+    );
+}
@@ -0,0 +1,84 @@
+use super::helpers::{assert_active_validators, members_init};
+use super::suite::SuiteBuilder;
+
+#[test]
+fn power_is_capped_after_scaling() {
+    let members = ["member1", "member2", "member3"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 50]))
+        .with_operators(&members)
+        .with_scaling(10)
+        .with_max_power(100)
+        .build();
+
+    // Genesis validator set is already capped
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 20), (members[1], 30), (members[2], 100)],
+    );
+
+    suite.advance_epoch().unwrap();
+    suite.app().advance_seconds(100);
+    let (responses, _) = suite.app().end_block().unwrap();
+    suite.app().begin_block(vec![]).unwrap();
+
+    // Only the capped validator is reported
+    let capped: Vec<_> = responses
+        .iter()
+        .flat_map(|resp| &resp.events)
+        .filter(|ev| ev.ty == "wasm-validator_power_capped")
+        .collect();
+    assert_eq!(capped.len(), 1);
+    let attr = |key: &str| {
+        capped[0]
+            .attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.as_str())
+    };
+    assert_eq!(attr("operator"), Some(members[2]));
+    assert_eq!(attr("power"), Some("500"));
+    assert_eq!(attr("max_power"), Some("100"));
+
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 20), (members[1], 30), (members[2], 100)],
+    );
+}
+
+#[test]
+fn cap_can_be_removed() {
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 50]))
+        .with_operators(&members)
+        .with_scaling(10)
+        .with_max_power(100)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    suite.update_max_power(&admin, 0).unwrap();
+    assert_eq!(suite.config().unwrap().max_power, None);
+
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 20), (members[1], 500)],
+    );
+}
+
+#[test]
+fn power_not_capped_by_default() {
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 50]))
+        .with_operators(&members)
+        .with_scaling(10)
+        .build();
+
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 20), (members[1], 500)],
+    );
+}
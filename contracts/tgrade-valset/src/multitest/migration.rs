@@ -1,5 +1,5 @@
 use super::suite::SuiteBuilder;
-use crate::msg::MigrateMsg;
+use crate::msg::{DistributionSchema, MigrateMsg};
 use crate::state::DistributionContract;
 use cosmwasm_std::{Addr, Decimal};
 
@@ -24,6 +24,7 @@ fn migration_can_alter_cfg() {
                 distribution_contracts: Some(vec![DistributionContract {
                     contract: Addr::unchecked("engagement1".to_string()),
                     ratio: Decimal::percent(50),
+                    schema: DistributionSchema::default(),
                 }]),
                 verify_validators: Some(true),
             },
@@ -39,6 +40,7 @@ fn migration_can_alter_cfg() {
         vec![DistributionContract {
             contract: Addr::unchecked("engagement1".to_string()),
             ratio: Decimal::percent(50),
+            schema: DistributionSchema::default(),
         }]
     );
 }
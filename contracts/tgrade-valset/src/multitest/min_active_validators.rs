@@ -0,0 +1,105 @@
+use tg_utils::JailingDuration;
+
+use super::helpers::{assert_active_validators, members_init};
+use super::suite::SuiteBuilder;
+
+#[test]
+fn set_not_padded_by_default() {
+    let members = ["member1", "member2", "member3", "member4"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5, 8]))
+        .with_operators(&members)
+        .with_min_points(5)
+        .build();
+
+    suite.advance_epoch().unwrap();
+
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[2], 5), (members[3], 8)],
+    );
+}
+
+#[test]
+fn set_padded_with_members_below_min_points() {
+    let members = ["member1", "member2", "member3", "member4"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5, 8]))
+        .with_operators(&members)
+        .with_min_points(5)
+        .with_min_active_validators(3)
+        .build();
+
+    suite.advance_epoch().unwrap();
+
+    // Highest ranked member below `min_points` is included
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[1], 3), (members[2], 5), (members[3], 8)],
+    );
+    assert_active_validators(
+        &suite.simulate_active_validators().unwrap(),
+        &[(members[1], 3), (members[2], 5), (members[3], 8)],
+    );
+
+    // Membership drops below the minimum after jailing
+    let admin = suite.admin().to_owned();
+    suite
+        .jail(&admin, members[3], JailingDuration::Forever {})
+        .unwrap();
+    suite.app().advance_seconds(100);
+    let (responses, _) = suite.app().end_block().unwrap();
+    suite.app().begin_block(vec![]).unwrap();
+
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[1], 3), (members[2], 5)],
+    );
+
+    let padded = responses
+        .iter()
+        .flat_map(|resp| &resp.events)
+        .find(|ev| ev.ty == "wasm-validators_padded")
+        .unwrap();
+    assert!(padded
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "padded" && attr.value == "2"));
+}
+
+#[test]
+fn padding_capped_at_max_validators() {
+    let members = ["member1", "member2", "member3", "member4"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5, 8]))
+        .with_operators(&members)
+        .with_min_points(10)
+        .with_max_validators(2)
+        .with_min_active_validators(3)
+        .build();
+
+    suite.advance_epoch().unwrap();
+
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[2], 5), (members[3], 8)],
+    );
+}
+
+#[test]
+fn zero_point_members_never_padded() {
+    let members = ["member1", "member2", "member3"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[0, 3, 5]))
+        .with_operators(&members)
+        .with_min_points(5)
+        .with_min_active_validators(3)
+        .build();
+
+    suite.advance_epoch().unwrap();
+
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[1], 3), (members[2], 5)],
+    );
+}
@@ -0,0 +1,130 @@
+use cosmwasm_std::{coin, coins, Addr};
+
+use super::helpers::{assert_active_validators, members_init};
+use super::suite::SuiteBuilder;
+use crate::msg::Eligibility;
+
+const BOND_DENOM: &str = "tgrade";
+
+#[test]
+fn raising_min_self_bond_drops_validator() {
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_stake(BOND_DENOM, 10u128)
+        .with_operators(&members)
+        .with_funds(&[
+            (members[0], &coins(100, BOND_DENOM)),
+            (members[1], &coins(60, BOND_DENOM)),
+        ])
+        .with_min_points(5)
+        .with_epoch_reward(coin(0, "usdc"))
+        .with_min_self_bond(50)
+        .build();
+
+    suite
+        .bond(&Addr::unchecked(members[0]), &coins(100, BOND_DENOM))
+        .unwrap();
+    suite
+        .bond(&Addr::unchecked(members[1]), &coins(60, BOND_DENOM))
+        .unwrap();
+    suite.advance_epoch().unwrap();
+
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 10), (members[1], 6)],
+    );
+
+    let admin = suite.admin().to_owned();
+    suite.update_min_self_bond(&admin, 80).unwrap();
+
+    // Active set is not changed until the next epoch
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 10), (members[1], 6)],
+    );
+    assert_active_validators(
+        &suite.simulate_active_validators().unwrap(),
+        &[(members[0], 10)],
+    );
+
+    suite.app().advance_seconds(100);
+    let (responses, _) = suite.app().end_block().unwrap();
+    suite.app().begin_block(vec![]).unwrap();
+
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 10)],
+    );
+
+    let excluded: Vec<_> = responses
+        .iter()
+        .flat_map(|resp| &resp.events)
+        .filter(|ev| ev.ty == "wasm-validator_excluded")
+        .collect();
+    assert_eq!(excluded.len(), 1);
+    assert!(excluded[0]
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "operator" && attr.value == members[1]));
+    assert!(excluded[0]
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "reason" && attr.value == "insufficient_self_bond"));
+}
+
+#[test]
+fn members_below_min_points_not_reported() {
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_stake(BOND_DENOM, 10u128)
+        .with_operators(&members)
+        .with_funds(&[
+            (members[0], &coins(100, BOND_DENOM)),
+            (members[1], &coins(40, BOND_DENOM)),
+        ])
+        .with_min_points(5)
+        .with_epoch_reward(coin(0, "usdc"))
+        .with_min_self_bond(80)
+        .build();
+
+    suite
+        .bond(&Addr::unchecked(members[0]), &coins(100, BOND_DENOM))
+        .unwrap();
+    suite
+        .bond(&Addr::unchecked(members[1]), &coins(40, BOND_DENOM))
+        .unwrap();
+
+    suite.app().advance_seconds(100);
+    let (responses, _) = suite.app().end_block().unwrap();
+    suite.app().begin_block(vec![]).unwrap();
+
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 10)],
+    );
+
+    // member2 is not eligible anyway, so it is not reported as excluded by self-bond
+    assert!(!responses
+        .iter()
+        .flat_map(|resp| &resp.events)
+        .any(|ev| ev.ty == "wasm-validator_excluded"));
+}
+
+#[test]
+fn non_stake_membership_does_not_fail_epoch() {
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_operators(&members)
+        .with_epoch_reward(coin(0, "usdc"))
+        .with_min_self_bond(50)
+        .build();
+
+    // Self bond can't be queried from tg4-engagement, so operators are considered below minimum
+    suite.advance_epoch().unwrap();
+    assert_active_validators(&suite.list_active_validators(None, None).unwrap(), &[]);
+    assert_eq!(
+        suite.eligibility(members[1]).unwrap(),
+        Eligibility::BelowMinSelfBond
+    );
+}
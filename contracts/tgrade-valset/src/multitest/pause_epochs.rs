@@ -0,0 +1,113 @@
+use cosmwasm_std::coin;
+use cw_controllers::AdminError;
+
+use super::helpers::{assert_active_validators, members_init};
+use super::suite::SuiteBuilder;
+use crate::error::ContractError;
+use crate::test_helpers::{mock_metadata, mock_pubkey};
+
+#[test]
+fn only_admin_can_pause() {
+    let members = ["member1"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2]))
+        .with_operators(&members)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    let err = suite
+        .set_epoch_processing_paused(members[0], true)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::AdminError(AdminError::NotAdmin {}),
+        err.downcast().unwrap(),
+    );
+    assert!(!suite.epoch().unwrap().epoch_processing_paused);
+
+    suite.set_epoch_processing_paused(&admin, true).unwrap();
+    assert!(suite.epoch().unwrap().epoch_processing_paused);
+}
+
+#[test]
+fn active_set_frozen_while_paused() {
+    let members = ["member1", "member2", "member3"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 0]))
+        .with_operators(&members[..2])
+        .with_epoch_reward(coin(1000, "usdc"))
+        .build();
+    let admin = suite.admin().to_owned();
+
+    // Genesis is just before the epoch boundary, align to the epoch start
+    suite.advance_epoch().unwrap();
+    suite.advance_seconds(1).unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[1], 3)],
+    );
+    suite.withdraw_validation_reward(members[0]).unwrap();
+    suite.withdraw_validation_reward(members[1]).unwrap();
+    let rewarded = suite.token_balance(members[0]).unwrap();
+
+    suite.set_epoch_processing_paused(&admin, true).unwrap();
+    let epoch = suite.epoch().unwrap().current_epoch;
+
+    // Operators can still register and change their metadata
+    suite
+        .register_validator_key(
+            members[2],
+            mock_pubkey(members[2].as_bytes()),
+            mock_metadata(members[2]),
+        )
+        .unwrap();
+    suite
+        .update_metadata(members[0], &mock_metadata("updated"))
+        .unwrap();
+    suite
+        .update_members(&members_init(&members, &[2, 0, 5]), &[])
+        .unwrap();
+
+    // Skipped epoch is reported only on its first block
+    suite.app().advance_seconds(100);
+    let (responses, diff) = suite.app().end_block().unwrap();
+    suite.app().begin_block(vec![]).unwrap();
+    assert_eq!(diff, None);
+    let skipped: Vec<_> = responses
+        .iter()
+        .flat_map(|resp| &resp.events)
+        .filter(|ev| ev.ty == "wasm-epoch_skipped")
+        .collect();
+    assert_eq!(skipped.len(), 1);
+    assert!(skipped[0]
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "epoch" && attr.value == (epoch + 1).to_string()));
+
+    suite.app().advance_blocks(1);
+    let (responses, _) = suite.app().end_block().unwrap();
+    suite.app().begin_block(vec![]).unwrap();
+    assert!(!responses
+        .iter()
+        .flat_map(|resp| &resp.events)
+        .any(|ev| ev.ty == "wasm-epoch_skipped"));
+
+    suite.advance_epoch().unwrap();
+    assert_eq!(suite.epoch().unwrap().current_epoch, epoch);
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[1], 3)],
+    );
+
+    // After resuming the set is recalculated, and skipped epochs are paid
+    suite.set_epoch_processing_paused(&admin, false).unwrap();
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[2], 5)],
+    );
+
+    suite.withdraw_validation_reward(members[0]).unwrap();
+    // Three epochs passed since the last payment: 3 * 2/5 * 1000
+    assert_eq!(suite.epoch().unwrap().current_epoch, epoch + 3);
+    assert_eq!(suite.token_balance(members[0]).unwrap(), rewarded + 1200);
+}
@@ -0,0 +1,75 @@
+use cosmwasm_std::Addr;
+
+use super::helpers::{assert_active_validators, members_init};
+use super::suite::SuiteBuilder;
+use crate::contract::HISTORY_RETENTION;
+use crate::msg::PowerChange;
+
+fn change(operator: &str, old_power: u64, new_power: u64) -> PowerChange {
+    PowerChange {
+        operator: Addr::unchecked(operator),
+        old_power,
+        new_power,
+    }
+}
+
+#[test]
+fn power_changes_between_epochs() {
+    let members = ["member1", "member2", "member3", "member4"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members[..3], &[2, 3, 5]))
+        .with_operators(&members)
+        .build();
+
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[1], 3), (members[2], 5)],
+    );
+    let epoch_height = suite.block_info().height;
+
+    // Nothing changed since the epoch
+    assert_eq!(suite.power_changes(epoch_height).unwrap(), []);
+
+    // Points change, a member leaves and another one joins
+    suite
+        .update_members(&[(members[1], 7), (members[3], 4)], &[members[0]])
+        .unwrap();
+    // Membership changes aren't reflected until the next epoch
+    assert_eq!(suite.power_changes(epoch_height).unwrap(), []);
+
+    suite.advance_epoch().unwrap();
+    assert_eq!(
+        suite.power_changes(epoch_height).unwrap(),
+        [
+            change(members[0], 2, 0),
+            change(members[1], 3, 7),
+            change(members[3], 0, 4),
+        ]
+    );
+
+    // No changes since the latest epoch
+    let latest_height = suite.block_info().height;
+    assert_eq!(suite.power_changes(latest_height).unwrap(), []);
+}
+
+#[test]
+fn old_snapshots_pruned() {
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_operators(&members)
+        .build();
+
+    suite.advance_epoch().unwrap();
+    let epoch_height = suite.block_info().height;
+
+    for _ in 1..HISTORY_RETENTION {
+        suite.advance_epoch().unwrap();
+    }
+    assert_eq!(suite.power_changes(epoch_height).unwrap(), []);
+
+    // Snapshot of the first epoch is pruned
+    suite.advance_epoch().unwrap();
+    suite.power_changes(epoch_height).unwrap_err();
+}
@@ -0,0 +1,60 @@
+use cosmwasm_std::Decimal;
+use tg_utils::Duration;
+
+use super::helpers::{assert_active_validators, members_init};
+use super::suite::SuiteBuilder;
+
+#[test]
+fn unjailed_validator_ramps_back_to_full_power() {
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[100, 40]))
+        .with_operators(&members)
+        .with_probation(Decimal::percent(25), 3)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 100), (members[1], 40)],
+    );
+
+    suite.jail(&admin, members[0], Duration::new(3600)).unwrap();
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[1], 40)],
+    );
+
+    suite.unjail(&admin, members[0]).unwrap();
+
+    // Power grows linearly from the scaled down one over probation epochs
+    for power in [25, 50, 75, 100, 100] {
+        suite.advance_epoch().unwrap();
+        assert_active_validators(
+            &suite.list_active_validators(None, None).unwrap(),
+            &[(members[0], power), (members[1], 40)],
+        );
+    }
+}
+
+#[test]
+fn no_probation_by_default() {
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[100, 40]))
+        .with_operators(&members)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    suite.jail(&admin, members[0], Duration::new(3600)).unwrap();
+    suite.advance_epoch().unwrap();
+    suite.unjail(&admin, members[0]).unwrap();
+    suite.advance_epoch().unwrap();
+
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 100), (members[1], 40)],
+    );
+}
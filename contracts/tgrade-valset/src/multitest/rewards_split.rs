@@ -1,5 +1,15 @@
 use super::suite::SuiteBuilder;
-use cosmwasm_std::{coin, Decimal};
+use crate::msg::{DistributionSchema, ValidatorMetadata};
+use crate::state::DistributionContract;
+use crate::test_helpers::mock_metadata;
+use cosmwasm_std::{
+    coin, to_binary, Addr, Binary, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdResult,
+};
+use cw_multi_test::{Contract, ContractWrapper, Executor};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tg_bindings::{TgradeMsg, TgradeQuery};
 
 use tg_utils::JailingDuration;
 
@@ -106,6 +116,42 @@ fn no_fees_three_way_split() {
     assert_eq!(suite.token_balance(community[0]).unwrap(), 100);
 }
 
+#[test]
+fn commission_taken_before_split() {
+    let engagement = ["dist1"];
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&[(members[0], 2), (members[1], 3)])
+        .with_operators(&members)
+        .with_epoch_reward(coin(1000, "usdc"))
+        .with_distribution(Decimal::percent(40), &[(engagement[0], 1)], None)
+        .build();
+
+    let metadata = ValidatorMetadata {
+        commission: Decimal::percent(10),
+        ..mock_metadata(members[0])
+    };
+    suite.update_metadata(members[0], &metadata).unwrap();
+
+    suite.advance_epoch().unwrap();
+
+    suite
+        .withdraw_distribution_reward(engagement[0], 0)
+        .unwrap();
+    suite.withdraw_validation_reward(members[0]).unwrap();
+    suite.withdraw_validation_reward(members[1]).unwrap();
+
+    // Single epoch reward, no fees.
+    // member1 takes 10% commission from its share first: 0.1 * 2/5 * 1000 = 40
+    // Remaining 960 is split:
+    // * dist1: 0.4 * 960 = 384
+    // * member1: 40 + 0.6 * 2/5 * 960 = 40 + 230.4 = 270 (rounded down)
+    // * member2: 0.6 * 3/5 * 960 = 345.6 = 345 (rounded down)
+    assert_eq!(suite.token_balance(members[0]).unwrap(), 270);
+    assert_eq!(suite.token_balance(members[1]).unwrap(), 345);
+    assert_eq!(suite.token_balance(engagement[0]).unwrap(), 384);
+}
+
 #[test]
 fn no_fees_invidivisible_reward() {
     let engagement = vec!["dist1", "dist2"];
@@ -268,3 +314,277 @@ fn jailed_validators_not_rewarded() {
     assert_eq!(suite.token_balance(engagement[0]).unwrap(), 240);
     assert_eq!(suite.token_balance(engagement[1]).unwrap(), 560);
 }
+
+#[test]
+fn simulate_rewards_two_distribution_contracts() {
+    let engagement = ["dist1", "dist2"];
+    let community = ["community"];
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&[(members[0], 2), (members[1], 3)])
+        .with_operators(&members)
+        .with_epoch_reward(coin(1001, "usdc"))
+        .with_distribution(
+            Decimal::percent(50),
+            &[(engagement[0], 3), (engagement[1], 7)],
+            None,
+        )
+        .with_distribution(Decimal::percent(50), &[(community[0], 10)], None)
+        .build();
+
+    // Each contract gets 50% of 1001, the indivisible token ends up with validators
+    let simulated = suite.simulate_rewards().unwrap();
+    assert_eq!(simulated.denom, "usdc");
+    assert_eq!(simulated.total.u128(), 1001);
+    assert_eq!(simulated.minted.u128(), 1001);
+    let distributions: Vec<_> = simulated
+        .distribution_contracts
+        .iter()
+        .map(|dc| dc.reward.u128())
+        .collect();
+    assert_eq!(distributions, vec![500, 500]);
+    assert_eq!(simulated.validators_reward.u128(), 1);
+
+    // Simulation matches the actual payment
+    suite.advance_epoch().unwrap();
+    suite.withdraw_distribution_reward(community[0], 1).unwrap();
+    assert_eq!(suite.token_balance(community[0]).unwrap(), 500);
+}
+
+#[test]
+fn simulate_rewards_indivisible_token() {
+    let members = ["member1", "member2"];
+    let suite = SuiteBuilder::new()
+        .with_engagement(&[(members[0], 2), (members[1], 3)])
+        .with_operators(&members)
+        .with_epoch_reward(coin(1, "usdc"))
+        .with_distribution(Decimal::percent(50), &[("dist1", 10)], None)
+        .with_distribution(Decimal::percent(50), &[("dist2", 10)], None)
+        .build();
+
+    let simulated = suite.simulate_rewards().unwrap();
+    assert!(simulated
+        .distribution_contracts
+        .iter()
+        .all(|dc| dc.reward.is_zero()));
+    assert_eq!(simulated.validators_reward.u128(), 1);
+
+    // Validators shares are split by their power
+    let shares: Vec<_> = simulated
+        .validators
+        .iter()
+        .map(|v| (v.operator.as_str(), v.commission.u128(), v.reward.u128()))
+        .collect();
+    assert_eq!(shares, vec![(members[1], 0, 0), (members[0], 0, 0)]);
+
+    // Simulation doesn't change the state
+    assert_eq!(suite.simulate_rewards().unwrap(), simulated);
+}
+
+#[test]
+fn rewards_sent_to_reward_address() {
+    let engagement = ["dist1"];
+    let members = ["member1", "member2"];
+    let cold = "cold1";
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&[(members[0], 2), (members[1], 3)])
+        .with_operators(&members)
+        .with_epoch_reward(coin(1000, "usdc"))
+        .with_distribution(Decimal::percent(40), &[(engagement[0], 1)], None)
+        .build();
+
+    let metadata = ValidatorMetadata {
+        commission: Decimal::percent(10),
+        ..mock_metadata(members[0])
+    };
+    suite.update_metadata(members[0], &metadata).unwrap();
+
+    // Only operators can set reward address
+    suite.set_reward_address(cold, members[0]).unwrap_err();
+
+    suite.set_reward_address(members[0], cold).unwrap();
+    assert_eq!(suite.reward_address(members[0]).unwrap(), cold);
+    assert_eq!(suite.reward_address(members[1]).unwrap(), members[1]);
+
+    suite.advance_epoch().unwrap();
+
+    suite.withdraw_validation_reward(members[0]).unwrap();
+    suite.withdraw_validation_reward(members[1]).unwrap();
+
+    // Same split as in `commission_taken_before_split`, but member1 commission and share are
+    // sent to its reward address directly:
+    // * cold1: 40 + 0.6 * 2/5 * 960 = 40 + 230.4 = 270 (rounded down)
+    // * member2: 0.6 * 3/5 * 960 = 345.6 = 345 (rounded down)
+    assert_eq!(suite.token_balance(cold).unwrap(), 270);
+    assert_eq!(suite.token_balance(members[0]).unwrap(), 0);
+    assert_eq!(suite.token_balance(members[1]).unwrap(), 345);
+
+    // Setting it back to the operator clears the reward address
+    suite.set_reward_address(members[0], members[0]).unwrap();
+    assert_eq!(suite.reward_address(members[0]).unwrap(), members[0]);
+
+    suite.advance_epoch().unwrap();
+
+    suite.withdraw_validation_reward(members[0]).unwrap();
+    suite.withdraw_validation_reward(members[1]).unwrap();
+
+    assert_eq!(suite.token_balance(cold).unwrap(), 270);
+    assert_eq!(suite.token_balance(members[0]).unwrap(), 270);
+    // member2 also gets the rounding remainder left in the validator group in the previous epoch
+    assert_eq!(suite.token_balance(members[1]).unwrap(), 345 + 346);
+}
+
+#[test]
+fn vested_rewards_not_transferred() {
+    let members = ["member1", "member2"];
+    let cold = "cold1";
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&[(members[0], 2), (members[1], 3)])
+        .with_operators(&members)
+        .with_epoch_reward(coin(1000, "usdc"))
+        .with_reward_vesting()
+        .build();
+
+    suite.set_reward_address(members[0], cold).unwrap();
+    suite.advance_epoch().unwrap();
+    suite.withdraw_validation_reward(members[1]).unwrap();
+
+    // member1 share is sent to the vesting account of its reward address, which is not a liquid
+    // balance. Validator group rewards are not affected.
+    assert_eq!(suite.token_balance(cold).unwrap(), 0);
+    assert_eq!(suite.token_balance(members[1]).unwrap(), 600);
+}
+
+/// Distribution contract accepting different message than tg4-engagement
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+enum SplitterMsg {
+    Split {},
+}
+
+fn contract_splitter() -> Box<dyn Contract<TgradeMsg, TgradeQuery>> {
+    fn execute(
+        _deps: DepsMut<TgradeQuery>,
+        _env: Env,
+        _info: MessageInfo,
+        msg: SplitterMsg,
+    ) -> StdResult<Response<TgradeMsg>> {
+        match msg {
+            SplitterMsg::Split {} => Ok(Response::new()),
+        }
+    }
+
+    fn instantiate(
+        _deps: DepsMut<TgradeQuery>,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response<TgradeMsg>> {
+        Ok(Response::new())
+    }
+
+    fn query(_deps: Deps<TgradeQuery>, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        Ok(Binary::default())
+    }
+
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+#[test]
+fn custom_distribution_schema() {
+    let members = vec!["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&[(members[0], 2), (members[1], 3)])
+        .with_operators(&members)
+        .with_epoch_reward(coin(1000, "usdc"))
+        .build();
+    let admin = suite.admin().to_owned();
+
+    let splitter_id = suite.app().store_code(contract_splitter());
+    let splitter = suite
+        .app()
+        .instantiate_contract(
+            splitter_id,
+            Addr::unchecked(&admin),
+            &Empty {},
+            &[],
+            "splitter",
+            None,
+        )
+        .unwrap();
+
+    suite
+        .update_config(
+            &admin,
+            None,
+            None,
+            vec![DistributionContract {
+                contract: splitter.clone(),
+                ratio: Decimal::percent(40),
+                schema: DistributionSchema::Custom {
+                    msg: to_binary(&SplitterMsg::Split {}).unwrap(),
+                },
+            }],
+        )
+        .unwrap();
+
+    suite.advance_epoch().unwrap();
+
+    // 40% of the reward is sent along with the custom message
+    assert_eq!(suite.token_balance(splitter.as_str()).unwrap(), 400);
+
+    suite.withdraw_validation_reward(members[0]).unwrap();
+    suite.withdraw_validation_reward(members[1]).unwrap();
+    assert_eq!(suite.token_balance(members[0]).unwrap(), 240);
+    assert_eq!(suite.token_balance(members[1]).unwrap(), 360);
+
+    // The splitter doesn't understand tg4-engagement message
+    suite
+        .update_config(
+            &admin,
+            None,
+            None,
+            vec![DistributionContract {
+                contract: splitter,
+                ratio: Decimal::percent(40),
+                schema: DistributionSchema::Engagement {},
+            }],
+        )
+        .unwrap();
+
+    suite.advance_epoch().unwrap_err();
+}
+
+#[test]
+fn distribution_contract_stats_accumulate() {
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&[(members[0], 2), (members[1], 3)])
+        .with_operators(&members)
+        .with_epoch_reward(coin(1000, "usdc"))
+        .with_distribution(Decimal::percent(40), &[("dist1", 1)], None)
+        .with_distribution(Decimal::percent(20), &[("dist2", 1)], None)
+        .build();
+
+    let stats = suite.distribution_contract_stats().unwrap();
+    assert_eq!(stats.len(), 2);
+    assert_eq!(stats[0].ratio, Decimal::percent(40));
+    assert_eq!(stats[1].ratio, Decimal::percent(20));
+    assert!(stats.iter().all(|s| s.distributed.is_zero()));
+
+    suite.advance_epoch().unwrap();
+    let stats = suite.distribution_contract_stats().unwrap();
+    assert_eq!(stats[0].distributed.u128(), 400);
+    assert_eq!(stats[1].distributed.u128(), 200);
+
+    suite.advance_epoch().unwrap();
+    let stats = suite.distribution_contract_stats().unwrap();
+    assert_eq!(stats[0].distributed.u128(), 800);
+    assert_eq!(stats[1].distributed.u128(), 400);
+
+    // Totals match what the contracts received
+    suite.withdraw_distribution_reward("dist1", 0).unwrap();
+    suite.withdraw_distribution_reward("dist2", 1).unwrap();
+    assert_eq!(suite.token_balance("dist1").unwrap(), 800);
+    assert_eq!(suite.token_balance("dist2").unwrap(), 400);
+}
@@ -1,8 +1,10 @@
 use cosmwasm_std::{coin, Decimal};
 use cw_controllers::AdminError;
 
+use super::helpers::{assert_active_validators, members_init};
 use super::suite::SuiteBuilder;
 use crate::error::ContractError;
+use crate::state::SlashingCause;
 
 #[test]
 fn admin_can_slash() {
@@ -150,3 +152,93 @@ fn non_validator_query_fails() {
         .to_string()
         .contains(&format!("Never a validator: {}", actors[1])));
 }
+
+#[test]
+fn slash_ratio_depends_on_reason() {
+    let members = vec!["member1", "member2", "member3"];
+
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[20, 20, 20]))
+        .with_operators(&members)
+        .with_downtime_slash_ratio(Decimal::percent(10))
+        .build();
+
+    let admin = suite.admin().to_owned();
+
+    // Downtime slash uses configured ratio regardless of passed portion
+    suite
+        .slash_with_reason(
+            &admin,
+            members[0],
+            Decimal::percent(80),
+            SlashingCause::Downtime,
+        )
+        .unwrap();
+    // Same for double sign
+    suite
+        .slash_with_reason(
+            &admin,
+            members[1],
+            Decimal::percent(80),
+            SlashingCause::DoubleSign,
+        )
+        .unwrap();
+    // Governance slash without configured ratio uses passed portion
+    suite
+        .slash(&admin, members[2], Decimal::percent(25))
+        .unwrap();
+
+    let slashing = suite.list_validator_slashing(members[0]).unwrap().slashing;
+    assert_eq!(slashing[0].portion, Decimal::percent(10));
+    assert_eq!(slashing[0].cause, Some(SlashingCause::Downtime));
+    let slashing = suite.list_validator_slashing(members[1]).unwrap().slashing;
+    assert_eq!(slashing[0].portion, Decimal::percent(50));
+    assert_eq!(slashing[0].cause, Some(SlashingCause::DoubleSign));
+    let slashing = suite.list_validator_slashing(members[2]).unwrap().slashing;
+    assert_eq!(slashing[0].portion, Decimal::percent(25));
+    assert_eq!(slashing[0].cause, Some(SlashingCause::Governance));
+
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 18), (members[1], 10), (members[2], 15)],
+    );
+}
+
+#[test]
+fn governance_slash_uses_configured_ratio() {
+    let members = vec!["member1", "member2"];
+
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[20, 20]))
+        .with_operators(&members)
+        .with_governance_slash_ratio(Decimal::percent(10))
+        .build();
+
+    let admin = suite.admin().to_owned();
+
+    suite
+        .slash(&admin, members[0], Decimal::percent(80))
+        .unwrap();
+
+    let slashing = suite.list_validator_slashing(members[0]).unwrap().slashing;
+    assert_eq!(slashing[0].portion, Decimal::percent(10));
+
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 18), (members[1], 20)],
+    );
+
+    // Unset ratio, the passed portion is used again
+    suite
+        .update_governance_slash_ratio(&admin, Decimal::zero())
+        .unwrap();
+    assert_eq!(suite.config().unwrap().governance_slash_ratio, None);
+
+    suite
+        .slash(&admin, members[1], Decimal::percent(50))
+        .unwrap();
+    let slashing = suite.list_validator_slashing(members[1]).unwrap().slashing;
+    assert_eq!(slashing[0].portion, Decimal::percent(50));
+}
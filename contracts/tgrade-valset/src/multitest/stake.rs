@@ -1,7 +1,8 @@
 #![cfg(test)]
-use cosmwasm_std::{coin, Addr, Decimal};
+use cosmwasm_std::{coin, Addr, Decimal, Uint128};
 use tg_utils::Duration;
 
+use crate::msg::TieBreak;
 use crate::multitest::suite::SuiteBuilder;
 use crate::state::{Config, ValidatorInfo};
 use crate::test_helpers::{addrs, valid_operator};
@@ -38,6 +39,23 @@ fn init_and_query_state() {
             validator_group: cfg.validator_group.clone(),
             verify_validators: false,
             offline_jail_duration: Duration::new(0),
+            offline_jail_immunity: Duration::new(0),
+            min_active_validators: 0,
+            delay_new_operators: false,
+            active_set_retention: 0,
+            min_self_bond: Uint128::zero(),
+            probation_scaling: Decimal::one(),
+            probation_epochs: 0,
+            metadata_update_cooldown: Duration::new(0),
+            uptime_rewards: false,
+            tie_break: TieBreak::PubkeyLex,
+            downtime_slash_ratio: Decimal::zero(),
+            governance_slash_ratio: None,
+            max_power: None,
+            max_churn: None,
+            reward_vesting: false,
+            verification_grace_blocks: 0,
+            max_epoch_reward: None,
         }
     );
 
@@ -1,5 +1,5 @@
 use super::helpers::addr_to_pubkey;
-use crate::state::{Config, DistributionContract, ValsetState};
+use crate::state::{Config, DistributionContract, SlashingCause, ValsetState};
 use crate::test_helpers::{mock_metadata, mock_pubkey};
 use crate::{msg::*, state::ValidatorInfo};
 use anyhow::{bail, Result as AnyResult};
@@ -31,7 +31,8 @@ pub fn contract_stake() -> Box<dyn Contract<TgradeMsg, TgradeQuery>> {
         tg4_stake::contract::execute,
         tg4_stake::contract::instantiate,
         tg4_stake::contract::query,
-    );
+    )
+    .with_reply(tg4_stake::contract::reply);
     Box::new(contract)
 }
 
@@ -103,6 +104,26 @@ pub struct SuiteBuilder {
     /// Funds to add on init per address
     init_funds: Vec<(String, Vec<Coin>)>,
     verify_validators: Option<Duration>,
+    /// Period after unjailing during which validators can't be jailed for being offline
+    offline_jail_immunity: u64,
+    /// Minimum number of active validators to keep
+    min_active_validators: u32,
+    delay_new_operators: bool,
+    active_set_retention: u64,
+    min_self_bond: u128,
+    #[derivative(Default(value = "Decimal::one()"))]
+    probation_scaling: Decimal,
+    probation_epochs: u64,
+    metadata_update_cooldown: u64,
+    uptime_rewards: bool,
+    tie_break: TieBreak,
+    downtime_slash_ratio: Decimal,
+    governance_slash_ratio: Option<Decimal>,
+    max_power: Option<u64>,
+    max_churn: Option<u32>,
+    reward_vesting: bool,
+    verification_grace_blocks: u64,
+    max_epoch_reward: Option<Coin>,
 }
 
 impl SuiteBuilder {
@@ -167,11 +188,97 @@ impl SuiteBuilder {
         self
     }
 
+    pub fn with_verification_grace_blocks(mut self, blocks: u64) -> Self {
+        self.verification_grace_blocks = blocks;
+        self
+    }
+
+    pub fn with_max_epoch_reward(mut self, max_epoch_reward: Coin) -> Self {
+        self.max_epoch_reward = Some(max_epoch_reward);
+        self
+    }
+
     pub fn with_verify_validators(mut self, duration: u64) -> Self {
         self.verify_validators = Some(Duration::new(duration));
         self
     }
 
+    pub fn with_offline_jail_immunity(mut self, duration: u64) -> Self {
+        self.offline_jail_immunity = duration;
+        self
+    }
+
+    pub fn with_min_active_validators(mut self, min_active_validators: u32) -> Self {
+        self.min_active_validators = min_active_validators;
+        self
+    }
+
+    pub fn with_delay_new_operators(mut self) -> Self {
+        self.delay_new_operators = true;
+        self
+    }
+
+    pub fn with_active_set_retention(mut self, epochs: u64) -> Self {
+        self.active_set_retention = epochs;
+        self
+    }
+
+    pub fn with_min_self_bond(mut self, min_self_bond: u128) -> Self {
+        self.min_self_bond = min_self_bond;
+        self
+    }
+
+    pub fn with_probation(mut self, scaling: Decimal, epochs: u64) -> Self {
+        self.probation_scaling = scaling;
+        self.probation_epochs = epochs;
+        self
+    }
+
+    pub fn with_metadata_update_cooldown(mut self, cooldown: u64) -> Self {
+        self.metadata_update_cooldown = cooldown;
+        self
+    }
+
+    pub fn with_uptime_rewards(mut self) -> Self {
+        self.uptime_rewards = true;
+        self
+    }
+
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    pub fn with_downtime_slash_ratio(mut self, ratio: Decimal) -> Self {
+        self.downtime_slash_ratio = ratio;
+        self
+    }
+
+    pub fn with_governance_slash_ratio(mut self, ratio: Decimal) -> Self {
+        self.governance_slash_ratio = Some(ratio);
+        self
+    }
+
+    pub fn with_scaling(mut self, scaling: u32) -> Self {
+        self.scaling = Some(scaling);
+        self
+    }
+
+    pub fn with_max_power(mut self, max_power: u64) -> Self {
+        self.max_power = Some(max_power);
+        self
+    }
+
+    pub fn with_max_churn(mut self, max_churn: u32) -> Self {
+        self.max_churn = Some(max_churn);
+        self
+    }
+
+    pub fn with_reward_vesting(mut self) -> Self {
+        self.reward_vesting = true;
+        self
+    }
+
     pub fn with_epoch_reward(mut self, epoch_reward: Coin) -> Self {
         self.epoch_reward = epoch_reward;
         self
@@ -252,7 +359,14 @@ impl SuiteBuilder {
                         preauths_hooks: 0,
                         preauths_slashing: 1,
                         halflife: None,
+                        decay: None,
                         denom: denom.clone(),
+                        max_denoms: tg4_engagement::msg::default_max_denoms(),
+                        distribution_fee: Decimal::zero(),
+                        fee_recipient: None,
+                        auto_distribute: false,
+                        halflife_floor: 1,
+                        initial_adjustments: vec![],
                     },
                     &[],
                     "group",
@@ -270,13 +384,19 @@ impl SuiteBuilder {
                     admin.clone(),
                     &tg4_stake::msg::InstantiateMsg {
                         denom,
-                        tokens_per_point: tokens_per_points,
+                        tokens_per_point: Decimal::from_ratio(tokens_per_points, 1u128),
                         min_bond: Uint128::zero(),
                         unbonding_period: 0,
                         admin: Some(admin.to_string()),
                         preauths_hooks: 0,
                         preauths_slashing: 1,
                         auto_return_limit: 0,
+                        reject_zero_funds: false,
+                        max_bond_per_tx: None,
+                        min_stake_duration: None,
+                        max_claims_per_addr: None,
+                        slash_policy: tg4_stake::msg::SlashPolicy::Remove,
+                        points_rounding: tg4_stake::msg::Rounding::Down,
                     },
                     &[],
                     "group",
@@ -320,7 +440,14 @@ impl SuiteBuilder {
                         preauths_hooks: 0,
                         preauths_slashing: 1,
                         halflife: config.halflife,
+                        decay: None,
                         denom: denom.clone(),
+                        max_denoms: tg4_engagement::msg::default_max_denoms(),
+                        distribution_fee: Decimal::zero(),
+                        fee_recipient: None,
+                        auto_distribute: false,
+                        halflife_floor: 1,
+                        initial_adjustments: vec![],
                     },
                     &[],
                     "distribution",
@@ -337,6 +464,7 @@ impl SuiteBuilder {
             .map(|(addr, cfg)| UnvalidatedDistributionContract {
                 contract: addr.to_string(),
                 ratio: cfg.reward_ratio,
+                schema: DistributionSchema::default(),
             })
             .collect();
 
@@ -364,6 +492,23 @@ impl SuiteBuilder {
                     offline_jail_duration: self
                         .verify_validators
                         .unwrap_or_else(|| Duration::new(0)),
+                    offline_jail_immunity: Duration::new(self.offline_jail_immunity),
+                    min_active_validators: self.min_active_validators,
+                    delay_new_operators: self.delay_new_operators,
+                    active_set_retention: self.active_set_retention,
+                    min_self_bond: self.min_self_bond.into(),
+                    probation_scaling: self.probation_scaling,
+                    probation_epochs: self.probation_epochs,
+                    metadata_update_cooldown: Duration::new(self.metadata_update_cooldown),
+                    uptime_rewards: self.uptime_rewards,
+                    tie_break: self.tie_break,
+                    downtime_slash_ratio: self.downtime_slash_ratio,
+                    governance_slash_ratio: self.governance_slash_ratio,
+                    max_power: self.max_power,
+                    max_churn: self.max_churn,
+                    reward_vesting: self.reward_vesting,
+                    verification_grace_blocks: self.verification_grace_blocks,
+                    max_epoch_reward: self.max_epoch_reward,
                 },
                 &[],
                 "valset",
@@ -522,11 +667,63 @@ impl Suite {
             &ExecuteMsg::Jail {
                 operator: operator.to_owned(),
                 duration: duration.into(),
+                immediate: false,
+            },
+            &[],
+        )
+    }
+
+    pub fn jail_immediately(
+        &mut self,
+        executor: &str,
+        operator: &str,
+        duration: impl Into<JailingDuration>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.valset.clone(),
+            &ExecuteMsg::Jail {
+                operator: operator.to_owned(),
+                duration: duration.into(),
+                immediate: true,
+            },
+            &[],
+        )
+    }
+
+    pub fn deregister(&mut self, executor: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.valset.clone(),
+            &ExecuteMsg::Deregister {},
+            &[],
+        )
+    }
+
+    pub fn tombstone(&mut self, executor: &str, operator: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.valset.clone(),
+            &ExecuteMsg::Tombstone {
+                operator: operator.to_owned(),
             },
             &[],
         )
     }
 
+    pub fn set_epoch_processing_paused(
+        &mut self,
+        executor: &str,
+        paused: bool,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.valset.clone(),
+            &ExecuteMsg::SetEpochProcessingPaused { paused },
+            &[],
+        )
+    }
+
     pub fn unjail<'a>(
         &mut self,
         executor: &str,
@@ -556,6 +753,17 @@ impl Suite {
         )
     }
 
+    pub fn set_reward_address(&mut self, executor: &str, address: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.valset.clone(),
+            &ExecuteMsg::SetRewardAddress {
+                address: address.to_owned(),
+            },
+            &[],
+        )
+    }
+
     pub fn update_metadata(
         &mut self,
         executor: &str,
@@ -605,16 +813,365 @@ impl Suite {
                 distribution_contracts: distribution_contracts.into(),
                 verify_validators: None,
                 offline_jail_duration: None,
+                offline_jail_immunity: None,
+                min_active_validators: None,
+                delay_new_operators: None,
+                active_set_retention: None,
+                min_self_bond: None,
+                probation_scaling: None,
+                probation_epochs: None,
+                metadata_update_cooldown: None,
+                uptime_rewards: None,
+                tie_break: None,
+                downtime_slash_ratio: None,
+                governance_slash_ratio: None,
+                max_power: None,
+                max_churn: None,
+                epoch_length: None,
+                reward_vesting: None,
+                verification_grace_blocks: None,
+                max_epoch_reward: None,
+            },
+            &[],
+        )
+    }
+
+    pub fn add_distribution_contract(
+        &mut self,
+        executor: &str,
+        contract: &str,
+        ratio: Decimal,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.valset.clone(),
+            &ExecuteMsg::AddDistributionContract {
+                contract: contract.to_owned(),
+                ratio,
+            },
+            &[],
+        )
+    }
+
+    pub fn remove_distribution_contract(
+        &mut self,
+        executor: &str,
+        contract: &str,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.valset.clone(),
+            &ExecuteMsg::RemoveDistributionContract {
+                contract: contract.to_owned(),
+            },
+            &[],
+        )
+    }
+
+    pub fn update_min_self_bond(
+        &mut self,
+        executor: &str,
+        min_self_bond: u128,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.valset.clone(),
+            &ExecuteMsg::UpdateConfig {
+                min_points: None,
+                max_validators: None,
+                scaling: None,
+                epoch_reward: None,
+                fee_percentage: None,
+                auto_unjail: None,
+                double_sign_slash_ratio: None,
+                distribution_contracts: None,
+                verify_validators: None,
+                offline_jail_duration: None,
+                offline_jail_immunity: None,
+                min_active_validators: None,
+                delay_new_operators: None,
+                active_set_retention: None,
+                min_self_bond: Some(min_self_bond.into()),
+                probation_scaling: None,
+                probation_epochs: None,
+                metadata_update_cooldown: None,
+                uptime_rewards: None,
+                tie_break: None,
+                downtime_slash_ratio: None,
+                governance_slash_ratio: None,
+                max_power: None,
+                max_churn: None,
+                epoch_length: None,
+                reward_vesting: None,
+                verification_grace_blocks: None,
+                max_epoch_reward: None,
+            },
+            &[],
+        )
+    }
+
+    pub fn update_tie_break(
+        &mut self,
+        executor: &str,
+        tie_break: TieBreak,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.valset.clone(),
+            &ExecuteMsg::UpdateConfig {
+                min_points: None,
+                max_validators: None,
+                scaling: None,
+                epoch_reward: None,
+                fee_percentage: None,
+                auto_unjail: None,
+                double_sign_slash_ratio: None,
+                distribution_contracts: None,
+                verify_validators: None,
+                offline_jail_duration: None,
+                offline_jail_immunity: None,
+                min_active_validators: None,
+                delay_new_operators: None,
+                active_set_retention: None,
+                min_self_bond: None,
+                probation_scaling: None,
+                probation_epochs: None,
+                metadata_update_cooldown: None,
+                uptime_rewards: None,
+                tie_break: Some(tie_break),
+                downtime_slash_ratio: None,
+                governance_slash_ratio: None,
+                max_power: None,
+                max_churn: None,
+                epoch_length: None,
+                reward_vesting: None,
+                verification_grace_blocks: None,
+                max_epoch_reward: None,
+            },
+            &[],
+        )
+    }
+
+    pub fn update_governance_slash_ratio(
+        &mut self,
+        executor: &str,
+        governance_slash_ratio: Decimal,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.valset.clone(),
+            &ExecuteMsg::UpdateConfig {
+                min_points: None,
+                max_validators: None,
+                scaling: None,
+                epoch_reward: None,
+                fee_percentage: None,
+                auto_unjail: None,
+                double_sign_slash_ratio: None,
+                distribution_contracts: None,
+                verify_validators: None,
+                offline_jail_duration: None,
+                offline_jail_immunity: None,
+                min_active_validators: None,
+                delay_new_operators: None,
+                active_set_retention: None,
+                min_self_bond: None,
+                probation_scaling: None,
+                probation_epochs: None,
+                metadata_update_cooldown: None,
+                uptime_rewards: None,
+                tie_break: None,
+                downtime_slash_ratio: None,
+                governance_slash_ratio: Some(governance_slash_ratio),
+                max_power: None,
+                max_churn: None,
+                epoch_length: None,
+                reward_vesting: None,
+                verification_grace_blocks: None,
+                max_epoch_reward: None,
             },
             &[],
         )
     }
 
+    pub fn update_max_power(&mut self, executor: &str, max_power: u64) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.valset.clone(),
+            &ExecuteMsg::UpdateConfig {
+                min_points: None,
+                max_validators: None,
+                scaling: None,
+                epoch_reward: None,
+                fee_percentage: None,
+                auto_unjail: None,
+                double_sign_slash_ratio: None,
+                distribution_contracts: None,
+                verify_validators: None,
+                offline_jail_duration: None,
+                offline_jail_immunity: None,
+                min_active_validators: None,
+                delay_new_operators: None,
+                active_set_retention: None,
+                min_self_bond: None,
+                probation_scaling: None,
+                probation_epochs: None,
+                metadata_update_cooldown: None,
+                uptime_rewards: None,
+                tie_break: None,
+                downtime_slash_ratio: None,
+                governance_slash_ratio: None,
+                max_power: Some(max_power),
+                max_churn: None,
+                epoch_length: None,
+                reward_vesting: None,
+                verification_grace_blocks: None,
+                max_epoch_reward: None,
+            },
+            &[],
+        )
+    }
+
+    pub fn update_max_churn(&mut self, executor: &str, max_churn: u32) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.valset.clone(),
+            &ExecuteMsg::UpdateConfig {
+                min_points: None,
+                max_validators: None,
+                scaling: None,
+                epoch_reward: None,
+                fee_percentage: None,
+                auto_unjail: None,
+                double_sign_slash_ratio: None,
+                distribution_contracts: None,
+                verify_validators: None,
+                offline_jail_duration: None,
+                offline_jail_immunity: None,
+                min_active_validators: None,
+                delay_new_operators: None,
+                active_set_retention: None,
+                min_self_bond: None,
+                probation_scaling: None,
+                probation_epochs: None,
+                metadata_update_cooldown: None,
+                uptime_rewards: None,
+                tie_break: None,
+                downtime_slash_ratio: None,
+                governance_slash_ratio: None,
+                max_power: None,
+                max_churn: Some(max_churn),
+                epoch_length: None,
+                reward_vesting: None,
+                verification_grace_blocks: None,
+                max_epoch_reward: None,
+            },
+            &[],
+        )
+    }
+
+    pub fn update_epoch_reward(
+        &mut self,
+        executor: &str,
+        epoch_reward: impl Into<Option<Coin>>,
+        max_epoch_reward: impl Into<Option<Coin>>,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.valset.clone(),
+            &ExecuteMsg::UpdateConfig {
+                min_points: None,
+                max_validators: None,
+                scaling: None,
+                epoch_reward: epoch_reward.into(),
+                fee_percentage: None,
+                auto_unjail: None,
+                double_sign_slash_ratio: None,
+                distribution_contracts: None,
+                verify_validators: None,
+                offline_jail_duration: None,
+                offline_jail_immunity: None,
+                min_active_validators: None,
+                delay_new_operators: None,
+                active_set_retention: None,
+                min_self_bond: None,
+                probation_scaling: None,
+                probation_epochs: None,
+                metadata_update_cooldown: None,
+                uptime_rewards: None,
+                tie_break: None,
+                downtime_slash_ratio: None,
+                governance_slash_ratio: None,
+                max_power: None,
+                max_churn: None,
+                epoch_length: None,
+                reward_vesting: None,
+                verification_grace_blocks: None,
+                max_epoch_reward: max_epoch_reward.into(),
+            },
+            &[],
+        )
+    }
+
+    pub fn update_epoch_length(
+        &mut self,
+        executor: &str,
+        epoch_length: u64,
+    ) -> AnyResult<AppResponse> {
+        let resp = self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.valset.clone(),
+            &ExecuteMsg::UpdateConfig {
+                min_points: None,
+                max_validators: None,
+                scaling: None,
+                epoch_reward: None,
+                fee_percentage: None,
+                auto_unjail: None,
+                double_sign_slash_ratio: None,
+                distribution_contracts: None,
+                verify_validators: None,
+                offline_jail_duration: None,
+                offline_jail_immunity: None,
+                min_active_validators: None,
+                delay_new_operators: None,
+                active_set_retention: None,
+                min_self_bond: None,
+                probation_scaling: None,
+                probation_epochs: None,
+                metadata_update_cooldown: None,
+                uptime_rewards: None,
+                tie_break: None,
+                downtime_slash_ratio: None,
+                governance_slash_ratio: None,
+                max_power: None,
+                max_churn: None,
+                epoch_length: Some(epoch_length),
+                reward_vesting: None,
+                verification_grace_blocks: None,
+                max_epoch_reward: None,
+            },
+            &[],
+        )?;
+        self.epoch_length = epoch_length;
+        Ok(resp)
+    }
+
     pub fn slash(
         &mut self,
         executor: &str,
         addr: &str,
         portion: Decimal,
+    ) -> AnyResult<AppResponse> {
+        self.slash_with_reason(executor, addr, portion, SlashingCause::Governance)
+    }
+
+    pub fn slash_with_reason(
+        &mut self,
+        executor: &str,
+        addr: &str,
+        portion: Decimal,
+        reason: SlashingCause,
     ) -> AnyResult<AppResponse> {
         self.app.execute_contract(
             Addr::unchecked(executor),
@@ -622,6 +1179,7 @@ impl Suite {
             &ExecuteMsg::Slash {
                 addr: addr.to_owned(),
                 portion,
+                reason,
             },
             &[],
         )
@@ -639,6 +1197,7 @@ impl Suite {
                 &tg4_engagement::msg::ExecuteMsg::WithdrawRewards {
                     owner: None,
                     receiver: None,
+                    as_vesting: false,
                 },
                 &[],
             )
@@ -657,6 +1216,7 @@ impl Suite {
             &tg4_engagement::msg::ExecuteMsg::WithdrawRewards {
                 owner: None,
                 receiver: None,
+                as_vesting: false,
             },
             &[],
         )
@@ -712,25 +1272,50 @@ impl Suite {
         start_after: impl Into<Option<String>>,
         limit: impl Into<Option<u32>>,
     ) -> StdResult<Vec<ValidatorInfo>> {
-        let resp: ListActiveValidatorsResponse = self.app.wrap().query_wasm_smart(
+        let resp = self.list_active_validators_page(start_after, limit)?;
+        Ok(resp.validators)
+    }
+
+    pub fn list_active_validators_page(
+        &self,
+        start_after: impl Into<Option<String>>,
+        limit: impl Into<Option<u32>>,
+    ) -> StdResult<ListActiveValidatorsResponse> {
+        self.app.wrap().query_wasm_smart(
             self.valset.clone(),
             &QueryMsg::ListActiveValidators {
                 start_after: start_after.into(),
                 limit: limit.into(),
             },
+        )
+    }
+
+    pub fn list_jailed_validators(
+        &self,
+        start_after: impl Into<Option<String>>,
+        limit: impl Into<Option<u32>>,
+    ) -> StdResult<Vec<OperatorResponse>> {
+        let resp: ListValidatorResponse = self.app.wrap().query_wasm_smart(
+            self.valset.clone(),
+            &QueryMsg::ListJailedValidators {
+                start_after: start_after.into(),
+                limit: limit.into(),
+            },
         )?;
 
         Ok(resp.validators)
     }
 
-    pub fn list_jailed_validators(
+    pub fn list_validators_by_status(
         &self,
+        status: ValidatorStatus,
         start_after: impl Into<Option<String>>,
         limit: impl Into<Option<u32>>,
     ) -> StdResult<Vec<OperatorResponse>> {
         let resp: ListValidatorResponse = self.app.wrap().query_wasm_smart(
             self.valset.clone(),
-            &QueryMsg::ListJailedValidators {
+            &QueryMsg::ListValidatorsByStatus {
+                status,
                 start_after: start_after.into(),
                 limit: limit.into(),
             },
@@ -750,12 +1335,60 @@ impl Suite {
         Ok(resp)
     }
 
+    pub fn jailing_history(
+        &self,
+        addr: &str,
+        start_after: impl Into<Option<u64>>,
+        limit: impl Into<Option<u32>>,
+    ) -> StdResult<JailingHistoryResponse> {
+        let resp = self.app.wrap().query_wasm_smart(
+            self.valset.clone(),
+            &QueryMsg::JailingHistory {
+                operator: addr.to_owned(),
+                start_after: start_after.into(),
+                limit: limit.into(),
+            },
+        )?;
+
+        Ok(resp)
+    }
+
+    pub fn validator_full_profile(&self, addr: &str) -> StdResult<ValidatorFullProfileResponse> {
+        self.app.wrap().query_wasm_smart(
+            self.valset.clone(),
+            &QueryMsg::ValidatorFullProfile {
+                operator: addr.to_owned(),
+            },
+        )
+    }
+
     pub fn simulate_active_validators(&self) -> StdResult<Vec<ValidatorInfo>> {
-        let resp: ListActiveValidatorsResponse = self
-            .app
+        let resp = self.simulate_active_validators_response()?;
+        Ok(resp.validators)
+    }
+
+    pub fn simulate_active_validators_response(&self) -> StdResult<ListActiveValidatorsResponse> {
+        self.app
             .wrap()
-            .query_wasm_smart(self.valset.clone(), &QueryMsg::SimulateActiveValidators {})?;
+            .query_wasm_smart(self.valset.clone(), &QueryMsg::SimulateActiveValidators {})
+    }
 
+    pub fn simulate_active_validators_with(
+        &self,
+        overrides: &[(&str, u64)],
+    ) -> StdResult<Vec<ValidatorInfo>> {
+        let overrides = overrides
+            .iter()
+            .map(|(addr, points)| Member {
+                addr: (*addr).to_owned(),
+                points: *points,
+                start_height: None,
+            })
+            .collect();
+        let resp: ListActiveValidatorsResponse = self.app.wrap().query_wasm_smart(
+            self.valset.clone(),
+            &QueryMsg::SimulateActiveValidatorsWith { overrides },
+        )?;
         Ok(resp.validators)
     }
 
@@ -783,6 +1416,14 @@ impl Suite {
             .query_wasm_smart(&self.valset, &QueryMsg::Epoch {})
     }
 
+    pub fn upcoming_epochs(&self, count: u32) -> StdResult<Vec<UpcomingEpoch>> {
+        let resp: UpcomingEpochsResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.valset, &QueryMsg::UpcomingEpochs { count })?;
+        Ok(resp.epochs)
+    }
+
     /// Queries valset contract for given validator info
     pub fn validator(&self, addr: &str) -> StdResult<ValidatorResponse> {
         self.app.wrap().query_wasm_smart(
@@ -793,6 +1434,107 @@ impl Suite {
         )
     }
 
+    pub fn power_changes(&self, since_height: u64) -> StdResult<Vec<PowerChange>> {
+        let resp: PowerChangesResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.valset, &QueryMsg::PowerChanges { since_height })?;
+
+        Ok(resp.changes)
+    }
+
+    pub fn double_sign_info(&self) -> StdResult<DoubleSignInfoResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.valset, &QueryMsg::DoubleSignInfo {})
+    }
+
+    pub fn active_validators_at_epoch(&self, epoch: u64) -> StdResult<Vec<ValidatorInfo>> {
+        let resp: ListActiveValidatorsResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.valset, &QueryMsg::ActiveValidatorsAtEpoch { epoch })?;
+
+        Ok(resp.validators)
+    }
+
+    pub fn operator_by_consensus_key(&self, pubkey: Pubkey) -> StdResult<Option<Addr>> {
+        let resp: OperatorByConsensusKeyResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.valset, &QueryMsg::OperatorByConsensusKey { pubkey })?;
+
+        Ok(resp.operator)
+    }
+
+    pub fn validate_pubkey(&self, pubkey: Pubkey) -> StdResult<ValidatePubkeyResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.valset, &QueryMsg::ValidatePubkey { pubkey })
+    }
+
+    pub fn epochs_since_signed(&self, operator: &str) -> StdResult<Option<u64>> {
+        let resp: EpochsSinceSignedResponse = self.app.wrap().query_wasm_smart(
+            &self.valset,
+            &QueryMsg::EpochsSinceSigned {
+                operator: operator.to_owned(),
+            },
+        )?;
+        Ok(resp.epochs)
+    }
+
+    pub fn reward_address(&self, operator: &str) -> StdResult<Addr> {
+        let resp: RewardAddressResponse = self.app.wrap().query_wasm_smart(
+            &self.valset,
+            &QueryMsg::RewardAddress {
+                operator: operator.to_owned(),
+            },
+        )?;
+        Ok(resp.address)
+    }
+
+    pub fn distribution_contract_stats(&self) -> StdResult<Vec<DistributionContractStats>> {
+        let resp: DistributionContractStatsResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.valset, &QueryMsg::DistributionContractStats {})?;
+        Ok(resp.contracts)
+    }
+
+    pub fn is_tombstoned(&self, operator: &str) -> StdResult<bool> {
+        let resp: IsTombstonedResponse = self.app.wrap().query_wasm_smart(
+            &self.valset,
+            &QueryMsg::IsTombstoned {
+                operator: operator.to_owned(),
+            },
+        )?;
+        Ok(resp.tombstoned)
+    }
+
+    pub fn validators_at_risk(&self) -> StdResult<Vec<ValidatorAtRisk>> {
+        let resp: ValidatorsAtRiskResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.valset, &QueryMsg::ValidatorsAtRisk {})?;
+        Ok(resp.validators)
+    }
+
+    pub fn eligibility(&self, operator: &str) -> StdResult<Eligibility> {
+        let resp: EligibilityResponse = self.app.wrap().query_wasm_smart(
+            &self.valset,
+            &QueryMsg::Eligibility {
+                operator: operator.to_owned(),
+            },
+        )?;
+        Ok(resp.eligibility)
+    }
+
+    pub fn simulate_rewards(&self) -> StdResult<SimulateRewardsResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.valset, &QueryMsg::SimulateRewards {})
+    }
+
     /// Bonds some tokens.
     /// Only works when the membership contract is tg4_stake. Will error otherwise.
     pub fn bond(&mut self, addr: &Addr, stake: &[Coin]) -> AnyResult<AppResponse> {
@@ -812,7 +1554,35 @@ impl Suite {
         self.app.execute_contract(
             addr.clone(),
             self.membership.clone(),
-            &tg4_stake::msg::ExecuteMsg::Unbond { tokens },
+            &tg4_stake::msg::ExecuteMsg::Unbond {
+                tokens,
+                owner: None,
+            },
+            &[],
+        )
+    }
+
+    /// Updates engagement points of members.
+    /// Only works when the membership contract is tg4_engagement. Will error otherwise.
+    pub fn update_members(
+        &mut self,
+        add: &[(&str, u64)],
+        remove: &[&str],
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(&self.admin),
+            self.membership.clone(),
+            &tg4_engagement::msg::ExecuteMsg::UpdateMembers {
+                add: add
+                    .iter()
+                    .map(|(addr, points)| Member {
+                        addr: addr.to_string(),
+                        points: *points,
+                        start_height: None,
+                    })
+                    .collect(),
+                remove: remove.iter().map(|addr| addr.to_string()).collect(),
+            },
             &[],
         )
     }
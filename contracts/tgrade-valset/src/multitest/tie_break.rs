@@ -0,0 +1,91 @@
+use super::helpers::{assert_active_validators, members_init};
+use super::suite::SuiteBuilder;
+use crate::msg::TieBreak;
+
+#[test]
+fn tie_at_last_slot_broken_by_strategy() {
+    let members = vec!["member1", "member2", "member3", "member4"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[10, 1, 5, 0]))
+        .with_operators(&members)
+        .with_max_validators(1)
+        .with_tie_break(TieBreak::OldestFirst)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    // Validators join one by one: member1, then member3, then member2. member4 is never a
+    // validator.
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 10)],
+    );
+    suite.update_config(&admin, None, 2, None).unwrap();
+    suite.advance_epoch().unwrap();
+    suite.update_config(&admin, None, 3, None).unwrap();
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 10), (members[1], 1), (members[2], 5)],
+    );
+
+    suite
+        .update_members(&members_init(&members, &[5, 5, 5, 5]), &[])
+        .unwrap();
+    suite.update_config(&admin, None, 2, None).unwrap();
+
+    // Oldest validators are selected first
+    assert_active_validators(
+        &suite.simulate_active_validators().unwrap(),
+        &[(members[0], 5), (members[2], 5)],
+    );
+
+    // Newest validators are selected first, ones never being validators are the newest
+    suite
+        .update_tie_break(&admin, TieBreak::NewestFirst)
+        .unwrap();
+    assert_active_validators(
+        &suite.simulate_active_validators().unwrap(),
+        &[(members[3], 5), (members[1], 5)],
+    );
+
+    // Lexicographically first pubkeys are selected first
+    suite.update_tie_break(&admin, TieBreak::PubkeyLex).unwrap();
+    assert_active_validators(
+        &suite.simulate_active_validators().unwrap(),
+        &[(members[0], 5), (members[1], 5)],
+    );
+
+    suite
+        .update_tie_break(&admin, TieBreak::NewestFirst)
+        .unwrap();
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[3], 5), (members[1], 5)],
+    );
+}
+
+#[test]
+fn never_validators_come_last_for_oldest_first() {
+    let members = vec!["member1", "member2", "member3"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[0, 5, 0]))
+        .with_operators(&members)
+        .with_tie_break(TieBreak::OldestFirst)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    suite.advance_epoch().unwrap();
+    suite
+        .update_members(&members_init(&members, &[5, 5, 5]), &[])
+        .unwrap();
+    suite.update_config(&admin, None, 2, None).unwrap();
+
+    // member2 is the only one who was a validator, the tie between the others is broken by
+    // pubkey
+    assert_active_validators(
+        &suite.simulate_active_validators().unwrap(),
+        &[(members[0], 5), (members[1], 5)],
+    );
+}
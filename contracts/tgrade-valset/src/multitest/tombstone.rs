@@ -0,0 +1,161 @@
+use cosmwasm_std::{coin, coins, Addr};
+use cw_controllers::AdminError;
+use tg_utils::{Duration, JailingDuration};
+
+use super::helpers::{assert_active_validators, members_init};
+use super::suite::SuiteBuilder;
+use crate::error::ContractError;
+use crate::test_helpers::{mock_metadata, mock_pubkey};
+
+const BOND_DENOM: &str = "tgrade";
+
+#[test]
+fn only_admin_can_tombstone() {
+    let members = vec!["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_operators(&members)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    let err = suite.tombstone(members[0], members[1]).unwrap_err();
+    assert_eq!(
+        ContractError::AdminError(AdminError::NotAdmin {}),
+        err.downcast().unwrap(),
+    );
+    assert!(!suite.is_tombstoned(members[1]).unwrap());
+
+    suite.tombstone(&admin, members[1]).unwrap();
+    assert!(suite.is_tombstoned(members[1]).unwrap());
+    assert!(!suite.is_tombstoned(members[0]).unwrap());
+
+    let slashing = suite.list_validator_slashing(members[1]).unwrap();
+    assert!(slashing.tombstoned);
+    assert_eq!(slashing.jailed_until, None);
+}
+
+#[test]
+fn jailing_forever_reported_as_tombstoned() {
+    let members = vec!["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_operators(&members)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    suite
+        .jail(&admin, members[0], JailingDuration::Forever {})
+        .unwrap();
+    assert!(suite.is_tombstoned(members[0]).unwrap());
+
+    suite.jail(&admin, members[1], Duration::new(3600)).unwrap();
+    assert!(!suite.is_tombstoned(members[1]).unwrap());
+}
+
+#[test]
+fn tombstoned_operator_cannot_deregister() {
+    let members = vec!["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_operators(&members)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    suite.tombstone(&admin, members[0]).unwrap();
+    suite
+        .jail(&admin, members[1], JailingDuration::Forever {})
+        .unwrap();
+
+    for member in members {
+        let err = suite.deregister(member).unwrap_err();
+        assert_eq!(
+            ContractError::Tombstoned(member.to_owned()),
+            err.downcast().unwrap(),
+        );
+        assert!(suite.validator(member).unwrap().validator.is_some());
+    }
+}
+
+#[test]
+fn tombstoned_operator_cannot_be_revived() {
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_stake(BOND_DENOM, 10u128)
+        .with_operators(&members)
+        .with_funds(&[
+            (members[0], &coins(200, BOND_DENOM)),
+            (members[1], &coins(50, BOND_DENOM)),
+        ])
+        .with_min_points(1)
+        .with_epoch_reward(coin(0, "usdc"))
+        .build();
+    let admin = suite.admin().to_owned();
+
+    suite
+        .bond(&Addr::unchecked(members[0]), &coins(100, BOND_DENOM))
+        .unwrap();
+    suite
+        .bond(&Addr::unchecked(members[1]), &coins(50, BOND_DENOM))
+        .unwrap();
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 10), (members[1], 5)],
+    );
+
+    suite.tombstone(&admin, members[0]).unwrap();
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[1], 5)],
+    );
+
+    // Neither admin nor operator can unjail
+    let err = suite.unjail(&admin, members[0]).unwrap_err();
+    assert_eq!(
+        ContractError::UnjailFromJailForeverForbidden {},
+        err.downcast().unwrap(),
+    );
+    let err = suite.unjail(members[0], None).unwrap_err();
+    assert_eq!(
+        ContractError::UnjailFromJailForeverForbidden {},
+        err.downcast().unwrap(),
+    );
+
+    // Jail cannot be shortened to unjail afterwards
+    let err = suite
+        .jail(&admin, members[0], Duration::new(3600))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Tombstoned(members[0].to_owned()),
+        err.downcast().unwrap(),
+    );
+
+    // Re-registration is not possible
+    let err = suite
+        .register_validator_key(
+            members[0],
+            mock_pubkey(b"new_key"),
+            mock_metadata(members[0]),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::OperatorRegistered {},
+        err.downcast().unwrap(),
+    );
+
+    // Raising points doesn't bring the validator back
+    suite
+        .bond(&Addr::unchecked(members[0]), &coins(100, BOND_DENOM))
+        .unwrap();
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[1], 5)],
+    );
+    assert_active_validators(
+        &suite.simulate_active_validators().unwrap(),
+        &[(members[1], 5)],
+    );
+    assert!(suite.is_tombstoned(members[0]).unwrap());
+}
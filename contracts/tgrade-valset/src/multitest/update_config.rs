@@ -1,7 +1,8 @@
-use cosmwasm_std::{Addr, Decimal};
+use cosmwasm_std::{coin, Addr, Decimal};
 use cw_controllers::AdminError;
 
 use crate::error::ContractError;
+use crate::msg::DistributionSchema;
 use crate::multitest::suite::Suite;
 use crate::state::DistributionContract;
 
@@ -27,6 +28,7 @@ fn update_cfg() {
             vec![DistributionContract {
                 contract: Addr::unchecked("contract1"),
                 ratio: Decimal::percent(15),
+                schema: DistributionSchema::default(),
             }],
         )
         .unwrap();
@@ -38,7 +40,8 @@ fn update_cfg() {
         cfg.distribution_contracts,
         vec![DistributionContract {
             contract: Addr::unchecked("contract1"),
-            ratio: Decimal::percent(15)
+            ratio: Decimal::percent(15),
+            schema: DistributionSchema::default(),
         }]
     );
 }
@@ -59,7 +62,8 @@ fn none_values_do_not_alter_cfg() {
         cfg.distribution_contracts,
         vec![DistributionContract {
             contract: Addr::unchecked("contract1"),
-            ratio: Decimal::percent(50)
+            ratio: Decimal::percent(50),
+            schema: DistributionSchema::default(),
         }]
     );
 
@@ -73,7 +77,8 @@ fn none_values_do_not_alter_cfg() {
         cfg.distribution_contracts,
         vec![DistributionContract {
             contract: Addr::unchecked("contract1"),
-            ratio: Decimal::percent(50)
+            ratio: Decimal::percent(50),
+            schema: DistributionSchema::default(),
         }]
     );
 }
@@ -90,3 +95,248 @@ fn non_admin_cannot_update_cfg() {
         err.downcast().unwrap(),
     );
 }
+
+/// Passes `seconds` and processes the end of block
+fn advance_seconds(suite: &mut Suite, seconds: u64) {
+    suite.app().advance_seconds(seconds);
+    suite.app().end_block().unwrap();
+    suite.app().begin_block(vec![]).unwrap();
+}
+
+/// Passes time up to the start of the next epoch, processing its boundary
+fn advance_to_next_epoch(suite: &mut Suite) {
+    let next_update_time = suite.epoch().unwrap().next_update_time;
+    let now = suite.timestamp().seconds();
+    advance_seconds(suite, next_update_time - now);
+}
+
+#[test]
+fn shortened_epoch_pays_once_at_next_boundary() {
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&[(members[0], 2), (members[1], 3)])
+        .with_operators(&members)
+        .with_epoch_reward(coin(1000, "usdc"))
+        .with_epoch_length(1000)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    advance_to_next_epoch(&mut suite);
+    let epoch = suite.epoch().unwrap().current_epoch;
+
+    // Epochs since the genesis are not renumbered with the new length
+    advance_seconds(&mut suite, 500);
+    suite.update_epoch_length(&admin, 100).unwrap();
+    let change_time = suite.timestamp().seconds();
+    assert_eq!(suite.epoch().unwrap().epoch_length, 100);
+    assert_eq!(suite.epoch().unwrap().current_epoch, epoch);
+    assert_eq!(suite.epoch().unwrap().next_update_time, change_time + 100);
+
+    advance_seconds(&mut suite, 50);
+    assert_eq!(suite.epoch().unwrap().current_epoch, epoch);
+
+    advance_seconds(&mut suite, 50);
+    assert_eq!(suite.epoch().unwrap().current_epoch, epoch + 1);
+
+    // Exactly two epochs paid: one before the change, one after
+    suite.withdraw_validation_reward(members[0]).unwrap();
+    suite.withdraw_validation_reward(members[1]).unwrap();
+    assert_eq!(suite.token_balance(members[0]).unwrap(), 800);
+    assert_eq!(suite.token_balance(members[1]).unwrap(), 1200);
+}
+
+#[test]
+fn extended_epoch_pays_once_at_next_boundary() {
+    let members = ["member1", "member2"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&[(members[0], 2), (members[1], 3)])
+        .with_operators(&members)
+        .with_epoch_reward(coin(1000, "usdc"))
+        .with_epoch_length(100)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    advance_to_next_epoch(&mut suite);
+    let epoch = suite.epoch().unwrap().current_epoch;
+
+    suite.update_epoch_length(&admin, 1000).unwrap();
+
+    // Old boundaries are not hit anymore
+    advance_seconds(&mut suite, 500);
+    assert_eq!(suite.epoch().unwrap().current_epoch, epoch);
+
+    advance_seconds(&mut suite, 500);
+    assert_eq!(suite.epoch().unwrap().current_epoch, epoch + 1);
+
+    let upcoming = suite.upcoming_epochs(2).unwrap();
+    assert_eq!(upcoming[1].update_time - upcoming[0].update_time, 1000);
+
+    suite.withdraw_validation_reward(members[0]).unwrap();
+    suite.withdraw_validation_reward(members[1]).unwrap();
+    assert_eq!(suite.token_balance(members[0]).unwrap(), 800);
+    assert_eq!(suite.token_balance(members[1]).unwrap(), 1200);
+}
+
+#[test]
+fn epoch_length_cannot_be_zero() {
+    let mut suite = SuiteBuilder::new().build();
+    let admin = suite.admin().to_owned();
+
+    let err = suite.update_epoch_length(&admin, 0).unwrap_err();
+    assert_eq!(ContractError::InvalidEpoch {}, err.downcast().unwrap());
+}
+
+#[test]
+fn add_and_remove_distribution_contracts() {
+    let mut suite = SuiteBuilder::new()
+        .with_distribution(Decimal::percent(50), &[("engagement1", 20)], None)
+        .build();
+    let admin = suite.admin().to_owned();
+    let existing = suite.config().unwrap().distribution_contracts;
+
+    suite
+        .add_distribution_contract(&admin, "community_pool", Decimal::percent(30))
+        .unwrap();
+
+    let mut expected = existing.clone();
+    expected.push(DistributionContract {
+        contract: Addr::unchecked("community_pool"),
+        ratio: Decimal::percent(30),
+        schema: DistributionSchema::default(),
+    });
+    assert_eq!(suite.config().unwrap().distribution_contracts, expected);
+
+    // Already configured contract cannot be added again
+    let err = suite
+        .add_distribution_contract(&admin, "community_pool", Decimal::percent(10))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::DistributionContractExists("community_pool".to_owned()),
+        err.downcast().unwrap()
+    );
+
+    suite
+        .remove_distribution_contract(&admin, existing[0].contract.as_str())
+        .unwrap();
+    assert_eq!(
+        suite.config().unwrap().distribution_contracts,
+        expected[1..].to_vec()
+    );
+
+    let err = suite
+        .remove_distribution_contract(&admin, "unknown")
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NoDistributionContract {},
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn adding_distribution_contract_cannot_exceed_ratio() {
+    let mut suite = SuiteBuilder::new()
+        .with_distribution(Decimal::percent(50), &[("engagement1", 20)], None)
+        .build();
+    let admin = suite.admin().to_owned();
+    let existing = suite.config().unwrap().distribution_contracts;
+
+    let err = suite
+        .add_distribution_contract(&admin, "community_pool", Decimal::percent(51))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidRewardsRatio {},
+        err.downcast().unwrap()
+    );
+    assert_eq!(suite.config().unwrap().distribution_contracts, existing);
+
+    // Filling up to exactly 1 is fine
+    suite
+        .add_distribution_contract(&admin, "community_pool", Decimal::percent(50))
+        .unwrap();
+}
+
+#[test]
+fn non_admin_cannot_change_distribution_contracts() {
+    let mut suite = SuiteBuilder::new()
+        .with_distribution(Decimal::percent(50), &[("engagement1", 20)], None)
+        .build();
+    let existing = suite.config().unwrap().distribution_contracts;
+
+    let err = suite
+        .add_distribution_contract("random fella", "community_pool", Decimal::percent(10))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::AdminError(AdminError::NotAdmin {}),
+        err.downcast().unwrap()
+    );
+
+    let err = suite
+        .remove_distribution_contract("random fella", existing[0].contract.as_str())
+        .unwrap_err();
+    assert_eq!(
+        ContractError::AdminError(AdminError::NotAdmin {}),
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn epoch_reward_cannot_exceed_cap() {
+    let mut suite = SuiteBuilder::new()
+        .with_epoch_reward(coin(100, "usdc"))
+        .with_max_epoch_reward(coin(1000, "usdc"))
+        .build();
+    let admin = suite.admin().to_string();
+
+    let err = suite
+        .update_epoch_reward(&admin, coin(1001, "usdc"), None)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::EpochRewardTooHigh {},
+        err.downcast().unwrap()
+    );
+    assert_eq!(suite.config().unwrap().epoch_reward, coin(100, "usdc"));
+
+    suite
+        .update_epoch_reward(&admin, coin(1000, "usdc"), None)
+        .unwrap();
+    assert_eq!(suite.config().unwrap().epoch_reward, coin(1000, "usdc"));
+
+    // Cap cannot be lowered below the current reward
+    let err = suite
+        .update_epoch_reward(&admin, None, coin(500, "usdc"))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::EpochRewardTooHigh {},
+        err.downcast().unwrap()
+    );
+
+    // Reward and cap cannot be changed together
+    let err = suite
+        .update_epoch_reward(&admin, coin(400, "usdc"), coin(500, "usdc"))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::EpochRewardAndCapUpdate {},
+        err.downcast().unwrap()
+    );
+
+    // The reward has to be lowered first
+    suite
+        .update_epoch_reward(&admin, coin(400, "usdc"), None)
+        .unwrap();
+    suite
+        .update_epoch_reward(&admin, None, coin(500, "usdc"))
+        .unwrap();
+    let cfg = suite.config().unwrap();
+    assert_eq!(cfg.epoch_reward, coin(400, "usdc"));
+    assert_eq!(cfg.max_epoch_reward, Some(coin(500, "usdc")));
+
+    // Zero amount removes the cap
+    suite
+        .update_epoch_reward(&admin, None, coin(0, "usdc"))
+        .unwrap();
+    assert_eq!(suite.config().unwrap().max_epoch_reward, None);
+    suite
+        .update_epoch_reward(&admin, coin(5000, "usdc"), None)
+        .unwrap();
+    assert_eq!(suite.config().unwrap().epoch_reward, coin(5000, "usdc"));
+}
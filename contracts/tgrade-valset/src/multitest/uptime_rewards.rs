@@ -0,0 +1,100 @@
+use cosmwasm_std::coin;
+use tg_bindings::ValidatorVote;
+
+use super::helpers::{addr_to_vote_addr, members_init};
+use super::suite::{Suite, SuiteBuilder};
+
+/// Withdraws validation rewards of members, returning withdrawn amounts
+fn withdraw(suite: &mut Suite, members: &[&str]) -> Vec<u128> {
+    members
+        .iter()
+        .map(|member| {
+            let before = suite.token_balance(member).unwrap();
+            suite.withdraw_validation_reward(member).unwrap();
+            suite.token_balance(member).unwrap() - before
+        })
+        .collect()
+}
+
+fn vote(suite: &mut Suite, voters: &[&str]) {
+    let votes: Vec<_> = voters
+        .iter()
+        .map(|voter| ValidatorVote {
+            address: addr_to_vote_addr(voter),
+            power: 10,
+            voted: true,
+        })
+        .collect();
+    suite.set_votes(&votes).unwrap();
+}
+
+#[test]
+fn rewards_scaled_by_uptime() {
+    let members = vec![
+        "member1member1member1member1memb",
+        "member2member2member2member2memb",
+    ];
+
+    let mut suite = SuiteBuilder::new()
+        .with_operators(&members)
+        .with_engagement(&members_init(&members, &[10, 10]))
+        .with_epoch_reward(coin(1000, "usdc"))
+        .with_verify_validators(600)
+        .with_epoch_length(100)
+        .with_uptime_rewards()
+        .build();
+
+    // Genesis is right before epoch boundary, move just past it
+    vote(&mut suite, &members);
+    suite.advance_seconds(1).unwrap();
+    suite.advance_epoch().unwrap();
+    withdraw(&mut suite, &members);
+
+    // Both validators sign first half of the epoch blocks, only the first one the rest
+    for _ in 0..4 {
+        suite.next_block().unwrap();
+    }
+    vote(&mut suite, &members[..1]);
+    for _ in 0..3 {
+        suite.next_block().unwrap();
+    }
+    suite.advance_epoch().unwrap();
+
+    // Validators have equal points, so 500 each, but member2 has 50% uptime
+    assert_eq!(withdraw(&mut suite, &members), vec![500, 250]);
+
+    // Forfeited 250 is added to the next epoch reward
+    vote(&mut suite, &members);
+    suite.advance_epoch().unwrap();
+
+    assert_eq!(withdraw(&mut suite, &members), vec![625, 625]);
+}
+
+#[test]
+fn rewards_not_scaled_by_default() {
+    let members = vec![
+        "member1member1member1member1memb",
+        "member2member2member2member2memb",
+    ];
+
+    let mut suite = SuiteBuilder::new()
+        .with_operators(&members)
+        .with_engagement(&members_init(&members, &[10, 10]))
+        .with_epoch_reward(coin(1000, "usdc"))
+        .with_verify_validators(600)
+        .with_epoch_length(100)
+        .build();
+
+    vote(&mut suite, &members);
+    suite.advance_seconds(1).unwrap();
+    suite.advance_epoch().unwrap();
+    withdraw(&mut suite, &members);
+
+    vote(&mut suite, &members[..1]);
+    for _ in 0..3 {
+        suite.next_block().unwrap();
+    }
+    suite.advance_epoch().unwrap();
+
+    assert_eq!(withdraw(&mut suite, &members), vec![500, 500]);
+}
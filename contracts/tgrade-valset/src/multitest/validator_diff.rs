@@ -0,0 +1,79 @@
+use cosmwasm_std::Event;
+
+use super::helpers::members_init;
+use super::suite::{Suite, SuiteBuilder};
+
+/// Moves to the next epoch, returning the `validator_diff` event emitted on its boundary
+fn advance_epoch_diff_event(suite: &mut Suite) -> Option<Event> {
+    suite.app().advance_seconds(100);
+    let (responses, _) = suite.app().end_block().unwrap();
+    suite.app().begin_block(vec![]).unwrap();
+    responses
+        .into_iter()
+        .flat_map(|resp| resp.events)
+        .find(|ev| ev.ty == "wasm-validator_diff")
+}
+
+fn attribute<'a>(event: &'a Event, key: &str) -> Option<&'a str> {
+    event
+        .attributes
+        .iter()
+        .find(|attr| attr.key == key)
+        .map(|attr| attr.value.as_str())
+}
+
+#[test]
+fn diff_event_emitted_on_set_changes_only() {
+    let members = ["member1", "member2", "member3"];
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &[2, 3, 5]))
+        .with_operators(&members)
+        .build();
+
+    // Set established on genesis didn't change
+    assert_eq!(advance_epoch_diff_event(&mut suite), None);
+
+    suite
+        .update_members(&members_init(&members, &[0, 4, 5]), &[])
+        .unwrap();
+    let event = advance_epoch_diff_event(&mut suite).unwrap();
+    assert_eq!(attribute(&event, "added_count"), Some("0"));
+    assert_eq!(attribute(&event, "added"), None);
+    assert_eq!(attribute(&event, "removed_count"), Some("1"));
+    assert_eq!(attribute(&event, "removed"), Some("member1"));
+    assert_eq!(attribute(&event, "updated_count"), Some("1"));
+    assert_eq!(attribute(&event, "updated"), Some("member2:4"));
+
+    assert_eq!(advance_epoch_diff_event(&mut suite), None);
+
+    suite
+        .update_members(&members_init(&members, &[2, 4, 5]), &[])
+        .unwrap();
+    let event = advance_epoch_diff_event(&mut suite).unwrap();
+    assert_eq!(attribute(&event, "added_count"), Some("1"));
+    assert_eq!(attribute(&event, "added"), Some("member1"));
+    assert_eq!(attribute(&event, "removed_count"), Some("0"));
+    assert_eq!(attribute(&event, "updated_count"), Some("0"));
+}
+
+#[test]
+fn diff_event_lists_are_capped() {
+    let joining: Vec<_> = (0..12).map(|idx| format!("member{:02}", idx)).collect();
+    let mut members: Vec<_> = joining.iter().map(String::as_str).collect();
+    members.push("anchor");
+    let mut points = [0; 13];
+    points[12] = 1;
+    let mut suite = SuiteBuilder::new()
+        .with_engagement(&members_init(&members, &points))
+        .with_operators(&members)
+        .with_max_validators(13)
+        .build();
+
+    suite
+        .update_members(&members_init(&members, &[1; 13]), &[])
+        .unwrap();
+    let event = advance_epoch_diff_event(&mut suite).unwrap();
+    assert_eq!(attribute(&event, "added_count"), Some("12"));
+    let listed = attribute(&event, "added").unwrap().split(',').count();
+    assert_eq!(listed, 10);
+}
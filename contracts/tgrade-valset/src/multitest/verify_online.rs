@@ -1,23 +1,18 @@
-use std::convert::TryInto;
+use cosmwasm_std::Addr;
 
 use crate::contract::MISSED_BLOCKS;
-use cosmwasm_std::Binary;
-use tg_bindings::{Ed25519Pubkey, ToAddress, ValidatorVote};
+use crate::msg::ValidatorAtRisk;
+use tg_bindings::ValidatorVote;
+use tg_utils::Duration;
 
 use crate::multitest::helpers::assert_active_validators;
 use crate::multitest::suite::Suite;
 
 use super::{
-    helpers::{addr_to_pubkey, members_init},
+    helpers::{addr_to_vote_addr, members_init},
     suite::SuiteBuilder,
 };
 
-fn addr_to_vote_addr(addr: &str) -> Binary {
-    let pubkey = addr_to_pubkey(addr);
-    let pubkey: Ed25519Pubkey = pubkey.try_into().unwrap();
-    Binary(pubkey.to_address().to_vec())
-}
-
 // Unreadable tests ahead! This deserves a refactor.
 
 #[test]
@@ -290,3 +285,350 @@ fn validator_needs_to_verify_if_unjailed_by_auto_unjail() {
         &[(members[0], 2)],
     );
 }
+
+#[test]
+fn unjailed_validator_is_immune_to_offline_jailing() {
+    let members = vec![
+        "member1member1member1member1memb",
+        "member2member2member2member2memb",
+    ];
+
+    let mut suite = SuiteBuilder::new()
+        .with_operators(&members)
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_verify_validators(600)
+        .with_offline_jail_immunity(10_000)
+        .with_epoch_length(600)
+        .build();
+
+    suite
+        .set_votes(&[ValidatorVote {
+            address: addr_to_vote_addr(members[0]),
+            power: 2,
+            voted: true,
+        }])
+        .unwrap();
+
+    suite.advance_blocks(MISSED_BLOCKS).unwrap();
+    suite.advance_epoch().unwrap();
+
+    // Validator 2 failed verification, so is jailed
+    assert!(suite
+        .validator(members[1])
+        .unwrap()
+        .validator
+        .unwrap()
+        .jailed_until
+        .is_some());
+
+    // An epoch passes and the validator gets to unjail themself
+    suite.advance_epoch().unwrap();
+    suite.unjail(members[1], members[1]).unwrap();
+    suite.advance_epoch().unwrap();
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[1], 3)],
+    );
+
+    // Validator still doesn't sign blocks, but is within the immunity window
+    suite.advance_blocks(MISSED_BLOCKS).unwrap();
+    suite.advance_epoch().unwrap();
+    assert!(suite
+        .validator(members[1])
+        .unwrap()
+        .validator
+        .unwrap()
+        .jailed_until
+        .is_none());
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[1], 3)],
+    );
+
+    // After the immunity window passes, the validator is jailed again
+    suite.advance_blocks(MISSED_BLOCKS).unwrap();
+    suite.advance_epoch().unwrap();
+    assert!(suite
+        .validator(members[1])
+        .unwrap()
+        .validator
+        .unwrap()
+        .jailed_until
+        .is_some());
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2)],
+    );
+}
+
+#[test]
+fn immunity_does_not_prevent_admin_jailing() {
+    let members = vec![
+        "member1member1member1member1memb",
+        "member2member2member2member2memb",
+    ];
+
+    let mut suite = SuiteBuilder::new()
+        .with_operators(&members)
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_verify_validators(600)
+        .with_offline_jail_immunity(10_000)
+        .with_epoch_length(600)
+        .build();
+
+    let admin = suite.admin().to_owned();
+
+    suite.jail(&admin, members[1], Duration::new(600)).unwrap();
+    suite.unjail(&admin, members[1]).unwrap();
+
+    // Admin can still jail the validator within the immunity window
+    suite.jail(&admin, members[1], Duration::new(600)).unwrap();
+    assert!(suite
+        .validator(members[1])
+        .unwrap()
+        .validator
+        .unwrap()
+        .jailed_until
+        .is_some());
+}
+
+#[test]
+fn epochs_since_signed() {
+    let members = vec![
+        "member1member1member1member1memb",
+        "member2member2member2member2memb",
+    ];
+
+    let mut suite = SuiteBuilder::new()
+        .with_operators(&members)
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_verify_validators(600)
+        .build();
+
+    // No boundary block signed yet
+    assert_eq!(suite.epochs_since_signed(members[0]).unwrap(), None);
+    assert_eq!(suite.epochs_since_signed(members[1]).unwrap(), None);
+
+    suite
+        .set_votes(&[
+            ValidatorVote {
+                address: addr_to_vote_addr(members[0]),
+                power: 2,
+                voted: true,
+            },
+            ValidatorVote {
+                address: addr_to_vote_addr(members[1]),
+                power: 3,
+                voted: true,
+            },
+        ])
+        .unwrap();
+    suite.advance_epoch().unwrap();
+
+    assert_eq!(suite.epochs_since_signed(members[0]).unwrap(), Some(0));
+    assert_eq!(suite.epochs_since_signed(members[1]).unwrap(), Some(0));
+
+    // Only member1 keeps signing
+    suite
+        .set_votes(&[ValidatorVote {
+            address: addr_to_vote_addr(members[0]),
+            power: 2,
+            voted: true,
+        }])
+        .unwrap();
+    suite.advance_epoch().unwrap();
+    suite.advance_epoch().unwrap();
+
+    assert_eq!(suite.epochs_since_signed(members[0]).unwrap(), Some(0));
+    assert_eq!(suite.epochs_since_signed(members[1]).unwrap(), Some(2));
+}
+
+#[test]
+fn skipped_epochs_not_recorded_as_signed() {
+    let members = vec![
+        "member1member1member1member1memb",
+        "member2member2member2member2memb",
+    ];
+
+    let mut suite = SuiteBuilder::new()
+        .with_operators(&members)
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_verify_validators(600)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    suite
+        .set_votes(&[
+            ValidatorVote {
+                address: addr_to_vote_addr(members[0]),
+                power: 2,
+                voted: true,
+            },
+            ValidatorVote {
+                address: addr_to_vote_addr(members[1]),
+                power: 3,
+                voted: true,
+            },
+        ])
+        .unwrap();
+    suite.advance_epoch().unwrap();
+
+    // Only member1 signs the boundary blocks of the skipped epoch
+    suite
+        .set_votes(&[ValidatorVote {
+            address: addr_to_vote_addr(members[0]),
+            power: 2,
+            voted: true,
+        }])
+        .unwrap();
+    suite.set_epoch_processing_paused(&admin, true).unwrap();
+    suite.advance_epoch().unwrap();
+
+    suite.set_votes(&[]).unwrap();
+    suite.set_epoch_processing_paused(&admin, false).unwrap();
+    suite.advance_epoch().unwrap();
+
+    assert_eq!(suite.epochs_since_signed(members[0]).unwrap(), Some(2));
+    assert_eq!(suite.epochs_since_signed(members[1]).unwrap(), Some(2));
+}
+
+#[test]
+fn validators_not_signing_reported_at_risk() {
+    let members = vec![
+        "member1member1member1member1memb",
+        "member2member2member2member2memb",
+    ];
+
+    let mut suite = SuiteBuilder::new()
+        .with_operators(&members)
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_verify_validators(600)
+        .with_epoch_length(600)
+        .build();
+    let admin = suite.admin().to_owned();
+
+    suite
+        .set_votes(&[ValidatorVote {
+            address: addr_to_vote_addr(members[0]),
+            power: 2,
+            voted: true,
+        }])
+        .unwrap();
+
+    // Within the missed blocks interval nobody is at risk
+    suite.advance_epoch().unwrap();
+    assert_eq!(suite.validators_at_risk().unwrap(), []);
+
+    // Validator who didn't sign for the missed blocks period is reported. Epochs are paused
+    // so it is not jailed right away.
+    suite.set_epoch_processing_paused(&admin, true).unwrap();
+    suite.advance_blocks(MISSED_BLOCKS).unwrap();
+    assert_eq!(
+        suite.validators_at_risk().unwrap(),
+        [ValidatorAtRisk {
+            operator: Addr::unchecked(members[1]),
+            last_signed_height: None,
+        }]
+    );
+
+    // ...and jailed on the next epoch boundary, so it is no longer active
+    suite.set_epoch_processing_paused(&admin, false).unwrap();
+    suite.advance_epoch().unwrap();
+    let info2 = suite.validator(members[1]).unwrap().validator.unwrap();
+    assert!(info2.jailed_until.is_some());
+    assert_eq!(suite.validators_at_risk().unwrap(), []);
+}
+
+#[test]
+fn validator_not_signing_jailed_after_grace_period() {
+    let members = vec![
+        "member1member1member1member1memb",
+        "member2member2member2member2memb",
+    ];
+
+    let mut suite = SuiteBuilder::new()
+        .with_operators(&members)
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_verify_validators(600)
+        .with_verification_grace_blocks(2 * MISSED_BLOCKS)
+        .build();
+
+    suite
+        .set_votes(&[ValidatorVote {
+            address: addr_to_vote_addr(members[0]),
+            power: 2,
+            voted: true,
+        }])
+        .unwrap();
+
+    // Missed blocks interval passed, but validator is still in the grace period
+    suite.advance_blocks(MISSED_BLOCKS).unwrap();
+    suite.advance_epoch().unwrap();
+    let info = suite.validator(members[1]).unwrap().validator.unwrap();
+    assert!(info.jailed_until.is_none());
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[1], 3)],
+    );
+
+    // Grace period is over, validator is jailed
+    suite.advance_blocks(MISSED_BLOCKS).unwrap();
+    suite.advance_epoch().unwrap();
+    let info = suite.validator(members[1]).unwrap().validator.unwrap();
+    assert!(info.jailed_until.is_some());
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2)],
+    );
+}
+
+#[test]
+fn validator_signing_within_grace_period_not_jailed() {
+    let members = vec![
+        "member1member1member1member1memb",
+        "member2member2member2member2memb",
+    ];
+
+    let mut suite = SuiteBuilder::new()
+        .with_operators(&members)
+        .with_engagement(&members_init(&members, &[2, 3]))
+        .with_verify_validators(600)
+        .with_verification_grace_blocks(2 * MISSED_BLOCKS)
+        .build();
+
+    suite
+        .set_votes(&[ValidatorVote {
+            address: addr_to_vote_addr(members[0]),
+            power: 2,
+            voted: true,
+        }])
+        .unwrap();
+
+    suite.advance_blocks(MISSED_BLOCKS).unwrap();
+    suite.advance_epoch().unwrap();
+
+    // Validator node is up and signing before the grace period is over
+    suite
+        .set_votes(&[
+            ValidatorVote {
+                address: addr_to_vote_addr(members[0]),
+                power: 2,
+                voted: true,
+            },
+            ValidatorVote {
+                address: addr_to_vote_addr(members[1]),
+                power: 3,
+                voted: true,
+            },
+        ])
+        .unwrap();
+
+    suite.advance_blocks(MISSED_BLOCKS).unwrap();
+    suite.advance_epoch().unwrap();
+    let info = suite.validator(members[1]).unwrap().validator.unwrap();
+    assert!(info.jailed_until.is_none());
+    assert_active_validators(
+        &suite.list_active_validators(None, None).unwrap(),
+        &[(members[0], 2), (members[1], 3)],
+    );
+}
@@ -1,71 +1,309 @@
-use crate::msg::{DistributionMsg, RewardsDistribution};
-use crate::state::Config;
+use std::cmp::min;
+use std::convert::TryFrom;
+
+use crate::msg::RewardsDistribution;
+use crate::state::{
+    operators, Config, OperatorPubkey, ValidatorInfo, DISTRIBUTED_REWARDS, EPOCH_BLOCKS,
+    EPOCH_SIGNED_BLOCKS, FORFEITED_REWARDS, REWARD_ADDRESS,
+};
 use cosmwasm_std::{
-    coins, to_binary, Coin, CustomQuery, DepsMut, Env, StdResult, SubMsg, Uint128, WasmMsg,
+    coin, coins, to_binary, Addr, BankMsg, Coin, CustomQuery, Decimal, Deps, DepsMut, Env, Order,
+    StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
 use tg_bindings::TgradeMsg;
 
-/// Ensure you pass in non-empty pay-validators, it will panic if total validator points is 0
-/// This handles all deps and calls into pure functions
-pub fn pay_block_rewards<Q: CustomQuery>(
-    deps: DepsMut<Q>,
-    env: Env,
+/// Reward of a single payment, split between all its receivers
+#[derive(Debug, PartialEq, Eq)]
+pub struct RewardsSplit {
+    pub denom: String,
+    /// Tokens to be minted for the payment
+    pub minted: Uint128,
+    /// Whole reward: minted tokens, collected fees and rewards forfeited in the previous payment
+    pub total: Uint128,
+    /// Commissions sent directly to operators reward addresses
+    pub commissions: Vec<(Addr, Uint128)>,
+    /// Rewards sent to distribution contracts, in `distribution_contracts` order
+    pub distributions: Vec<(Addr, Uint128)>,
+    /// Remainder sent to the validator group
+    pub validators_reward: Uint128,
+    /// Validators shares of the validators reward
+    pub validators_shares: Vec<(Addr, Uint128)>,
+    /// Validators shares sent directly to operators reward addresses, instead of being
+    /// distributed by the validator group
+    pub direct_shares: Vec<(Addr, Uint128)>,
+    /// Rewards forfeited for low uptime, kept for the next payment
+    pub forfeited: Uint128,
+    /// Validator group reward multipliers to be set for the distribution
+    multipliers: Vec<(Addr, Decimal)>,
+}
+
+/// Calculates how the reward for `pay_epochs` would be split, without changing any state.
+///
+/// `validators` are the validators active during the paid epochs. Their commissions are taken
+/// from their share of the reward before it is split.
+///
+/// If `uptimes` are given (matching `validators`), every validator share is scaled by its uptime,
+/// and the forfeited part is kept for the next payment.
+pub fn calculate_rewards<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: &Env,
     pay_epochs: u64,
     config: &Config,
-) -> StdResult<Vec<SubMsg<TgradeMsg>>> {
+    validators: &[ValidatorInfo],
+    uptimes: Option<&[Decimal]>,
+) -> StdResult<RewardsSplit> {
     // calculate the desired block reward
     let mut block_reward = config.epoch_reward.clone();
     block_reward.amount = Uint128::new(block_reward.amount.u128() * (pay_epochs as u128));
     let denom = block_reward.denom.clone();
 
-    // query existing balance
+    // query existing balance, rewards forfeited in the previous payment are not fees
+    let previously_forfeited = FORFEITED_REWARDS
+        .may_load(deps.storage)?
+        .unwrap_or_default();
     let balances = deps.querier.query_all_balances(&env.contract.address)?;
-    let fees_amount = get_fees_amount(&balances, &denom);
+    let fees_amount = get_fees_amount(&balances, &denom).saturating_sub(previously_forfeited);
 
     let amount = block_reward
         .amount
         .saturating_sub(config.fee_percentage * fees_amount);
-    block_reward.amount = amount + fees_amount;
+    block_reward.amount = amount + fees_amount + previously_forfeited;
 
+    let mut split = RewardsSplit {
+        denom,
+        minted: amount,
+        total: block_reward.amount,
+        commissions: vec![],
+        distributions: vec![],
+        validators_reward: Uint128::zero(),
+        validators_shares: vec![],
+        direct_shares: vec![],
+        forfeited: Uint128::zero(),
+        multipliers: vec![],
+    };
     let mut reward_pool = block_reward.amount;
 
+    // Validators' commissions are taken from their share of the whole reward, proportional to
+    // their power
+    let total_power: u64 = validators.iter().map(|v| v.power).sum();
+    if total_power > 0 {
+        for (idx, validator) in validators.iter().enumerate() {
+            let commission = operators()
+                .may_load(deps.storage, &validator.operator)?
+                .map(|op| op.metadata.commission)
+                .unwrap_or_default();
+            let mut reward = block_reward
+                .amount
+                .multiply_ratio(validator.power, total_power)
+                * commission;
+            if let Some(uptimes) = uptimes {
+                let lost = reward - reward * uptimes[idx];
+                split.forfeited += lost;
+                reward_pool -= lost;
+                reward -= lost;
+            }
+            if reward > Uint128::zero() {
+                reward_pool -= reward;
+                split.commissions.push((validator.operator.clone(), reward));
+            }
+        }
+    }
+
+    // The rest is split between distribution contracts and validators. Ratios are applied with
+    // decimal multiplication, so indivisible tokens end up with the validators.
+    let split_pool = reward_pool;
+    for contract in &config.distribution_contracts {
+        let reward = split_pool * contract.ratio;
+        if reward > Uint128::zero() {
+            reward_pool -= reward;
+            split
+                .distributions
+                .push((contract.contract.clone(), reward));
+        }
+    }
+
+    // Validators are rewarded proportionally to their uptime. Validator group splits rewards by
+    // points, so its reward multipliers are set to uptimes for the distribution. Shares of
+    // validators with reward address set are paid directly, so their multipliers are zeroed.
+    if total_power > 0 {
+        let validators_pool = reward_pool;
+        let mut group_paid = false;
+        for (idx, validator) in validators.iter().enumerate() {
+            let mut share = validators_pool.multiply_ratio(validator.power, total_power);
+            let uptime = uptimes.map_or(Decimal::one(), |uptimes| uptimes[idx]);
+            let lost = share - share * uptime;
+            split.forfeited += lost;
+            reward_pool -= lost;
+            share -= lost;
+
+            if REWARD_ADDRESS.has(deps.storage, &validator.operator) {
+                if share > Uint128::zero() {
+                    reward_pool -= share;
+                    split
+                        .direct_shares
+                        .push((validator.operator.clone(), share));
+                }
+                split
+                    .multipliers
+                    .push((validator.operator.clone(), Decimal::zero()));
+            } else {
+                group_paid |= !uptime.is_zero();
+                if uptime < Decimal::one() {
+                    split.multipliers.push((validator.operator.clone(), uptime));
+                }
+            }
+            split
+                .validators_shares
+                .push((validator.operator.clone(), share));
+        }
+
+        // Noone would get the rest from the validator group, keep it for the next payment
+        if !group_paid {
+            split.forfeited += reward_pool;
+            reward_pool = Uint128::zero();
+        }
+    }
+    split.validators_reward = reward_pool;
+
+    Ok(split)
+}
+
+/// Ensure you pass in non-empty pay-validators, it will panic if total validator points is 0
+/// This handles all deps and calls into pure functions
+///
+/// See `calculate_rewards` for details of the split.
+pub fn pay_block_rewards<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    pay_epochs: u64,
+    config: &Config,
+    validators: &[ValidatorInfo],
+    uptimes: Option<&[Decimal]>,
+) -> StdResult<Vec<SubMsg<TgradeMsg>>> {
+    let split = calculate_rewards(deps.as_ref(), &env, pay_epochs, config, validators, uptimes)?;
+    FORFEITED_REWARDS.save(deps.storage, &split.forfeited)?;
+
     // create the distribution messages
     let mut messages = vec![];
 
     // create a minting action if needed (and do this first)
-    if amount > Uint128::zero() {
+    if split.minted > Uint128::zero() {
         let minting = SubMsg::new(TgradeMsg::MintTokens {
-            denom,
-            amount,
+            denom: split.denom.clone(),
+            amount: split.minted,
             recipient: env.contract.address.into(),
         });
         messages.push(minting);
     }
 
-    for contract in &config.distribution_contracts {
-        let reward = block_reward.amount * contract.ratio;
-        if reward > Uint128::zero() {
-            reward_pool -= reward;
-            messages.push(SubMsg::new(WasmMsg::Execute {
-                contract_addr: contract.contract.to_string(),
-                msg: to_binary(&DistributionMsg::DistributeRewards {})?,
-                funds: coins(reward.into(), &block_reward.denom),
-            }));
-        }
+    for (operator, reward) in split.commissions.iter().chain(&split.direct_shares) {
+        let recipient = REWARD_ADDRESS
+            .may_load(deps.storage, operator)?
+            .unwrap_or_else(|| operator.clone());
+        let msg = if config.reward_vesting {
+            // Delegating to the vesting account of the recipient
+            SubMsg::new(TgradeMsg::Delegate {
+                funds: coin(reward.u128(), &split.denom),
+                staker: recipient.to_string(),
+            })
+        } else {
+            SubMsg::new(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: coins(reward.u128(), &split.denom),
+            })
+        };
+        messages.push(msg);
+    }
+
+    for (contract, reward) in &split.distributions {
+        DISTRIBUTED_REWARDS.update(deps.storage, contract, |total| -> StdResult<_> {
+            Ok(total.unwrap_or_default() + reward)
+        })?;
+        let schema = config
+            .distribution_contracts
+            .iter()
+            .find(|c| &c.contract == contract)
+            .map(|c| c.schema.clone())
+            .unwrap_or_default();
+        messages.push(SubMsg::new(WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg: schema.distribute_msg()?,
+            funds: coins(reward.u128(), &split.denom),
+        }));
     }
 
     // After rewarding all non-validators, the remainder goes to validators.
-    if reward_pool > Uint128::zero() {
+    if split.validators_reward > Uint128::zero() {
+        for (addr, uptime) in &split.multipliers {
+            // Group doesn't accept zero multiplier, the smallest one gives no reward either
+            let multiplier = if uptime.is_zero() {
+                Decimal::raw(1)
+            } else {
+                *uptime
+            };
+            messages.push(set_reward_multiplier(config, addr, multiplier)?);
+        }
         messages.push(SubMsg::new(WasmMsg::Execute {
             contract_addr: config.validator_group.to_string(),
             msg: to_binary(&RewardsDistribution::DistributeRewards {})?,
-            funds: coins(reward_pool.into(), &block_reward.denom),
+            funds: coins(split.validators_reward.u128(), &split.denom),
         }));
+        for (addr, _) in &split.multipliers {
+            messages.push(set_reward_multiplier(config, addr, Decimal::one())?);
+        }
     }
 
     Ok(messages)
 }
 
+fn set_reward_multiplier(
+    config: &Config,
+    addr: &Addr,
+    multiplier: Decimal,
+) -> StdResult<SubMsg<TgradeMsg>> {
+    Ok(SubMsg::new(WasmMsg::Execute {
+        contract_addr: config.validator_group.to_string(),
+        msg: to_binary(&RewardsDistribution::SetRewardMultiplier {
+            addr: addr.to_string(),
+            multiplier,
+        })?,
+        funds: vec![],
+    }))
+}
+
+/// Calculates uptime of `validators` in the current epoch, as the part of epoch blocks they
+/// signed. Validators with unsupported keys can't be verified, so they are not scaled.
+pub fn validators_uptime<Q: CustomQuery>(
+    deps: Deps<Q>,
+    validators: &[ValidatorInfo],
+) -> StdResult<Vec<Decimal>> {
+    let blocks = EPOCH_BLOCKS.may_load(deps.storage)?.unwrap_or_default();
+    validators
+        .iter()
+        .map(|v| match OperatorPubkey::try_from(&v.validator_pubkey) {
+            Ok(pubkey) if blocks > 0 => {
+                let signed = EPOCH_SIGNED_BLOCKS
+                    .may_load(deps.storage, &pubkey.to_address())?
+                    .unwrap_or_default();
+                Ok(Decimal::from_ratio(min(signed, blocks), blocks))
+            }
+            _ => Ok(Decimal::one()),
+        })
+        .collect()
+}
+
+/// Clears signed blocks counters of the current epoch
+pub fn reset_epoch_uptime(storage: &mut dyn Storage) -> StdResult<()> {
+    let signers = EPOCH_SIGNED_BLOCKS
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for signer in signers {
+        EPOCH_SIGNED_BLOCKS.remove(storage, &signer);
+    }
+    EPOCH_BLOCKS.remove(storage);
+    Ok(())
+}
+
 fn get_fees_amount(coins: &[Coin], denom: &str) -> Uint128 {
     coins
         .iter()
@@ -73,3 +311,98 @@ fn get_fees_amount(coins: &[Coin], denom: &str) -> Uint128 {
         .map(|coin| coin.amount)
         .unwrap_or_else(Uint128::zero)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::TieBreak;
+    use crate::test_helpers::valid_validator;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::CosmosMsg;
+    use tg4::Tg4Contract;
+    use tg_utils::Duration;
+
+    fn mock_config(reward_vesting: bool) -> Config {
+        Config {
+            membership: Tg4Contract::new(Addr::unchecked("membership")),
+            min_points: 1,
+            max_validators: 10,
+            scaling: None,
+            epoch_reward: coin(1000, "usdc"),
+            fee_percentage: Decimal::zero(),
+            auto_unjail: false,
+            double_sign_slash_ratio: Decimal::percent(50),
+            distribution_contracts: vec![],
+            validator_group: Addr::unchecked("group"),
+            verify_validators: false,
+            offline_jail_duration: Duration::new(0),
+            offline_jail_immunity: Duration::new(0),
+            min_active_validators: 0,
+            delay_new_operators: false,
+            active_set_retention: 0,
+            min_self_bond: Uint128::zero(),
+            probation_scaling: Decimal::one(),
+            probation_epochs: 0,
+            metadata_update_cooldown: Duration::new(0),
+            uptime_rewards: false,
+            tie_break: TieBreak::PubkeyLex,
+            downtime_slash_ratio: Decimal::zero(),
+            governance_slash_ratio: None,
+            max_power: None,
+            max_churn: None,
+            reward_vesting,
+            verification_grace_blocks: 0,
+            max_epoch_reward: None,
+        }
+    }
+
+    /// Pays a single epoch to a validator with reward address set, so its whole share is paid
+    /// directly, returning the payment message
+    fn direct_payment(reward_vesting: bool) -> CosmosMsg<TgradeMsg> {
+        let mut deps = mock_dependencies();
+        let validators = [valid_validator("operator", 1)];
+        REWARD_ADDRESS
+            .save(
+                &mut deps.storage,
+                &validators[0].operator,
+                &Addr::unchecked("receiver"),
+            )
+            .unwrap();
+
+        let messages = pay_block_rewards(
+            deps.as_mut(),
+            mock_env(),
+            1,
+            &mock_config(reward_vesting),
+            &validators,
+            None,
+        )
+        .unwrap();
+
+        // Minting comes first
+        assert_eq!(messages.len(), 2);
+        messages[1].msg.clone()
+    }
+
+    #[test]
+    fn direct_rewards_transferred() {
+        assert_eq!(
+            direct_payment(false),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "receiver".to_owned(),
+                amount: coins(1000, "usdc"),
+            })
+        );
+    }
+
+    #[test]
+    fn direct_rewards_vested() {
+        assert_eq!(
+            direct_payment(true),
+            CosmosMsg::Custom(TgradeMsg::Delegate {
+                funds: coin(1000, "usdc"),
+                staker: "receiver".to_owned(),
+            })
+        );
+    }
+}
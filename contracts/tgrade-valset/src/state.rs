@@ -1,17 +1,24 @@
 use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::convert::TryFrom;
+use std::convert::{TryFrom, TryInto};
 
 use cosmwasm_std::Order::Ascending;
-use cosmwasm_std::{to_binary, Addr, Coin, Decimal, Deps, DepsMut, Response, StdResult};
+use cosmwasm_std::{
+    to_binary, Addr, Coin, Decimal, Deps, DepsMut, Response, StdResult, Storage, Timestamp, Uint128,
+};
 use cw2::{get_contract_version, set_contract_version, ContractVersion};
 use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, UniqueIndex};
 use tg4::Tg4Contract;
 use tg_utils::{Duration, ADMIN};
 
 use crate::error::ContractError;
-use crate::msg::{default_fee_percentage, JailingPeriod, OperatorResponse, ValidatorMetadata};
-use tg_bindings::{Ed25519Pubkey, Pubkey, TgradeMsg, TgradeQuery};
+use crate::msg::{
+    default_fee_percentage, default_metadata_update_cooldown, default_offline_jail_immunity,
+    default_probation_scaling, DistributionSchema, JailingPeriod, JailingRecord, OperatorResponse,
+    TieBreak, ValidatorMetadata,
+};
+use tg_bindings::{Ed25519Pubkey, Pubkey, Secp256k1Pubkey, TgradeMsg, TgradeQuery, ToAddress};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct Config {
@@ -23,7 +30,7 @@ pub struct Config {
     pub min_points: u64,
     /// The maximum number of validators that can be included in the Tendermint validator set.
     /// If there are more validators than slots, we select the top N by membership points
-    /// descending. In case of ties at the last slot, the first (oldest) validator wins.
+    /// descending. Ties at the last slot are broken according to `tie_break`.
     pub max_validators: u32,
     /// A scaling factor to multiply tg4-engagement points to produce the tendermint validator power
     pub scaling: Option<u32>,
@@ -63,12 +70,96 @@ pub struct Config {
     /// The duration to jail a validator for in case they don't sign any blocks for a period of time.
     /// After the jailing period, they will be jailed again if not signing, ad infinitum.
     pub offline_jail_duration: Duration,
+
+    /// The period after being unjailed during which a validator cannot be jailed again for being
+    /// offline. Jailing by the admin is not affected.
+    #[serde(default = "default_offline_jail_immunity")]
+    pub offline_jail_immunity: Duration,
+
+    /// The minimum number of active validators to keep. If fewer members than this meet
+    /// `min_points`, the set is padded with the highest ranked members below `min_points`.
+    /// 0 means disabled.
+    #[serde(default)]
+    pub min_active_validators: u32,
+
+    /// If set, operators are not eligible to be active validators until the epoch following
+    /// their registration has passed.
+    #[serde(default)]
+    pub delay_new_operators: bool,
+
+    /// Number of most recent epochs for which the active validator set can be queried by epoch.
+    /// 0 means sets are not available.
+    #[serde(default)]
+    pub active_set_retention: u64,
+
+    /// Minimum amount of tokens an operator has to bond by themselves in the `membership`
+    /// contract to be eligible as an active validator. 0 means no minimum.
+    #[serde(default)]
+    pub min_self_bond: Uint128,
+
+    /// Power scaling of validators in the first epoch after `Unjail`, ramping back linearly to
+    /// full power over `probation_epochs` epochs.
+    #[serde(default = "default_probation_scaling")]
+    pub probation_scaling: Decimal,
+
+    /// Number of epochs of probation after `Unjail`. 0 means no probation.
+    #[serde(default)]
+    pub probation_epochs: u64,
+
+    /// Minimal time between two `UpdateMetadata` calls of a single operator, not applied to
+    /// the admin. Zero means no limit.
+    #[serde(default = "default_metadata_update_cooldown")]
+    pub metadata_update_cooldown: Duration,
+
+    /// If set together with `verify_validators`, validators rewards are scaled by their uptime
+    /// in the paid epoch.
+    #[serde(default)]
+    pub uptime_rewards: bool,
+
+    /// Strategy of selecting validators among operators with equal points at the last slot of
+    /// the validator set.
+    #[serde(default)]
+    pub tie_break: TieBreak,
+
+    /// Ratio of bonded tokens slashed by `Slash` message with `downtime` reason.
+    #[serde(default)]
+    pub downtime_slash_ratio: Decimal,
+
+    /// Ratio of bonded tokens slashed by `Slash` message with `governance` reason. If not set,
+    /// `portion` passed with the message is used.
+    #[serde(default)]
+    pub governance_slash_ratio: Option<Decimal>,
+
+    /// Maximum Tendermint power of a single validator, applied after `scaling`.
+    #[serde(default)]
+    pub max_power: Option<u64>,
+
+    /// Maximum number of validators entering and leaving the active set in a single epoch.
+    #[serde(default)]
+    pub max_churn: Option<u32>,
+
+    /// Whether rewards paid directly to validators are sent to vesting accounts, instead of being
+    /// transferred as liquid tokens
+    #[serde(default)]
+    pub reward_vesting: bool,
+
+    /// Number of blocks after joining the active validator set during which a validator is not
+    /// jailed for being offline
+    #[serde(default)]
+    pub verification_grace_blocks: u64,
+
+    /// Upper bound of `epoch_reward`, in the same denom
+    #[serde(default)]
+    pub max_epoch_reward: Option<Coin>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct DistributionContract {
     pub contract: Addr,
     pub ratio: Decimal,
+    /// Message sent to the contract along with its reward
+    #[serde(default)]
+    pub schema: DistributionSchema,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -80,6 +171,19 @@ pub struct EpochInfo {
     /// The last time we updated the validator set - block time and height
     pub last_update_time: u64,
     pub last_update_height: u64,
+    /// Point since which epochs are counted with the current `epoch_length`
+    #[serde(default)]
+    pub epoch_offset: EpochOffset,
+}
+
+/// Anchors epoch numbering when `epoch_length` changes, so the new length applies from the
+/// change on, instead of renumbering all epochs since the genesis.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, JsonSchema, Debug)]
+pub struct EpochOffset {
+    /// Epoch in progress when `epoch_length` changed
+    pub epoch: u64,
+    /// Time of the change, in seconds
+    pub time: u64,
 }
 
 /// Tendermint public key, Operator SDK address, and tendermint voting power.
@@ -100,11 +204,46 @@ pub const EPOCH: Item<EpochInfo> = Item::new("epoch");
 /// This will be empty only on the first run.
 pub const VALIDATORS: Item<Vec<ValidatorInfo>> = Item::new("validators");
 
+/// Active validator set recorded whenever it changes - at the end of every epoch, and when
+/// validators are removed by immediate jailing. Keyed by epoch and block height.
+pub const VALIDATORS_HISTORY: Map<(u64, u64), Vec<ValidatorInfo>> = Map::new("validators_history");
+
 /// A map of validators to block heights they had last signed a block.
 /// To verify they're online / active.
 /// The key are the first 20 bytes of the SHA-256 hashed validator pubkey (from Cosmos SDK).
 pub const BLOCK_SIGNERS: Map<&[u8], u64> = Map::new("block_signers");
 
+/// A map of validators to the last epoch whose boundary block they signed. Keyed the same as
+/// `BLOCK_SIGNERS`.
+pub const BOUNDARY_SIGNERS: Map<&[u8], u64> = Map::new("boundary_signers");
+
+/// Number of blocks of the current epoch checked for validators signatures. Only counted with
+/// `uptime_rewards` enabled.
+pub const EPOCH_BLOCKS: Item<u64> = Item::new("epoch_blocks");
+
+/// A map of validators to the number of blocks of the current epoch they signed. Keyed the same
+/// as `BLOCK_SIGNERS`. Only counted with `uptime_rewards` enabled.
+pub const EPOCH_SIGNED_BLOCKS: Map<&[u8], u64> = Map::new("epoch_signed_blocks");
+
+/// Validators rewards forfeited for low uptime, added to the next epoch reward.
+pub const FORFEITED_REWARDS: Item<Uint128> = Item::new("forfeited_rewards");
+
+/// Operators jailed with `immediate` flag, to be removed from the active validator set at the end
+/// of the block
+pub const IMMEDIATE_JAILS: Item<Vec<Addr>> = Item::new("immediate_jails");
+
+/// Whether epoch processing is paused for maintenance
+pub const EPOCH_PROCESSING_PAUSED: Item<bool> = Item::new("epoch_processing_paused");
+
+/// The last epoch whose boundary was skipped while epoch processing was paused
+pub const SKIPPED_EPOCH: Item<u64> = Item::new("skipped_epoch");
+
+/// Total rewards sent to every distribution contract in its lifetime, in the epoch reward denom.
+pub const DISTRIBUTED_REWARDS: Map<&Addr, Uint128> = Map::new("distributed_rewards");
+
+/// Addresses receiving validation rewards instead of the operators, if set.
+pub const REWARD_ADDRESS: Map<&Addr, Addr> = Map::new("reward_address");
+
 /// Map of operator addr to block height it initially became a validator. If operator doesn't
 /// appear in this map, he was never in the validator set.
 pub const VALIDATOR_START_HEIGHT: Map<&Addr, u64> = Map::new("start_height");
@@ -116,14 +255,111 @@ pub const VALIDATOR_SLASHING: Map<&Addr, Vec<ValidatorSlashing>> = Map::new("val
 /// is not jailed
 pub const JAIL: Map<&Addr, JailingPeriod> = Map::new("jail");
 
+/// Map of operator addr and jailing sequence number to the jailing record.
+pub const JAILING_HISTORY: Map<(&Addr, u64), JailingRecord> = Map::new("jailing_history");
+
+/// Map of tombstoned operator addr to the height it was tombstoned at. Tombstoned operators are
+/// jailed forever, and are never again considered for the validator set.
+pub const TOMBSTONED: Map<&Addr, u64> = Map::new("tombstoned");
+
+/// Map of operator addr to the time it was last unjailed. Used to grant immunity from offline
+/// jailing for `offline_jail_immunity` after unjailing.
+pub const UNJAILED_AT: Map<&Addr, Timestamp> = Map::new("unjailed_at");
+
+/// Map of active validator addr to the height it most recently joined the active set at. Used
+/// to grant `verification_grace_blocks` before offline jailing applies.
+pub const ACTIVE_SINCE: Map<&Addr, u64> = Map::new("active_since");
+
+/// Epoch in which operator registered validator key. Not set for operators registered on
+/// instantiation.
+pub const OPERATOR_REGISTRATION_EPOCH: Map<&Addr, u64> = Map::new("operator_registration_epoch");
+
 /// This stores the info for an operator. Both their Tendermint key as well as
 /// their metadata.
 #[derive(Serialize, Deserialize, Clone, JsonSchema, Debug, PartialEq, Eq)]
 pub struct OperatorInfo {
-    pub pubkey: Ed25519Pubkey,
+    pub pubkey: OperatorPubkey,
     pub metadata: ValidatorMetadata,
     /// Is this currently an active validator?
     pub active_validator: bool,
+    /// First epoch of probation after being unjailed, during which validator power is scaled down
+    #[serde(default)]
+    pub probation_start_epoch: Option<u64>,
+    /// Time of the last `UpdateMetadata` call, used to rate-limit metadata updates
+    #[serde(default)]
+    pub metadata_updated_at: Option<Timestamp>,
+}
+
+/// Tendermint consensus pubkey of an operator, of one of the supported types.
+///
+/// It is untagged, so ed25519 keys are stored the same way as before other key types were
+/// supported.
+#[derive(Serialize, Deserialize, Clone, JsonSchema, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum OperatorPubkey {
+    Ed25519(Ed25519Pubkey),
+    Secp256k1(Secp256k1Pubkey),
+}
+
+impl OperatorPubkey {
+    pub fn to_vec(&self) -> Vec<u8> {
+        match self {
+            OperatorPubkey::Ed25519(pubkey) => pubkey.to_vec(),
+            OperatorPubkey::Secp256k1(pubkey) => pubkey.to_vec(),
+        }
+    }
+
+    /// Returns the base64 encoded raw pubkey data.
+    pub fn to_base64(&self) -> String {
+        match self {
+            OperatorPubkey::Ed25519(pubkey) => pubkey.to_base64(),
+            OperatorPubkey::Secp256k1(pubkey) => pubkey.to_base64(),
+        }
+    }
+
+    pub fn key_type(&self) -> &'static str {
+        match self {
+            OperatorPubkey::Ed25519(_) => "ed25519",
+            OperatorPubkey::Secp256k1(_) => "secp256k1",
+        }
+    }
+
+    /// Tendermint address of the validator
+    pub fn to_address(&self) -> [u8; 20] {
+        match self {
+            OperatorPubkey::Ed25519(pubkey) => pubkey.to_address(),
+            OperatorPubkey::Secp256k1(pubkey) => pubkey.to_address(),
+        }
+    }
+}
+
+impl From<OperatorPubkey> for Pubkey {
+    fn from(pubkey: OperatorPubkey) -> Self {
+        match pubkey {
+            OperatorPubkey::Ed25519(pubkey) => pubkey.into(),
+            OperatorPubkey::Secp256k1(pubkey) => pubkey.into(),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a Pubkey> for OperatorPubkey {
+    type Error = ContractError;
+
+    fn try_from(pubkey: &'a Pubkey) -> Result<Self, Self::Error> {
+        match pubkey {
+            Pubkey::Ed25519(_) => Ok(OperatorPubkey::Ed25519(pubkey.try_into()?)),
+            Pubkey::Secp256k1(_) => Ok(OperatorPubkey::Secp256k1(pubkey.try_into()?)),
+            _ => Err(ContractError::UnsupportedPubkeyType {}),
+        }
+    }
+}
+
+impl TryFrom<Pubkey> for OperatorPubkey {
+    type Error = ContractError;
+
+    fn try_from(pubkey: Pubkey) -> Result<Self, Self::Error> {
+        OperatorPubkey::try_from(&pubkey)
+    }
 }
 
 /// This defines the stored and returned data for a slashing event.
@@ -132,6 +368,31 @@ pub struct ValidatorSlashing {
     /// Block height of slashing event
     pub slash_height: u64,
     pub portion: Decimal,
+    /// Reason of slashing. `None` for events recorded before causes were tracked.
+    #[serde(default)]
+    pub cause: Option<SlashingCause>,
+    /// Stake tokens (including claims) burned by the membership contract. `None` if it didn't
+    /// report them (only tg4-stake does), or for events recorded before amounts were tracked.
+    #[serde(default)]
+    pub amount: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SlashingCause {
+    /// Slashed by the admin with `Slash` message
+    Governance,
+    /// Slashed and tombstoned for double signing evidence, or slashed by the admin for double
+    /// signing
+    DoubleSign,
+    /// Slashed by the admin for being offline
+    Downtime,
+}
+
+impl Default for SlashingCause {
+    fn default() -> Self {
+        Self::Governance
+    }
 }
 
 /// All this to get a unique secondary index on the pubkey, so we can ensure uniqueness.
@@ -168,6 +429,13 @@ pub struct SlashingResponse {
     pub slashing: Vec<ValidatorSlashing>,
 }
 
+/// Ancillary struct for exporting per operator values
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct OperatorValue<T> {
+    pub operator: String,
+    pub value: T,
+}
+
 /// Export / Import state
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct ValsetState {
@@ -179,6 +447,75 @@ pub struct ValsetState {
     pub validators: Vec<ValidatorInfo>,
     pub validators_start_height: Vec<StartHeightResponse>,
     pub validators_slashing: Vec<SlashingResponse>,
+    /// Operators probation start epochs, for operators on probation
+    #[serde(default)]
+    pub probation_start_epochs: Vec<OperatorValue<u64>>,
+    /// Operators last metadata update times, for operators which ever updated it
+    #[serde(default)]
+    pub metadata_updated_at: Vec<OperatorValue<Timestamp>>,
+    /// Heights operators were tombstoned at
+    #[serde(default)]
+    pub tombstoned: Vec<OperatorValue<u64>>,
+    /// Times operators were last unjailed at
+    #[serde(default)]
+    pub unjailed_at: Vec<OperatorValue<Timestamp>>,
+    /// Operators reward addresses
+    #[serde(default)]
+    pub reward_addresses: Vec<OperatorValue<Addr>>,
+    /// Epochs operators registered their validator keys in
+    #[serde(default)]
+    pub registration_epochs: Vec<OperatorValue<u64>>,
+    /// Jailing records of all operators
+    #[serde(default)]
+    pub jailing_history: Vec<OperatorValue<JailingRecord>>,
+    /// Heights operators are active validators since
+    #[serde(default)]
+    pub active_since: Vec<OperatorValue<u64>>,
+}
+
+/// Lists all values of an operator keyed map.
+fn export_map<T>(storage: &dyn Storage, map: &Map<&Addr, T>) -> StdResult<Vec<OperatorValue<T>>>
+where
+    T: Serialize + DeserializeOwned,
+{
+    map.range(storage, None, None, Ascending)
+        .map(|r| {
+            let (operator, value) = r?;
+            Ok(OperatorValue {
+                operator: operator.to_string(),
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Value of the operator in exported values, if any.
+fn find_value<T: Clone>(values: &[OperatorValue<T>], operator: &str) -> Option<T> {
+    values
+        .iter()
+        .find(|value| value.operator == operator)
+        .map(|value| value.value.clone())
+}
+
+/// Replaces all values of an operator keyed map.
+fn import_map<T>(
+    storage: &mut dyn Storage,
+    map: &Map<&Addr, T>,
+    values: &[OperatorValue<T>],
+) -> StdResult<()>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let keys = map
+        .keys(storage, None, None, Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for key in keys {
+        map.remove(storage, &key);
+    }
+    for value in values {
+        map.save(storage, &Addr::unchecked(&value.operator), &value.value)?;
+    }
+    Ok(())
 }
 
 /// Export state
@@ -193,19 +530,48 @@ pub fn export(deps: Deps<TgradeQuery>) -> Result<Response<TgradeMsg>, ContractEr
         validators: VALIDATORS.load(deps.storage)?,
         validators_start_height: vec![],
         validators_slashing: vec![],
+        probation_start_epochs: vec![],
+        metadata_updated_at: vec![],
+        tombstoned: export_map(deps.storage, &TOMBSTONED)?,
+        unjailed_at: export_map(deps.storage, &UNJAILED_AT)?,
+        reward_addresses: export_map(deps.storage, &REWARD_ADDRESS)?,
+        registration_epochs: export_map(deps.storage, &OPERATOR_REGISTRATION_EPOCH)?,
+        jailing_history: vec![],
+        active_since: export_map(deps.storage, &ACTIVE_SINCE)?,
     };
 
     // Operator items
-    state.operators = operators()
+    for item in operators().range(deps.storage, None, None, Ascending) {
+        let (operator, info) = item?;
+        if let Some(epoch) = info.probation_start_epoch {
+            state.probation_start_epochs.push(OperatorValue {
+                operator: operator.to_string(),
+                value: epoch,
+            });
+        }
+        if let Some(time) = info.metadata_updated_at {
+            state.metadata_updated_at.push(OperatorValue {
+                operator: operator.to_string(),
+                value: time,
+            });
+        }
+        let jailed = JAIL.may_load(deps.storage, &operator)?;
+        state.operators.push(OperatorResponse::from_info(
+            info,
+            operator.to_string(),
+            jailed,
+        ));
+    }
+
+    // Jailing history items
+    state.jailing_history = JAILING_HISTORY
         .range(deps.storage, None, None, Ascending)
         .map(|r| {
-            let (operator, info) = r?;
-            let jailed = JAIL.may_load(deps.storage, &operator)?;
-            Ok(OperatorResponse::from_info(
-                info,
-                operator.to_string(),
-                jailed,
-            ))
+            let ((operator, _), record) = r?;
+            Ok(OperatorValue {
+                operator: operator.to_string(),
+                value: record,
+            })
         })
         .collect::<StdResult<_>>()?;
 
@@ -270,9 +636,11 @@ pub fn import(
     // Import operators
     for op in state.operators {
         let info = OperatorInfo {
-            pubkey: Ed25519Pubkey::try_from(op.pubkey)?,
+            pubkey: OperatorPubkey::try_from(op.pubkey)?,
             metadata: op.metadata,
             active_validator: op.active_validator,
+            probation_start_epoch: find_value(&state.probation_start_epochs, &op.operator),
+            metadata_updated_at: find_value(&state.metadata_updated_at, &op.operator),
         };
         let addr = Addr::unchecked(&op.operator);
         operators().save(deps.storage, &addr, &info)?;
@@ -315,5 +683,31 @@ pub fn import(
         )?;
     }
 
+    // Per operator items
+    import_map(deps.storage, &TOMBSTONED, &state.tombstoned)?;
+    import_map(deps.storage, &UNJAILED_AT, &state.unjailed_at)?;
+    import_map(deps.storage, &REWARD_ADDRESS, &state.reward_addresses)?;
+    import_map(
+        deps.storage,
+        &OPERATOR_REGISTRATION_EPOCH,
+        &state.registration_epochs,
+    )?;
+    import_map(deps.storage, &ACTIVE_SINCE, &state.active_since)?;
+
+    // Jailing history items
+    let records = JAILING_HISTORY
+        .keys(deps.storage, None, None, Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (operator, id) in records {
+        JAILING_HISTORY.remove(deps.storage, (&operator, id));
+    }
+    for record in &state.jailing_history {
+        JAILING_HISTORY.save(
+            deps.storage,
+            (&Addr::unchecked(&record.operator), record.value.id),
+            &record.value,
+        )?;
+    }
+
     Ok(Response::default())
 }
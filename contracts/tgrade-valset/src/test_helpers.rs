@@ -52,3 +52,12 @@ pub fn mock_pubkey(base: &[u8]) -> Pubkey {
     raw.truncate(ED25519_PUBKEY_LENGTH);
     Pubkey::Ed25519(Binary(raw))
 }
+
+// creates a valid compressed secp256k1 pubkey from a seed
+pub fn mock_secp256k1_pubkey(base: &[u8]) -> Pubkey {
+    let copies = (ED25519_PUBKEY_LENGTH / base.len()) + 1;
+    let mut raw = base.repeat(copies);
+    raw.truncate(ED25519_PUBKEY_LENGTH);
+    raw.insert(0, 0x02);
+    Pubkey::Secp256k1(Binary(raw))
+}
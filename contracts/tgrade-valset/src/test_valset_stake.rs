@@ -78,7 +78,7 @@ fn instantiate_stake(app: &mut BasicApp<TgradeMsg>) -> Addr {
     let admin = Some(STAKE_OWNER.into());
     let msg = tg4_stake::msg::InstantiateMsg {
         denom: BOND_DENOM.to_owned(),
-        tokens_per_point: Uint128::new(TOKENS_PER_POINT),
+        tokens_per_point: Decimal::from_ratio(TOKENS_PER_POINT, 1u128),
         min_bond: Uint128::new(MIN_BOND),
         unbonding_period: 1234,
         admin: admin.clone(),
@@ -192,6 +192,7 @@ fn init_and_query_state() {
             last_update_time: 0,
             last_update_height: 0,
             next_update_time: app.block_info().time.nanos() / 1_000_000_000,
+            epoch_processing_paused: false,
         }
     );
 
@@ -2,6 +2,7 @@ mod gov;
 mod hooks;
 mod msg;
 mod query;
+mod ripemd;
 mod sudo;
 mod validator;
 
@@ -11,8 +12,8 @@ pub use msg::{BlockParams, ConsensusParams, EvidenceParams, TgradeMsg};
 pub use query::{ListPrivilegedResponse, TgradeQuery, ValidatorVoteResponse};
 pub use sudo::{Evidence, EvidenceType, PrivilegeChangeMsg, TgradeSudoMsg, ValidatorDiff};
 pub use validator::{
-    Ed25519Pubkey, Ed25519PubkeyConversionError, Pubkey, ToAddress, Validator, ValidatorUpdate,
-    ValidatorVote,
+    Ed25519Pubkey, Ed25519PubkeyConversionError, Pubkey, Secp256k1Pubkey,
+    Secp256k1PubkeyConversionError, ToAddress, Validator, ValidatorUpdate, ValidatorVote,
 };
 
 // This is a signal, such that any contract that imports these helpers will only run on the tgrade
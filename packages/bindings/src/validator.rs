@@ -6,6 +6,8 @@ use std::convert::TryInto;
 
 use sha2::{Digest, Sha256};
 
+use crate::ripemd::ripemd160;
+
 use cosmwasm_std::Binary;
 
 /// This is returned by most queries from Tendermint
@@ -87,8 +89,7 @@ impl Ord for Pubkey {
 /// This type is known to have the correct length, which serves as a minimal validation. This
 /// does not mean it is a valid curve point though.
 ///
-/// Similar types `struct Secp256k1Pubkey([u8; 33])` and `struct Sr25519Pubkey([u8; 32])`
-/// should be created on demand.
+/// A similar type `struct Sr25519Pubkey([u8; 32])` should be created on demand.
 ///
 /// ## Examples
 ///
@@ -182,6 +183,100 @@ impl TryFrom<Pubkey> for Ed25519Pubkey {
     }
 }
 
+/// A Secp256k1 public key in the 33 bytes compressed format.
+///
+/// This type is known to have the correct length and prefix, which serves as a minimal
+/// validation. This does not mean it is a valid curve point though.
+///
+/// ## Examples
+///
+/// ```
+/// # use hex_literal::hex;
+/// use std::convert::TryFrom;
+/// use tg_bindings::{Pubkey, Secp256k1Pubkey};
+///
+/// let pubkey = Pubkey::Secp256k1(hex!("0292a066ec32d37c607519d7a86eb2107013a26b160ce3da732ee76e9b2e502492").into());
+/// let secp256k1_pubkey = Secp256k1Pubkey::try_from(pubkey);
+/// assert!(secp256k1_pubkey.is_ok());
+///
+/// let pubkey = Pubkey::Ed25519(hex!("14253d61ef42d166d02e68d540d07fdf8d65a9af0acaa46302688e788a8521e2").into());
+/// let secp256k1_pubkey = Secp256k1Pubkey::try_from(pubkey);
+/// assert!(secp256k1_pubkey.is_err());
+/// ```
+///
+/// When we have a [Secp256k1Pubkey] we can derive an address:
+///
+/// ```
+/// # use hex_literal::hex;
+/// use std::convert::TryFrom;
+/// use tg_bindings::{Pubkey, Secp256k1Pubkey, ToAddress};
+///
+/// let pubkey = Pubkey::Secp256k1(hex!("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798").into());
+/// let secp256k1_pubkey = Secp256k1Pubkey::try_from(pubkey).unwrap();
+/// let address = secp256k1_pubkey.to_address();
+/// assert_eq!(address, hex!("751E76E8199196D454941C45D1B3A323F1433BD6"));
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, PartialEq, Eq)]
+pub struct Secp256k1Pubkey(Binary);
+
+impl Secp256k1Pubkey {
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+
+    /// Returns the base64 encoded raw pubkey data.
+    pub fn to_base64(&self) -> String {
+        self.0.to_base64()
+    }
+}
+
+impl ToAddress for Secp256k1Pubkey {
+    /// RIPEMD160(SHA256(pubkey)), as Tendermint derives addresses of secp256k1 keys
+    fn to_address(&self) -> [u8; 20] {
+        ripemd160(&Sha256::digest(self.0.as_slice()))
+    }
+}
+
+impl From<Secp256k1Pubkey> for Pubkey {
+    fn from(secp: Secp256k1Pubkey) -> Self {
+        Pubkey::Secp256k1(secp.0)
+    }
+}
+
+#[derive(Debug)]
+pub enum Secp256k1PubkeyConversionError {
+    WrongType,
+    InvalidDataLength,
+    InvalidPrefix,
+}
+
+impl<'a> TryFrom<&'a Pubkey> for Secp256k1Pubkey {
+    type Error = Secp256k1PubkeyConversionError;
+
+    fn try_from(pubkey: &'a Pubkey) -> Result<Self, Self::Error> {
+        match pubkey {
+            Pubkey::Secp256k1(data) => {
+                if data.len() != 33 {
+                    return Err(Secp256k1PubkeyConversionError::InvalidDataLength);
+                }
+                if data[0] != 0x02 && data[0] != 0x03 {
+                    return Err(Secp256k1PubkeyConversionError::InvalidPrefix);
+                }
+                Ok(Secp256k1Pubkey(data.clone()))
+            }
+            _ => Err(Secp256k1PubkeyConversionError::WrongType),
+        }
+    }
+}
+
+impl TryFrom<Pubkey> for Secp256k1Pubkey {
+    type Error = Secp256k1PubkeyConversionError;
+
+    fn try_from(pubkey: Pubkey) -> Result<Self, Self::Error> {
+        Secp256k1Pubkey::try_from(&pubkey)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -215,4 +310,26 @@ mod tests {
         let address = pubkey.to_address();
         assert_eq!(address, hex!("0CDA3F47EF3C4906693B170EF650EB968C5F4B2C"))
     }
+
+    #[test]
+    fn secp256k1pubkey_validation() {
+        let valid = hex!("0292a066ec32d37c607519d7a86eb2107013a26b160ce3da732ee76e9b2e502492");
+        let pubkey = Secp256k1Pubkey::try_from(Pubkey::Secp256k1(valid.into())).unwrap();
+        assert_eq!(Pubkey::from(pubkey), Pubkey::Secp256k1(valid.into()));
+
+        let mut wrong_prefix = valid;
+        wrong_prefix[0] = 0x04;
+        assert!(matches!(
+            Secp256k1Pubkey::try_from(Pubkey::Secp256k1(wrong_prefix.into())),
+            Err(Secp256k1PubkeyConversionError::InvalidPrefix)
+        ));
+        assert!(matches!(
+            Secp256k1Pubkey::try_from(Pubkey::Secp256k1(valid[..32].into())),
+            Err(Secp256k1PubkeyConversionError::InvalidDataLength)
+        ));
+        assert!(matches!(
+            Secp256k1Pubkey::try_from(Pubkey::Ed25519(valid.into())),
+            Err(Secp256k1PubkeyConversionError::WrongType)
+        ));
+    }
 }
@@ -26,6 +26,8 @@ pub enum JailMsg {
 #[serde(rename_all = "snake_case")]
 pub enum JailingDuration {
     Duration(Duration),
+    /// Jailed for given number of blocks, independently of block times
+    Blocks(u64),
     Forever {},
 }
 
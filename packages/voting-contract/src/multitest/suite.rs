@@ -4,7 +4,7 @@ use super::contracts::{
     VotingContract,
 };
 use anyhow::Result as AnyResult;
-use cosmwasm_std::{Addr, StdResult};
+use cosmwasm_std::{Addr, Decimal, StdResult};
 use cw_multi_test::{AppResponse, Executor};
 use derivative::Derivative;
 use tg3::{
@@ -69,7 +69,14 @@ impl SuiteBuilder {
                     preauths_hooks: 0,
                     preauths_slashing: 0,
                     halflife: None,
+                    decay: None,
                     denom: "poe-coin".to_string(),
+                    max_denoms: tg4_engagement::msg::default_max_denoms(),
+                    distribution_fee: Decimal::zero(),
+                    fee_recipient: None,
+                    auto_distribute: false,
+                    halflife_floor: 1,
+                    initial_adjustments: vec![],
                 },
                 &[],
                 "engagement",